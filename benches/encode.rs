@@ -0,0 +1,72 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use wifi_qr_code::{AuthenticationType, EscapingMode, QrCodeEcc, Visibility, WifiCredentials};
+
+fn wifi_credentials() -> WifiCredentials {
+    WifiCredentials {
+        ssid: String::from("example ssid"),
+        authentication_type: AuthenticationType::WPA("example password".into()),
+        visibility: Visibility::Hidden,
+    }
+}
+
+fn special_character_credentials() -> WifiCredentials {
+    WifiCredentials {
+        ssid: String::from(r#"caf\e; "lobby", floor:2"#),
+        authentication_type: AuthenticationType::WPA(r#"pass;word,with:"all"\of them"#.into()),
+        visibility: Visibility::Visible,
+    }
+}
+
+fn encode(c: &mut Criterion) {
+    let wifi_credentials = wifi_credentials();
+    c.bench_function("encode", |b| {
+        b.iter(|| black_box(&wifi_credentials).encode())
+    });
+    let mut payload = String::new();
+    c.bench_function("encode_to reused buffer", |b| {
+        b.iter(|| {
+            payload.clear();
+            black_box(&wifi_credentials).encode_to(&mut payload)
+        })
+    });
+}
+
+fn escape(c: &mut Criterion) {
+    let wifi_credentials = special_character_credentials();
+    for (name, escaping_mode) in [
+        ("escape spec", EscapingMode::Spec),
+        ("escape zxing quoted", EscapingMode::ZxingQuoted),
+        ("escape minimal", EscapingMode::Minimal),
+    ] {
+        c.bench_function(name, |b| {
+            b.iter(|| black_box(&wifi_credentials).encode_with(escaping_mode))
+        });
+    }
+}
+
+fn render(c: &mut Criterion) {
+    let wifi_credentials = wifi_credentials();
+    c.bench_function("render matrix", |b| {
+        b.iter(|| black_box(&wifi_credentials).qr(QrCodeEcc::Medium).matrix())
+    });
+    c.bench_function("render image", |b| {
+        b.iter(|| {
+            black_box(&wifi_credentials)
+                .qr(QrCodeEcc::Medium)
+                .size(512)
+                .image()
+        })
+    });
+    c.bench_function("render png", |b| {
+        b.iter(|| {
+            let mut png = Vec::new();
+            black_box(&wifi_credentials)
+                .qr(QrCodeEcc::Medium)
+                .size(512)
+                .png(&mut png)
+        })
+    });
+}
+
+criterion_group!(benches, encode, escape, render);
+criterion_main!(benches);
@@ -0,0 +1,104 @@
+//! Provisioning firmware for an ESP32 with an SSD1306 OLED on I2C (SDA on GPIO 21, SCL on GPIO 22).
+//!
+//! The device starts a softAP with a random WPA2 passphrase and shows the QR code to join it on the display, so a phone can connect by scanning the screen instead of typing the password. Flash it with `cargo run --release`, which uses [espflash](https://github.com/esp-rs/espflash).
+
+#![no_std]
+#![no_main]
+
+extern crate alloc;
+
+use alloc::format;
+use alloc::string::String;
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use embedded_graphics_core::prelude::*;
+use esp_backtrace as _;
+use esp_hal::i2c::master::{Config as I2cConfig, I2c};
+use esp_hal::rng::Rng;
+use esp_hal::timer::timg::TimerGroup;
+use esp_hal::{clock::CpuClock, main};
+use esp_println::println;
+use esp_wifi::wifi::{AccessPointConfiguration, AuthMethod, Configuration};
+use ssd1306::prelude::*;
+use ssd1306::{I2CDisplayInterface, Ssd1306};
+use wifi_qr_code::display;
+use wifi_qr_code::{
+    qr_matrix_side, AuthenticationTypeRef, QrCodeEcc, QrMatrix, Visibility, WifiCredentialsRef,
+};
+
+esp_bootloader_esp_idf::esp_app_desc!();
+
+/// Characters of the passphrase. There are 32 of them, so 5 random bits pick one without bias, and none of them look alike or need escaping in the payload.
+const PASSPHRASE_CHARACTERS: &[u8; 32] = b"abcdefghijkmnpqrstuvwxyz23456789";
+
+/// 16 characters of 5 bits each, for 80 bits of entropy.
+const PASSPHRASE_LEN: usize = 16;
+
+#[main]
+fn main() -> ! {
+    let peripherals = esp_hal::init(esp_hal::Config::default().with_cpu_clock(CpuClock::max()));
+    // The wifi driver allocates its buffers on the heap. Encoding and drawing the QR code does not.
+    esp_alloc::heap_allocator!(size: 72 * 1024);
+
+    let mut rng = Rng::new(peripherals.RNG);
+    let ssid = format!("setup-{:04x}", rng.random() & 0xffff);
+    let passphrase: String = (0..PASSPHRASE_LEN)
+        .map(|_| char::from(PASSPHRASE_CHARACTERS[(rng.random() % 32) as usize]))
+        .collect();
+
+    let timer_group = TimerGroup::new(peripherals.TIMG0);
+    let wifi = esp_wifi::init(timer_group.timer0, rng).expect("Failed to initialize the radio.");
+    let (mut controller, _interfaces) =
+        esp_wifi::wifi::new(&wifi, peripherals.WIFI).expect("Failed to initialize wifi.");
+    controller
+        .set_configuration(&Configuration::AccessPoint(AccessPointConfiguration {
+            ssid: ssid.clone(),
+            password: passphrase.clone(),
+            auth_method: AuthMethod::WPA2Personal,
+            ..Default::default()
+        }))
+        .expect("Failed to configure the access point.");
+    controller.start().expect("Failed to start the access point.");
+    println!("Started the access point {}", ssid);
+
+    let wifi_credentials = WifiCredentialsRef {
+        ssid: &ssid,
+        authentication_type: AuthenticationTypeRef::WPA(&passphrase),
+        visibility: Visibility::Visible,
+    };
+    // The payload of a 10 character SSID and a 16 character passphrase fits into a version 3 code at low error correction, which is 29 modules wide, so with a quiet zone of one module each module is 2 pixels on the 64 pixel high screen.
+    let mut matrix = QrMatrix::<{ qr_matrix_side(3) }>::new();
+    wifi_credentials
+        .encode_to_qr_matrix(&mut matrix, QrCodeEcc::Low)
+        .expect("The credentials do not fit into a version 3 QR code.");
+
+    let i2c = I2c::new(peripherals.I2C0, I2cConfig::default())
+        .expect("Failed to configure I2C.")
+        .with_sda(peripherals.GPIO21)
+        .with_scl(peripherals.GPIO22);
+    let mut screen = Ssd1306::new(
+        I2CDisplayInterface::new(i2c),
+        DisplaySize128x64,
+        DisplayRotation::Rotate0,
+    )
+    .into_buffered_graphics_mode();
+    screen.init().expect("Failed to initialize the display.");
+    // An OLED gives off light, so the light modules are lit pixels, and the rest of the screen is lit too so that it extends the quiet zone.
+    screen
+        .clear(BinaryColor::On)
+        .expect("Failed to clear the display.");
+    let area = screen.bounding_box();
+    display::draw_qr_matrix(
+        &mut screen,
+        &matrix,
+        area,
+        1,
+        BinaryColor::Off,
+        BinaryColor::On,
+    )
+    .expect("Failed to draw the QR code.");
+    screen.flush().expect("Failed to refresh the display.");
+
+    loop {
+        core::hint::spin_loop();
+    }
+}
@@ -1,8 +1,9 @@
 use structopt::StructOpt;
 
-use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
-use wifi_qr_code::{QRCodeError, QrCodeEcc};
+use wifi_qr_code::QrCodeEcc;
+use wifi_qr_code::{AuthenticationType, ControlCharacterPolicy, Visibility, WifiCredentials};
 
+use std::error::Error;
 use std::fs::File;
 use std::path::PathBuf;
 
@@ -12,6 +13,9 @@ struct Opt {
     #[structopt(short, long)]
     hidden: bool,
 
+    #[structopt(short, long, default_value = "wpa2")]
+    authentication_type: AuthenticationType,
+
     #[structopt(short, long, default_value = "512")]
     size: usize,
 
@@ -22,9 +26,14 @@ struct Opt {
     png_file: PathBuf,
 }
 
-fn main() -> Result<(), QRCodeError> {
+fn main() -> Result<(), Box<dyn Error>> {
     let opt = Opt::from_args();
-    let password = rpassword::prompt_password("Password: ").expect("Failed to get password.");
+    let authentication_type = match opt.authentication_type {
+        AuthenticationType::NoPassword => AuthenticationType::NoPassword,
+        authentication_type => authentication_type.with_password(
+            rpassword::prompt_password("Password: ").expect("Failed to get password."),
+        ),
+    };
 
     let visibility = if opt.hidden {
         Visibility::Hidden
@@ -32,14 +41,18 @@ fn main() -> Result<(), QRCodeError> {
         Visibility::Visible
     };
 
-    let wifi_credentials = WifiCredentials {
+    let mut wifi_credentials = WifiCredentials {
         ssid: opt.ssid,
-        authentication_type: AuthenticationType::WPA(password),
+        authentication_type,
         visibility,
     };
+    wifi_credentials.apply_control_character_policy(ControlCharacterPolicy::Strip)?;
 
     let png_file = File::create(opt.png_file)?;
-    wifi_qr_code::encode_as_png(&wifi_credentials, QrCodeEcc::Medium, opt.size, png_file)?;
+    wifi_credentials
+        .qr(QrCodeEcc::Medium)
+        .size(opt.size)
+        .png(png_file)?;
 
     Ok(())
 }
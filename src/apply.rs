@@ -0,0 +1,418 @@
+//! Join the network described by credentials on the local machine, e.g. right after decoding a photo of a wifi QR code, using the tools that ship with the operating system: `nmcli` with NetworkManager on Linux, `networksetup` on macOS, and `netsh` on Windows.
+//!
+//! With the `hotspot` feature, `create_hotspot` goes the other way and shares the machine's connection through a new hotspot on Linux and Windows.
+//!
+//! The tools receive the password on their command line (on Windows, in a profile written to a new file with a random name in the temporary directory and removed again), so other users of the machine may be able to see it while the network is joined.
+
+#[cfg(target_os = "windows")]
+use crate::export::windows_profile;
+#[cfg(target_os = "macos")]
+use crate::tool::wifi_interface;
+use crate::tool::{self, Failure};
+use crate::{AuthenticationType, ValidationError, Visibility, WifiCredentials};
+#[cfg(all(feature = "hotspot", any(target_os = "linux", target_os = "windows")))]
+use crate::{QrCodeEcc, WifiQrCode};
+
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+#[cfg(all(feature = "hotspot", target_os = "windows"))]
+use std::process::Command;
+use std::process::ExitStatus;
+
+/// Errors when joining a network with [`join`].
+#[derive(Debug)]
+pub enum ApplyError {
+    /// The credentials do not meet the requirements of the wifi standards.
+    Validation(ValidationError),
+    /// The network tool could not be started, e.g. because it is not installed, or its input could not be written.
+    Io(io::Error),
+    /// The network tool ran, but failed to join the network.
+    Command {
+        /// The tool that failed, such as `nmcli`.
+        program: &'static str,
+        /// How the tool exited.
+        status: ExitStatus,
+        /// What the tool wrote to standard error, or to standard output if nothing was written to standard error.
+        message: String,
+    },
+    /// There is no wifi interface to join the network with.
+    NoInterface,
+    /// No passphrase could be generated for a hotspot, because the operating system's random number generator failed.
+    #[cfg(feature = "hotspot")]
+    Random(getrandom::Error),
+}
+
+impl Display for ApplyError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(error) => write!(f, "invalid credentials: {}", error),
+            Self::Io(error) => write!(f, "failed to run the network tool: {}", error),
+            Self::Command {
+                program,
+                status,
+                message,
+            } => write!(f, "{} failed ({}): {}", program, status, message),
+            Self::NoInterface => f.write_str("no wifi interface was found"),
+            #[cfg(feature = "hotspot")]
+            Self::Random(error) => write!(f, "failed to generate a passphrase: {}", error),
+        }
+    }
+}
+
+impl error::Error for ApplyError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Validation(error) => Some(error),
+            Self::Io(error) => Some(error),
+            Self::Command { .. } | Self::NoInterface => None,
+            #[cfg(feature = "hotspot")]
+            Self::Random(error) => Some(error),
+        }
+    }
+}
+
+impl From<ValidationError> for ApplyError {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+impl From<io::Error> for ApplyError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<Failure> for ApplyError {
+    fn from(failure: Failure) -> Self {
+        match failure {
+            Failure::Io(error) => Self::Io(error),
+            Failure::Exit {
+                program,
+                status,
+                message,
+            } => Self::Command {
+                program,
+                status,
+                message,
+            },
+        }
+    }
+}
+
+/// Validate the credentials and join the network, saving it so the machine reconnects later. This blocks until the operating system has connected, or given up.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wifi_qr_code::WifiCredentials;
+///
+/// let wifi_credentials = WifiCredentials::parse("WIFI:S:example ssid;T:WPA;P:example password;H:false;;")
+///     .expect("Failed to parse example payload.");
+/// wifi_qr_code::apply::join(&wifi_credentials).expect("Failed to join the network.");
+/// ```
+pub fn join(wifi_credentials: &WifiCredentials) -> Result<(), ApplyError> {
+    wifi_credentials.validate()?;
+    join_validated(wifi_credentials)
+}
+
+#[cfg(target_os = "linux")]
+fn join_validated(wifi_credentials: &WifiCredentials) -> Result<(), ApplyError> {
+    tool::run("nmcli", &nmcli_arguments(wifi_credentials))?;
+    Ok(())
+}
+
+#[cfg(target_os = "macos")]
+fn join_validated(wifi_credentials: &WifiCredentials) -> Result<(), ApplyError> {
+    let hardware_ports = tool::run("networksetup", &[String::from("-listallhardwareports")])?;
+    let interface = wifi_interface(&String::from_utf8_lossy(&hardware_ports.stdout))
+        .map(String::from)
+        .ok_or(ApplyError::NoInterface)?;
+    let output = tool::run(
+        "networksetup",
+        &networksetup_arguments(&interface, wifi_credentials),
+    )?;
+    // networksetup exits successfully even when it fails to join, and explains why on standard output instead.
+    let message = String::from_utf8_lossy(&output.stdout);
+    if message.trim().is_empty() {
+        Ok(())
+    } else {
+        Err(ApplyError::Command {
+            program: "networksetup",
+            status: output.status,
+            message: String::from(message.trim()),
+        })
+    }
+}
+
+#[cfg(target_os = "windows")]
+fn join_validated(wifi_credentials: &WifiCredentials) -> Result<(), ApplyError> {
+    use std::io::Write;
+
+    let contents = windows_profile::encode(wifi_credentials)?;
+    // The file is created with a random name and fails if it already exists, so other users cannot read it from a known path or plant their own. It is deleted when dropped, including when writing it fails.
+    let mut profile = tempfile::Builder::new()
+        .prefix("wifi-qr-code-")
+        .suffix(".xml")
+        .tempfile()?;
+    profile.write_all(contents.as_bytes())?;
+    profile.flush()?;
+    let added = tool::run(
+        "netsh",
+        &[
+            String::from("wlan"),
+            String::from("add"),
+            String::from("profile"),
+            format!("filename={}", profile.path().display()),
+            String::from("user=current"),
+        ],
+    );
+    // The profile contains the password, so it is removed even when netsh fails.
+    let removed = profile.close();
+    added?;
+    removed?;
+    tool::run("netsh", &netsh_connect_arguments(wifi_credentials))?;
+    Ok(())
+}
+
+/// A hotspot started by [`create_hotspot`], along with the QR code for joining it.
+#[cfg(all(feature = "hotspot", any(target_os = "linux", target_os = "windows")))]
+#[derive(Debug, Clone)]
+pub struct Hotspot {
+    /// The credentials of the hotspot, with a freshly generated passphrase.
+    pub wifi_credentials: WifiCredentials,
+    /// The QR code for the credentials.
+    pub qr_code: WifiQrCode,
+}
+
+/// Share the machine's connection through a hotspot with the given SSID and a passphrase from [`generate_passphrase`](crate::generate_passphrase), and return the QR code for joining it, e.g. to put on a screen for everyone in the room. On Linux, NetworkManager creates the hotspot on the first wifi interface, which disconnects it from any wifi network. On Windows, the Mobile Hotspot is reconfigured and started, so it needs an internet connection to share.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wifi_qr_code::QrCodeEcc;
+///
+/// let hotspot = wifi_qr_code::apply::create_hotspot("example hotspot", QrCodeEcc::Medium)
+///     .expect("Failed to create the hotspot.");
+/// print!("{}", hotspot.qr_code.to_terminal());
+/// ```
+#[cfg(all(feature = "hotspot", any(target_os = "linux", target_os = "windows")))]
+pub fn create_hotspot(
+    ssid: impl Into<String>,
+    qr_code_error_checking: QrCodeEcc,
+) -> Result<Hotspot, ApplyError> {
+    let wifi_credentials = WifiCredentials::wpa2_random(ssid).map_err(ApplyError::Random)?;
+    wifi_credentials.validate()?;
+    start_hotspot(&wifi_credentials)?;
+    let qr_code = WifiQrCode::new(&wifi_credentials, qr_code_error_checking)
+        .expect("Validated credentials with a generated passphrase always fit in a QR code.");
+    Ok(Hotspot {
+        wifi_credentials,
+        qr_code,
+    })
+}
+
+#[cfg(all(feature = "hotspot", target_os = "linux"))]
+fn start_hotspot(wifi_credentials: &WifiCredentials) -> Result<(), ApplyError> {
+    tool::run("nmcli", &nmcli_hotspot_arguments(wifi_credentials))?;
+    Ok(())
+}
+
+#[cfg(all(feature = "hotspot", target_os = "windows"))]
+fn start_hotspot(wifi_credentials: &WifiCredentials) -> Result<(), ApplyError> {
+    // The credentials are passed through the environment so that they do not need to be quoted for PowerShell.
+    tool::output(
+        "powershell",
+        Command::new("powershell")
+            .args([
+                "-NoProfile",
+                "-NonInteractive",
+                "-Command",
+                MOBILE_HOTSPOT_SCRIPT,
+            ])
+            .env("WIFI_QR_CODE_SSID", &wifi_credentials.ssid)
+            .env(
+                "WIFI_QR_CODE_PASSPHRASE",
+                wifi_credentials
+                    .authentication_type
+                    .password_text()
+                    .unwrap_or_default(),
+            ),
+    )?;
+    Ok(())
+}
+
+/// Configure and start the Mobile Hotspot through the WinRT tethering API, which has no command line tool of its own.
+#[cfg(all(feature = "hotspot", target_os = "windows"))]
+const MOBILE_HOTSPOT_SCRIPT: &str = r#"
+Add-Type -AssemblyName System.Runtime.WindowsRuntime
+function Await($operation, $name, $resultType) {
+    $asTask = [System.WindowsRuntimeSystemExtensions].GetMethods() |
+        Where-Object { $_.Name -eq 'AsTask' -and $_.GetParameters().Count -eq 1 -and $_.GetParameters()[0].ParameterType.Name -eq $name } |
+        Select-Object -First 1
+    if ($resultType) { $asTask = $asTask.MakeGenericMethod($resultType) }
+    $task = $asTask.Invoke($null, @($operation))
+    $task.Wait(-1) | Out-Null
+    if ($resultType) { $task.Result }
+}
+$connectionProfile = [Windows.Networking.Connectivity.NetworkInformation,Windows.Networking.Connectivity,ContentType=WindowsRuntime]::GetInternetConnectionProfile()
+if (-not $connectionProfile) { [Console]::Error.WriteLine('there is no internet connection to share'); exit 1 }
+$manager = [Windows.Networking.NetworkOperators.NetworkOperatorTetheringManager,Windows.Networking.NetworkOperators,ContentType=WindowsRuntime]::CreateFromConnectionProfile($connectionProfile)
+$configuration = $manager.GetCurrentAccessPointConfiguration()
+$configuration.Ssid = $env:WIFI_QR_CODE_SSID
+$configuration.Passphrase = $env:WIFI_QR_CODE_PASSPHRASE
+Await $manager.ConfigureAccessPointAsync($configuration) 'IAsyncAction' $null
+$result = Await $manager.StartTetheringAsync() 'IAsyncOperation`1' ([Windows.Networking.NetworkOperators.NetworkOperatorTetheringOperationResult])
+if ($result.Status -ne 'Success') { [Console]::Error.WriteLine("$($result.Status) $($result.AdditionalErrorMessage)"); exit 1 }
+"#;
+
+#[cfg(all(feature = "hotspot", any(target_os = "linux", test)))]
+fn nmcli_hotspot_arguments(wifi_credentials: &WifiCredentials) -> Vec<String> {
+    let password = wifi_credentials
+        .authentication_type
+        .password_text()
+        .unwrap_or_default();
+    [
+        "device",
+        "wifi",
+        "hotspot",
+        "ssid",
+        &wifi_credentials.ssid,
+        "password",
+        &password,
+    ]
+    .iter()
+    .map(|argument| String::from(*argument))
+    .collect()
+}
+
+#[cfg(any(target_os = "linux", test))]
+fn nmcli_arguments(wifi_credentials: &WifiCredentials) -> Vec<String> {
+    let mut arguments: Vec<String> = ["device", "wifi", "connect", &wifi_credentials.ssid]
+        .iter()
+        .map(|argument| String::from(*argument))
+        .collect();
+    if let Some(password) = wifi_credentials.authentication_type.password_text() {
+        arguments.push(String::from("password"));
+        arguments.push(password.into_inner());
+    }
+    if let AuthenticationType::WEP(_) = wifi_credentials.authentication_type {
+        arguments.push(String::from("wep-key-type"));
+        arguments.push(String::from("key"));
+    }
+    if wifi_credentials.visibility == Visibility::Hidden {
+        arguments.push(String::from("hidden"));
+        arguments.push(String::from("yes"));
+    }
+    arguments
+}
+
+#[cfg(any(target_os = "macos", test))]
+fn networksetup_arguments(interface: &str, wifi_credentials: &WifiCredentials) -> Vec<String> {
+    let mut arguments = vec![
+        String::from("-setairportnetwork"),
+        String::from(interface),
+        wifi_credentials.ssid.clone(),
+    ];
+    arguments.extend(
+        wifi_credentials
+            .authentication_type
+            .password_text()
+            .map(|password| password.into_inner()),
+    );
+    arguments
+}
+
+#[cfg(any(target_os = "windows", test))]
+fn netsh_connect_arguments(wifi_credentials: &WifiCredentials) -> Vec<String> {
+    // The profile written by `windows_profile::encode` is named after the SSID.
+    vec![
+        String::from("wlan"),
+        String::from("connect"),
+        format!("name={}", wifi_credentials.ssid),
+        format!("ssid={}", wifi_credentials.ssid),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_builds_nmcli_arguments() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("c0ffee1234".into()),
+            visibility: Visibility::Hidden,
+        };
+        assert_eq!(
+            vec![
+                "device",
+                "wifi",
+                "connect",
+                "test ssid",
+                "password",
+                "c0ffee1234",
+                "wep-key-type",
+                "key",
+                "hidden",
+                "yes"
+            ],
+            nmcli_arguments(&wifi_credentials)
+        );
+        assert_eq!(
+            vec!["device", "wifi", "connect", "test ssid"],
+            nmcli_arguments(&WifiCredentials::open("test ssid"))
+        );
+    }
+
+    #[test]
+    fn it_builds_networksetup_and_netsh_arguments() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WpaPsk([0xab; 32].into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            vec![
+                String::from("-setairportnetwork"),
+                String::from("en0"),
+                String::from("test ssid"),
+                "ab".repeat(32)
+            ],
+            networksetup_arguments("en0", &wifi_credentials)
+        );
+        assert_eq!(
+            vec!["wlan", "connect", "name=test ssid", "ssid=test ssid"],
+            netsh_connect_arguments(&wifi_credentials)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "hotspot")]
+    fn it_builds_nmcli_hotspot_arguments() {
+        assert_eq!(
+            vec![
+                "device",
+                "wifi",
+                "hotspot",
+                "ssid",
+                "test ssid",
+                "password",
+                "test password"
+            ],
+            nmcli_hotspot_arguments(&WifiCredentials::wpa2("test ssid", "test password"))
+        );
+    }
+
+    #[test]
+    fn it_validates_before_joining() {
+        assert!(matches!(
+            join(&WifiCredentials::wpa2("test ssid", "short")),
+            Err(ApplyError::Validation(
+                ValidationError::WpaPassphraseLength(5)
+            ))
+        ));
+    }
+}
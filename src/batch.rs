@@ -0,0 +1,260 @@
+#[cfg(feature = "png")]
+use crate::OutputStage;
+use crate::{EncodeOptions, Error, WifiCredentials};
+
+#[cfg(feature = "png")]
+use std::fs::File;
+#[cfg(feature = "png")]
+use std::io::{BufWriter, Write};
+#[cfg(feature = "png")]
+use std::path::Path;
+
+/// Render many credentials one after another, reusing the payload and pixel buffers between them. This avoids an allocation per code for provisioning jobs that generate thousands of codes, such as one per hotel room.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+/// use wifi_qr_code::{BatchEncoder, EncodeOptions};
+///
+/// let mut batch_encoder = BatchEncoder::new(EncodeOptions::default(), 200);
+/// for room in 100..110 {
+///     let wifi_credentials = WifiCredentials {
+///         ssid: format!("room {}", room),
+///         authentication_type: AuthenticationType::WPA(format!("password for room {}", room).into()),
+///         visibility: Visibility::Visible,
+///     };
+///     let image = batch_encoder.encode_as_image(&wifi_credentials)
+///         .expect("Failed to encode example image.");
+///     assert_eq!(200 * 200, image.len());
+/// }
+/// ```
+pub struct BatchEncoder {
+    options: EncodeOptions,
+    image_size: usize,
+    payload: String,
+    pixels: Vec<u8>,
+}
+
+impl BatchEncoder {
+    /// Create an encoder that renders square images of `image_size` pixels, generating QR codes with the given options.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{BatchEncoder, EncodeOptions};
+    ///
+    /// let batch_encoder = BatchEncoder::new(EncodeOptions::default(), 200);
+    /// ```
+    pub fn new(options: EncodeOptions, image_size: usize) -> Self {
+        BatchEncoder {
+            options,
+            image_size,
+            payload: String::new(),
+            pixels: Vec::new(),
+        }
+    }
+
+    /// Encode credentials as raw image data, laid out like [`encode_as_image`](crate::encode_as_image). The returned image borrows the encoder's pixel buffer, which is overwritten by the next call.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    /// use wifi_qr_code::{BatchEncoder, EncodeOptions};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// let mut batch_encoder = BatchEncoder::new(EncodeOptions::default(), 100);
+    /// let image = batch_encoder.encode_as_image(&wifi_credentials)
+    ///     .expect("Failed to encode example image.");
+    /// assert_eq!(100 * 100, image.len());
+    /// ```
+    pub fn encode_as_image(&mut self, wifi_credentials: &WifiCredentials) -> Result<&[u8], Error> {
+        wifi_credentials.validate()?;
+        self.payload.clear();
+        // Growing the buffer while writing would leave partial copies of the password behind.
+        self.payload.reserve(crate::secret::measure(|mut writer| {
+            wifi_credentials.encode_to(&mut writer)
+        }));
+        wifi_credentials
+            .encode_to(&mut self.payload)
+            .expect("Writing to a String cannot fail.");
+        let qr_code = crate::options::generate_text(&self.payload, &self.options);
+        #[cfg(feature = "zeroize")]
+        zeroize::Zeroize::zeroize(&mut self.payload);
+        crate::render::to_image_into(&qr_code?, self.image_size, &mut self.pixels)?;
+        Ok(&self.pixels)
+    }
+
+    /// Encode credentials as a PNG image.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    /// use wifi_qr_code::{BatchEncoder, EncodeOptions};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// let mut batch_encoder = BatchEncoder::new(EncodeOptions::default(), 100);
+    /// let mut png = Vec::new();
+    /// batch_encoder.encode_as_png(&wifi_credentials, &mut png)
+    ///     .expect("Failed to encode example PNG.");
+    /// ```
+    #[cfg(feature = "png")]
+    pub fn encode_as_png(
+        &mut self,
+        wifi_credentials: &WifiCredentials,
+        writer: impl Write,
+    ) -> Result<(), Error> {
+        self.encode_as_image(wifi_credentials)?;
+        crate::render::write_png(&self.pixels, self.image_size, writer)
+    }
+
+    /// Encode credentials as a PNG image written to the file at `path`, replacing any existing file.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    /// use wifi_qr_code::{BatchEncoder, EncodeOptions};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// let mut batch_encoder = BatchEncoder::new(EncodeOptions::default(), 100);
+    /// let path = std::env::temp_dir().join("wifi_qr.png");
+    /// batch_encoder.encode_as_png_to_file(&wifi_credentials, &path)
+    ///     .expect("Failed to encode example PNG.");
+    /// ```
+    #[cfg(feature = "png")]
+    pub fn encode_as_png_to_file(
+        &mut self,
+        wifi_credentials: &WifiCredentials,
+        path: impl AsRef<Path>,
+    ) -> Result<(), Error> {
+        self.encode_as_image(wifi_credentials)?;
+        let file = File::create(path).map_err(|source| Error::Io {
+            stage: OutputStage::CreateFile,
+            source,
+        })?;
+        crate::render::write_png(&self.pixels, self.image_size, BufWriter::new(file))
+    }
+}
+
+/// Encode each of the credentials as a PNG image of `image_size` pixels, spreading the work across all cores. Every thread reuses its own buffers like a [`BatchEncoder`]. The results are in the same order as the credentials, so one invalid entry does not stop the rest of the batch.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{AuthenticationType, EncodeOptions, Visibility, WifiCredentials};
+///
+/// let wifi_credentials: Vec<_> = (100..110)
+///     .map(|room| WifiCredentials {
+///         ssid: format!("room {}", room),
+///         authentication_type: AuthenticationType::WPA(format!("password for room {}", room).into()),
+///         visibility: Visibility::Visible,
+///     })
+///     .collect();
+/// let pngs = wifi_qr_code::encode_batch_parallel(&wifi_credentials, &EncodeOptions::default(), 200);
+/// assert!(pngs.iter().all(Result::is_ok));
+/// ```
+#[cfg(feature = "parallel")]
+pub fn encode_batch_parallel(
+    wifi_credentials: &[WifiCredentials],
+    options: &EncodeOptions,
+    image_size: usize,
+) -> Vec<Result<Vec<u8>, Error>> {
+    use rayon::prelude::*;
+
+    wifi_credentials
+        .par_iter()
+        .map_init(
+            || BatchEncoder::new(*options, image_size),
+            |batch_encoder, wifi_credentials| {
+                let mut png = Vec::new();
+                batch_encoder.encode_as_png(wifi_credentials, &mut png)?;
+                Ok(png)
+            },
+        )
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, QrCodeEcc, Visibility};
+
+    #[test]
+    fn it_renders_the_same_images_as_the_single_encoders() {
+        let options = EncodeOptions {
+            error_correction: QrCodeEcc::Low,
+            ..EncodeOptions::default()
+        };
+        let mut batch_encoder = BatchEncoder::new(options, 120);
+        for ssid in ["a", "a much longer ssid than a"] {
+            let wifi_credentials = WifiCredentials {
+                ssid: String::from(ssid),
+                authentication_type: AuthenticationType::WPA("test password".into()),
+                visibility: Visibility::Visible,
+            };
+            assert_eq!(
+                wifi_credentials
+                    .qr(QrCodeEcc::Low)
+                    .size(120)
+                    .image()
+                    .unwrap(),
+                batch_encoder.encode_as_image(&wifi_credentials).unwrap()
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "parallel")]
+    fn it_reports_results_in_order() {
+        let wifi_credentials: Vec<_> = ["first", "", "third"]
+            .iter()
+            .map(|ssid| WifiCredentials {
+                ssid: String::from(*ssid),
+                authentication_type: AuthenticationType::NoPassword,
+                visibility: Visibility::Visible,
+            })
+            .collect();
+        let results = encode_batch_parallel(&wifi_credentials, &EncodeOptions::default(), 100);
+        let mut batch_encoder = BatchEncoder::new(EncodeOptions::default(), 100);
+        let mut third = Vec::new();
+        batch_encoder
+            .encode_as_png(&wifi_credentials[2], &mut third)
+            .unwrap();
+        assert_eq!(3, results.len());
+        assert!(results[0].is_ok());
+        assert!(matches!(results[1], Err(Error::Validation(_))));
+        assert_eq!(&third, results[2].as_ref().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn it_refuses_invalid_credentials() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::new(),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Visible,
+        };
+        let mut batch_encoder = BatchEncoder::new(EncodeOptions::default(), 120);
+        let mut png = Vec::new();
+        assert!(matches!(
+            batch_encoder.encode_as_png(&wifi_credentials, &mut png),
+            Err(Error::Validation(_))
+        ));
+        assert!(png.is_empty());
+    }
+}
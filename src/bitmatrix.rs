@@ -0,0 +1,82 @@
+use qrcode_generator::qrcodegen::QrCode;
+
+/// A QR code packed one bit per module, as returned by [`encode_as_bitmatrix`](crate::encode_as_bitmatrix). This takes an eighth of the memory of a `Vec<Vec<bool>>`, and matches the layout monochrome display drivers expect.
+///
+/// Each row starts on a byte boundary, `stride` bytes after the previous one. Within a byte, the most significant bit is the leftmost module, and a set bit is a dark module. Padding bits at the end of each row are clear.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct BitMatrix {
+    /// The width and height of the QR code in modules.
+    pub size: usize,
+    /// The number of bytes per row.
+    pub stride: usize,
+    /// The packed rows, `size * stride` bytes long.
+    pub data: Vec<u8>,
+}
+
+impl BitMatrix {
+    pub(crate) fn new(qr_code: &QrCode) -> Self {
+        let size = qr_code.size() as usize;
+        let stride = size.div_ceil(8);
+        let mut data = vec![0u8; size * stride];
+        for (y, row) in data.chunks_exact_mut(stride).enumerate() {
+            for x in 0..size {
+                if qr_code.get_module(x as i32, y as i32) {
+                    row[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+        }
+        BitMatrix { size, stride, data }
+    }
+
+    /// Whether the module at column `x` and row `y` is dark. Modules outside of the QR code are light.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::QrCodeEcc;
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// let bitmatrix = wifi_qr_code::encode_as_bitmatrix(&wifi_credentials, QrCodeEcc::Medium)
+    ///     .expect("Failed to encode example bitmatrix.");
+    /// // The top left corner is part of a finder pattern.
+    /// assert!(bitmatrix.get(0, 0));
+    /// assert!(!bitmatrix.get(bitmatrix.size, 0));
+    /// ```
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.size
+            && y < self.size
+            && self.data[y * self.stride + x / 8] & (0x80 >> (x % 8)) != 0
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AuthenticationType, QrCodeEcc, Visibility, WifiCredentials};
+
+    #[test]
+    fn it_packs_the_same_modules_as_the_matrix() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        let matrix = wifi_credentials.qr(QrCodeEcc::Medium).matrix().unwrap();
+        let bitmatrix = crate::encode_as_bitmatrix(&wifi_credentials, QrCodeEcc::Medium).unwrap();
+        assert_eq!(matrix.len(), bitmatrix.size);
+        assert_eq!(33, bitmatrix.size);
+        assert_eq!(5, bitmatrix.stride);
+        assert_eq!(bitmatrix.size * bitmatrix.stride, bitmatrix.data.len());
+        for (y, row) in matrix.iter().enumerate() {
+            for (x, dark) in row.iter().enumerate() {
+                assert_eq!(*dark, bitmatrix.get(x, y));
+            }
+            let last = bitmatrix.data[(y + 1) * bitmatrix.stride - 1];
+            assert_eq!(0, last & (0xff >> (matrix.len() % 8)));
+        }
+    }
+}
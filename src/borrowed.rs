@@ -0,0 +1,300 @@
+use crate::escaping::{self, write_value};
+#[cfg(feature = "std")]
+use crate::{AuthenticationType, EncodedPayload, QrPayload, Secret, WifiCredentials};
+use crate::{EscapingMode, ValidationError, Visibility, WepKeyFormat};
+
+use core::fmt::{self, Debug, Formatter};
+
+/// Credentials that borrow their SSID and password, e.g. from a request body, so they can be encoded without allocating owned `String`s. They encode and validate exactly like [`WifiCredentials`], and can be passed to any of the `encode_as_*` functions.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationTypeRef, Visibility, WifiCredentialsRef};
+///
+/// let (ssid, password) = ("example ssid", "example password");
+/// let wifi_credentials = WifiCredentialsRef {
+///     ssid,
+///     authentication_type: AuthenticationTypeRef::WPA(password),
+///     visibility: Visibility::Hidden,
+/// };
+/// assert_eq!("WIFI:S:example ssid;T:WPA;P:example password;H:true;;", wifi_credentials.encode());
+/// wifi_qr_code::encode_as_matrix(&wifi_credentials, QrCodeEcc::Medium)
+///     .expect("Failed to encode example matrix.");
+/// # }
+/// ```
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct WifiCredentialsRef<'a> {
+    /// The SSID of the network. It must be between 1 and 32 bytes long when encoded as UTF-8.
+    pub ssid: &'a str,
+    /// The authentication protocol and the password required to authenticate with it.
+    pub authentication_type: AuthenticationTypeRef<'a>,
+    /// Whether the network broadcasts its SSID.
+    pub visibility: Visibility,
+}
+
+/// A borrowed [`AuthenticationType`], for [`WifiCredentialsRef`].
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum AuthenticationTypeRef<'a> {
+    /// See [`AuthenticationType::WEP`].
+    WEP(&'a str),
+    /// See [`AuthenticationType::WPA`].
+    WPA(&'a str),
+    /// See [`AuthenticationType::WpaPsk`].
+    WpaPsk(&'a [u8; 32]),
+    /// See [`AuthenticationType::NoPassword`].
+    NoPassword,
+}
+
+impl<'a> WifiCredentialsRef<'a> {
+    /// Encode the credentials like [`WifiCredentials::encode`].
+    #[cfg(feature = "std")]
+    pub fn encode(&self) -> EncodedPayload {
+        self.encode_with(EscapingMode::Spec)
+    }
+
+    /// Encode the credentials like [`WifiCredentials::encode_with`].
+    #[cfg(feature = "std")]
+    pub fn encode_with(&self, escaping_mode: EscapingMode) -> EncodedPayload {
+        EncodedPayload::new(Secret::write_with(|mut writer| {
+            self.encode_to_with(&mut writer, escaping_mode)
+        }))
+    }
+
+    /// Write the encoded credentials to `writer` like [`WifiCredentials::encode_to_with`].
+    pub fn encode_to_with(
+        &self,
+        writer: &mut impl fmt::Write,
+        escaping_mode: EscapingMode,
+    ) -> fmt::Result {
+        writer.write_str("WIFI:S:")?;
+        write_value(writer, self.ssid, escaping_mode)?;
+        writer.write_char(';')?;
+        self.authentication_type.encode_to(writer, escaping_mode)?;
+        writer.write_str(self.visibility.encode())?;
+        writer.write_char(';')
+    }
+
+    /// Check the credentials like [`WifiCredentials::validate`].
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.ssid.is_empty() {
+            return Err(ValidationError::EmptySsid);
+        }
+        if self.ssid.len() > 32 {
+            return Err(ValidationError::SsidLength(self.ssid.len()));
+        }
+        self.authentication_type.validate()
+    }
+
+    /// Copy the borrowed values into owned credentials, e.g. to store them.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationTypeRef, Visibility, WifiCredentials, WifiCredentialsRef};
+    ///
+    /// let wifi_credentials = WifiCredentialsRef {
+    ///     ssid: "example ssid",
+    ///     authentication_type: AuthenticationTypeRef::NoPassword,
+    ///     visibility: Visibility::Visible,
+    /// };
+    /// assert_eq!(WifiCredentials::open("example ssid"), wifi_credentials.into_owned());
+    /// ```
+    #[cfg(feature = "std")]
+    pub fn into_owned(self) -> WifiCredentials {
+        WifiCredentials {
+            ssid: String::from(self.ssid),
+            authentication_type: self.authentication_type.into_owned(),
+            visibility: self.visibility,
+        }
+    }
+}
+
+impl<'a> AuthenticationTypeRef<'a> {
+    pub(crate) fn encode_to(
+        &self,
+        writer: &mut impl fmt::Write,
+        escaping_mode: EscapingMode,
+    ) -> fmt::Result {
+        match *self {
+            Self::WEP(key) => {
+                writer.write_str("T:WEP;P:")?;
+                match WepKeyFormat::detect(key) {
+                    Some(WepKeyFormat::Hex) => escaping::write_escaped(writer, key, escaping_mode)?,
+                    _ => write_value(writer, key, escaping_mode)?,
+                }
+            }
+            Self::WPA(password) => {
+                writer.write_str("T:WPA;P:")?;
+                write_value(writer, password, escaping_mode)?;
+            }
+            Self::WpaPsk(psk) => {
+                writer.write_str("T:WPA;P:")?;
+                for byte in psk {
+                    write!(writer, "{:02x}", byte)?;
+                }
+            }
+            Self::NoPassword => return writer.write_str("T:nopass;"),
+        }
+        writer.write_char(';')
+    }
+
+    /// Check the password like [`AuthenticationType::validate`].
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        match *self {
+            Self::WPA(passphrase) => {
+                if !passphrase.chars().all(|c| (' '..='~').contains(&c)) {
+                    return Err(ValidationError::WpaPassphraseCharacters);
+                }
+                if !(8..=63).contains(&passphrase.len()) {
+                    return Err(ValidationError::WpaPassphraseLength(passphrase.len()));
+                }
+                Ok(())
+            }
+            Self::WEP(key) => WepKeyFormat::detect(key)
+                .map(|_| ())
+                .ok_or(ValidationError::WepKeyFormat),
+            Self::WpaPsk(_) | Self::NoPassword => Ok(()),
+        }
+    }
+
+    /// Copy the borrowed password into an owned authentication type.
+    #[cfg(feature = "std")]
+    pub fn into_owned(self) -> AuthenticationType {
+        match self {
+            Self::WEP(key) => AuthenticationType::WEP(key.into()),
+            Self::WPA(password) => AuthenticationType::WPA(password.into()),
+            Self::WpaPsk(psk) => AuthenticationType::WpaPsk(Secret::new(*psk)),
+            Self::NoPassword => AuthenticationType::NoPassword,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl WifiCredentials {
+    /// Borrow the credentials as a [`WifiCredentialsRef`].
+    pub fn as_borrowed(&self) -> WifiCredentialsRef<'_> {
+        WifiCredentialsRef {
+            ssid: &self.ssid,
+            authentication_type: self.authentication_type.as_borrowed(),
+            visibility: self.visibility,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl AuthenticationType {
+    /// Borrow the authentication type as an [`AuthenticationTypeRef`].
+    pub fn as_borrowed(&self) -> AuthenticationTypeRef<'_> {
+        match self {
+            Self::WEP(key) => AuthenticationTypeRef::WEP(key),
+            Self::WPA(password) => AuthenticationTypeRef::WPA(password),
+            Self::WpaPsk(psk) => AuthenticationTypeRef::WpaPsk(psk),
+            Self::NoPassword => AuthenticationTypeRef::NoPassword,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl QrPayload for WifiCredentialsRef<'_> {
+    fn encode(&self) -> String {
+        WifiCredentialsRef::encode(self).into_string()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        WifiCredentialsRef::validate(self)
+    }
+}
+
+/// Redacts the password, like the `Debug` implementation of [`WifiCredentials`].
+impl Debug for WifiCredentialsRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WifiCredentialsRef")
+            .field("ssid", &self.ssid)
+            .field("authentication_type", &self.authentication_type)
+            .field("visibility", &self.visibility)
+            .finish()
+    }
+}
+
+/// Redacts the password, like the `Debug` implementation of [`AuthenticationType`].
+impl Debug for AuthenticationTypeRef<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::WEP(_) => f.write_str("WEP(<redacted>)"),
+            Self::WPA(_) => f.write_str("WPA(<redacted>)"),
+            Self::WpaPsk(_) => f.write_str("WpaPsk(<redacted>)"),
+            Self::NoPassword => f.write_str("NoPassword"),
+        }
+    }
+}
+
+/// Redacts the password, like the `Debug` implementation.
+#[cfg(feature = "defmt")]
+impl defmt::Format for WifiCredentialsRef<'_> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "WifiCredentialsRef {{ ssid: {=str}, authentication_type: {}, visibility: {} }}",
+            self.ssid,
+            self.authentication_type,
+            self.visibility
+        )
+    }
+}
+
+/// Redacts the password, like the `Debug` implementation.
+#[cfg(feature = "defmt")]
+impl defmt::Format for AuthenticationTypeRef<'_> {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::WEP(_) => defmt::write!(f, "WEP(<redacted>)"),
+            Self::WPA(_) => defmt::write!(f, "WPA(<redacted>)"),
+            Self::WpaPsk(_) => defmt::write!(f, "WpaPsk(<redacted>)"),
+            Self::NoPassword => defmt::write!(f, "NoPassword"),
+        }
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_encodes_like_owned_credentials() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from(r#"special_characters ";,:\"#),
+            authentication_type: AuthenticationType::WpaPsk([0x0f; 32].into()),
+            visibility: Visibility::Hidden,
+        };
+        let borrowed = wifi_credentials.as_borrowed();
+        assert_eq!(wifi_credentials.encode(), borrowed.encode());
+        assert_eq!(
+            wifi_credentials.encode_with(EscapingMode::Minimal),
+            borrowed.encode_with(EscapingMode::Minimal)
+        );
+        assert_eq!(wifi_credentials, borrowed.into_owned());
+    }
+
+    #[test]
+    fn it_validates_and_redacts_borrowed_credentials() {
+        let wifi_credentials = WifiCredentialsRef {
+            ssid: "test ssid",
+            authentication_type: AuthenticationTypeRef::WEP("short"),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(Ok(()), wifi_credentials.validate());
+        assert_eq!(
+            r#"WifiCredentialsRef { ssid: "test ssid", authentication_type: WEP(<redacted>), visibility: Visible }"#,
+            format!("{:?}", wifi_credentials)
+        );
+        let wifi_credentials = WifiCredentialsRef {
+            ssid: "",
+            ..wifi_credentials
+        };
+        assert_eq!(Err(ValidationError::EmptySsid), wifi_credentials.validate());
+    }
+}
@@ -0,0 +1,188 @@
+#[cfg(feature = "svg")]
+use crate::SvgAttributes;
+use crate::{EncodeOptions, Error, QrCodeEcc, QrPayload, WifiCredentials, WifiQrCode};
+
+#[cfg(any(feature = "png", feature = "svg"))]
+use std::io::Write;
+
+/// A chain of settings that ends in one output format, started by [`WifiCredentials::qr`] or [`QrCodeBuilder::new`]. This is the entry point for rendering credentials: new settings are added as methods here rather than as more `encode_as_*` variants.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::WifiCredentials;
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// let mut png = Vec::new();
+/// wifi_credentials
+///     .qr(QrCodeEcc::Medium)
+///     .size(512)
+///     .png(&mut png)
+///     .expect("Failed to write example PNG.");
+/// ```
+#[derive(Debug, Clone, Copy)]
+pub struct QrCodeBuilder<'a, P> {
+    payload: &'a P,
+    options: EncodeOptions,
+    image_size: usize,
+}
+
+impl<'a, P: QrPayload> QrCodeBuilder<'a, P> {
+    /// Start rendering any payload, such as [`dpp::DppCredentials`](crate::dpp::DppCredentials), with the given minimum error correction level. Images default to 512 pixels square.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::dpp::DppCredentials;
+    /// use wifi_qr_code::{QrCodeBuilder, QrCodeEcc};
+    ///
+    /// let dpp_credentials = DppCredentials {
+    ///     public_key: String::from("MDkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDIgADM2206avxHJaHXgLMkq/24e0rsrfMP9K1Tm8gx+ovP0I="),
+    ///     channels: Vec::new(),
+    ///     mac_address: None,
+    ///     information: None,
+    ///     version: None,
+    /// };
+    /// let image = QrCodeBuilder::new(&dpp_credentials, QrCodeEcc::Medium)
+    ///     .image()
+    ///     .expect("Failed to encode example image.");
+    /// assert_eq!(512 * 512, image.len());
+    /// ```
+    pub fn new(payload: &'a P, qr_code_error_checking: QrCodeEcc) -> Self {
+        QrCodeBuilder {
+            payload,
+            options: EncodeOptions {
+                error_correction: qr_code_error_checking,
+                ..EncodeOptions::default()
+            },
+            image_size: 512,
+        }
+    }
+
+    /// Set the width and height of images in pixels.
+    pub fn size(mut self, image_size: usize) -> Self {
+        self.image_size = image_size;
+        self
+    }
+
+    /// Control how the QR code is generated, as with the `encode_as_*_with_options` functions. This replaces the error correction level the builder was started with.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{EncodeOptions, QrCodeEcc, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials::open("example ssid");
+    /// let qr_code = wifi_credentials
+    ///     .qr(QrCodeEcc::Medium)
+    ///     .options(EncodeOptions {
+    ///         min_version: 5,
+    ///         ..EncodeOptions::default()
+    ///     })
+    ///     .build()
+    ///     .expect("Failed to encode example QR code.");
+    /// assert_eq!(5, qr_code.version());
+    /// ```
+    pub fn options(mut self, options: EncodeOptions) -> Self {
+        self.options = options;
+        self
+    }
+
+    /// Generate the QR code, e.g. to write it out in several formats.
+    pub fn build(&self) -> Result<WifiQrCode, Error> {
+        WifiQrCode::with_options(self.payload, &self.options)
+    }
+
+    /// The QR code as a matrix of boolean values, like [`WifiQrCode::to_matrix`].
+    pub fn matrix(&self) -> Result<Vec<Vec<bool>>, Error> {
+        Ok(self.build()?.to_matrix())
+    }
+
+    /// The QR code as raw image data, like [`WifiQrCode::to_image`].
+    pub fn image(&self) -> Result<Vec<u8>, Error> {
+        self.build()?.to_image(self.image_size)
+    }
+
+    /// The QR code as an [`image::DynamicImage`].
+    #[cfg(feature = "image")]
+    pub fn dynamic_image(&self) -> Result<image::DynamicImage, Error> {
+        crate::render::to_dynamic_image(self.image()?, self.image_size)
+    }
+
+    /// Write the QR code as a PNG image, like [`WifiQrCode::to_png`].
+    #[cfg(feature = "png")]
+    pub fn png(&self, writer: impl Write) -> Result<(), Error> {
+        self.build()?.to_png(self.image_size, writer)
+    }
+
+    /// Write the QR code as an SVG image, like [`WifiQrCode::to_svg`].
+    #[cfg(feature = "svg")]
+    pub fn svg(&self, writer: impl Write) -> Result<(), Error> {
+        self.build()?.to_svg(self.image_size, writer)
+    }
+
+    /// The QR code as a bare `<svg>` element for HTML pages, like [`WifiQrCode::to_svg_element`].
+    #[cfg(feature = "svg")]
+    pub fn svg_element(&self, attributes: &SvgAttributes) -> Result<String, Error> {
+        self.build()?.to_svg_element(self.image_size, attributes)
+    }
+}
+
+impl WifiCredentials {
+    /// Start rendering the credentials as a QR code with the given minimum error correction level.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{QrCodeEcc, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let matrix = wifi_credentials
+    ///     .qr(QrCodeEcc::Medium)
+    ///     .matrix()
+    ///     .expect("Failed to encode example matrix.");
+    /// assert_eq!(33, matrix.len());
+    /// ```
+    pub fn qr(&self, qr_code_error_checking: QrCodeEcc) -> QrCodeBuilder<'_, Self> {
+        QrCodeBuilder::new(self, qr_code_error_checking)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    #[allow(deprecated)]
+    fn it_matches_the_free_functions() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        let builder = wifi_credentials.qr(QrCodeEcc::Quartile).size(120);
+        assert_eq!(
+            crate::encode_as_matrix(&wifi_credentials, QrCodeEcc::Quartile).unwrap(),
+            builder.matrix().unwrap()
+        );
+        assert_eq!(
+            crate::encode_as_image(&wifi_credentials, QrCodeEcc::Quartile, 120).unwrap(),
+            builder.image().unwrap()
+        );
+        #[cfg(feature = "svg")]
+        {
+            let mut expected = Vec::new();
+            crate::encode_as_svg(&wifi_credentials, QrCodeEcc::Quartile, 120, &mut expected)
+                .unwrap();
+            let mut svg = Vec::new();
+            builder.svg(&mut svg).unwrap();
+            assert_eq!(expected, svg);
+        }
+    }
+
+    #[test]
+    fn it_validates_before_rendering() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "short");
+        assert!(matches!(
+            wifi_credentials.qr(QrCodeEcc::Medium).matrix(),
+            Err(Error::Validation(_))
+        ));
+    }
+}
@@ -0,0 +1,67 @@
+use crate::decode::{decode_greyscale, DecodeError};
+use crate::WifiCredentials;
+
+use nokhwa::pixel_format::LumaFormat;
+use nokhwa::utils::{CameraIndex, RequestedFormat, RequestedFormatType};
+use nokhwa::{Camera, NokhwaError};
+
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+/// Open the default camera and read frames until one contains a wifi QR code, then return its credentials, e.g. for tools that join the network on a sign held up to a laptop. Frames without a QR code, and QR codes that are not wifi QR codes, are skipped until `timeout` has passed, after which the error of the last frame is returned, [`DecodeError::NotFound`] or [`DecodeError::Parse`]. The timeout is checked between frames, so a camera that hangs while delivering a frame can still block for longer. On macOS, camera access is requested first if the application does not have it yet.
+///
+/// # Examples
+///
+/// ```no_run
+/// use std::time::Duration;
+///
+/// let wifi_credentials = wifi_qr_code::decode_from_camera(Duration::from_secs(30))
+///     .expect("Failed to scan a wifi QR code.");
+/// println!("Joining {}", wifi_credentials);
+/// ```
+pub fn decode_from_camera(timeout: Duration) -> Result<WifiCredentials, DecodeError> {
+    let deadline = Instant::now() + timeout;
+    request_permission()?;
+    let format = RequestedFormat::new::<LumaFormat>(RequestedFormatType::AbsoluteHighestFrameRate);
+    let mut camera = Camera::new(CameraIndex::Index(0), format).map_err(DecodeError::Camera)?;
+    camera.open_stream().map_err(DecodeError::Camera)?;
+    let result = loop {
+        let frame = match camera
+            .frame()
+            .and_then(|frame| frame.decode_image::<LumaFormat>())
+        {
+            Ok(frame) => frame,
+            Err(error) => break Err(DecodeError::Camera(error)),
+        };
+        let (width, height) = (frame.width() as usize, frame.height() as usize);
+        match decode_greyscale(width, height, &frame.into_raw()) {
+            Err(DecodeError::NotFound) | Err(DecodeError::Parse(_))
+                if Instant::now() < deadline =>
+            {
+                continue
+            }
+            result => break result,
+        }
+    };
+    // The result is already known, so a camera that fails to stop does not change it.
+    let _ = camera.stop_stream();
+    result
+}
+
+/// Wait for the user to grant camera access. This only prompts on macOS; other platforms are always ready.
+fn request_permission() -> Result<(), DecodeError> {
+    if nokhwa::nokhwa_check() {
+        return Ok(());
+    }
+    let (sender, receiver) = mpsc::channel();
+    nokhwa::nokhwa_initialize(move |granted| {
+        let _ = sender.send(granted);
+    });
+    if receiver.recv().unwrap_or(false) {
+        Ok(())
+    } else {
+        Err(DecodeError::Camera(NokhwaError::GeneralError(
+            String::from("camera access was denied"),
+        )))
+    }
+}
@@ -0,0 +1,266 @@
+use crate::export::escape_xml;
+use crate::print::Locale;
+use crate::{render, Error, OutputStage, QrCodeEcc, WifiCredentials};
+
+use std::io::{self, Write};
+
+/// The width of a card, in SVG user units.
+const WIDTH: usize = 400;
+/// The height of a card, in SVG user units.
+const HEIGHT: usize = 520;
+/// The size of the square that the QR code is centered in, including its quiet zone.
+const QR_CODE_SIZE: usize = 320;
+/// The distance from the top of the card to the square the QR code is drawn in.
+const QR_CODE_TOP: usize = 64;
+
+impl WifiCredentials {
+    /// Write a printable card for the network as an SVG image in English: a title, the QR code, instructions to scan it, and the SSID and password written out for devices that cannot scan it. The card is 400 by 520 units, which prints at about 10 by 13 centimeters.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{QrCodeEcc, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let mut svg = Vec::new();
+    /// wifi_credentials
+    ///     .write_svg_card(QrCodeEcc::Medium, &mut svg)
+    ///     .expect("Failed to write example card.");
+    /// assert!(String::from_utf8(svg).unwrap().contains("example password"));
+    /// ```
+    pub fn write_svg_card(
+        &self,
+        qr_code_error_checking: QrCodeEcc,
+        writer: impl Write,
+    ) -> Result<(), Error> {
+        self.write_localized_svg_card(qr_code_error_checking, Locale::English, writer)
+    }
+
+    /// Write a printable card like [`WifiCredentials::write_svg_card`], with its text in the locale.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::Locale;
+    /// use wifi_qr_code::{QrCodeEcc, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let mut svg = Vec::new();
+    /// wifi_credentials
+    ///     .write_localized_svg_card(QrCodeEcc::Medium, Locale::German, &mut svg)
+    ///     .expect("Failed to write example card.");
+    /// assert!(String::from_utf8(svg).unwrap().contains("Zum Verbinden scannen"));
+    /// ```
+    pub fn write_localized_svg_card(
+        &self,
+        qr_code_error_checking: QrCodeEcc,
+        locale: Locale,
+        writer: impl Write,
+    ) -> Result<(), Error> {
+        self.write_card(qr_code_error_checking, locale, "", Line::text, writer)
+    }
+
+    /// Write a printable card like [`WifiCredentials::write_localized_svg_card`], with all of its text set in the font rather than in the generic `sans-serif` and `monospace` fonts of the viewer. Depending on its [`FontMode`](crate::print::font::FontMode), the font is embedded into the card or the text is converted into paths.
+    ///
+    /// Requires the `fonts` feature.
+    #[cfg(feature = "fonts")]
+    pub fn write_svg_card_with_font(
+        &self,
+        qr_code_error_checking: QrCodeEcc,
+        locale: Locale,
+        font: &crate::print::font::Font,
+        writer: impl Write,
+    ) -> Result<(), Error> {
+        use crate::print::font::{FontMode, EMBEDDED_FAMILY};
+
+        let text = |line: &Line| match font.mode() {
+            FontMode::Embed => Line {
+                family: EMBEDDED_FAMILY,
+                text: line.text.clone(),
+                ..*line
+            }
+            .text(),
+            FontMode::Outline => format!(
+                "<path d=\"{}\"/>",
+                font.outline(
+                    &line.text,
+                    line.size as f64,
+                    (WIDTH / 2) as f64,
+                    line.y as f64
+                )
+            ),
+        };
+        self.write_card(qr_code_error_checking, locale, &font.style(), text, writer)
+    }
+
+    /// Write a card with the `<style>` element, setting each line of text with the function.
+    fn write_card(
+        &self,
+        qr_code_error_checking: QrCodeEcc,
+        locale: Locale,
+        style: &str,
+        text: impl Fn(&Line) -> String,
+        writer: impl Write,
+    ) -> Result<(), Error> {
+        let qr_code =
+            crate::generate_symbol(&crate::encode_payload(self)?, qr_code_error_checking)?;
+        let modules = qr_code.size() as usize;
+        let point_size = QR_CODE_SIZE / (modules + 2);
+        let margin = (QR_CODE_SIZE - point_size * modules) / 2;
+        let [network, password] = crate::print::captions(self, locale);
+        let title = Line {
+            y: 48,
+            size: 32,
+            family: "sans-serif",
+            style: " font-weight=\"bold\"",
+            text: locale.message("title", &[]),
+        };
+        let captions = [
+            Line {
+                y: 408,
+                size: 16,
+                family: "sans-serif",
+                style: " font-style=\"italic\"",
+                text: locale.message("instructions", &[]),
+            },
+            Line {
+                y: 444,
+                size: 20,
+                family: "sans-serif",
+                style: "",
+                text: network,
+            },
+            Line {
+                y: 480,
+                size: 20,
+                family: "monospace",
+                style: "",
+                text: password,
+            },
+        ];
+        let mut writer = io::BufWriter::new(writer);
+        let mut write = || -> io::Result<()> {
+            write!(
+                writer,
+                "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg width=\"{width}\" height=\"{height}\" viewBox=\"0 0 {width} {height}\" version=\"1.1\" xmlns=\"http://www.w3.org/2000/svg\">\n{style}\t<rect x=\"1\" y=\"1\" width=\"{inner_width}\" height=\"{inner_height}\" rx=\"16\" fill=\"#FFF\" stroke=\"#000\" stroke-width=\"2\"/>\n\t{title}\n\t<path shape-rendering=\"crispEdges\" d=\"",
+                width = WIDTH,
+                height = HEIGHT,
+                style = style,
+                inner_width = WIDTH - 2,
+                inner_height = HEIGHT - 2,
+                title = text(&title)
+            )?;
+            render::write_svg_path(
+                &mut writer,
+                &qr_code,
+                (WIDTH - QR_CODE_SIZE) / 2 + margin,
+                QR_CODE_TOP + margin,
+                point_size,
+            )?;
+            writer.write_all(b"\"/>\n")?;
+            for caption in &captions {
+                writeln!(writer, "\t{}", text(caption))?;
+            }
+            writer.write_all(b"</svg>\n")?;
+            writer.flush()
+        };
+        write().map_err(|source| Error::Io {
+            stage: OutputStage::Svg,
+            source,
+        })
+    }
+}
+
+/// A line of text on a card, centered horizontally.
+struct Line {
+    /// The baseline of the text.
+    y: usize,
+    /// The font size.
+    size: usize,
+    /// The font family.
+    family: &'static str,
+    /// Further attributes styling the text, each with a leading space.
+    style: &'static str,
+    text: String,
+}
+
+impl Line {
+    /// The line as a `<text>` element.
+    fn text(&self) -> String {
+        format!(
+            "<text x=\"{}\" y=\"{}\" font-family=\"{}\" font-size=\"{}\"{} text-anchor=\"middle\">{}</text>",
+            WIDTH / 2,
+            self.y,
+            self.family,
+            self.size,
+            self.style,
+            escape_xml(&self.text)
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility};
+
+    #[test]
+    fn it_writes_cards_with_escaped_captions() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("Café <guest>"),
+            authentication_type: AuthenticationType::WPA("fish & chips".into()),
+            visibility: Visibility::Hidden,
+        };
+        let mut svg = Vec::new();
+        wifi_credentials
+            .write_svg_card(QrCodeEcc::Medium, &mut svg)
+            .expect("Card should render.");
+        let svg = String::from_utf8(svg).expect("Card should be UTF-8.");
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains(">Network: Café &lt;guest&gt; (hidden)</text>"));
+        assert!(svg.contains(">Password: fish &amp; chips</text>"));
+        assert!(svg.ends_with("</svg>\n"));
+
+        let mut svg = Vec::new();
+        WifiCredentials::open("open ssid")
+            .write_svg_card(QrCodeEcc::Medium, &mut svg)
+            .expect("Card should render.");
+        assert!(String::from_utf8(svg)
+            .unwrap()
+            .contains(">No password</text>"));
+
+        let mut svg = Vec::new();
+        wifi_credentials
+            .write_localized_svg_card(QrCodeEcc::Medium, Locale::Spanish, &mut svg)
+            .expect("Card should render.");
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.contains(">Escanea para conectarte</text>"));
+        assert!(svg.contains(">Red: Café &lt;guest&gt; (oculta)</text>"));
+        assert!(svg.contains(">Contraseña: fish &amp; chips</text>"));
+    }
+
+    #[cfg(feature = "fonts")]
+    #[test]
+    fn it_sets_cards_in_fonts() {
+        use crate::print::font::{tests::square_font, Font, FontMode};
+
+        let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+        let font = Font::new(square_font(), FontMode::Embed).unwrap();
+        let mut svg = Vec::new();
+        wifi_credentials
+            .write_svg_card_with_font(QrCodeEcc::Medium, Locale::English, &font, &mut svg)
+            .expect("Card should render.");
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(svg.contains("<style>@font-face{font-family:\"wifi-qr-code-embedded\""));
+        assert!(svg.contains("<text x=\"200\" y=\"480\" font-family=\"wifi-qr-code-embedded\" font-size=\"20\" text-anchor=\"middle\">Password: example password</text>"));
+
+        let font = Font::new(square_font(), FontMode::Outline).unwrap();
+        let mut svg = Vec::new();
+        wifi_credentials
+            .write_svg_card_with_font(QrCodeEcc::Medium, Locale::English, &font, &mut svg)
+            .expect("Card should render.");
+        let svg = String::from_utf8(svg).unwrap();
+        assert!(!svg.contains("<text") && !svg.contains("<style>"));
+        assert_eq!(5, svg.matches("<path ").count());
+    }
+}
@@ -0,0 +1,228 @@
+/// A `WIFI:` payload that was escaped and validated at compile time by the [`wifi_qr!`](crate::wifi_qr) macro, for firmware images with fixed provisioning credentials. `N` is the size of the buffer holding the payload, which is large enough for every value to be escaped.
+#[derive(Debug, Clone, Copy)]
+pub struct ConstPayload<const N: usize> {
+    bytes: [u8; N],
+    len: usize,
+}
+
+impl<const N: usize> ConstPayload<N> {
+    /// Escape and validate the values the same way [`WifiCredentials::encode`](crate::WifiCredentials::encode) and [`WifiCredentials::validate`](crate::WifiCredentials::validate) do, panicking (and so failing the build, when evaluated in a constant) if the credentials are invalid. Use the [`wifi_qr!`](crate::wifi_qr) macro instead of calling this directly.
+    #[doc(hidden)]
+    pub const fn new(
+        ssid: &str,
+        authentication_type: &str,
+        password: Option<&str>,
+        hidden: bool,
+    ) -> Self {
+        validate_ssid(ssid);
+        let mut payload = ConstPayload {
+            bytes: [0; N],
+            len: 0,
+        };
+        payload.push_raw("WIFI:S:");
+        payload.push_escaped(ssid);
+        payload.push_raw(";T:");
+        payload.push_raw(authentication_type);
+        payload.push_raw(";");
+        if let Some(password) = password {
+            match authentication_type.as_bytes() {
+                b"WPA" => validate_wpa_passphrase(password),
+                b"WEP" => validate_wep_key(password),
+                _ => panic!("only WPA and WEP networks have passwords"),
+            }
+            payload.push_raw("P:");
+            payload.push_escaped(password);
+            payload.push_raw(";");
+        }
+        payload.push_raw(if hidden { "H:true;;" } else { "H:false;;" });
+        payload
+    }
+
+    /// The escaped payload.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// static PAYLOAD: wifi_qr_code::ConstPayload<64> =
+    ///     wifi_qr_code::ConstPayload::new("example ssid", "nopass", None, false);
+    /// assert_eq!("WIFI:S:example ssid;T:nopass;H:false;;", PAYLOAD.as_str());
+    /// ```
+    pub const fn as_str(&self) -> &str {
+        match core::str::from_utf8(self.bytes.split_at(self.len).0) {
+            Ok(payload) => payload,
+            Err(_) => panic!("escaping only inserts ASCII characters between whole characters"),
+        }
+    }
+
+    const fn push_raw(&mut self, input: &str) {
+        let input = input.as_bytes();
+        let mut index = 0;
+        while index < input.len() {
+            self.push(input[index]);
+            index += 1;
+        }
+    }
+
+    /// Backslash escape the characters that [`EscapingMode::Spec`](crate::EscapingMode::Spec) escapes.
+    const fn push_escaped(&mut self, input: &str) {
+        let input = input.as_bytes();
+        let mut index = 0;
+        while index < input.len() {
+            let byte = input[index];
+            if matches!(byte, b'\\' | b'"' | b';' | b',' | b':') {
+                self.push(b'\\');
+            }
+            self.push(byte);
+            index += 1;
+        }
+    }
+
+    const fn push(&mut self, byte: u8) {
+        if self.len == N {
+            panic!("the payload buffer is too small for the escaped credentials");
+        }
+        self.bytes[self.len] = byte;
+        self.len += 1;
+    }
+}
+
+/// The size of buffer that always fits the escaped payload, since escaping at most doubles each value.
+#[doc(hidden)]
+pub const fn payload_capacity(ssid: &str, password: Option<&str>) -> usize {
+    let password_len = match password {
+        Some(password) => "P:;".len() + 2 * password.len(),
+        None => 0,
+    };
+    "WIFI:S:;T:nopass;H:false;;".len() + 2 * ssid.len() + password_len
+}
+
+const fn validate_ssid(ssid: &str) {
+    if ssid.is_empty() {
+        panic!("SSIDs must not be empty");
+    }
+    if ssid.len() > 32 {
+        panic!("SSIDs may be at most 32 bytes long");
+    }
+}
+
+const fn validate_wpa_passphrase(passphrase: &str) {
+    let bytes = passphrase.as_bytes();
+    let mut index = 0;
+    while index < bytes.len() {
+        if !matches!(bytes[index], b' '..=b'~') {
+            panic!("WPA passphrases may only contain printable ASCII characters");
+        }
+        index += 1;
+    }
+    if bytes.len() < 8 || bytes.len() > 63 {
+        panic!("WPA passphrases must be 8 to 63 characters long");
+    }
+}
+
+const fn validate_wep_key(key: &str) {
+    let bytes = key.as_bytes();
+    let mut ascii = true;
+    let mut hex = true;
+    let mut index = 0;
+    while index < bytes.len() {
+        ascii &= matches!(bytes[index], b' '..=b'~');
+        hex &= bytes[index].is_ascii_hexdigit();
+        index += 1;
+    }
+    let valid = match bytes.len() {
+        5 | 13 => ascii,
+        10 | 26 => hex,
+        _ => false,
+    };
+    if !valid {
+        panic!("WEP keys must be 5 or 13 ASCII characters, or 10 or 26 hex digits");
+    }
+}
+
+/// Build an escaped `WIFI:` payload at compile time, for firmware images with fixed provisioning credentials. The credentials are validated like [`WifiCredentials::validate`](crate::WifiCredentials::validate), and invalid credentials fail the build. The macro evaluates to a `&'static str`.
+///
+/// The SSID comes first, followed by `wpa = "password"` (or `wpa2`) or `wep = "key"` for secured networks, and `hidden` for networks that do not broadcast their SSID.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::wifi_qr;
+///
+/// const PAYLOAD: &str = wifi_qr!("example ssid", wpa2 = "example password", hidden);
+/// assert_eq!("WIFI:S:example ssid;T:WPA;P:example password;H:true;;", PAYLOAD);
+/// assert_eq!("WIFI:S:guests;T:nopass;H:false;;", wifi_qr!("guests"));
+/// ```
+///
+/// Invalid credentials are rejected at compile time:
+///
+/// ```compile_fail
+/// const PAYLOAD: &str = wifi_qr_code::wifi_qr!("example ssid", wpa2 = "short");
+/// ```
+#[macro_export]
+macro_rules! wifi_qr {
+    (@payload $ssid:expr, $authentication_type:expr, $password:expr, $($visibility:ident)?) => {{
+        static PAYLOAD: $crate::ConstPayload<{ $crate::payload_capacity($ssid, $password) }> =
+            $crate::ConstPayload::new(
+                $ssid,
+                $authentication_type,
+                $password,
+                $crate::wifi_qr!(@hidden $($visibility)?),
+            );
+        PAYLOAD.as_str()
+    }};
+    (@hidden hidden) => {
+        true
+    };
+    (@hidden visible) => {
+        false
+    };
+    (@hidden) => {
+        false
+    };
+    ($ssid:expr, wpa = $password:expr $(, $visibility:ident)? $(,)?) => {
+        $crate::wifi_qr!(@payload $ssid, "WPA", Some($password), $($visibility)?)
+    };
+    ($ssid:expr, wpa2 = $password:expr $(, $visibility:ident)? $(,)?) => {
+        $crate::wifi_qr!(@payload $ssid, "WPA", Some($password), $($visibility)?)
+    };
+    ($ssid:expr, wep = $password:expr $(, $visibility:ident)? $(,)?) => {
+        $crate::wifi_qr!(@payload $ssid, "WEP", Some($password), $($visibility)?)
+    };
+    ($ssid:expr $(, $visibility:ident)? $(,)?) => {
+        $crate::wifi_qr!(@payload $ssid, "nopass", None, $($visibility)?)
+    };
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+    #[test]
+    fn it_matches_the_runtime_encoding() {
+        const SSID: &str = "special \"\\;,:\u{7}\u{85}é";
+        const PASSWORD: &str = "pass;word:\"";
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from(SSID),
+            authentication_type: AuthenticationType::WEP("0123456789".into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            wifi_credentials.encode(),
+            wifi_qr!(SSID, wep = "0123456789", visible)
+        );
+
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from(SSID),
+            authentication_type: AuthenticationType::WPA(PASSWORD.into()),
+            visibility: Visibility::Hidden,
+        };
+        let payload = crate::ConstPayload::<64>::new(SSID, "WPA", Some(PASSWORD), true);
+        assert_eq!(wifi_credentials.encode(), payload.as_str());
+    }
+
+    #[test]
+    #[should_panic(expected = "WPA passphrases must be 8 to 63 characters long")]
+    fn it_rejects_invalid_credentials() {
+        crate::ConstPayload::<64>::new("test ssid", "WPA", Some("short"), false);
+    }
+}
@@ -0,0 +1,215 @@
+use crate::{
+    AuthenticationType, CredentialField, EncodedPayload, Secret, ValidationError, WifiCredentials,
+};
+
+use std::fmt;
+
+/// How control characters (such as the trailing newline left behind when a password is read from a terminal) in the SSID and password are handled. Scanners do not agree on how to treat them, so a stray newline usually produces a code that fails to join.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ControlCharacterPolicy {
+    /// Fail with [`ValidationError::ControlCharacter`] if the SSID or password contain control characters.
+    Reject,
+    /// Remove control characters from the SSID and password.
+    Strip,
+    /// Keep control characters in the credentials. [`WifiCredentials::encode_with_control_character_policy`] backslash escapes them in the QR code payload, so scanners that follow the escaping rules see them as part of the value, while the other encoding functions write them as they are.
+    Escape,
+}
+
+impl WifiCredentials {
+    /// Handle control characters in the SSID and password according to the policy.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, ControlCharacterPolicy, Visibility, WifiCredentials};
+    ///
+    /// let mut wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password\n".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// wifi_credentials
+    ///     .apply_control_character_policy(ControlCharacterPolicy::Strip)
+    ///     .expect("Stripping control characters never fails.");
+    /// assert_eq!("WIFI:S:example ssid;T:WPA;P:example password;H:true;;", wifi_credentials.encode());
+    /// ```
+    pub fn apply_control_character_policy(
+        &mut self,
+        policy: ControlCharacterPolicy,
+    ) -> Result<(), ValidationError> {
+        apply(&mut self.ssid, policy, CredentialField::Ssid)?;
+        match &mut self.authentication_type {
+            AuthenticationType::WEP(password) | AuthenticationType::WPA(password) => {
+                apply(password, policy, CredentialField::Password)
+            }
+            AuthenticationType::WpaPsk(_) | AuthenticationType::NoPassword => Ok(()),
+        }
+    }
+
+    /// Encode the credentials like [`WifiCredentials::encode`], handling control characters in the SSID and password according to the policy without changing the credentials.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, ControlCharacterPolicy, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password\n".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// let payload = wifi_credentials
+    ///     .encode_with_control_character_policy(ControlCharacterPolicy::Escape)
+    ///     .expect("Escaping control characters never fails.");
+    /// assert_eq!("WIFI:S:example ssid;T:WPA;P:example password\\\n;H:true;;", payload);
+    /// ```
+    pub fn encode_with_control_character_policy(
+        &self,
+        policy: ControlCharacterPolicy,
+    ) -> Result<EncodedPayload, ValidationError> {
+        if policy == ControlCharacterPolicy::Reject {
+            reject(&self.ssid, CredentialField::Ssid)?;
+            if let AuthenticationType::WEP(password) | AuthenticationType::WPA(password) =
+                &self.authentication_type
+            {
+                reject(password, CredentialField::Password)?;
+            }
+        }
+        Ok(EncodedPayload::new(Secret::write_with(|writer| {
+            self.encode_to(&mut ControlCharacterWriter {
+                inner: writer,
+                policy,
+            })
+        })))
+    }
+}
+
+fn apply(
+    value: &mut String,
+    policy: ControlCharacterPolicy,
+    field: CredentialField,
+) -> Result<(), ValidationError> {
+    match policy {
+        ControlCharacterPolicy::Reject => reject(value, field),
+        ControlCharacterPolicy::Strip => {
+            value.retain(|c| !c.is_control());
+            Ok(())
+        }
+        ControlCharacterPolicy::Escape => Ok(()),
+    }
+}
+
+fn reject(value: &str, field: CredentialField) -> Result<(), ValidationError> {
+    if value.chars().any(char::is_control) {
+        return Err(ValidationError::ControlCharacter(field));
+    }
+    Ok(())
+}
+
+/// Strips or escapes the control characters written through it. The rest of the payload is fixed text, so they can only come from the SSID and password.
+struct ControlCharacterWriter<'a> {
+    inner: &'a mut dyn fmt::Write,
+    policy: ControlCharacterPolicy,
+}
+
+impl fmt::Write for ControlCharacterWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let mut unescaped = 0;
+        for (index, c) in s.char_indices().filter(|(_, c)| c.is_control()) {
+            self.inner.write_str(&s[unescaped..index])?;
+            match self.policy {
+                ControlCharacterPolicy::Strip => unescaped = index + c.len_utf8(),
+                ControlCharacterPolicy::Reject | ControlCharacterPolicy::Escape => {
+                    self.inner.write_char('\\')?;
+                    unescaped = index;
+                }
+            }
+        }
+        self.inner.write_str(&s[unescaped..])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Visibility;
+
+    fn credentials() -> WifiCredentials {
+        WifiCredentials {
+            ssid: String::from("test\tssid"),
+            authentication_type: AuthenticationType::WEP("test password\r\n".into()),
+            visibility: Visibility::Visible,
+        }
+    }
+
+    #[test]
+    fn it_rejects_control_characters() {
+        assert_eq!(
+            Err(ValidationError::ControlCharacter(CredentialField::Ssid)),
+            credentials().apply_control_character_policy(ControlCharacterPolicy::Reject)
+        );
+        let mut wifi_credentials = credentials();
+        wifi_credentials.ssid = String::from("test ssid");
+        assert_eq!(
+            Err(ValidationError::ControlCharacter(CredentialField::Password)),
+            wifi_credentials.apply_control_character_policy(ControlCharacterPolicy::Reject)
+        );
+    }
+
+    #[test]
+    fn it_strips_control_characters() {
+        let mut wifi_credentials = credentials();
+        assert_eq!(
+            Ok(()),
+            wifi_credentials.apply_control_character_policy(ControlCharacterPolicy::Strip)
+        );
+        assert_eq!(
+            "WIFI:S:testssid;T:WEP;P:test password;H:false;;",
+            wifi_credentials.encode()
+        );
+    }
+
+    #[test]
+    fn it_escapes_control_characters() {
+        let mut wifi_credentials = credentials();
+        assert_eq!(
+            Ok(()),
+            wifi_credentials.apply_control_character_policy(ControlCharacterPolicy::Escape)
+        );
+        assert_eq!(credentials(), wifi_credentials);
+        assert_eq!(
+            "WIFI:S:test\tssid;T:WEP;P:test password\r\n;H:false;;",
+            wifi_credentials.encode()
+        );
+    }
+
+    #[test]
+    fn it_encodes_differently_for_each_policy() {
+        let wifi_credentials = credentials();
+        assert_eq!(
+            Some(ValidationError::ControlCharacter(CredentialField::Ssid)),
+            wifi_credentials
+                .encode_with_control_character_policy(ControlCharacterPolicy::Reject)
+                .err()
+        );
+        assert_eq!(
+            "WIFI:S:testssid;T:WEP;P:test password;H:false;;",
+            wifi_credentials
+                .encode_with_control_character_policy(ControlCharacterPolicy::Strip)
+                .unwrap()
+        );
+        assert_eq!(
+            "WIFI:S:test\\\tssid;T:WEP;P:test password\\\r\\\n;H:false;;",
+            wifi_credentials
+                .encode_with_control_character_policy(ControlCharacterPolicy::Escape)
+                .unwrap()
+        );
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        assert_eq!(
+            wifi_credentials.encode(),
+            wifi_credentials
+                .encode_with_control_character_policy(ControlCharacterPolicy::Reject)
+                .unwrap()
+        );
+    }
+}
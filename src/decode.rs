@@ -0,0 +1,287 @@
+use crate::{Error, ParseError, QrCodeEcc, WifiCredentials};
+
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::io::Read;
+
+/// Pixels per module when rasterizing a matrix for the decoder.
+const MODULE_SIZE: usize = 4;
+/// The quiet zone around the code, in modules, required for the decoder to find it.
+const QUIET_ZONE: usize = 4;
+
+/// Reasons credentials failed [`WifiCredentials::verify_roundtrip`].
+#[derive(Debug)]
+pub enum VerificationError {
+    /// The credentials could not be encoded as a QR code.
+    Encode(Error),
+    /// The rendered QR code could not be read back by the decoder.
+    Unreadable,
+    /// The text read back from the QR code is not a valid payload.
+    Parse(ParseError),
+    /// The credentials read back from the QR code differ from the original credentials.
+    Mismatch,
+}
+
+impl Display for VerificationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Encode(error) => Display::fmt(error, f),
+            Self::Unreadable => f.write_str("the rendered QR code could not be read back"),
+            Self::Parse(error) => write!(f, "the decoded payload is invalid: {}", error),
+            Self::Mismatch => f.write_str("the decoded credentials differ from the original"),
+        }
+    }
+}
+
+impl error::Error for VerificationError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Encode(error) => Some(error),
+            Self::Parse(error) => Some(error),
+            Self::Unreadable | Self::Mismatch => None,
+        }
+    }
+}
+
+impl From<Error> for VerificationError {
+    fn from(error: Error) -> Self {
+        Self::Encode(error)
+    }
+}
+
+impl From<ParseError> for VerificationError {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+/// Errors when reading credentials from an image with [`decode_from_image`].
+#[derive(Debug)]
+pub enum DecodeError {
+    /// The image could not be read, or is not a PNG or JPEG image.
+    Image(image::ImageError),
+    /// No QR code could be read from the image.
+    NotFound,
+    /// A QR code was read from the image, but it is not a valid `WIFI:` payload.
+    Parse(ParseError),
+    /// The camera could not be opened or stopped delivering frames.
+    #[cfg(feature = "camera")]
+    Camera(nokhwa::NokhwaError),
+}
+
+impl Display for DecodeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Image(error) => write!(f, "failed to read the image: {}", error),
+            Self::NotFound => f.write_str("no QR code was found in the image"),
+            Self::Parse(error) => write!(f, "the QR code is not a wifi QR code: {}", error),
+            #[cfg(feature = "camera")]
+            Self::Camera(error) => write!(f, "failed to read from the camera: {}", error),
+        }
+    }
+}
+
+impl error::Error for DecodeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Image(error) => Some(error),
+            Self::Parse(error) => Some(error),
+            #[cfg(feature = "camera")]
+            Self::Camera(error) => Some(error),
+            Self::NotFound => None,
+        }
+    }
+}
+
+impl From<image::ImageError> for DecodeError {
+    fn from(error: image::ImageError) -> Self {
+        Self::Image(error)
+    }
+}
+
+/// Read the credentials from a PNG or JPEG image of a wifi QR code, such as a photo of a printed sign or a code exported by another tool. Payloads are parsed with [`WifiCredentials::parse_lenient`], since other generators vary in how they write them. If the image contains several QR codes, the first one with a valid payload is returned.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{QrCodeEcc, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// let mut png = Vec::new();
+/// wifi_credentials
+///     .qr(QrCodeEcc::Medium)
+///     .png(&mut png)
+///     .expect("Failed to write example PNG.");
+///
+/// let decoded = wifi_qr_code::decode_from_image(png.as_slice())
+///     .expect("Failed to decode example PNG.");
+/// assert_eq!(wifi_credentials, decoded);
+/// ```
+pub fn decode_from_image(mut reader: impl Read) -> Result<WifiCredentials, DecodeError> {
+    let mut bytes = Vec::new();
+    reader
+        .read_to_end(&mut bytes)
+        .map_err(image::ImageError::IoError)?;
+    let image = image::load_from_memory(&bytes)?.into_luma8();
+    decode_greyscale(
+        image.width() as usize,
+        image.height() as usize,
+        image.as_raw(),
+    )
+}
+
+/// Decode the first wifi QR code in a greyscale image with one byte per pixel, row by row.
+pub(crate) fn decode_greyscale(
+    width: usize,
+    height: usize,
+    pixels: &[u8],
+) -> Result<WifiCredentials, DecodeError> {
+    let mut prepared =
+        rqrr::PreparedImage::prepare_from_greyscale(width, height, |x, y| pixels[y * width + x]);
+    let mut result = Err(DecodeError::NotFound);
+    for (_, payload) in prepared
+        .detect_grids()
+        .into_iter()
+        .filter_map(|grid| grid.decode().ok())
+    {
+        match WifiCredentials::parse_lenient(&payload) {
+            Ok(wifi_credentials) => return Ok(wifi_credentials),
+            Err(error) if matches!(result, Err(DecodeError::NotFound)) => {
+                result = Err(DecodeError::Parse(error))
+            }
+            Err(_) => {}
+        }
+    }
+    result
+}
+
+impl WifiCredentials {
+    /// Render the credentials as a QR code matrix, read the matrix back with a QR code decoder, parse the decoded payload, and check that it matches the original credentials. This guarantees that what is generated will scan back to the same network.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::QrCodeEcc;
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// assert!(wifi_credentials.verify_roundtrip(QrCodeEcc::Medium).is_ok());
+    /// ```
+    pub fn verify_roundtrip(
+        &self,
+        qr_code_error_checking: QrCodeEcc,
+    ) -> Result<(), VerificationError> {
+        let matrix = self.qr(qr_code_error_checking).matrix()?;
+        let payload = decode_matrix(&matrix).ok_or(VerificationError::Unreadable)?;
+        let decoded = WifiCredentials::parse(&payload)?;
+        if decoded.encode() == self.encode() {
+            Ok(())
+        } else {
+            Err(VerificationError::Mismatch)
+        }
+    }
+}
+
+/// Rasterize the matrix with a quiet zone and decode the first QR code found in it.
+pub(crate) fn decode_matrix(matrix: &[Vec<bool>]) -> Option<String> {
+    let side = (matrix.len() + 2 * QUIET_ZONE) * MODULE_SIZE;
+    let mut image = rqrr::PreparedImage::prepare_from_greyscale(side, side, |x, y| {
+        let module = |pixel: usize| (pixel / MODULE_SIZE).checked_sub(QUIET_ZONE);
+        match (module(x), module(y)) {
+            (Some(x), Some(y)) if matrix.get(y).and_then(|row| row.get(x)) == Some(&true) => 0,
+            _ => 255,
+        }
+    });
+    image
+        .detect_grids()
+        .into_iter()
+        .find_map(|grid| grid.decode().ok())
+        .map(|(_, payload)| payload)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility};
+
+    #[test]
+    fn it_verifies_credentials_with_special_characters() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from(r#"special_characters ";,:\"#),
+            authentication_type: AuthenticationType::WpaPsk([0x5a; 32].into()),
+            visibility: Visibility::Visible,
+        };
+        for ecc in [
+            QrCodeEcc::Low,
+            QrCodeEcc::Medium,
+            QrCodeEcc::Quartile,
+            QrCodeEcc::High,
+        ] {
+            assert!(wifi_credentials.verify_roundtrip(ecc).is_ok());
+        }
+    }
+
+    fn encode_png(image: &image::GrayImage) -> Vec<u8> {
+        let mut png = std::io::Cursor::new(Vec::new());
+        image
+            .write_to(&mut png, image::ImageFormat::Png)
+            .expect("PNG should encode.");
+        png.into_inner()
+    }
+
+    #[test]
+    fn it_decodes_credentials_from_images() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from(r#"special_characters ";,:\"#),
+            authentication_type: AuthenticationType::WEP("0123456789".into()),
+            visibility: Visibility::Hidden,
+        };
+        let image = wifi_credentials
+            .qr(QrCodeEcc::Quartile)
+            .size(300)
+            .image()
+            .expect("Image should render.");
+        let image = image::GrayImage::from_raw(300, 300, image).expect("Image should fit.");
+        let decoded = decode_from_image(encode_png(&image).as_slice());
+        assert_eq!(wifi_credentials, decoded.expect("Image should decode."));
+    }
+
+    #[test]
+    fn it_reports_images_without_wifi_qr_codes() {
+        let blank = image::GrayImage::from_pixel(100, 100, image::Luma([255]));
+        assert!(matches!(
+            decode_from_image(encode_png(&blank).as_slice()),
+            Err(DecodeError::NotFound)
+        ));
+
+        let image = qrcode_generator::to_image("https://example.com", QrCodeEcc::Medium, 200)
+            .expect("Image should render.");
+        let image = image::GrayImage::from_raw(200, 200, image).expect("Image should fit.");
+        assert!(matches!(
+            decode_from_image(encode_png(&image).as_slice()),
+            Err(DecodeError::Parse(ParseError::MissingPrefix))
+        ));
+
+        assert!(matches!(
+            decode_from_image(&b"not an image"[..]),
+            Err(DecodeError::Image(_))
+        ));
+    }
+
+    #[test]
+    fn it_reports_invalid_credentials_as_encoding_failures() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("short".into()),
+            visibility: Visibility::Visible,
+        };
+        match wifi_credentials.verify_roundtrip(QrCodeEcc::Medium) {
+            Err(VerificationError::Encode(Error::Validation(_))) => {}
+            _ => panic!("Expected the invalid passphrase to be rejected."),
+        }
+    }
+}
@@ -0,0 +1,398 @@
+//! Drawing QR codes onto the frame buffers of small displays, through the [embedded-graphics](https://docs.rs/embedded-graphics) `DrawTarget` trait, for badges and door signs that show the current network without a computer attached.
+//!
+//! [`draw_bitmatrix`] draws onto any target, with the colors of the target, and [`draw_qr_matrix`] does the same for a [`QrMatrix`] on firmware without the `std` feature. The submodules drive specific displays.
+//!
+//! # Examples
+//!
+//! ```
+//! # #[cfg(feature = "std")] {
+//! use embedded_graphics_core::pixelcolor::BinaryColor;
+//! use embedded_graphics_core::prelude::*;
+//! use embedded_graphics_core::primitives::Rectangle;
+//! use wifi_qr_code::display;
+//! use wifi_qr_code::{QrCodeEcc, WifiCredentials};
+//!
+//! # struct Screen;
+//! # impl OriginDimensions for Screen {
+//! #     fn size(&self) -> Size {
+//! #         Size::new(128, 64)
+//! #     }
+//! # }
+//! # impl DrawTarget for Screen {
+//! #     type Color = BinaryColor;
+//! #     type Error = core::convert::Infallible;
+//! #     fn draw_iter<I: IntoIterator<Item = Pixel<BinaryColor>>>(&mut self, _: I) -> Result<(), Self::Error> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # let mut screen = Screen;
+//! let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+//! let bitmatrix = wifi_qr_code::encode_as_bitmatrix(&wifi_credentials, QrCodeEcc::Low)
+//!     .expect("Failed to encode example bitmatrix.");
+//! let area = screen.bounding_box();
+//! let drawn = display::draw_bitmatrix(&mut screen, &bitmatrix, area, 2, BinaryColor::On, BinaryColor::Off)
+//!     .expect("Failed to draw the QR code.");
+//! // The code is 33 modules wide, plus the quiet zone, so only one pixel per module fits.
+//! assert_eq!(Size::new(37, 37), drawn.size);
+//! # }
+//! ```
+
+#[cfg(feature = "epd")]
+pub mod epd;
+#[cfg(feature = "hub75")]
+pub mod hub75;
+#[cfg(feature = "oled")]
+pub mod oled;
+#[cfg(feature = "tft")]
+pub mod tft;
+
+#[cfg(feature = "std")]
+use crate::BitMatrix;
+use crate::QrMatrix;
+
+use core::error;
+use core::fmt::{self, Debug, Display, Formatter};
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
+
+/// Errors when showing a QR code on a display.
+#[derive(Debug)]
+#[non_exhaustive]
+pub enum DisplayError<E> {
+    /// The credentials could not be encoded as a QR code.
+    #[cfg(feature = "std")]
+    Encode(crate::Error),
+    /// The QR code and its quiet zone do not fit into the area even at one pixel per module.
+    TooSmall {
+        /// The width of the QR code with its quiet zone, in modules.
+        modules: u32,
+        /// The size of the area, in pixels.
+        size: Size,
+    },
+    /// The colors chosen for the code would make it hard to scan.
+    Unscannable(ScanProblem),
+    /// The display driver failed, e.g. because of an SPI or I2C error.
+    Display(E),
+}
+
+impl<E: Debug> Display for DisplayError<E> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Encode(error) => write!(f, "failed to encode the QR code: {}", error),
+            Self::TooSmall { modules, size } => write!(
+                f,
+                "a QR code {} modules wide does not fit into {} by {} pixels",
+                modules, size.width, size.height
+            ),
+            Self::Unscannable(problem) => {
+                write!(f, "the QR code would be hard to scan: {}", problem)
+            }
+            Self::Display(error) => write!(f, "the display failed: {:?}", error),
+        }
+    }
+}
+
+impl<E: Debug> error::Error for DisplayError<E> {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            #[cfg(feature = "std")]
+            Self::Encode(error) => Some(error),
+            Self::TooSmall { .. } | Self::Unscannable(_) | Self::Display(_) => None,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl<E> From<crate::Error> for DisplayError<E> {
+    fn from(error: crate::Error) -> Self {
+        Self::Encode(error)
+    }
+}
+
+/// Reasons the colors of a code on a display that gives off light would make it hard to scan, reported by renderers that let the colors be adjusted, such as [`hub75::Hub75Renderer`](crate::display::hub75::Hub75Renderer).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ScanProblem {
+    /// The light modules are too dim for cameras to expose for, and flicker in camera previews because they are lit for only a short part of each refresh.
+    TooDim {
+        /// The brightness of the light modules, from 0 to 255.
+        brightness: u8,
+        /// The lowest brightness that scans reliably.
+        minimum: u8,
+    },
+    /// The dark modules are lit too brightly to tell apart from the light modules.
+    LowContrast {
+        /// The contrast between light and dark modules, from 0 to 255.
+        contrast: u8,
+        /// The lowest contrast that scans reliably.
+        minimum: u8,
+    },
+}
+
+impl Display for ScanProblem {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::TooDim {
+                brightness,
+                minimum,
+            } => write!(
+                f,
+                "the brightness is {}, but should be at least {}",
+                brightness, minimum
+            ),
+            Self::LowContrast { contrast, minimum } => write!(
+                f,
+                "the contrast is {}, but should be at least {}",
+                contrast, minimum
+            ),
+        }
+    }
+}
+
+/// Draw the QR code as large as it fits into the area, centered in it, with a quiet zone of `quiet_zone` modules in the light color around it. Modules are a whole number of pixels wide so that every module has the same size, which matters on displays with few pixels. Returns the part of the target that was drawn, e.g. to refresh only that part of the display.
+#[cfg(feature = "std")]
+pub fn draw_bitmatrix<D: DrawTarget>(
+    target: &mut D,
+    bitmatrix: &BitMatrix,
+    area: Rectangle,
+    quiet_zone: u32,
+    dark: D::Color,
+    light: D::Color,
+) -> Result<Rectangle, DisplayError<D::Error>> {
+    draw_modules(
+        target,
+        bitmatrix.size,
+        |x, y| bitmatrix.get(x, y),
+        area,
+        quiet_zone,
+        dark,
+        light,
+    )
+}
+
+/// Draw the QR code in the matrix like [`draw_bitmatrix`], e.g. after [`WifiCredentialsRef::encode_to_qr_matrix`](crate::WifiCredentialsRef::encode_to_qr_matrix) on firmware without the `std` feature.
+///
+/// # Examples
+///
+/// ```
+/// use embedded_graphics_core::pixelcolor::BinaryColor;
+/// use embedded_graphics_core::prelude::*;
+/// use wifi_qr_code::display;
+/// use wifi_qr_code::{AuthenticationTypeRef, QrCodeEcc, QrMatrix, Visibility, WifiCredentialsRef};
+///
+/// # struct Screen;
+/// # impl OriginDimensions for Screen {
+/// #     fn size(&self) -> Size {
+/// #         Size::new(128, 64)
+/// #     }
+/// # }
+/// # impl DrawTarget for Screen {
+/// #     type Color = BinaryColor;
+/// #     type Error = core::convert::Infallible;
+/// #     fn draw_iter<I: IntoIterator<Item = Pixel<BinaryColor>>>(&mut self, _: I) -> Result<(), Self::Error> {
+/// #         Ok(())
+/// #     }
+/// # }
+/// # let mut screen = Screen;
+/// let wifi_credentials = WifiCredentialsRef {
+///     ssid: "example ssid",
+///     authentication_type: AuthenticationTypeRef::WPA("example password"),
+///     visibility: Visibility::Visible,
+/// };
+/// let mut matrix = QrMatrix::<{ wifi_qr_code::qr_matrix_side(5) }>::new();
+/// wifi_credentials
+///     .encode_to_qr_matrix(&mut matrix, QrCodeEcc::Low)
+///     .expect("Failed to encode example matrix.");
+/// let area = screen.bounding_box();
+/// let drawn = display::draw_qr_matrix(&mut screen, &matrix, area, 2, BinaryColor::On, BinaryColor::Off)
+///     .expect("Failed to draw the QR code.");
+/// assert_eq!(Size::new(37, 37), drawn.size);
+/// ```
+pub fn draw_qr_matrix<D: DrawTarget, const SIDE: usize>(
+    target: &mut D,
+    matrix: &QrMatrix<SIDE>,
+    area: Rectangle,
+    quiet_zone: u32,
+    dark: D::Color,
+    light: D::Color,
+) -> Result<Rectangle, DisplayError<D::Error>> {
+    draw_modules(
+        target,
+        matrix.size(),
+        |x, y| matrix.get(x, y),
+        area,
+        quiet_zone,
+        dark,
+        light,
+    )
+}
+
+/// Draw a QR code of `size` modules, whose dark modules `is_dark` picks out.
+fn draw_modules<D: DrawTarget>(
+    target: &mut D,
+    size: usize,
+    is_dark: impl Fn(usize, usize) -> bool + Copy,
+    area: Rectangle,
+    quiet_zone: u32,
+    dark: D::Color,
+    light: D::Color,
+) -> Result<Rectangle, DisplayError<D::Error>> {
+    let modules = size as u32 + 2 * quiet_zone;
+    let module_size = area.size.width.min(area.size.height) / modules;
+    if module_size == 0 {
+        return Err(DisplayError::TooSmall {
+            modules,
+            size: area.size,
+        });
+    }
+    let side = modules * module_size;
+    let bounds = Rectangle::new(
+        area.top_left
+            + Point::new(
+                ((area.size.width - side) / 2) as i32,
+                ((area.size.height - side) / 2) as i32,
+            ),
+        Size::new_equal(side),
+    );
+    // The module a pixel belongs to, counted from the first module of the code rather than of the quiet zone.
+    let module = move |pixel: u32| (pixel / module_size) as usize;
+    let quiet_zone = quiet_zone as usize;
+    let colors = (0..side).flat_map(move |y| {
+        (0..side).map(move |x| {
+            let (x, y) = (module(x), module(y));
+            if x >= quiet_zone && y >= quiet_zone && is_dark(x - quiet_zone, y - quiet_zone) {
+                dark
+            } else {
+                light
+            }
+        })
+    });
+    target
+        .fill_contiguous(&bounds, colors)
+        .map_err(DisplayError::Display)?;
+    Ok(bounds)
+}
+
+#[cfg(all(test, feature = "std"))]
+pub(super) mod tests {
+    use super::*;
+    use crate::{QrCodeEcc, WifiCredentials};
+
+    use embedded_graphics_core::pixelcolor::BinaryColor;
+    use std::convert::Infallible;
+
+    /// A display that keeps its pixels in memory.
+    pub(in crate::display) struct Screen<C = BinaryColor> {
+        size: Size,
+        pixels: Vec<C>,
+    }
+
+    impl<C: PixelColor + Default> Screen<C> {
+        /// A screen with every pixel in the default color, which is off or black.
+        pub(in crate::display) fn new(width: u32, height: u32) -> Self {
+            Screen {
+                size: Size::new(width, height),
+                pixels: vec![C::default(); (width * height) as usize],
+            }
+        }
+
+        /// The color of the pixel.
+        pub(in crate::display) fn pixel(&self, x: u32, y: u32) -> C {
+            self.pixels[(y * self.size.width + x) as usize]
+        }
+    }
+
+    impl<C> OriginDimensions for Screen<C> {
+        fn size(&self) -> Size {
+            self.size
+        }
+    }
+
+    impl<C: PixelColor> DrawTarget for Screen<C> {
+        type Color = C;
+        type Error = Infallible;
+
+        fn draw_iter<I: IntoIterator<Item = Pixel<C>>>(
+            &mut self,
+            pixels: I,
+        ) -> Result<(), Infallible> {
+            for Pixel(point, color) in pixels {
+                if self.bounding_box().contains(point) {
+                    self.pixels[point.y as usize * self.size.width as usize + point.x as usize] =
+                        color;
+                }
+            }
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn it_centers_whole_modules_in_the_area() {
+        let bitmatrix = crate::encode_as_bitmatrix(
+            &WifiCredentials::wpa2("test ssid", "test password"),
+            QrCodeEcc::Low,
+        )
+        .unwrap();
+        let mut screen: Screen = Screen::new(128, 64);
+        let area = Rectangle::new(Point::new(64, 0), Size::new(64, 64));
+        let drawn = draw_bitmatrix(
+            &mut screen,
+            &bitmatrix,
+            area,
+            1,
+            BinaryColor::Off,
+            BinaryColor::On,
+        )
+        .unwrap();
+        // Version 3 codes are 29 modules wide, so at 2 pixels per module the code and its quiet zone leave 2 pixels of the area.
+        assert_eq!(Rectangle::new(Point::new(65, 1), Size::new(62, 62)), drawn);
+        let on = |x, y| screen.pixel(x, y).is_on();
+        assert!(!on(64, 1) && on(65, 1) && on(66, 2));
+        // The first module of the code is part of a finder pattern, so it is dark.
+        assert!(!on(67, 3) && !on(68, 4));
+
+        assert!(matches!(
+            draw_bitmatrix(
+                &mut screen,
+                &bitmatrix,
+                Rectangle::new(Point::zero(), Size::new(26, 64)),
+                1,
+                BinaryColor::Off,
+                BinaryColor::On,
+            ),
+            Err(DisplayError::TooSmall { modules: 31, .. })
+        ));
+    }
+
+    #[test]
+    fn it_draws_qr_matrices_like_bitmatrices() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        let bitmatrix = crate::encode_as_bitmatrix(&wifi_credentials, QrCodeEcc::Low).unwrap();
+        let mut matrix = QrMatrix::<{ crate::qr_matrix_side(3) }>::new();
+        wifi_credentials
+            .encode_to_qr_matrix(&mut matrix, QrCodeEcc::Low)
+            .unwrap();
+        let area = Rectangle::new(Point::new(64, 0), Size::new(64, 64));
+        let mut expected: Screen = Screen::new(128, 64);
+        draw_bitmatrix(
+            &mut expected,
+            &bitmatrix,
+            area,
+            1,
+            BinaryColor::Off,
+            BinaryColor::On,
+        )
+        .unwrap();
+        let mut screen: Screen = Screen::new(128, 64);
+        draw_qr_matrix(
+            &mut screen,
+            &matrix,
+            area,
+            1,
+            BinaryColor::Off,
+            BinaryColor::On,
+        )
+        .unwrap();
+        assert!(expected.pixels == screen.pixels);
+    }
+}
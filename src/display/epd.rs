@@ -0,0 +1,303 @@
+//! QR codes on black and white e-paper displays from Waveshare, driven by [epd-waveshare](https://docs.rs/epd-waveshare). E-paper keeps its image without power, so a battery-powered badge or door sign only has to wake up when the guest network changes.
+//!
+//! Partial refreshes only send and redraw the part of the panel with the QR code, which is faster and does not flash the whole panel, but leaves ghosting behind over time. Use a full refresh every few updates, or whenever the rest of the panel changes too.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wifi_qr_code::display::epd::{EpdRenderer, Refresh, Rotation};
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! use epd_waveshare::epd2in9_v2::{Display2in9, Epd2in9};
+//! use epd_waveshare::prelude::*;
+//!
+//! # fn show<SPI, BUSY, DC, RST, DELAY>(mut spi: SPI, busy: BUSY, dc: DC, rst: RST, mut delay: DELAY) -> Result<(), Box<dyn std::error::Error>>
+//! # where
+//! #     SPI: embedded_hal::spi::SpiDevice,
+//! #     SPI::Error: std::error::Error + 'static,
+//! #     BUSY: embedded_hal::digital::InputPin,
+//! #     DC: embedded_hal::digital::OutputPin,
+//! #     RST: embedded_hal::digital::OutputPin,
+//! #     DELAY: embedded_hal::delay::DelayNs,
+//! # {
+//! let mut epd = Epd2in9::new(&mut spi, busy, dc, rst, &mut delay, None)?;
+//! let mut display = Box::new(Display2in9::default());
+//! let mut renderer = EpdRenderer::new();
+//! renderer.rotation = Rotation::Rotate90;
+//!
+//! let wifi_credentials = WifiCredentials::wpa2("example guest network", "example password");
+//! renderer.show(&wifi_credentials, &mut epd, &mut spi, &mut delay, &mut display)?;
+//! renderer.refresh = Refresh::Partial;
+//! // Later, after the passphrase of the guest network was rotated:
+//! let wifi_credentials = WifiCredentials::wpa2("example guest network", "example new password");
+//! renderer.show(&wifi_credentials, &mut epd, &mut spi, &mut delay, &mut display)?;
+//! epd.sleep(&mut spi, &mut delay)?;
+//! # Ok(())
+//! # }
+//! ```
+
+use super::DisplayError;
+use crate::{QrCodeEcc, WifiCredentials};
+
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
+use embedded_hal::delay::DelayNs;
+use embedded_hal::digital::{InputPin, OutputPin};
+use embedded_hal::spi::SpiDevice;
+use epd_waveshare::color::Color;
+use epd_waveshare::graphics::{Display, DisplayRotation};
+use epd_waveshare::prelude::{RefreshLut, WaveshareDisplay};
+
+/// How the image is turned on the panel, clockwise from the orientation of the display driver.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Rotation {
+    /// The orientation of the display driver, which is portrait for most panels.
+    Rotate0,
+    /// Turned by 90 degrees, which is landscape for most panels.
+    Rotate90,
+    /// Upside down.
+    Rotate180,
+    /// Turned by 270 degrees.
+    Rotate270,
+}
+
+impl From<Rotation> for DisplayRotation {
+    fn from(rotation: Rotation) -> Self {
+        match rotation {
+            Rotation::Rotate0 => Self::Rotate0,
+            Rotation::Rotate90 => Self::Rotate90,
+            Rotation::Rotate180 => Self::Rotate180,
+            Rotation::Rotate270 => Self::Rotate270,
+        }
+    }
+}
+
+/// How the panel is updated.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Refresh {
+    /// Send the whole frame and redraw the panel with the full waveform, which flashes it but clears any ghosting.
+    Full,
+    /// Send only the part of the frame with the QR code and redraw with the quick waveform. Not every driver in epd-waveshare supports partial frames.
+    Partial,
+}
+
+/// The settings for showing a QR code on an e-paper display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EpdRenderer {
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The width of the white border around the code, in modules.
+    pub quiet_zone: u32,
+    /// How the image is turned on the panel.
+    pub rotation: Rotation,
+    /// How the panel is updated.
+    pub refresh: Refresh,
+}
+
+impl EpdRenderer {
+    /// A QR code using medium error correction and a quiet zone of 2 modules, in the orientation of the display driver, with a full refresh.
+    pub fn new() -> Self {
+        EpdRenderer {
+            qr_code_error_checking: QrCodeEcc::Medium,
+            quiet_zone: 2,
+            rotation: Rotation::Rotate0,
+            refresh: Refresh::Full,
+        }
+    }
+
+    /// Draw the QR code of the network into the frame buffer, as large as fits and centered, with the rest of the frame left as it is. Returns the rectangle that was drawn, in the coordinates of the rotated frame.
+    pub fn draw<const WIDTH: u32, const HEIGHT: u32, const BWRBIT: bool, const BYTECOUNT: usize>(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        display: &mut Display<WIDTH, HEIGHT, BWRBIT, BYTECOUNT, Color>,
+    ) -> Result<Rectangle, DisplayError<core::convert::Infallible>> {
+        let bitmatrix = crate::encode_as_bitmatrix(wifi_credentials, self.qr_code_error_checking)?;
+        display.set_rotation(self.rotation.into());
+        let area = display.bounding_box();
+        super::draw_bitmatrix(
+            display,
+            &bitmatrix,
+            area,
+            self.quiet_zone,
+            Color::Black,
+            Color::White,
+        )
+    }
+
+    /// Draw the QR code of the network into the frame buffer like [`EpdRenderer::draw`], then send it to the panel and refresh it. The panel has to be awake.
+    pub fn show<
+        SPI,
+        BUSY,
+        DC,
+        RST,
+        DELAY,
+        EPD,
+        const WIDTH: u32,
+        const HEIGHT: u32,
+        const BWRBIT: bool,
+        const BYTECOUNT: usize,
+    >(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        epd: &mut EPD,
+        spi: &mut SPI,
+        delay: &mut DELAY,
+        display: &mut Display<WIDTH, HEIGHT, BWRBIT, BYTECOUNT, Color>,
+    ) -> Result<(), DisplayError<SPI::Error>>
+    where
+        SPI: SpiDevice,
+        BUSY: InputPin,
+        DC: OutputPin,
+        RST: OutputPin,
+        DELAY: DelayNs,
+        EPD: WaveshareDisplay<SPI, BUSY, DC, RST, DELAY>,
+    {
+        let drawn = self
+            .draw(wifi_credentials, display)
+            .map_err(|error| match error {
+                DisplayError::Encode(error) => DisplayError::Encode(error),
+                DisplayError::TooSmall { modules, size } => {
+                    DisplayError::TooSmall { modules, size }
+                }
+                DisplayError::Unscannable(problem) => DisplayError::Unscannable(problem),
+                DisplayError::Display(never) => match never {},
+            })?;
+        match self.refresh {
+            Refresh::Full => {
+                epd.set_lut(spi, delay, Some(RefreshLut::Full))
+                    .map_err(DisplayError::Display)?;
+                epd.update_and_display_frame(spi, display.buffer(), delay)
+                    .map_err(DisplayError::Display)?;
+            }
+            Refresh::Partial => {
+                let window = native_window(drawn, self.rotation, WIDTH, HEIGHT);
+                let buffer = partial_buffer(display.buffer(), WIDTH, window);
+                epd.set_lut(spi, delay, Some(RefreshLut::Quick))
+                    .map_err(DisplayError::Display)?;
+                epd.update_partial_frame(
+                    spi,
+                    delay,
+                    &buffer,
+                    window.top_left.x as u32,
+                    window.top_left.y as u32,
+                    window.size.width,
+                    window.size.height,
+                )
+                .map_err(DisplayError::Display)?;
+                epd.display_frame(spi, delay)
+                    .map_err(DisplayError::Display)?;
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for EpdRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The rectangle of the unrotated panel that a rectangle of the rotated frame covers, widened to whole bytes of the frame buffer, since panels take partial frames in columns of 8 pixels.
+fn native_window(drawn: Rectangle, rotation: Rotation, width: u32, height: u32) -> Rectangle {
+    let (x, y) = (drawn.top_left.x as u32, drawn.top_left.y as u32);
+    let Size {
+        width: drawn_width,
+        height: drawn_height,
+    } = drawn.size;
+    let (x, y, window_width, window_height) = match rotation {
+        Rotation::Rotate0 => (x, y, drawn_width, drawn_height),
+        Rotation::Rotate90 => (width - y - drawn_height, x, drawn_height, drawn_width),
+        Rotation::Rotate180 => (
+            width - x - drawn_width,
+            height - y - drawn_height,
+            drawn_width,
+            drawn_height,
+        ),
+        Rotation::Rotate270 => (y, height - x - drawn_width, drawn_height, drawn_width),
+    };
+    let left = x / 8 * 8;
+    let right = ((x + window_width).div_ceil(8) * 8).min(width.div_ceil(8) * 8);
+    Rectangle::new(
+        Point::new(left as i32, y as i32),
+        Size::new(right - left, window_height),
+    )
+}
+
+/// The bytes of the frame buffer inside a window of the unrotated panel, row by row.
+fn partial_buffer(buffer: &[u8], width: u32, window: Rectangle) -> Vec<u8> {
+    let stride = width.div_ceil(8) as usize;
+    let left = window.top_left.x as usize / 8;
+    let columns = window.size.width as usize / 8;
+    buffer
+        .chunks(stride)
+        .skip(window.top_left.y as usize)
+        .take(window.size.height as usize)
+        .flat_map(|row| &row[left..left + columns])
+        .copied()
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use epd_waveshare::epd2in9::Display2in9;
+
+    #[test]
+    fn it_maps_the_code_onto_the_unrotated_panel() {
+        // The panel is 128 by 296 pixels, and 296 by 128 when turned by 90 degrees.
+        let drawn = Rectangle::new(Point::new(100, 10), Size::new(60, 60));
+        assert_eq!(
+            Rectangle::new(Point::new(96, 10), Size::new(24, 60)),
+            native_window(
+                Rectangle::new(Point::new(100, 10), Size::new(20, 60)),
+                Rotation::Rotate0,
+                128,
+                296
+            )
+        );
+        assert_eq!(
+            Rectangle::new(Point::new(56, 100), Size::new(64, 60)),
+            native_window(drawn, Rotation::Rotate90, 128, 296)
+        );
+        assert_eq!(
+            Rectangle::new(Point::new(56, 136), Size::new(64, 60)),
+            native_window(
+                Rectangle::new(Point::new(10, 100), Size::new(60, 60)),
+                Rotation::Rotate180,
+                128,
+                296
+            )
+        );
+        assert_eq!(
+            Rectangle::new(Point::new(8, 136), Size::new(64, 60)),
+            native_window(drawn, Rotation::Rotate270, 128, 296)
+        );
+    }
+
+    #[test]
+    fn it_draws_the_code_into_the_rotated_frame() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        let mut display = Display2in9::default();
+        display.clear(Color::White).unwrap();
+        let mut renderer = EpdRenderer::new();
+        renderer.rotation = Rotation::Rotate90;
+        let drawn = renderer.draw(&wifi_credentials, &mut display).unwrap();
+        assert_eq!(Size::new(296, 128), display.size());
+        assert_eq!(drawn.size.width, drawn.size.height);
+        assert_eq!((296 - drawn.size.width as i32) / 2, drawn.top_left.x);
+
+        let window = native_window(drawn, Rotation::Rotate90, 128, 296);
+        let buffer = partial_buffer(display.buffer(), 128, window);
+        assert_eq!(
+            (window.size.width / 8 * window.size.height) as usize,
+            buffer.len()
+        );
+        // Rows of the panel outside of the window stay white.
+        assert!(display.buffer()[..16 * window.top_left.y as usize]
+            .iter()
+            .all(|&byte| byte == 0xff));
+        assert!(buffer.iter().any(|&byte| byte != 0xff));
+    }
+}
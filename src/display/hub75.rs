@@ -0,0 +1,190 @@
+//! QR codes on RGB LED matrix panels with a HUB75 connector, such as the 32 by 32 and 64 by 64 panels used for maker-space signage.
+//!
+//! [`Hub75Renderer`] draws onto any embedded-graphics target with RGB pixels, so it works with whichever HUB75 driver fits the board. LEDs give off light, so the light modules of the code are lit and the dark modules are off or dimmed, and the rest of the panel is lit as part of the quiet zone.
+//!
+//! LED panels are much brighter than the screens scanners are tuned for, and are driven by binary code modulation, which lights dim colors for only a short part of each refresh. Phone cameras see both as smeared or flickering modules, so the brightness and contrast can be turned down and up, and [`Hub75Renderer::check`] rejects settings that are too dim or too low in contrast to scan reliably.
+//!
+//! # Examples
+//!
+//! ```
+//! use wifi_qr_code::display::hub75::Hub75Renderer;
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! use embedded_graphics_core::pixelcolor::{Rgb565, Rgb888};
+//! use embedded_graphics_core::prelude::*;
+//!
+//! # struct Panel;
+//! # impl OriginDimensions for Panel {
+//! #     fn size(&self) -> Size {
+//! #         Size::new(64, 64)
+//! #     }
+//! # }
+//! # impl DrawTarget for Panel {
+//! #     type Color = Rgb565;
+//! #     type Error = core::convert::Infallible;
+//! #     fn draw_iter<I: IntoIterator<Item = Pixel<Rgb565>>>(&mut self, _: I) -> Result<(), Self::Error> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # let mut panel = Panel;
+//! let wifi_credentials = WifiCredentials::wpa2("maker space", "example password");
+//! let mut renderer = Hub75Renderer::new();
+//! renderer.color = Rgb888::new(0xff, 0xd0, 0xa0);
+//! renderer.brightness = 96;
+//! renderer
+//!     .draw(&wifi_credentials, &mut panel)
+//!     .expect("Failed to draw the QR code.");
+//! ```
+
+use super::{DisplayError, ScanProblem};
+use crate::{QrCodeEcc, WifiCredentials};
+
+use embedded_graphics_core::pixelcolor::{Rgb888, RgbColor};
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
+
+/// The lowest brightness at which panels are lit for long enough in each refresh for cameras to see steady modules.
+const MIN_BRIGHTNESS: u8 = 32;
+/// The lowest contrast at which scanners tell dark modules from light ones, where dark modules are lit at most half as brightly as light modules.
+const MIN_CONTRAST: u8 = 128;
+
+/// The settings for showing a QR code on an RGB LED matrix.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Hub75Renderer {
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The narrowest the lit border around the code may be, in modules. The border is wider wherever the panel has room.
+    pub quiet_zone: u32,
+    /// The color of the light modules at full brightness.
+    pub color: Rgb888,
+    /// How brightly the light modules are lit, from 0 for off to 255 for the full `color`.
+    pub brightness: u8,
+    /// How much darker the dark modules are than the light ones, from 0 for the same color to 255 for off. Dimly lit dark modules reduce the glare that makes modules bleed into each other at close range.
+    pub contrast: u8,
+}
+
+impl Hub75Renderer {
+    /// A white QR code at half brightness with the dark modules off, using low error correction, which keeps the code small enough for two LEDs per module on a 64 by 64 panel with most credentials, and a quiet zone of at least 1 module.
+    pub fn new() -> Self {
+        Hub75Renderer {
+            qr_code_error_checking: QrCodeEcc::Low,
+            quiet_zone: 1,
+            color: Rgb888::WHITE,
+            brightness: 128,
+            contrast: 255,
+        }
+    }
+
+    /// The color of the light modules, which is `color` scaled to the brightness.
+    pub fn light_color(&self) -> Rgb888 {
+        scale(self.color, self.brightness)
+    }
+
+    /// The color of the dark modules, which is the light color dimmed by the contrast.
+    pub fn dark_color(&self) -> Rgb888 {
+        scale(self.light_color(), 255 - self.contrast)
+    }
+
+    /// Check that the brightness and contrast make for a code that scans reliably. [`Hub75Renderer::draw`] does the same check before drawing.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::display::hub75::Hub75Renderer;
+    /// use wifi_qr_code::display::ScanProblem;
+    ///
+    /// let mut renderer = Hub75Renderer::new();
+    /// assert_eq!(Ok(()), renderer.check());
+    /// renderer.contrast = 64;
+    /// assert_eq!(Err(ScanProblem::LowContrast { contrast: 64, minimum: 128 }), renderer.check());
+    /// ```
+    pub fn check(&self) -> Result<(), ScanProblem> {
+        // The brightest channel decides how long the LEDs are lit, whatever the color.
+        let light = self.light_color();
+        let brightness = light.r().max(light.g()).max(light.b());
+        if brightness < MIN_BRIGHTNESS {
+            return Err(ScanProblem::TooDim {
+                brightness,
+                minimum: MIN_BRIGHTNESS,
+            });
+        }
+        if self.contrast < MIN_CONTRAST {
+            return Err(ScanProblem::LowContrast {
+                contrast: self.contrast,
+                minimum: MIN_CONTRAST,
+            });
+        }
+        Ok(())
+    }
+
+    /// Light the whole panel and draw the QR code of the network onto it, scaled to the largest whole number of LEDs per module that fits and centered, after checking the colors with [`Hub75Renderer::check`]. Returns the rectangle with the code and its quiet zone.
+    pub fn draw<D>(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        display: &mut D,
+    ) -> Result<Rectangle, DisplayError<D::Error>>
+    where
+        D: DrawTarget,
+        D::Color: From<Rgb888>,
+    {
+        self.check().map_err(DisplayError::Unscannable)?;
+        let bitmatrix = crate::encode_as_bitmatrix(wifi_credentials, self.qr_code_error_checking)?;
+        let light = D::Color::from(self.light_color());
+        let area = display.bounding_box();
+        display.clear(light).map_err(DisplayError::Display)?;
+        super::draw_bitmatrix(
+            display,
+            &bitmatrix,
+            area,
+            self.quiet_zone,
+            D::Color::from(self.dark_color()),
+            light,
+        )
+    }
+}
+
+impl Default for Hub75Renderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The color with every channel scaled by `level / 255`, rounded to the nearest value.
+fn scale(color: Rgb888, level: u8) -> Rgb888 {
+    let channel = |value: u8| ((u16::from(value) * u16::from(level) + 127) / 255) as u8;
+    Rgb888::new(channel(color.r()), channel(color.g()), channel(color.b()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::tests::Screen;
+
+    #[test]
+    fn it_dims_the_modules_as_configured() {
+        let mut renderer = Hub75Renderer::new();
+        renderer.color = Rgb888::new(255, 200, 0);
+        renderer.brightness = 51;
+        renderer.contrast = 204;
+        assert_eq!(Rgb888::new(51, 40, 0), renderer.light_color());
+        assert_eq!(Rgb888::new(10, 8, 0), renderer.dark_color());
+
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        let mut screen: Screen<Rgb888> = Screen::new(64, 64);
+        let drawn = renderer.draw(&wifi_credentials, &mut screen).unwrap();
+        assert_eq!(Rectangle::new(Point::new(1, 1), Size::new(62, 62)), drawn);
+        assert_eq!(Rgb888::new(51, 40, 0), screen.pixel(0, 0));
+        assert_eq!(Rgb888::new(51, 40, 0), screen.pixel(2, 2));
+        // The first module of the code is part of a finder pattern.
+        assert_eq!(Rgb888::new(10, 8, 0), screen.pixel(3, 3));
+
+        renderer.brightness = 31;
+        assert!(matches!(
+            renderer.draw(&wifi_credentials, &mut screen),
+            Err(DisplayError::Unscannable(ScanProblem::TooDim {
+                brightness: 31,
+                minimum: 32
+            }))
+        ));
+    }
+}
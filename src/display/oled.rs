@@ -0,0 +1,133 @@
+//! QR codes on small monochrome OLED displays, such as the 128 by 64 pixel SSD1306 and SH1106 modules used by gadgets that show a "scan to configure" code on first boot.
+//!
+//! [`OledRenderer`] draws onto any target with [`BinaryColor`] pixels, whether it is connected over I2C or SPI, e.g. the buffered graphics mode of [ssd1306](https://docs.rs/ssd1306) or the graphics mode of [sh1106](https://docs.rs/sh1106). Those drivers keep the frame in memory, so call their `flush` afterwards to send it to the display.
+//!
+//! OLED pixels give off light, so the light modules of the code are lit and the dark modules are off. The whole screen is lit around the code, which makes the quiet zone as wide as the screen allows.
+//!
+//! # Examples
+//!
+//! ```
+//! use wifi_qr_code::display::oled::OledRenderer;
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! use embedded_graphics_core::pixelcolor::BinaryColor;
+//! use embedded_graphics_core::prelude::*;
+//!
+//! # struct Ssd1306;
+//! # impl OriginDimensions for Ssd1306 {
+//! #     fn size(&self) -> Size {
+//! #         Size::new(128, 64)
+//! #     }
+//! # }
+//! # impl DrawTarget for Ssd1306 {
+//! #     type Color = BinaryColor;
+//! #     type Error = core::convert::Infallible;
+//! #     fn draw_iter<I: IntoIterator<Item = Pixel<BinaryColor>>>(&mut self, _: I) -> Result<(), Self::Error> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # impl Ssd1306 {
+//! #     fn flush(&mut self) -> Result<(), core::convert::Infallible> {
+//! #         Ok(())
+//! #     }
+//! # }
+//! # let mut display = Ssd1306;
+//! let wifi_credentials = WifiCredentials::wpa2("gadget setup", "example1");
+//! let drawn = OledRenderer::new()
+//!     .draw(&wifi_credentials, &mut display)
+//!     .expect("Failed to draw the QR code.");
+//! display.flush().expect("Failed to send the frame to the display.");
+//! // Two pixels per module, centered on the screen.
+//! assert_eq!(Size::new(62, 62), drawn.size);
+//! ```
+
+use super::DisplayError;
+use crate::{QrCodeEcc, WifiCredentials};
+
+use embedded_graphics_core::pixelcolor::BinaryColor;
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
+
+/// The settings for showing a QR code on a monochrome OLED display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct OledRenderer {
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The narrowest the lit border around the code may be, in modules. The border is wider wherever the screen has room.
+    pub quiet_zone: u32,
+}
+
+impl OledRenderer {
+    /// A QR code using low error correction, which keeps the code small enough for two pixels per module on a 128 by 64 display with most credentials, and a quiet zone of at least 1 module. A screen does not get dirty or creased like a printed code, so the larger modules help scanners more than error correction would.
+    pub fn new() -> Self {
+        OledRenderer {
+            qr_code_error_checking: QrCodeEcc::Low,
+            quiet_zone: 1,
+        }
+    }
+
+    /// Light the whole display and draw the QR code of the network onto it, scaled to the largest whole number of pixels per module that fits and centered. Returns the rectangle with the code and its quiet zone.
+    pub fn draw<D: DrawTarget<Color = BinaryColor>>(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        display: &mut D,
+    ) -> Result<Rectangle, DisplayError<D::Error>> {
+        let bitmatrix = crate::encode_as_bitmatrix(wifi_credentials, self.qr_code_error_checking)?;
+        let area = display.bounding_box();
+        display
+            .clear(BinaryColor::On)
+            .map_err(DisplayError::Display)?;
+        super::draw_bitmatrix(
+            display,
+            &bitmatrix,
+            area,
+            self.quiet_zone,
+            BinaryColor::Off,
+            BinaryColor::On,
+        )
+    }
+}
+
+impl Default for OledRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::tests::Screen;
+
+    #[test]
+    fn it_lights_the_screen_around_the_code() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        let mut screen: Screen = Screen::new(128, 64);
+        let drawn = OledRenderer::new()
+            .draw(&wifi_credentials, &mut screen)
+            .unwrap();
+        assert_eq!(Rectangle::new(Point::new(33, 1), Size::new(62, 62)), drawn);
+        let bitmatrix = crate::encode_as_bitmatrix(&wifi_credentials, QrCodeEcc::Low).unwrap();
+        for y in 0..64 {
+            for x in 0..128 {
+                let module = ((x as i32 - 35).div_euclid(2), (y as i32 - 3).div_euclid(2));
+                let dark = module.0 >= 0
+                    && module.1 >= 0
+                    && bitmatrix.get(module.0 as usize, module.1 as usize);
+                assert_eq!(!dark, screen.pixel(x, y).is_on(), "{}, {}", x, y);
+            }
+        }
+
+        let mut screen: Screen = Screen::new(128, 32);
+        assert!(matches!(
+            OledRenderer::new().draw(&wifi_credentials, &mut screen),
+            Ok(Rectangle {
+                size: Size {
+                    width: 31,
+                    height: 31
+                },
+                ..
+            })
+        ));
+    }
+}
@@ -0,0 +1,191 @@
+//! QR codes on color TFT displays, such as the ST7789, ILI9341, and GC9A01 modules used in hobby projects, driven by [mipidsi](https://docs.rs/mipidsi) or any other embedded-graphics driver with RGB pixels.
+//!
+//! Most TFT drivers write to the display as they draw, without a frame buffer, so [`TftRenderer`] sets every pixel exactly once instead of clearing the screen first, which halves the time the update takes over SPI. The margin keeps the code clear of rounded corners and bezels that hide the edge of the panel, and round displays fit the code inside the circle.
+//!
+//! # Examples
+//!
+//! ```
+//! use wifi_qr_code::display::tft::TftRenderer;
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! use mipidsi::models::ILI9341Rgb565;
+//! use mipidsi::Builder;
+//!
+//! # let interface = mipidsi::_mock::MockDisplayInterface;
+//! # let mut delay = mipidsi::_mock::MockDelay;
+//! let mut display = Builder::new(ILI9341Rgb565, interface)
+//!     .init(&mut delay)
+//!     .expect("Failed to initialize the display.");
+//! let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+//! let drawn = TftRenderer::new()
+//!     .draw(&wifi_credentials, &mut display)
+//!     .expect("Failed to draw the QR code.");
+//! // The display is 240 by 320 pixels in portrait, so there is more room above and below the code than beside it.
+//! assert!(drawn.top_left.y > drawn.top_left.x);
+//! ```
+
+use super::DisplayError;
+use crate::{QrCodeEcc, WifiCredentials};
+
+use embedded_graphics_core::geometry::AnchorPoint;
+use embedded_graphics_core::pixelcolor::{Rgb888, RgbColor};
+use embedded_graphics_core::prelude::*;
+use embedded_graphics_core::primitives::Rectangle;
+
+/// The settings for showing a QR code on a color TFT display.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TftRenderer {
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The width of the border around the code in the light color, in modules.
+    pub quiet_zone: u32,
+    /// The pixels left in the background color at each edge of the screen, or of the square inside a round screen.
+    pub margin: u32,
+    /// Whether the screen is round, like GC9A01 displays, so the code has to fit inside the circle rather than the rectangle of the frame.
+    pub round: bool,
+    /// The color of the dark modules.
+    pub dark_color: Rgb888,
+    /// The color of the light modules and the quiet zone.
+    pub light_color: Rgb888,
+    /// The color of the rest of the screen.
+    pub background_color: Rgb888,
+}
+
+impl TftRenderer {
+    /// A black on white QR code using medium error correction, with the standard quiet zone of 4 modules, on a rectangular screen without a margin.
+    pub fn new() -> Self {
+        TftRenderer {
+            qr_code_error_checking: QrCodeEcc::Medium,
+            quiet_zone: 4,
+            margin: 0,
+            round: false,
+            dark_color: Rgb888::BLACK,
+            light_color: Rgb888::WHITE,
+            background_color: Rgb888::WHITE,
+        }
+    }
+
+    /// The part of a screen the code and its quiet zone are fitted into, after the margin and the shape of the screen are taken into account.
+    pub fn area(&self, screen: Rectangle) -> Rectangle {
+        let screen = if self.round {
+            let side = (f64::from(screen.size.width.min(screen.size.height))
+                / std::f64::consts::SQRT_2)
+                .floor() as u32;
+            screen.resized(Size::new_equal(side), AnchorPoint::Center)
+        } else {
+            screen
+        };
+        Rectangle::new(
+            screen.top_left + Point::new(self.margin as i32, self.margin as i32),
+            screen.size.saturating_sub(Size::new_equal(2 * self.margin)),
+        )
+    }
+
+    /// Draw the QR code of the network onto the display, scaled to the largest whole number of pixels per module that fits into [`TftRenderer::area`] and centered, with the rest of the screen in the background color. Returns the rectangle with the code and its quiet zone.
+    pub fn draw<D>(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        display: &mut D,
+    ) -> Result<Rectangle, DisplayError<D::Error>>
+    where
+        D: DrawTarget,
+        D::Color: From<Rgb888>,
+    {
+        let bitmatrix = crate::encode_as_bitmatrix(wifi_credentials, self.qr_code_error_checking)?;
+        let screen = display.bounding_box();
+        let drawn = super::draw_bitmatrix(
+            display,
+            &bitmatrix,
+            self.area(screen),
+            self.quiet_zone,
+            D::Color::from(self.dark_color),
+            D::Color::from(self.light_color),
+        )?;
+        for strip in &surroundings(screen, drawn) {
+            display
+                .fill_solid(strip, D::Color::from(self.background_color))
+                .map_err(DisplayError::Display)?;
+        }
+        Ok(drawn)
+    }
+}
+
+impl Default for TftRenderer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// The strips of the screen above, below, left of, and right of a rectangle inside it, which together cover the rest of the screen without overlapping. Strips may be empty.
+fn surroundings(screen: Rectangle, inner: Rectangle) -> [Rectangle; 4] {
+    let top = (inner.top_left.y - screen.top_left.y) as u32;
+    let bottom = screen.size.height - top - inner.size.height;
+    let left = (inner.top_left.x - screen.top_left.x) as u32;
+    let right = screen.size.width - left - inner.size.width;
+    [
+        Rectangle::new(screen.top_left, Size::new(screen.size.width, top)),
+        Rectangle::new(
+            Point::new(
+                screen.top_left.x,
+                inner.top_left.y + inner.size.height as i32,
+            ),
+            Size::new(screen.size.width, bottom),
+        ),
+        Rectangle::new(
+            Point::new(screen.top_left.x, inner.top_left.y),
+            Size::new(left, inner.size.height),
+        ),
+        Rectangle::new(
+            Point::new(inner.top_left.x + inner.size.width as i32, inner.top_left.y),
+            Size::new(right, inner.size.height),
+        ),
+    ]
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::display::tests::Screen;
+
+    use embedded_graphics_core::pixelcolor::Rgb565;
+
+    #[test]
+    fn it_fits_the_code_inside_round_screens() {
+        let mut renderer = TftRenderer::new();
+        renderer.round = true;
+        renderer.margin = 4;
+        renderer.background_color = Rgb888::new(0, 0, 0xff);
+        let screen = Rectangle::new(Point::zero(), Size::new_equal(240));
+        // The largest square inside the circle is 169 pixels wide.
+        assert_eq!(
+            Rectangle::new(Point::new(39, 39), Size::new_equal(161)),
+            renderer.area(screen)
+        );
+
+        let mut screen: Screen<Rgb565> = Screen::new(240, 240);
+        let drawn = renderer
+            .draw(
+                &WifiCredentials::wpa2("test ssid", "test password"),
+                &mut screen,
+            )
+            .unwrap();
+        // 33 modules and the quiet zone fit 3 pixels per module.
+        assert_eq!(
+            Rectangle::new(Point::new(58, 58), Size::new_equal(123)),
+            drawn
+        );
+        let background = Rgb565::from(Rgb888::new(0, 0, 0xff));
+        for (x, y) in &[
+            (0, 0),
+            (239, 239),
+            (57, 120),
+            (181, 120),
+            (120, 57),
+            (120, 181),
+        ] {
+            assert_eq!(background, screen.pixel(*x, *y), "{}, {}", x, y);
+        }
+        assert_eq!(Rgb565::WHITE, screen.pixel(58, 58));
+        assert_eq!(Rgb565::BLACK, screen.pixel(70, 70));
+    }
+}
@@ -0,0 +1,413 @@
+//! Wi-Fi Easy Connect (also known as the Device Provisioning Protocol, or DPP) bootstrap URIs.
+//!
+//! Rather than sharing a password, a device that supports Easy Connect displays a `DPP:` URI containing its public key. Scanning that code with a configurator (such as a phone) lets the configurator securely send network credentials to the device. The URI can be rendered with the same `encode_as_*` functions as [`WifiCredentials`](crate::WifiCredentials). URIs read from device labels can be parsed back with [`DppCredentials::parse`].
+
+use crate::{CredentialField, EncodedPayload, QrPayload, Secret, ValidationError};
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// A channel the device listens on for DPP authentication, written as `<operating class>/<channel>`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Channel {
+    /// The global operating class, such as `81` for 2.4 GHz channels.
+    pub operating_class: u8,
+    /// The channel number within the operating class.
+    pub channel: u8,
+}
+
+/// The bootstrapping information a device advertises for Wi-Fi Easy Connect.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DppCredentials {
+    /// The base64 encoded DER `SubjectPublicKeyInfo` of the device's bootstrapping key. This is the only required field.
+    pub public_key: String,
+    /// The channels the device listens on. Configurators scan every channel when this is empty.
+    pub channels: Vec<Channel>,
+    /// The MAC address of the device.
+    pub mac_address: Option<[u8; 6]>,
+    /// Free-form information about the device, such as a serial number. This may only contain printable ASCII characters other than semicolons.
+    pub information: Option<String>,
+    /// The version of the DPP protocol the device supports.
+    pub version: Option<u8>,
+}
+
+impl DppCredentials {
+    /// Encode the bootstrapping information as a `DPP:` URI, in the same [`EncodedPayload`] as the credentials of other payloads.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::dpp::{Channel, DppCredentials};
+    ///
+    /// let dpp_credentials = DppCredentials {
+    ///     public_key: String::from("MDkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDIgADM2206avxHJaHXgLMkq/24e0rsrfMP9K1Tm8gx+ovP0I="),
+    ///     channels: vec![Channel { operating_class: 81, channel: 1 }],
+    ///     mac_address: Some([0x5c, 0xff, 0x35, 0x7a, 0x08, 0x1e]),
+    ///     information: None,
+    ///     version: Some(2),
+    /// };
+    /// assert_eq!(
+    ///     "DPP:C:81/1;M:5cff357a081e;V:2;K:MDkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDIgADM2206avxHJaHXgLMkq/24e0rsrfMP9K1Tm8gx+ovP0I=;;",
+    ///     dpp_credentials.encode().as_str()
+    /// );
+    /// ```
+    pub fn encode(&self) -> EncodedPayload {
+        EncodedPayload::new(Secret::format(format_args!(
+            "DPP:{}{}{}{}K:{};;",
+            self.encode_channels(),
+            self.encode_mac_address(),
+            self.encode_information(),
+            self.encode_version(),
+            self.public_key
+        )))
+    }
+
+    /// Check that the public key is present and that neither it nor the information would end their fields of the URI early, as the `encode_as_*` functions do before rendering it.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::dpp::DppCredentials;
+    /// use wifi_qr_code::{CredentialField, ValidationError};
+    ///
+    /// let mut dpp_credentials = DppCredentials::parse("DPP:I:serial 1234;K:MDkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDIgADM2206avxHJaHXgLMkq/24e0rsrfMP9K1Tm8gx+ovP0I=;;")
+    ///     .expect("Failed to parse example URI.");
+    /// assert_eq!(Ok(()), dpp_credentials.validate());
+    /// dpp_credentials.information = Some(String::from("serial;1234"));
+    /// assert_eq!(
+    ///     Err(ValidationError::DppCharacters(CredentialField::Information)),
+    ///     dpp_credentials.validate()
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.public_key.is_empty() {
+            return Err(ValidationError::EmptyPublicKey);
+        }
+        if !self
+            .public_key
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '/' | '='))
+        {
+            return Err(ValidationError::DppCharacters(CredentialField::PublicKey));
+        }
+        if let Some(information) = &self.information {
+            if !information
+                .chars()
+                .all(|c| (' '..='~').contains(&c) && c != ';')
+            {
+                return Err(ValidationError::DppCharacters(CredentialField::Information));
+            }
+        }
+        Ok(())
+    }
+
+    fn encode_channels(&self) -> String {
+        if self.channels.is_empty() {
+            return String::new();
+        }
+        let channels: Vec<String> = self
+            .channels
+            .iter()
+            .map(|channel| format!("{}/{}", channel.operating_class, channel.channel))
+            .collect();
+        format!("C:{};", channels.join(","))
+    }
+
+    fn encode_mac_address(&self) -> String {
+        match &self.mac_address {
+            Some(mac_address) => {
+                let mac_address: String = mac_address
+                    .iter()
+                    .map(|byte| format!("{:02x}", byte))
+                    .collect();
+                format!("M:{};", mac_address)
+            }
+            None => String::new(),
+        }
+    }
+
+    fn encode_information(&self) -> String {
+        match &self.information {
+            Some(information) => format!("I:{};", information),
+            None => String::new(),
+        }
+    }
+
+    fn encode_version(&self) -> String {
+        match self.version {
+            Some(version) => format!("V:{};", version),
+            None => String::new(),
+        }
+    }
+}
+
+/// Errors when parsing a `DPP:` URI with [`DppCredentials::parse`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum DppParseError {
+    /// The URI does not start with `DPP:`.
+    MissingPrefix,
+    /// The URI does not end with the `;;` that terminates the last field and the URI.
+    MissingTerminator,
+    /// The URI has no `K:` field, or it is empty.
+    MissingPublicKey,
+    /// The same field appeared twice. Contains the key of the field.
+    DuplicateField(String),
+    /// A channel list, MAC address, or version could not be read.
+    InvalidField {
+        /// The key of the field.
+        key: String,
+        /// The value that could not be read.
+        value: String,
+    },
+}
+
+impl Display for DppParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPrefix => f.write_str("URI does not start with DPP:"),
+            Self::MissingTerminator => f.write_str("URI does not end with ;;"),
+            Self::MissingPublicKey => f.write_str("URI does not contain a public key"),
+            Self::DuplicateField(key) => write!(f, "the {}: field appears more than once", key),
+            Self::InvalidField { key, value } => write!(f, "invalid {}: field {:?}", key, value),
+        }
+    }
+}
+
+impl Error for DppParseError {}
+
+impl DppCredentials {
+    /// Parse a `DPP:` URI, such as the text of a QR code on a device, into its bootstrapping information. Fields this crate does not know about are ignored, as the Easy Connect specification requires, and MAC addresses may be written with `:` or `-` separators.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::dpp::{Channel, DppCredentials};
+    ///
+    /// let dpp_credentials = DppCredentials::parse("DPP:C:81/1,115/36;M:5c:ff:35:7a:08:1e;K:MDkwEwYHKoZIzj0CAQYIKoZIzj0DAQcDIgADM2206avxHJaHXgLMkq/24e0rsrfMP9K1Tm8gx+ovP0I=;;")
+    ///     .expect("Failed to parse example URI.");
+    /// assert_eq!(Channel { operating_class: 115, channel: 36 }, dpp_credentials.channels[1]);
+    /// assert_eq!(Some([0x5c, 0xff, 0x35, 0x7a, 0x08, 0x1e]), dpp_credentials.mac_address);
+    /// assert_eq!(None, dpp_credentials.version);
+    /// ```
+    pub fn parse(uri: &str) -> Result<Self, DppParseError> {
+        let body = uri
+            .strip_prefix("DPP:")
+            .ok_or(DppParseError::MissingPrefix)?
+            .strip_suffix(";;")
+            .ok_or(DppParseError::MissingTerminator)?;
+        let mut dpp_credentials = DppCredentials {
+            public_key: String::new(),
+            channels: Vec::new(),
+            mac_address: None,
+            information: None,
+            version: None,
+        };
+        let mut seen = Vec::new();
+        for field in body.split(';') {
+            let (key, value) = field.split_once(':').unwrap_or((field, ""));
+            if seen.contains(&key) {
+                return Err(DppParseError::DuplicateField(String::from(key)));
+            }
+            seen.push(key);
+            let invalid = || DppParseError::InvalidField {
+                key: String::from(key),
+                value: String::from(value),
+            };
+            match key {
+                "C" => dpp_credentials.channels = parse_channels(value).ok_or_else(invalid)?,
+                "M" => {
+                    dpp_credentials.mac_address =
+                        Some(parse_mac_address(value).ok_or_else(invalid)?)
+                }
+                "I" => dpp_credentials.information = Some(String::from(value)),
+                "V" => dpp_credentials.version = Some(value.parse().map_err(|_| invalid())?),
+                "K" => dpp_credentials.public_key = String::from(value),
+                _ => {}
+            }
+        }
+        if dpp_credentials.public_key.is_empty() {
+            return Err(DppParseError::MissingPublicKey);
+        }
+        Ok(dpp_credentials)
+    }
+}
+
+impl FromStr for DppCredentials {
+    type Err = DppParseError;
+
+    fn from_str(uri: &str) -> Result<Self, Self::Err> {
+        DppCredentials::parse(uri)
+    }
+}
+
+fn parse_channels(value: &str) -> Option<Vec<Channel>> {
+    value
+        .split(',')
+        .map(|channel| {
+            let (operating_class, channel) = channel.split_once('/')?;
+            Some(Channel {
+                operating_class: operating_class.parse().ok()?,
+                channel: channel.parse().ok()?,
+            })
+        })
+        .collect()
+}
+
+fn parse_mac_address(value: &str) -> Option<[u8; 6]> {
+    let digits: String = value.chars().filter(|c| !matches!(c, ':' | '-')).collect();
+    if digits.len() != 12 || !digits.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+        return None;
+    }
+    let mut mac_address = [0u8; 6];
+    for (byte, digits) in mac_address.iter_mut().zip(digits.as_bytes().chunks(2)) {
+        *byte = u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok()?;
+    }
+    Some(mac_address)
+}
+
+impl QrPayload for DppCredentials {
+    fn encode(&self) -> String {
+        DppCredentials::encode(self).into_string()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        DppCredentials::validate(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_encodes_only_the_public_key_when_nothing_else_is_known() {
+        let dpp_credentials = DppCredentials {
+            public_key: String::from("dGVzdCBrZXk="),
+            channels: Vec::new(),
+            mac_address: None,
+            information: None,
+            version: None,
+        };
+        assert_eq!("DPP:K:dGVzdCBrZXk=;;", dpp_credentials.encode());
+    }
+
+    #[test]
+    fn it_renders_as_a_qr_code() {
+        let dpp_credentials = DppCredentials {
+            public_key: String::from("dGVzdCBrZXk="),
+            channels: Vec::new(),
+            mac_address: None,
+            information: None,
+            version: None,
+        };
+        let matrix = crate::QrCodeBuilder::new(&dpp_credentials, crate::QrCodeEcc::Medium)
+            .matrix()
+            .expect("DPP URI should fit in a QR code.");
+        assert_eq!(25, matrix.len());
+
+        let unterminated = DppCredentials {
+            public_key: String::new(),
+            information: Some(String::from("test;information")),
+            ..dpp_credentials.clone()
+        };
+        assert!(matches!(
+            crate::QrCodeBuilder::new(&unterminated, crate::QrCodeEcc::Medium).matrix(),
+            Err(crate::Error::Validation(ValidationError::EmptyPublicKey))
+        ));
+        let unterminated = DppCredentials {
+            public_key: String::from("dGVzdCBrZXk=;C:81/1"),
+            ..dpp_credentials
+        };
+        assert_eq!(
+            Err(ValidationError::DppCharacters(CredentialField::PublicKey)),
+            unterminated.validate()
+        );
+    }
+
+    #[test]
+    fn it_parses_what_it_encodes() {
+        let dpp_credentials = DppCredentials {
+            public_key: String::from("dGVzdCBrZXk="),
+            channels: vec![Channel {
+                operating_class: 81,
+                channel: 6,
+            }],
+            mac_address: Some([0xab, 1, 2, 3, 4, 0xcd]),
+            information: Some(String::from("test information")),
+            version: Some(3),
+        };
+        assert_eq!(
+            Ok(&dpp_credentials),
+            dpp_credentials
+                .encode()
+                .as_str()
+                .parse::<DppCredentials>()
+                .as_ref()
+        );
+        let minimal = DppCredentials::parse("DPP:X:ignored;K:dGVzdCBrZXk=;;").unwrap();
+        assert_eq!("DPP:K:dGVzdCBrZXk=;;", minimal.encode());
+    }
+
+    #[test]
+    fn it_rejects_malformed_uris() {
+        let invalid = |key: &str, value: &str| {
+            Err(DppParseError::InvalidField {
+                key: String::from(key),
+                value: String::from(value),
+            })
+        };
+        assert_eq!(
+            Err(DppParseError::MissingPrefix),
+            DppCredentials::parse("WIFI:K:key;;")
+        );
+        assert_eq!(
+            Err(DppParseError::MissingTerminator),
+            DppCredentials::parse("DPP:K:key;")
+        );
+        assert_eq!(
+            Err(DppParseError::MissingPublicKey),
+            DppCredentials::parse("DPP:C:81/1;;")
+        );
+        assert_eq!(
+            Err(DppParseError::DuplicateField(String::from("K"))),
+            DppCredentials::parse("DPP:K:key;K:other key;;")
+        );
+        assert_eq!(
+            invalid("C", "81/x"),
+            DppCredentials::parse("DPP:C:81/x;K:key;;")
+        );
+        assert_eq!(
+            invalid("M", "0102"),
+            DppCredentials::parse("DPP:M:0102;K:key;;")
+        );
+        assert_eq!(
+            invalid("V", "300"),
+            DppCredentials::parse("DPP:V:300;K:key;;")
+        );
+    }
+
+    #[test]
+    fn it_encodes_every_field() {
+        let dpp_credentials = DppCredentials {
+            public_key: String::from("dGVzdCBrZXk="),
+            channels: vec![
+                Channel {
+                    operating_class: 81,
+                    channel: 1,
+                },
+                Channel {
+                    operating_class: 115,
+                    channel: 36,
+                },
+            ],
+            mac_address: Some([0, 1, 2, 3, 4, 5]),
+            information: Some(String::from("test information")),
+            version: Some(2),
+        };
+        assert_eq!(
+            "DPP:C:81/1,115/36;M:000102030405;I:test information;V:2;K:dGVzdCBrZXk=;;",
+            dpp_credentials.encode()
+        );
+    }
+}
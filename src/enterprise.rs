@@ -0,0 +1,357 @@
+//! WPA-Enterprise (802.1X) networks, which authenticate each user with an EAP method instead of sharing one password.
+//!
+//! Android and ZXing describe them with the `E:` (EAP method), `PH2:` (phase 2 method), `A:` (anonymous identity), and `I:` (identity) fields alongside the usual `WIFI:` fields. Scanners disagree on which other fields they support, so fields this crate does not know about are kept as they were written and encoded again unchanged.
+
+use crate::escaping::write_value;
+use crate::parse::{parse_visibility, split_fields, unescape};
+use crate::{
+    CredentialField, EncodedPayload, EscapingMode, ParseError, QrPayload, Secret, ValidationError,
+    Visibility,
+};
+
+use std::fmt::{self, Debug, Formatter, Write};
+
+/// The credentials for joining a WPA-Enterprise network.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct EnterpriseCredentials {
+    /// The SSID of the network. It must be between 1 and 32 bytes long when encoded as UTF-8.
+    pub ssid: String,
+    /// The `T:` field, usually `WPA2-EAP`.
+    pub authentication_type: String,
+    /// The EAP method, such as `PEAP`, `TTLS`, `TLS`, or `PWD`.
+    pub eap_method: String,
+    /// The inner authentication method for tunneled EAP methods, such as `MSCHAPV2` or `GTC`.
+    pub phase2_method: Option<String>,
+    /// The identity sent before the tunnel is established, hiding the real identity from eavesdroppers.
+    pub anonymous_identity: Option<String>,
+    /// The user name to authenticate as.
+    pub identity: Option<String>,
+    /// The password for the identity, which is wiped from memory with the `zeroize` feature.
+    pub password: Option<Secret<String>>,
+    /// Whether the network broadcasts its SSID.
+    pub visibility: Visibility,
+    /// Fields this crate does not know about, as pairs of keys and values still escaped the way they were written. [`EnterpriseCredentials::validate`] checks that the keys are letters and digits, and that the values have no unescaped `;`, so they cannot add other fields to the payload.
+    pub unknown_fields: Vec<(String, String)>,
+}
+
+impl EnterpriseCredentials {
+    /// Encode the credentials as a `WIFI:` payload, like [`WifiCredentials::encode`](crate::WifiCredentials::encode).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::enterprise::EnterpriseCredentials;
+    /// use wifi_qr_code::Visibility;
+    ///
+    /// let enterprise_credentials = EnterpriseCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: String::from("WPA2-EAP"),
+    ///     eap_method: String::from("PEAP"),
+    ///     phase2_method: Some(String::from("MSCHAPV2")),
+    ///     anonymous_identity: None,
+    ///     identity: Some(String::from("example identity")),
+    ///     password: Some("example password".into()),
+    ///     visibility: Visibility::Visible,
+    ///     unknown_fields: Vec::new(),
+    /// };
+    /// assert_eq!(
+    ///     "WIFI:S:example ssid;T:WPA2-EAP;E:PEAP;PH2:MSCHAPV2;I:example identity;P:example password;H:false;;",
+    ///     enterprise_credentials.encode().as_str()
+    /// );
+    /// ```
+    pub fn encode(&self) -> EncodedPayload {
+        EncodedPayload::new(Secret::write_with(|writer| self.encode_to(writer)))
+    }
+
+    fn encode_to(&self, mut writer: &mut dyn Write) -> fmt::Result {
+        writer.write_str("WIFI:")?;
+        let fields = [
+            ("S", Some(self.ssid.as_str())),
+            ("T", Some(self.authentication_type.as_str())),
+            ("E", Some(self.eap_method.as_str())),
+            ("PH2", self.phase2_method.as_deref()),
+            ("A", self.anonymous_identity.as_deref()),
+            ("I", self.identity.as_deref()),
+            ("P", self.password.as_deref().map(String::as_str)),
+        ];
+        for (key, value) in fields {
+            if let Some(value) = value {
+                write!(writer, "{}:", key)?;
+                write_value(&mut writer, value, EscapingMode::Spec)?;
+                writer.write_char(';')?;
+            }
+        }
+        writer.write_str(self.visibility.encode())?;
+        for (key, raw_value) in &self.unknown_fields {
+            write!(writer, "{}:{};", key, raw_value)?;
+        }
+        writer.write_char(';')
+    }
+
+    /// Parse a `WIFI:` payload that contains an `E:` field. Fields may appear in any order and with lowercase keys, like [`WifiCredentials::parse_lenient`](crate::WifiCredentials::parse_lenient), and unknown fields are kept in [`EnterpriseCredentials::unknown_fields`]. A missing `T:` field is taken to be `WPA2-EAP`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::enterprise::EnterpriseCredentials;
+    ///
+    /// let enterprise_credentials = EnterpriseCredentials::parse(
+    ///     "WIFI:T:WPA2-EAP;S:example ssid;E:TTLS;PH2:PAP;I:example identity;P:example password;R:1;;",
+    /// )
+    /// .expect("Failed to parse example payload.");
+    /// assert_eq!("TTLS", enterprise_credentials.eap_method);
+    /// assert_eq!(Some("PAP"), enterprise_credentials.phase2_method.as_deref());
+    /// assert_eq!(vec![(String::from("R"), String::from("1"))], enterprise_credentials.unknown_fields);
+    /// ```
+    pub fn parse(payload: &str) -> Result<Self, ParseError> {
+        let mut ssid = None;
+        let mut authentication_type = None;
+        let mut eap_method = None;
+        let mut phase2_method = None;
+        let mut anonymous_identity = None;
+        let mut identity = None;
+        let mut password = None;
+        let mut hidden = None;
+        let mut unknown_fields = Vec::new();
+        for field in split_fields(payload.trim(), true)? {
+            let slot = match field.key.to_ascii_uppercase().as_str() {
+                "S" => &mut ssid,
+                "T" => &mut authentication_type,
+                "E" => &mut eap_method,
+                "PH2" => &mut phase2_method,
+                "A" => &mut anonymous_identity,
+                "I" => &mut identity,
+                "P" => &mut password,
+                "H" => &mut hidden,
+                _ => {
+                    unknown_fields.push((String::from(field.key), String::from(field.raw_value)));
+                    continue;
+                }
+            };
+            if slot.replace(field.raw_value).is_some() {
+                return Err(ParseError::DuplicateField(String::from(field.key)));
+            }
+        }
+
+        let visibility = match hidden.map(str::to_ascii_lowercase).as_deref() {
+            None | Some("") => Visibility::Visible,
+            Some(hidden) => parse_visibility(hidden)?,
+        };
+        Ok(EnterpriseCredentials {
+            ssid: unescape(ssid.ok_or(ParseError::MissingField("S"))?),
+            authentication_type: authentication_type
+                .map(unescape)
+                .unwrap_or_else(|| String::from("WPA2-EAP")),
+            eap_method: unescape(eap_method.ok_or(ParseError::MissingField("E"))?),
+            phase2_method: phase2_method.map(unescape),
+            anonymous_identity: anonymous_identity.map(unescape),
+            identity: identity.map(unescape),
+            password: password.map(|password| Secret::new(unescape(password))),
+            visibility,
+            unknown_fields,
+        })
+    }
+
+    /// Check that the SSID meets the requirements of the wifi standards, that no field contains control characters, and that the [`EnterpriseCredentials::unknown_fields`] are escaped. Enterprise passwords are otherwise checked by the authentication server, so they are not restricted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::enterprise::EnterpriseCredentials;
+    /// use wifi_qr_code::ValidationError;
+    ///
+    /// let mut enterprise_credentials =
+    ///     EnterpriseCredentials::parse("WIFI:S:example ssid;E:PEAP;I:example identity;R:1;;")
+    ///         .expect("Failed to parse example payload.");
+    /// assert_eq!(Ok(()), enterprise_credentials.validate());
+    /// enterprise_credentials.unknown_fields[0].1 = String::from("1;P:other password");
+    /// assert_eq!(
+    ///     Err(ValidationError::UnknownFieldFormat),
+    ///     enterprise_credentials.validate()
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        if self.ssid.is_empty() {
+            return Err(ValidationError::EmptySsid);
+        }
+        if self.ssid.len() > 32 {
+            return Err(ValidationError::SsidLength(self.ssid.len()));
+        }
+        let fields = [
+            (CredentialField::Ssid, Some(self.ssid.as_str())),
+            (
+                CredentialField::Method,
+                Some(self.authentication_type.as_str()),
+            ),
+            (CredentialField::Method, Some(self.eap_method.as_str())),
+            (CredentialField::Method, self.phase2_method.as_deref()),
+            (
+                CredentialField::Identity,
+                self.anonymous_identity.as_deref(),
+            ),
+            (CredentialField::Identity, self.identity.as_deref()),
+            (
+                CredentialField::Password,
+                self.password.as_deref().map(String::as_str),
+            ),
+        ];
+        let unknown_values = self
+            .unknown_fields
+            .iter()
+            .map(|(_, raw_value)| (CredentialField::UnknownFields, Some(raw_value.as_str())));
+        for (field, value) in fields.iter().copied().chain(unknown_values) {
+            if value.is_some_and(|value| value.chars().any(char::is_control)) {
+                return Err(ValidationError::ControlCharacter(field));
+            }
+        }
+        if self
+            .unknown_fields
+            .iter()
+            .all(|(key, raw_value)| is_field_key(key) && is_escaped(raw_value))
+        {
+            Ok(())
+        } else {
+            Err(ValidationError::UnknownFieldFormat)
+        }
+    }
+}
+
+/// Whether the key of a field is made of letters and digits, like `S` or `PH2`, rather than containing the `:` or `;` that would split it.
+fn is_field_key(key: &str) -> bool {
+    !key.is_empty() && key.chars().all(|c| c.is_ascii_alphanumeric())
+}
+
+/// Whether a raw value ends inside its field: every `;` is escaped, and it does not end with a backslash that would escape the `;` written after it.
+fn is_escaped(raw_value: &str) -> bool {
+    let mut escaped = false;
+    for c in raw_value.chars() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ';' => return false,
+            _ => {}
+        }
+    }
+    !escaped
+}
+
+impl QrPayload for EnterpriseCredentials {
+    fn encode(&self) -> String {
+        EnterpriseCredentials::encode(self).into_string()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        EnterpriseCredentials::validate(self)
+    }
+}
+
+/// Redacts the password, like the `Debug` implementation of [`WifiCredentials`](crate::WifiCredentials).
+impl Debug for EnterpriseCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EnterpriseCredentials")
+            .field("ssid", &self.ssid)
+            .field("authentication_type", &self.authentication_type)
+            .field("eap_method", &self.eap_method)
+            .field("phase2_method", &self.phase2_method)
+            .field("anonymous_identity", &self.anonymous_identity)
+            .field("identity", &self.identity)
+            .field(
+                "password",
+                &self.password.as_ref().map(|_| format_args!("<redacted>")),
+            )
+            .field("visibility", &self.visibility)
+            .field("unknown_fields", &self.unknown_fields)
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_what_it_encodes_including_unknown_fields() {
+        let payload = r#"wifi:e:PEAP;S:test\;ssid;A:anonymous;I:test identity;P:test\:password;X:raw\,value;H:true"#;
+        let enterprise_credentials =
+            EnterpriseCredentials::parse(payload).expect("Payload should parse.");
+        assert_eq!(
+            EnterpriseCredentials {
+                ssid: String::from("test;ssid"),
+                authentication_type: String::from("WPA2-EAP"),
+                eap_method: String::from("PEAP"),
+                phase2_method: None,
+                anonymous_identity: Some(String::from("anonymous")),
+                identity: Some(String::from("test identity")),
+                password: Some("test:password".into()),
+                visibility: Visibility::Hidden,
+                unknown_fields: vec![(String::from("X"), String::from(r"raw\,value"))],
+            },
+            enterprise_credentials
+        );
+        let encoded = enterprise_credentials.encode();
+        assert_eq!(
+            r"WIFI:S:test\;ssid;T:WPA2-EAP;E:PEAP;A:anonymous;I:test identity;P:test\:password;H:true;X:raw\,value;;",
+            encoded
+        );
+        assert_eq!(
+            Ok(&enterprise_credentials),
+            EnterpriseCredentials::parse(encoded.as_str()).as_ref()
+        );
+    }
+
+    #[test]
+    fn it_rejects_payloads_without_an_eap_method() {
+        assert_eq!(
+            Err(ParseError::MissingField("E")),
+            EnterpriseCredentials::parse("WIFI:S:ssid;T:WPA;P:password;;")
+        );
+        assert_eq!(
+            Err(ParseError::DuplicateField(String::from("E"))),
+            EnterpriseCredentials::parse("WIFI:S:ssid;E:PEAP;E:TLS;;")
+        );
+    }
+
+    #[test]
+    fn it_rejects_fields_that_would_change_the_payload() {
+        let enterprise_credentials =
+            EnterpriseCredentials::parse(r"WIFI:S:ssid;E:TLS;X:a\;b;Y:;;").unwrap();
+        assert_eq!(Ok(()), enterprise_credentials.validate());
+        for unknown_field in [("X", "a;P:b"), ("X", r"ab\"), ("X:P", "b"), ("", "b")] {
+            let enterprise_credentials = EnterpriseCredentials {
+                unknown_fields: vec![(
+                    String::from(unknown_field.0),
+                    String::from(unknown_field.1),
+                )],
+                ..enterprise_credentials.clone()
+            };
+            assert_eq!(
+                Err(ValidationError::UnknownFieldFormat),
+                enterprise_credentials.validate(),
+                "{:?}",
+                unknown_field
+            );
+        }
+        let enterprise_credentials = EnterpriseCredentials {
+            identity: Some(String::from("user\nname")),
+            ..enterprise_credentials
+        };
+        assert_eq!(
+            Err(ValidationError::ControlCharacter(CredentialField::Identity)),
+            enterprise_credentials.validate()
+        );
+    }
+
+    #[test]
+    fn it_redacts_the_password_and_renders() {
+        let enterprise_credentials =
+            EnterpriseCredentials::parse("WIFI:S:ssid;E:PWD;I:user;P:secret;;").unwrap();
+        let debug = format!("{:?}", enterprise_credentials);
+        assert!(debug.contains(r#"password: Some(<redacted>)"#));
+        assert!(!debug.contains("secret"));
+        assert!(
+            crate::QrCodeBuilder::new(&enterprise_credentials, crate::QrCodeEcc::Medium)
+                .matrix()
+                .is_ok()
+        );
+    }
+}
@@ -0,0 +1,283 @@
+#[cfg(feature = "std")]
+use qrcode_generator::QRCodeError;
+
+use core::error;
+use core::fmt::{self, Display, Formatter};
+#[cfg(feature = "std")]
+use std::io;
+
+/// A `Result` whose error defaults to this crate's [`Error`].
+#[cfg(feature = "std")]
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Errors when encoding credentials as a QR code.
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum Error {
+    /// The credentials do not meet the requirements of the wifi standards, so devices would fail to join the network.
+    Validation(ValidationError),
+    /// The QR code could not be generated.
+    QrCode(QRCodeError),
+    /// The QR code was generated, but writing it out failed.
+    Io {
+        /// The part of the output that was being written.
+        stage: OutputStage,
+        /// The underlying error, e.g. from the file system.
+        source: io::Error,
+    },
+    /// The QR code version bounds in [`EncodeOptions`](crate::EncodeOptions) are outside of 1 to 40, or the minimum is above the maximum.
+    VersionRange {
+        /// The requested minimum version.
+        min: u8,
+        /// The requested maximum version.
+        max: u8,
+    },
+    /// The mask pattern in [`EncodeOptions`](crate::EncodeOptions) is not from 0 to 7. Contains the requested pattern.
+    MaskPattern(u8),
+}
+
+#[cfg(feature = "std")]
+impl Display for Error {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(error) => write!(f, "invalid credentials: {}", error),
+            Self::QrCode(error) => Display::fmt(error, f),
+            Self::Io { stage, source } => write!(f, "failed to {}: {}", stage, source),
+            Self::VersionRange { min, max } => write!(
+                f,
+                "QR code versions must be from 1 to 40, but the range is {} to {}",
+                min, max
+            ),
+            Self::MaskPattern(pattern) => write!(
+                f,
+                "QR code mask patterns must be from 0 to 7, but {} was requested",
+                pattern
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl error::Error for Error {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Validation(error) => Some(error),
+            Self::QrCode(error) => Some(error),
+            Self::Io { source, .. } => Some(source),
+            Self::VersionRange { .. } | Self::MaskPattern(_) => None,
+        }
+    }
+}
+
+/// Formats the error like its `Display` implementation. The errors of the underlying QR code library and of I/O have no `defmt` support, so they are formatted with `core::fmt`.
+#[cfg(all(feature = "defmt", feature = "std"))]
+impl defmt::Format for Error {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        match self {
+            Self::Validation(error) => defmt::write!(f, "invalid credentials: {}", error),
+            Self::QrCode(error) => defmt::write!(f, "{}", defmt::Display2Format(error)),
+            Self::Io { stage, source } => defmt::write!(
+                f,
+                "failed at the {} stage: {}",
+                stage,
+                defmt::Display2Format(source)
+            ),
+            Self::VersionRange { min, max } => defmt::write!(
+                f,
+                "QR code versions must be from 1 to 40, but the range is {=u8} to {=u8}",
+                min,
+                max
+            ),
+            Self::MaskPattern(pattern) => defmt::write!(
+                f,
+                "QR code mask patterns must be from 0 to 7, but {=u8} was requested",
+                pattern
+            ),
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<ValidationError> for Error {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+#[cfg(feature = "std")]
+impl From<QRCodeError> for Error {
+    fn from(error: QRCodeError) -> Self {
+        Self::QrCode(error)
+    }
+}
+
+/// The parts of the output that can fail to be written, reported by [`Error::Io`].
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[non_exhaustive]
+pub enum OutputStage {
+    /// Creating the file to write the image to.
+    CreateFile,
+    /// Encoding and writing a PNG image.
+    Png,
+    /// Writing an SVG image.
+    Svg,
+    /// Writing a configuration file exported alongside the image.
+    ConfigFile,
+    /// Writing a PDF document.
+    Pdf,
+    /// Writing an HTML page.
+    Html,
+    /// Drawing onto a framebuffer device.
+    Framebuffer,
+}
+
+#[cfg(feature = "std")]
+impl Display for OutputStage {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::CreateFile => "create the output file",
+            Self::Png => "write the PNG image",
+            Self::Svg => "write the SVG image",
+            Self::ConfigFile => "write the configuration file",
+            Self::Pdf => "write the PDF document",
+            Self::Html => "write the HTML page",
+            Self::Framebuffer => "draw onto the framebuffer",
+        })
+    }
+}
+
+/// The part of the credentials a [`ValidationError`] is about.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum CredentialField {
+    /// The network name.
+    Ssid,
+    /// The WPA passphrase, pre-shared key, or WEP key.
+    Password,
+    /// The public key of a [`DppCredentials`](crate::dpp::DppCredentials) bootstrap URI.
+    PublicKey,
+    /// The device information of a [`DppCredentials`](crate::dpp::DppCredentials) bootstrap URI.
+    Information,
+    /// The network interface passed to an exporter such as [`systemd::encode`](crate::export::systemd::encode).
+    Interface,
+    /// The authentication type, EAP method, or phase 2 method of [`EnterpriseCredentials`](crate::enterprise::EnterpriseCredentials).
+    Method,
+    /// The identity or anonymous identity of [`EnterpriseCredentials`](crate::enterprise::EnterpriseCredentials).
+    Identity,
+    /// The [`EnterpriseCredentials::unknown_fields`](crate::enterprise::EnterpriseCredentials::unknown_fields).
+    UnknownFields,
+}
+
+impl Display for CredentialField {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Ssid => "SSID",
+            Self::Password => "password",
+            Self::PublicKey => "public key",
+            Self::Information => "device information",
+            Self::Interface => "interface name",
+            Self::Method => "EAP method",
+            Self::Identity => "identity",
+            Self::UnknownFields => "unknown fields",
+        })
+    }
+}
+
+/// Reasons credentials do not meet the requirements of the wifi standards.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum ValidationError {
+    /// SSIDs must contain at least one character.
+    EmptySsid,
+    /// SSIDs may be at most 32 bytes long when encoded as UTF-8. Contains the length of the rejected SSID in bytes.
+    SsidLength(usize),
+    /// WPA passphrases must be between 8 and 63 characters long. Contains the length of the rejected passphrase.
+    WpaPassphraseLength(usize),
+    /// WPA passphrases may only contain printable ASCII characters.
+    WpaPassphraseCharacters,
+    /// WPA pre-shared keys written as hex must be exactly 64 hex digits.
+    WpaPskFormat,
+    /// The SSID or password contains control characters and [`ControlCharacterPolicy::Reject`](crate::ControlCharacterPolicy::Reject) was requested. Contains the field that was rejected.
+    ControlCharacter(CredentialField),
+    /// WEP keys must be in one of the [`WepKeyFormat`](crate::WepKeyFormat)s.
+    WepKeyFormat,
+    /// DPP bootstrap URIs must contain the public key of the device.
+    EmptyPublicKey,
+    /// The public key of a DPP bootstrap URI may only contain base64 characters, and the device information only printable ASCII characters other than `;`, since either would otherwise end its field early. Contains the field that was rejected.
+    DppCharacters(CredentialField),
+    /// Network interface names must be 1 to 15 ASCII letters, digits, `-`, `_`, or `.`, other than `.` and `..`, since they are used in the paths of configuration files.
+    InterfaceName,
+    /// The keys of [`EnterpriseCredentials::unknown_fields`](crate::enterprise::EnterpriseCredentials::unknown_fields) may only contain ASCII letters and digits, and their values must escape `;`, since they are written to the payload as they are.
+    UnknownFieldFormat,
+}
+
+impl Display for ValidationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::EmptySsid => f.write_str("SSIDs must not be empty"),
+            Self::SsidLength(length) => write!(
+                f,
+                "SSIDs may be at most 32 bytes long, but this one is {} bytes long",
+                length
+            ),
+            Self::WpaPassphraseLength(length) => write!(
+                f,
+                "WPA passphrases must be 8 to 63 characters long, but this one is {} characters long",
+                length
+            ),
+            Self::WpaPassphraseCharacters => {
+                f.write_str("WPA passphrases may only contain printable ASCII characters")
+            }
+            Self::WpaPskFormat => f.write_str("WPA pre-shared keys must be exactly 64 hex digits"),
+            Self::ControlCharacter(field) => write!(f, "the {} contains control characters", field),
+            Self::WepKeyFormat => f.write_str(
+                "WEP keys must be 5 or 13 ASCII characters, or 10 or 26 hex digits",
+            ),
+            Self::EmptyPublicKey => f.write_str("DPP URIs must contain a public key"),
+            Self::DppCharacters(field) => write!(
+                f,
+                "the {} contains characters that are not allowed in DPP URIs",
+                field
+            ),
+            Self::UnknownFieldFormat => f.write_str(
+                "unknown fields must have keys of letters and digits, and values with escaped semicolons",
+            ),
+            Self::InterfaceName => f.write_str(
+                "interface names must be 1 to 15 ASCII letters, digits, '-', '_', or '.', other than '.' and '..'",
+            ),
+        }
+    }
+}
+
+impl ValidationError {
+    /// The part of the credentials that failed validation, e.g. to highlight the matching input.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// # #[cfg(feature = "std")] {
+    /// use wifi_qr_code::{CredentialField, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "short");
+    /// let error = wifi_credentials.validate().unwrap_err();
+    /// assert_eq!(CredentialField::Password, error.field());
+    /// # }
+    /// ```
+    pub fn field(&self) -> CredentialField {
+        match self {
+            Self::EmptySsid | Self::SsidLength(_) => CredentialField::Ssid,
+            Self::EmptyPublicKey => CredentialField::PublicKey,
+            Self::InterfaceName => CredentialField::Interface,
+            Self::UnknownFieldFormat => CredentialField::UnknownFields,
+            Self::WpaPassphraseLength(_)
+            | Self::WpaPassphraseCharacters
+            | Self::WpaPskFormat
+            | Self::WepKeyFormat => CredentialField::Password,
+            Self::ControlCharacter(field) | Self::DppCharacters(field) => *field,
+        }
+    }
+}
+
+impl error::Error for ValidationError {}
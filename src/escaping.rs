@@ -0,0 +1,223 @@
+#[cfg(feature = "std")]
+use crate::{EncodedPayload, QrPayload, Secret, ValidationError, WifiCredentials};
+
+use core::fmt;
+
+/// The escaping dialect used when encoding values in a wifi QR code. Scanners disagree on how special characters are handled, so the dialect can be matched to the devices that will scan the code.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum EscapingMode {
+    /// Backslash escape `\`, `"`, `;`, `,`, and `:`, as described by the original ZXing format. This is the default.
+    #[default]
+    Spec,
+    /// Escape like [`EscapingMode::Spec`], and additionally wrap SSIDs and passwords in double quotes when they consist only of hex digits. ZXing based scanners otherwise interpret such values as raw hex bytes.
+    ZxingQuoted,
+    /// Only backslash escape `\` and `;`, which are needed to find the end of each value. Some scanners fail on escaped quotes, commas, or colons.
+    Minimal,
+}
+
+/// Credentials paired with the escaping dialect to encode them with. This is what [`WifiCredentials::with_escaping`] returns, and it can be passed to any of the `encode_as_*` functions.
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy)]
+pub struct EscapedCredentials<'a> {
+    wifi_credentials: &'a WifiCredentials,
+    escaping_mode: EscapingMode,
+}
+
+#[cfg(feature = "std")]
+impl WifiCredentials {
+    /// Encode the credentials using a specific escaping dialect. [`WifiCredentials::encode`] uses [`EscapingMode::Spec`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, EscapingMode, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("cafe"),
+    ///     authentication_type: AuthenticationType::WPA("\"quoted\" password".into()),
+    ///     visibility: Visibility::Visible,
+    /// };
+    /// assert_eq!(
+    ///     r#"WIFI:S:"cafe";T:WPA;P:\"quoted\" password;H:false;;"#,
+    ///     wifi_credentials.encode_with(EscapingMode::ZxingQuoted)
+    /// );
+    /// assert_eq!(
+    ///     r#"WIFI:S:cafe;T:WPA;P:"quoted" password;H:false;;"#,
+    ///     wifi_credentials.encode_with(EscapingMode::Minimal)
+    /// );
+    /// ```
+    pub fn encode_with(&self, escaping_mode: EscapingMode) -> EncodedPayload {
+        EncodedPayload::new(Secret::write_with(|mut writer| {
+            self.encode_to_with(&mut writer, escaping_mode)
+        }))
+    }
+
+    /// Write the encoded credentials to `writer` using a specific escaping dialect, without building intermediate strings. [`WifiCredentials::encode_to`] uses [`EscapingMode::Spec`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, EscapingMode, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("cafe"),
+    ///     authentication_type: AuthenticationType::NoPassword,
+    ///     visibility: Visibility::Visible,
+    /// };
+    /// let mut payload = String::new();
+    /// wifi_credentials.encode_to_with(&mut payload, EscapingMode::ZxingQuoted)
+    ///     .expect("Failed to write example payload.");
+    /// assert_eq!(r#"WIFI:S:"cafe";T:nopass;H:false;;"#, payload);
+    /// ```
+    pub fn encode_to_with(
+        &self,
+        writer: &mut impl fmt::Write,
+        escaping_mode: EscapingMode,
+    ) -> fmt::Result {
+        self.as_borrowed().encode_to_with(writer, escaping_mode)
+    }
+
+    /// Pair the credentials with an escaping dialect so they can be rendered by the `encode_as_*` functions.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::QrCodeEcc;
+    /// use wifi_qr_code::{AuthenticationType, EscapingMode, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// wifi_qr_code::encode_as_matrix(&wifi_credentials.with_escaping(EscapingMode::Minimal), QrCodeEcc::Medium);
+    /// ```
+    pub fn with_escaping(&self, escaping_mode: EscapingMode) -> EscapedCredentials<'_> {
+        EscapedCredentials {
+            wifi_credentials: self,
+            escaping_mode,
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl QrPayload for EscapedCredentials<'_> {
+    fn encode(&self) -> String {
+        self.wifi_credentials
+            .encode_with(self.escaping_mode)
+            .into_string()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        self.wifi_credentials.validate()
+    }
+}
+
+/// Write a free-form value, such as an SSID or passphrase, that scanners should read as text.
+pub(crate) fn write_value(
+    out: &mut impl fmt::Write,
+    input: &str,
+    escaping_mode: EscapingMode,
+) -> fmt::Result {
+    let quoted = escaping_mode == EscapingMode::ZxingQuoted
+        && !input.is_empty()
+        && input.bytes().all(|byte| byte.is_ascii_hexdigit());
+    if quoted {
+        out.write_char('"')?;
+    }
+    write_escaped(out, input, escaping_mode)?;
+    if quoted {
+        out.write_char('"')?;
+    }
+    Ok(())
+}
+
+/// Write a value without quoting it, such as a hex key that scanners should read as hex. Runs of characters that need no escaping are written as a single slice.
+pub(crate) fn write_escaped(
+    out: &mut impl fmt::Write,
+    input: &str,
+    escaping_mode: EscapingMode,
+) -> fmt::Result {
+    let mut unescaped = 0;
+    for (index, c) in input.char_indices() {
+        let special = match escaping_mode {
+            EscapingMode::Spec | EscapingMode::ZxingQuoted => {
+                matches!(c, '\\' | '"' | ';' | ',' | ':')
+            }
+            EscapingMode::Minimal => matches!(c, '\\' | ';'),
+        };
+        if special {
+            out.write_str(&input[unescaped..index])?;
+            out.write_char('\\')?;
+            unescaped = index;
+        }
+    }
+    out.write_str(&input[unescaped..])
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility};
+
+    #[test]
+    fn it_quotes_hex_looking_values_for_zxing() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("c0ffee"),
+            authentication_type: AuthenticationType::WEP("0123456789".into()),
+            visibility: Visibility::Visible,
+        };
+        // Hex WEP keys are meant to be read as hex, so only the SSID is quoted.
+        assert_eq!(
+            r#"WIFI:S:"c0ffee";T:WEP;P:0123456789;H:false;;"#,
+            wifi_credentials.encode_with(EscapingMode::ZxingQuoted)
+        );
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("c0ffee"),
+            authentication_type: AuthenticationType::WPA("12345678".into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            r#"WIFI:S:"c0ffee";T:WPA;P:"12345678";H:false;;"#,
+            wifi_credentials.encode_with(EscapingMode::ZxingQuoted)
+        );
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("c0ffee"),
+            authentication_type: AuthenticationType::WpaPsk([0xab; 32].into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            format!(r#"WIFI:S:"c0ffee";T:WPA;P:{};H:false;;"#, "ab".repeat(32)),
+            wifi_credentials.encode_with(EscapingMode::ZxingQuoted)
+        );
+    }
+
+    fn escape(input: &str, escaping_mode: EscapingMode) -> String {
+        let mut escaped = String::new();
+        write_escaped(&mut escaped, input, escaping_mode).unwrap();
+        escaped
+    }
+
+    #[test]
+    fn it_escapes_characters_between_runs() {
+        assert_eq!(
+            r#"\;leading, middle\\ and trailing\;"#,
+            escape(r#";leading, middle\ and trailing;"#, EscapingMode::Minimal)
+        );
+        // Control characters are left to the `ControlCharacterPolicy`.
+        assert_eq!("line\n", escape("line\n", EscapingMode::Spec));
+        assert_eq!("", escape("", EscapingMode::Spec));
+    }
+
+    #[test]
+    fn it_escapes_only_delimiters_in_minimal_mode() {
+        assert_eq!(
+            r#"special_characters ",:\;\\"#,
+            escape(r#"special_characters ",:;\"#, EscapingMode::Minimal)
+        );
+        assert_eq!(
+            r#"special_characters \"\,\:\;\\"#,
+            escape(r#"special_characters ",:;\"#, EscapingMode::Spec)
+        );
+    }
+}
@@ -0,0 +1,111 @@
+//! Export credentials to the configuration formats used by operating systems and network managers, so the same [`WifiCredentials`](crate::WifiCredentials) can provision devices directly in addition to being printed as a QR code.
+
+pub mod android;
+pub mod iwd;
+pub mod mobileconfig;
+pub mod ndef;
+pub mod network_manager;
+pub mod raspberry_pi;
+pub mod systemd;
+pub mod windows_profile;
+pub mod wpa_supplicant;
+
+use crate::Secret;
+
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Component, Path, PathBuf};
+
+/// A configuration file to install on the device being provisioned, for exporters that produce more than a single document.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigFile {
+    /// The absolute path the file is installed at on the device, such as `/var/lib/iwd/example.psk`.
+    pub path: String,
+    /// The contents of the file, which are wiped from memory with the `zeroize` feature since they may contain a password.
+    pub contents: Secret<String>,
+    /// Whether the file contains a password and should only be readable by its owner.
+    pub private: bool,
+}
+
+impl ConfigFile {
+    /// Write the file below `root`, e.g. the mount point of an SD card's root partition, creating its parent directories. Private files are given `0600` permissions on Unix, including files that already existed. Paths with `..` components are rejected with [`io::ErrorKind::InvalidInput`], so a file cannot be written outside of `root`. Returns the path that was written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wifi_qr_code::export::iwd;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let config_file = iwd::encode(&wifi_credentials)
+    ///     .expect("Failed to validate example credentials.")
+    ///     .expect("iwd supports WPA.");
+    /// config_file
+    ///     .write_to("/mnt/rootfs")
+    ///     .expect("Failed to write the configuration file.");
+    /// ```
+    pub fn write_to(&self, root: impl AsRef<Path>) -> io::Result<PathBuf> {
+        let relative = Path::new(self.path.trim_start_matches('/'));
+        if relative
+            .components()
+            .any(|component| !matches!(component, Component::Normal(_) | Component::CurDir))
+        {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                format!("{} is not a path below the root directory", self.path),
+            ));
+        }
+        let path = root.as_ref().join(relative);
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        write_file(&path, self.contents.as_bytes(), self.private)?;
+        Ok(path)
+    }
+}
+
+/// Replace the contents of the file at `path`. Private files are created with `0600` permissions on Unix, and existing ones are restricted to them before the contents are written.
+#[cfg_attr(not(unix), allow(unused_variables))]
+pub(crate) fn write_file(path: &Path, contents: &[u8], private: bool) -> io::Result<()> {
+    let mut options = fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::OpenOptionsExt;
+        options.mode(0o600);
+    }
+    let mut file = options.open(path)?;
+    // The mode only applies to new files, so existing ones are restricted before the password is written.
+    #[cfg(unix)]
+    if private {
+        use std::os::unix::fs::PermissionsExt;
+        file.set_permissions(fs::Permissions::from_mode(0o600))?;
+    }
+    file.write_all(contents)
+}
+
+pub(crate) fn escape_xml(input: &str) -> String {
+    let mut escaped = String::with_capacity(input.len());
+    write_xml_escaped(&mut escaped, input).expect("Writing to a String cannot fail.");
+    escaped
+}
+
+/// Escape a password for XML like [`escape_xml`], without leaving copies of it behind.
+pub(crate) fn escape_xml_secret(input: &str) -> Secret<String> {
+    Secret::write_with(|writer| write_xml_escaped(writer, input))
+}
+
+fn write_xml_escaped(writer: &mut dyn fmt::Write, input: &str) -> fmt::Result {
+    for c in input.chars() {
+        match c {
+            '&' => writer.write_str("&amp;")?,
+            '<' => writer.write_str("&lt;")?,
+            '>' => writer.write_str("&gt;")?,
+            '"' => writer.write_str("&quot;")?,
+            '\'' => writer.write_str("&apos;")?,
+            c => writer.write_char(c)?,
+        }
+    }
+    Ok(())
+}
@@ -0,0 +1,123 @@
+//! JSON describing a network the way Android apps add it programmatically, with field names matching the `WifiNetworkSuggestion.Builder` setters (`setSsid`, `setWpa2Passphrase`, `setIsHiddenSsid`).
+//!
+//! Network suggestions cannot describe WEP networks or raw pre-shared keys, so those credentials use the `wepKeys`, `wepTxKeyIndex`, and `preSharedKey` fields of the legacy `WifiConfiguration` class instead.
+
+use crate::{AuthenticationType, Secret, Visibility, WifiCredentials};
+
+use std::fmt::{self, Write};
+
+/// Encode the credentials as a JSON object that a companion Android app can map onto a network suggestion.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::android;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// assert_eq!(
+///     r#"{"ssid":"example ssid","wpa2Passphrase":"example password","isHiddenSsid":true}"#,
+///     android::encode(&wifi_credentials)
+/// );
+/// ```
+pub fn encode(wifi_credentials: &WifiCredentials) -> Secret<String> {
+    Secret::write_with(|writer| {
+        writer.write_str(r#"{"ssid":""#)?;
+        write_json_escaped(writer, &wifi_credentials.ssid)?;
+        writer.write_str(r#"","#)?;
+        write_authentication_type(writer, &wifi_credentials.authentication_type)?;
+        write!(
+            writer,
+            r#""isHiddenSsid":{}}}"#,
+            encode_visibility(&wifi_credentials.visibility)
+        )
+    })
+}
+
+fn write_authentication_type(
+    writer: &mut dyn Write,
+    authentication_type: &AuthenticationType,
+) -> fmt::Result {
+    match authentication_type {
+        AuthenticationType::WEP(password) => {
+            writer.write_str(r#""wepKeys":[""#)?;
+            write_json_escaped(writer, password)?;
+            writer.write_str(r#""],"wepTxKeyIndex":0,"#)
+        }
+        AuthenticationType::WPA(password) => {
+            writer.write_str(r#""wpa2Passphrase":""#)?;
+            write_json_escaped(writer, password)?;
+            writer.write_str(r#"","#)
+        }
+        AuthenticationType::WpaPsk(psk) => write!(
+            writer,
+            r#""preSharedKey":"{}","#,
+            crate::encode_hex(&**psk).as_str()
+        ),
+        AuthenticationType::NoPassword => Ok(()),
+    }
+}
+
+fn encode_visibility(visibility: &Visibility) -> &'static str {
+    match visibility {
+        Visibility::Visible => "false",
+        Visibility::Hidden => "true",
+    }
+}
+
+fn write_json_escaped(writer: &mut dyn Write, input: &str) -> fmt::Result {
+    for c in input.chars() {
+        match c {
+            '"' => writer.write_str(r#"\""#)?,
+            '\\' => writer.write_str(r#"\\"#)?,
+            '\n' => writer.write_str(r#"\n"#)?,
+            '\r' => writer.write_str(r#"\r"#)?,
+            '\t' => writer.write_str(r#"\t"#)?,
+            c if c.is_control() => write!(writer, r#"\u{:04x}"#, c as u32)?,
+            c => writer.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_encodes_wep_and_open_networks() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            r#"{"ssid":"test ssid","wepKeys":["test password"],"wepTxKeyIndex":0,"isHiddenSsid":false}"#,
+            encode(&wifi_credentials)
+        );
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            r#"{"ssid":"test ssid","isHiddenSsid":false}"#,
+            encode(&wifi_credentials)
+        );
+    }
+
+    #[test]
+    fn it_escapes_json_strings() {
+        assert_eq!(
+            r#"quote\" backslash\\ newline\n bell\u0007"#,
+            Secret::write_with(|writer| write_json_escaped(
+                writer,
+                "quote\" backslash\\ newline\n bell\u{7}"
+            ))
+        );
+    }
+}
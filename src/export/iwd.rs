@@ -0,0 +1,142 @@
+//! Network configuration files for [iwd](https://iwd.wiki.kernel.org/), the wireless daemon used by many minimal systemd-based images in place of wpa_supplicant.
+//!
+//! iwd looks networks up by file name, so the name encodes both the SSID and the kind of security, e.g. `/var/lib/iwd/example.psk`. WEP is not supported by iwd, so no file is produced for WEP networks.
+
+use super::ConfigFile;
+use crate::{AuthenticationType, Secret, ValidationError, Visibility, WifiCredentials};
+
+/// The directory iwd reads network configuration files from.
+pub const DIRECTORY: &str = "/var/lib/iwd";
+
+/// Validate the credentials, then encode them as an iwd network configuration file, or `None` for WEP networks. Validation keeps passwords from adding settings to the file, since it only allows printable characters.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::iwd;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let config_file = iwd::encode(&wifi_credentials)
+///     .expect("Failed to validate example credentials.")
+///     .expect("iwd supports WPA.");
+/// assert_eq!("/var/lib/iwd/example ssid.psk", config_file.path);
+/// assert_eq!(
+///     "[Security]\nPassphrase=example password\n\n[Settings]\nHidden=true\n",
+///     config_file.contents
+/// );
+/// ```
+pub fn encode(wifi_credentials: &WifiCredentials) -> Result<Option<ConfigFile>, ValidationError> {
+    wifi_credentials.validate()?;
+    let (extension, security) = match &wifi_credentials.authentication_type {
+        AuthenticationType::WEP(_) => return Ok(None),
+        AuthenticationType::WPA(password) => (
+            "psk",
+            Secret::format(format_args!(
+                "[Security]\nPassphrase={}\n",
+                password.as_str()
+            )),
+        ),
+        AuthenticationType::WpaPsk(psk) => (
+            "psk",
+            Secret::format(format_args!(
+                "[Security]\nPreSharedKey={}\n",
+                crate::encode_hex(&**psk).as_str()
+            )),
+        ),
+        AuthenticationType::NoPassword => ("open", Secret::default()),
+    };
+    let settings = match wifi_credentials.visibility {
+        Visibility::Visible => "",
+        Visibility::Hidden => "[Settings]\nHidden=true\n",
+    };
+    let separator = if security.is_empty() || settings.is_empty() {
+        ""
+    } else {
+        "\n"
+    };
+    Ok(Some(ConfigFile {
+        path: format!(
+            "{}/{}.{}",
+            DIRECTORY,
+            file_stem(&wifi_credentials.ssid),
+            extension
+        ),
+        contents: Secret::format(format_args!(
+            "{}{}{}",
+            security.as_str(),
+            separator,
+            settings
+        )),
+        private: !security.is_empty(),
+    }))
+}
+
+fn file_stem(ssid: &str) -> String {
+    if ssid
+        .chars()
+        .all(|c| c.is_ascii_alphanumeric() || [' ', '-', '_'].contains(&c))
+    {
+        String::from(ssid)
+    } else {
+        format!("={}", crate::encode_hex(ssid.as_bytes()).as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_encodes_pre_shared_keys_and_open_networks() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WpaPsk([0xab; 32].into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            Ok(Some(ConfigFile {
+                path: String::from("/var/lib/iwd/test ssid.psk"),
+                contents: format!("[Security]\nPreSharedKey={}\n", "ab".repeat(32)).into(),
+                private: true,
+            })),
+            encode(&wifi_credentials)
+        );
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Hidden,
+        };
+        assert_eq!(
+            Ok(Some(ConfigFile {
+                path: String::from("/var/lib/iwd/test ssid.open"),
+                contents: "[Settings]\nHidden=true\n".into(),
+                private: false,
+            })),
+            encode(&wifi_credentials)
+        );
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("abcde".into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(Ok(None), encode(&wifi_credentials));
+        let wifi_credentials =
+            WifiCredentials::wpa2("test ssid", "password\n[Settings]\nAutoConnect=true");
+        assert_eq!(
+            Err(ValidationError::WpaPassphraseCharacters),
+            encode(&wifi_credentials)
+        );
+    }
+
+    #[test]
+    fn it_hex_encodes_other_ssids_in_file_names() {
+        assert_eq!("Test_ssid-2", file_stem("Test_ssid-2"));
+        assert_eq!("=636166c3a9", file_stem("café"));
+        assert_eq!("=612e62", file_stem("a.b"));
+    }
+}
@@ -0,0 +1,176 @@
+//! Apple configuration profiles (`.mobileconfig`) that install a Wi-Fi payload on iOS and macOS devices.
+//!
+//! The generated profile is an unsigned XML property list. It can be distributed as-is or signed afterward (e.g. with `openssl smime -sign`) so devices display it as verified.
+
+use super::{escape_xml, escape_xml_secret};
+use crate::{AuthenticationType, Secret, ValidationError, Visibility, WifiCredentials};
+
+/// The metadata that identifies a configuration profile and its Wi-Fi payload.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Profile {
+    /// A reverse-DNS identifier for the profile, such as `com.example.guest-wifi`. The Wi-Fi payload uses this identifier with a `.wifi` suffix.
+    pub identifier: String,
+    /// The name displayed to the user when installing the profile.
+    pub display_name: String,
+    /// A UUID that uniquely identifies the profile. Reusing the same UUID allows a newer profile to replace an installed one.
+    pub profile_uuid: String,
+    /// A UUID that uniquely identifies the Wi-Fi payload within the profile.
+    pub payload_uuid: String,
+}
+
+/// Validate the credentials, then encode them as a configuration profile containing a single `com.apple.wifi.managed` payload. Validation rejects passwords with control characters, which XML 1.0 cannot represent even when escaped.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::mobileconfig::{self, Profile};
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let profile = Profile {
+///     identifier: String::from("com.example.wifi"),
+///     display_name: String::from("Example Wi-Fi"),
+///     profile_uuid: String::from("8C8E0A4B-4A7A-4D7E-9B4F-8D2E7C1A5B01"),
+///     payload_uuid: String::from("1F6B2C3D-0E4F-4A5B-8C6D-7E8F9A0B1C02"),
+/// };
+/// let plist = mobileconfig::encode(&wifi_credentials, &profile)
+///     .expect("Failed to validate example credentials.");
+/// assert!(plist.contains("<string>example ssid</string>"));
+/// ```
+pub fn encode(
+    wifi_credentials: &WifiCredentials,
+    profile: &Profile,
+) -> Result<Secret<String>, ValidationError> {
+    wifi_credentials.validate()?;
+    Ok(Secret::format(format_args!(
+        r#"<?xml version="1.0" encoding="UTF-8"?>
+<!DOCTYPE plist PUBLIC "-//Apple//DTD PLIST 1.0//EN" "http://www.apple.com/DTDs/PropertyList-1.0.dtd">
+<plist version="1.0">
+<dict>
+	<key>PayloadContent</key>
+	<array>
+		<dict>
+			<key>AutoJoin</key>
+			<true/>
+{}{}			<key>PayloadDisplayName</key>
+			<string>Wi-Fi</string>
+			<key>PayloadIdentifier</key>
+			<string>{}.wifi</string>
+			<key>PayloadType</key>
+			<string>com.apple.wifi.managed</string>
+			<key>PayloadUUID</key>
+			<string>{}</string>
+			<key>PayloadVersion</key>
+			<integer>1</integer>
+			<key>SSID_STR</key>
+			<string>{}</string>
+		</dict>
+	</array>
+	<key>PayloadDisplayName</key>
+	<string>{}</string>
+	<key>PayloadIdentifier</key>
+	<string>{}</string>
+	<key>PayloadType</key>
+	<string>Configuration</string>
+	<key>PayloadUUID</key>
+	<string>{}</string>
+	<key>PayloadVersion</key>
+	<integer>1</integer>
+</dict>
+</plist>
+"#,
+        encode_authentication_type(&wifi_credentials.authentication_type).as_str(),
+        encode_visibility(&wifi_credentials.visibility),
+        escape_xml(&profile.identifier),
+        escape_xml(&profile.payload_uuid),
+        escape_xml(&wifi_credentials.ssid),
+        escape_xml(&profile.display_name),
+        escape_xml(&profile.identifier),
+        escape_xml(&profile.profile_uuid),
+    )))
+}
+
+fn encode_authentication_type(authentication_type: &AuthenticationType) -> Secret<String> {
+    match authentication_type {
+        AuthenticationType::WEP(password) => encode_encryption("WEP", password),
+        AuthenticationType::WPA(password) => encode_encryption("WPA", password),
+        AuthenticationType::WpaPsk(psk) => encode_encryption("WPA", &crate::encode_hex(&**psk)),
+        AuthenticationType::NoPassword => {
+            "\t\t\t<key>EncryptionType</key>\n\t\t\t<string>None</string>\n".into()
+        }
+    }
+}
+
+fn encode_encryption(encryption_type: &str, password: &str) -> Secret<String> {
+    Secret::format(format_args!(
+        "\t\t\t<key>EncryptionType</key>\n\t\t\t<string>{}</string>\n\t\t\t<key>Password</key>\n\t\t\t<string>{}</string>\n",
+        encryption_type,
+        escape_xml_secret(password).as_str()
+    ))
+}
+
+fn encode_visibility(visibility: &Visibility) -> String {
+    match visibility {
+        Visibility::Visible => String::from("\t\t\t<key>HIDDEN_NETWORK</key>\n\t\t\t<false/>\n"),
+        Visibility::Hidden => String::from("\t\t\t<key>HIDDEN_NETWORK</key>\n\t\t\t<true/>\n"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn profile() -> Profile {
+        Profile {
+            identifier: String::from("com.example.wifi"),
+            display_name: String::from("Example & Co Wi-Fi"),
+            profile_uuid: String::from("profile-uuid"),
+            payload_uuid: String::from("payload-uuid"),
+        }
+    }
+
+    #[test]
+    fn it_encodes_a_wpa_payload() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test <ssid>"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Hidden,
+        };
+        let plist = encode(&wifi_credentials, &profile()).unwrap();
+        assert!(plist.contains(
+            "\t\t\t<key>EncryptionType</key>\n\t\t\t<string>WPA</string>\n\t\t\t<key>Password</key>\n\t\t\t<string>test password</string>\n"
+        ));
+        assert!(plist.contains("\t\t\t<key>HIDDEN_NETWORK</key>\n\t\t\t<true/>\n"));
+        assert!(plist.contains("\t\t\t<string>test &lt;ssid&gt;</string>\n"));
+        assert!(plist.contains("\t<string>Example &amp; Co Wi-Fi</string>\n"));
+        assert!(plist.contains("\t\t\t<string>com.example.wifi.wifi</string>\n"));
+        assert!(plist.contains("\t\t\t<string>payload-uuid</string>\n"));
+        assert!(plist.contains("\t<string>profile-uuid</string>\n"));
+    }
+
+    #[test]
+    fn it_omits_the_password_for_open_networks() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Visible,
+        };
+        let plist = encode(&wifi_credentials, &profile()).unwrap();
+        assert!(plist.contains("\t\t\t<key>EncryptionType</key>\n\t\t\t<string>None</string>\n"));
+        assert!(plist.contains("\t\t\t<key>HIDDEN_NETWORK</key>\n\t\t\t<false/>\n"));
+        assert!(!plist.contains("<key>Password</key>"));
+    }
+
+    #[test]
+    fn it_rejects_control_characters() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test\u{1}password");
+        assert_eq!(
+            Err(ValidationError::WpaPassphraseCharacters),
+            encode(&wifi_credentials, &profile())
+        );
+    }
+}
@@ -0,0 +1,157 @@
+//! NFC Data Exchange Format (NDEF) messages containing a Wi-Fi Simple Configuration credential (`application/vnd.wfa.wsc`), which Android and other platforms join when the tag is tapped.
+//!
+//! The message can be written to an NFC tag as a companion to the printed QR code. Wi-Fi Simple Configuration has no way to mark a network as hidden, so the visibility of the credentials is not included.
+
+use crate::{AuthenticationType, Secret, WifiCredentials};
+
+const MIME_TYPE: &[u8] = b"application/vnd.wfa.wsc";
+
+const VERSION: u16 = 0x104A;
+const CREDENTIAL: u16 = 0x100E;
+const NETWORK_INDEX: u16 = 0x1026;
+const SSID: u16 = 0x1045;
+const AUTHENTICATION_TYPE: u16 = 0x1003;
+const ENCRYPTION_TYPE: u16 = 0x100F;
+const NETWORK_KEY: u16 = 0x1027;
+const MAC_ADDRESS: u16 = 0x1020;
+const VENDOR_EXTENSION: u16 = 0x1049;
+
+const AUTHENTICATION_OPEN: u16 = 0x0001;
+const AUTHENTICATION_WPA2_PSK: u16 = 0x0020;
+const ENCRYPTION_NONE: u16 = 0x0001;
+const ENCRYPTION_WEP: u16 = 0x0002;
+const ENCRYPTION_AES: u16 = 0x0008;
+
+/// The Wi-Fi Alliance vendor extension declaring support for version 2.0 of Wi-Fi Simple Configuration.
+const WFA_VERSION_2: &[u8] = &[0x00, 0x37, 0x2A, 0x00, 0x01, 0x20];
+
+/// The length of the type and length fields in front of each attribute value.
+const ATTRIBUTE_HEADER_LEN: usize = 4;
+
+/// Encode the credentials as an NDEF message containing a single Wi-Fi Simple Configuration record.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::ndef;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Visible,
+/// };
+/// let message = ndef::encode(&wifi_credentials);
+/// assert_eq!(&b"application/vnd.wfa.wsc"[..], &message[3..26]);
+/// ```
+pub fn encode(wifi_credentials: &WifiCredentials) -> Secret<Vec<u8>> {
+    let payload = encode_payload(wifi_credentials);
+    let mut message = Secret::new(Vec::with_capacity(payload.len() + MIME_TYPE.len() + 6));
+    // Message begin, message end, and a type name format of 2 (media type). Short records store the payload length in one byte.
+    if payload.len() <= usize::from(u8::MAX) {
+        message.push(0xD2);
+        message.push(MIME_TYPE.len() as u8);
+        message.push(payload.len() as u8);
+    } else {
+        message.push(0xC2);
+        message.push(MIME_TYPE.len() as u8);
+        message.extend_from_slice(&(payload.len() as u32).to_be_bytes());
+    }
+    message.extend_from_slice(MIME_TYPE);
+    message.extend_from_slice(&payload);
+    message
+}
+
+fn encode_payload(wifi_credentials: &WifiCredentials) -> Secret<Vec<u8>> {
+    let (authentication_type, encryption_type, network_key) = match &wifi_credentials
+        .authentication_type
+    {
+        AuthenticationType::WEP(password) => {
+            (AUTHENTICATION_OPEN, ENCRYPTION_WEP, password.clone())
+        }
+        AuthenticationType::WPA(password) => {
+            (AUTHENTICATION_WPA2_PSK, ENCRYPTION_AES, password.clone())
+        }
+        AuthenticationType::WpaPsk(psk) => (
+            AUTHENTICATION_WPA2_PSK,
+            ENCRYPTION_AES,
+            crate::encode_hex(&**psk),
+        ),
+        AuthenticationType::NoPassword => (AUTHENTICATION_OPEN, ENCRYPTION_NONE, Secret::default()),
+    };
+
+    // The buffers are allocated at their final size, so that growing them does not leave copies of the key behind.
+    let mut credential = Secret::new(Vec::with_capacity(
+        6 * ATTRIBUTE_HEADER_LEN + 1 + wifi_credentials.ssid.len() + 2 + 2 + network_key.len() + 6,
+    ));
+    push_attribute(&mut credential, NETWORK_INDEX, &[0x01]);
+    push_attribute(&mut credential, SSID, wifi_credentials.ssid.as_bytes());
+    push_attribute(
+        &mut credential,
+        AUTHENTICATION_TYPE,
+        &authentication_type.to_be_bytes(),
+    );
+    push_attribute(
+        &mut credential,
+        ENCRYPTION_TYPE,
+        &encryption_type.to_be_bytes(),
+    );
+    push_attribute(&mut credential, NETWORK_KEY, network_key.as_bytes());
+    push_attribute(&mut credential, MAC_ADDRESS, &[0xFF; 6]);
+
+    let mut payload = Secret::new(Vec::with_capacity(
+        3 * ATTRIBUTE_HEADER_LEN + 1 + credential.len() + WFA_VERSION_2.len(),
+    ));
+    push_attribute(&mut payload, VERSION, &[0x10]);
+    push_attribute(&mut payload, CREDENTIAL, &credential);
+    push_attribute(&mut payload, VENDOR_EXTENSION, WFA_VERSION_2);
+    payload
+}
+
+fn push_attribute(buffer: &mut Vec<u8>, attribute_type: u16, value: &[u8]) {
+    buffer.extend_from_slice(&attribute_type.to_be_bytes());
+    buffer.extend_from_slice(&(value.len() as u16).to_be_bytes());
+    buffer.extend_from_slice(value);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Visibility;
+
+    #[test]
+    fn it_encodes_a_wpa_credential() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("ssid"),
+            authentication_type: AuthenticationType::WPA("password".into()),
+            visibility: Visibility::Visible,
+        };
+        let mut expected = vec![0xD2, 23, 66];
+        expected.extend_from_slice(b"application/vnd.wfa.wsc");
+        expected.extend_from_slice(&[0x10, 0x4A, 0x00, 0x01, 0x10]);
+        expected.extend_from_slice(&[0x10, 0x0E, 0x00, 0x2F]);
+        expected.extend_from_slice(&[0x10, 0x26, 0x00, 0x01, 0x01]);
+        expected.extend_from_slice(&[0x10, 0x45, 0x00, 0x04]);
+        expected.extend_from_slice(b"ssid");
+        expected.extend_from_slice(&[0x10, 0x03, 0x00, 0x02, 0x00, 0x20]);
+        expected.extend_from_slice(&[0x10, 0x0F, 0x00, 0x02, 0x00, 0x08]);
+        expected.extend_from_slice(&[0x10, 0x27, 0x00, 0x08]);
+        expected.extend_from_slice(b"password");
+        expected.extend_from_slice(&[0x10, 0x20, 0x00, 0x06, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF, 0xFF]);
+        expected.extend_from_slice(&[0x10, 0x49, 0x00, 0x06, 0x00, 0x37, 0x2A, 0x00, 0x01, 0x20]);
+        assert_eq!(expected, *encode(&wifi_credentials));
+    }
+
+    #[test]
+    fn it_uses_long_records_for_large_payloads() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("ssid"),
+            authentication_type: AuthenticationType::WEP("k".repeat(300).into()),
+            visibility: Visibility::Visible,
+        };
+        let message = encode(&wifi_credentials);
+        assert_eq!(0xC2, message[0]);
+        let payload_length = u32::from_be_bytes([message[2], message[3], message[4], message[5]]);
+        assert_eq!(message.len() - 6 - MIME_TYPE.len(), payload_length as usize);
+    }
+}
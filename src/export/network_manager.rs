@@ -0,0 +1,137 @@
+//! NetworkManager keyfiles (`.nmconnection`) for provisioning headless Linux machines.
+//!
+//! The keyfile should be written to `/etc/NetworkManager/system-connections/` with `0600` permissions, otherwise NetworkManager will refuse to load it. No `uuid` is emitted, so NetworkManager derives a stable one from the file name.
+
+use crate::{AuthenticationType, Secret, ValidationError, Visibility, WifiCredentials};
+
+use std::fmt::{self, Write};
+
+/// Validate the credentials, then encode them as a NetworkManager keyfile. The connection is named after the SSID and uses DHCP for both IPv4 and IPv6.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::network_manager;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let keyfile = network_manager::encode(&wifi_credentials)
+///     .expect("Failed to validate example credentials.");
+/// assert!(keyfile.contains("key-mgmt=wpa-psk\npsk=example password\n"));
+/// ```
+pub fn encode(wifi_credentials: &WifiCredentials) -> Result<Secret<String>, ValidationError> {
+    wifi_credentials.validate()?;
+    let ssid = escape_keyfile(&wifi_credentials.ssid);
+    Ok(Secret::format(format_args!(
+        "[connection]\nid={}\ntype=wifi\n\n[wifi]\nmode=infrastructure\nssid={}\n{}{}\n[ipv4]\nmethod=auto\n\n[ipv6]\nmethod=auto\n",
+        ssid.as_str(),
+        ssid.as_str(),
+        encode_visibility(&wifi_credentials.visibility),
+        encode_authentication_type(&wifi_credentials.authentication_type).as_str(),
+    )))
+}
+
+fn encode_visibility(visibility: &Visibility) -> &'static str {
+    match visibility {
+        Visibility::Visible => "",
+        Visibility::Hidden => "hidden=true\n",
+    }
+}
+
+fn encode_authentication_type(authentication_type: &AuthenticationType) -> Secret<String> {
+    match authentication_type {
+        AuthenticationType::WEP(password) => Secret::format(format_args!(
+            "\n[wifi-security]\nkey-mgmt=none\nwep-key-type=1\nwep-key0={}\n",
+            escape_keyfile(password).as_str()
+        )),
+        AuthenticationType::WPA(password) => Secret::format(format_args!(
+            "\n[wifi-security]\nkey-mgmt=wpa-psk\npsk={}\n",
+            escape_keyfile(password).as_str()
+        )),
+        AuthenticationType::WpaPsk(psk) => Secret::format(format_args!(
+            "\n[wifi-security]\nkey-mgmt=wpa-psk\npsk={}\n",
+            crate::encode_hex(&**psk).as_str()
+        )),
+        AuthenticationType::NoPassword => Secret::default(),
+    }
+}
+
+fn escape_keyfile(input: &str) -> Secret<String> {
+    Secret::write_with(|writer| write_keyfile_escaped(writer, input))
+}
+
+fn write_keyfile_escaped(writer: &mut dyn Write, input: &str) -> fmt::Result {
+    let input = match input.strip_prefix(' ') {
+        Some(rest) => {
+            writer.write_str(r#"\s"#)?;
+            rest
+        }
+        None => input,
+    };
+    for c in input.chars() {
+        match c {
+            '\\' => writer.write_str(r#"\\"#)?,
+            '\n' => writer.write_str(r#"\n"#)?,
+            '\r' => writer.write_str(r#"\r"#)?,
+            '\t' => writer.write_str(r#"\t"#)?,
+            c => writer.write_char(c)?,
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_encodes_a_wpa_keyfile() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Hidden,
+        };
+        assert_eq!(
+            "[connection]\nid=test ssid\ntype=wifi\n\n[wifi]\nmode=infrastructure\nssid=test ssid\nhidden=true\n\n[wifi-security]\nkey-mgmt=wpa-psk\npsk=test password\n\n[ipv4]\nmethod=auto\n\n[ipv6]\nmethod=auto\n",
+            encode(&wifi_credentials).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_encodes_wep_and_open_keyfiles() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        let keyfile = encode(&wifi_credentials).unwrap();
+        assert!(keyfile.contains("key-mgmt=none\nwep-key-type=1\nwep-key0=test password\n"));
+        assert!(!keyfile.contains("hidden="));
+
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Visible,
+        };
+        assert!(!encode(&wifi_credentials)
+            .unwrap()
+            .contains("[wifi-security]"));
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "short");
+        assert_eq!(
+            Err(ValidationError::WpaPassphraseLength(5)),
+            encode(&wifi_credentials)
+        );
+    }
+
+    #[test]
+    fn it_escapes_keyfile_values() {
+        assert_eq!(
+            r#"\sleading\\back\nslash"#,
+            escape_keyfile(" leading\\back\nslash")
+        );
+    }
+}
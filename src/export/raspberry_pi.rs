@@ -0,0 +1,192 @@
+//! Headless provisioning bundles for Raspberry Pi OS: the `wpa_supplicant.conf` that the first boot copies from the boot partition, an optional empty `ssh` file that enables the SSH server, and the QR code for the same network, so the card and the label on the enclosure come from one set of credentials.
+//!
+//! Raspberry Pi OS only reads `wpa_supplicant.conf` from the boot partition up to Bullseye. Bookworm and later use NetworkManager, so install [`network_manager::encode`](super::network_manager::encode) on their root partition instead. A user account still has to be configured, e.g. with a `userconf.txt`, before SSH logins work.
+
+use super::{wpa_supplicant, ConfigFile};
+#[cfg(feature = "png")]
+use crate::{Error, OutputStage};
+use crate::{QrCodeEcc, Secret, ValidationError, WifiCredentials};
+
+#[cfg(feature = "png")]
+use std::fs::File;
+#[cfg(feature = "png")]
+use std::path::{Path, PathBuf};
+
+/// The file name of the QR code written by `Bundle::write`.
+pub const QR_CODE_FILE: &str = "wifi-qr-code.png";
+
+/// The settings of a provisioning bundle.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Bundle {
+    /// The ISO 3166 country code that the wireless regulatory domain is set to, such as `GB`. The wifi radio of a Pi stays blocked until a country is set.
+    pub country: String,
+    /// Whether to add the `ssh` file that enables the SSH server on first boot.
+    pub ssh: bool,
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The width and height of the QR code image in pixels.
+    pub image_size: usize,
+}
+
+impl Bundle {
+    /// A bundle for the country, without the `ssh` file, with a 512 pixel QR code using medium error correction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::export::raspberry_pi::Bundle;
+    ///
+    /// let bundle = Bundle {
+    ///     ssh: true,
+    ///     ..Bundle::new("DE")
+    /// };
+    /// ```
+    pub fn new(country: impl Into<String>) -> Self {
+        Bundle {
+            country: country.into(),
+            ssh: false,
+            qr_code_error_checking: QrCodeEcc::Medium,
+            image_size: 512,
+        }
+    }
+
+    /// Validate the credentials, then return the configuration files of the bundle, with paths relative to the root of the boot partition.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::export::raspberry_pi::Bundle;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let config_files = Bundle::new("GB")
+    ///     .files(&wifi_credentials)
+    ///     .expect("Failed to validate example credentials.");
+    /// assert_eq!("/wpa_supplicant.conf", config_files[0].path);
+    /// assert!(config_files[0].contents.starts_with(
+    ///     "ctrl_interface=DIR=/var/run/wpa_supplicant GROUP=netdev\nupdate_config=1\ncountry=GB\n\nnetwork={"
+    /// ));
+    /// ```
+    pub fn files(
+        &self,
+        wifi_credentials: &WifiCredentials,
+    ) -> Result<Vec<ConfigFile>, ValidationError> {
+        let mut files = vec![ConfigFile {
+            path: String::from("/wpa_supplicant.conf"),
+            contents: Secret::format(format_args!(
+                "ctrl_interface=DIR=/var/run/wpa_supplicant GROUP=netdev\nupdate_config=1\ncountry={}\n\n{}",
+                self.country,
+                wpa_supplicant::encode(wifi_credentials)?.as_str()
+            )),
+            private: true,
+        }];
+        if self.ssh {
+            files.push(ConfigFile {
+                path: String::from("/ssh"),
+                contents: Secret::default(),
+                private: false,
+            });
+        }
+        Ok(files)
+    }
+
+    /// Validate the credentials, then write the configuration files and the QR code as `wifi-qr-code.png` to the directory, usually the mounted boot partition. Returns the paths that were written.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wifi_qr_code::export::raspberry_pi::Bundle;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let bundle = Bundle {
+    ///     ssh: true,
+    ///     ..Bundle::new("GB")
+    /// };
+    /// bundle
+    ///     .write(&wifi_credentials, "/media/user/bootfs")
+    ///     .expect("Failed to write the bundle.");
+    /// ```
+    #[cfg(feature = "png")]
+    pub fn write(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        directory: impl AsRef<Path>,
+    ) -> Result<Vec<PathBuf>, Error> {
+        let directory = directory.as_ref();
+        let mut paths = self
+            .files(wifi_credentials)?
+            .iter()
+            .map(|config_file| config_file.write_to(directory))
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|source| Error::Io {
+                stage: OutputStage::ConfigFile,
+                source,
+            })?;
+        let qr_code = directory.join(QR_CODE_FILE);
+        let png_file = File::create(&qr_code).map_err(|source| Error::Io {
+            stage: OutputStage::CreateFile,
+            source,
+        })?;
+        wifi_credentials
+            .qr(self.qr_code_error_checking)
+            .size(self.image_size)
+            .png(png_file)?;
+        paths.push(qr_code);
+        Ok(paths)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_adds_the_ssh_file_when_requested() {
+        let wifi_credentials = WifiCredentials::open("test ssid");
+        assert_eq!(1, Bundle::new("GB").files(&wifi_credentials).unwrap().len());
+        let bundle = Bundle {
+            ssh: true,
+            ..Bundle::new("GB")
+        };
+        let config_files = bundle.files(&wifi_credentials).unwrap();
+        assert_eq!(
+            ConfigFile {
+                path: String::from("/ssh"),
+                contents: Secret::default(),
+                private: false,
+            },
+            config_files[1]
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn it_writes_the_bundle_into_a_directory() {
+        let directory =
+            std::env::temp_dir().join(format!("wifi-qr-code-raspberry-pi-{}", std::process::id()));
+        let bundle = Bundle {
+            ssh: true,
+            ..Bundle::new("NZ")
+        };
+        let paths = bundle
+            .write(
+                &WifiCredentials::wpa2("test ssid", "test password"),
+                &directory,
+            )
+            .expect("Bundle should be written.");
+        assert_eq!(
+            vec![
+                directory.join("wpa_supplicant.conf"),
+                directory.join("ssh"),
+                directory.join(QR_CODE_FILE),
+            ],
+            paths
+        );
+        assert!(std::fs::read(&paths[2]).unwrap().starts_with(b"\x89PNG"));
+        assert!(bundle
+            .write(&WifiCredentials::wpa2("test ssid", "short"), &directory)
+            .is_err());
+        std::fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,153 @@
+//! Configuration for headless Linux machines that connect with the `wpa_supplicant@.service` template unit and get their address from systemd-networkd, as on many Debian-based and Yocto images without NetworkManager.
+//!
+//! After installing the files, the connection is started with `systemctl enable --now wpa_supplicant@wlan0.service systemd-networkd.service`, replacing `wlan0` with the interface name. For images that use iwd instead, install [`iwd::encode`](super::iwd::encode) along with [`network_file`].
+
+use super::{wpa_supplicant, ConfigFile};
+use crate::{Secret, ValidationError, WifiCredentials};
+
+/// Validate the credentials and the interface name, then encode them as the files that `wpa_supplicant@<interface>.service` and systemd-networkd read: the interface's `wpa_supplicant-<interface>.conf`, and a `.network` file that configures the interface with DHCP.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::systemd;
+/// use wifi_qr_code::WifiCredentials;
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// let config_files = systemd::encode(&wifi_credentials, "wlan0")
+///     .expect("Failed to validate example credentials.");
+/// assert_eq!(
+///     "/etc/wpa_supplicant/wpa_supplicant-wlan0.conf",
+///     config_files[0].path
+/// );
+/// assert!(config_files[0].private);
+/// assert_eq!("/etc/systemd/network/25-wireless-wlan0.network", config_files[1].path);
+/// ```
+pub fn encode(
+    wifi_credentials: &WifiCredentials,
+    interface: &str,
+) -> Result<Vec<ConfigFile>, ValidationError> {
+    let network_file = network_file(interface)?;
+    Ok(vec![
+        ConfigFile {
+            path: format!("/etc/wpa_supplicant/wpa_supplicant-{}.conf", interface),
+            contents: Secret::format(format_args!(
+                "ctrl_interface=DIR=/run/wpa_supplicant GROUP=netdev\nupdate_config=1\n\n{}",
+                wpa_supplicant::encode(wifi_credentials)?.as_str()
+            )),
+            private: true,
+        },
+        network_file,
+    ])
+}
+
+/// A systemd-networkd `.network` file that configures the wireless interface with DHCP for both IPv4 and IPv6 once it has associated. Fails with [`ValidationError::InterfaceName`] unless `interface` is a plain interface name, which keeps it from changing the path or adding lines to the file.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::systemd;
+///
+/// assert_eq!(
+///     "[Match]\nName=wlan0\nType=wlan\n\n[Network]\nDHCP=yes\n",
+///     systemd::network_file("wlan0").unwrap().contents
+/// );
+/// assert!(systemd::network_file("../wlan0").is_err());
+/// ```
+pub fn network_file(interface: &str) -> Result<ConfigFile, ValidationError> {
+    validate_interface(interface)?;
+    Ok(ConfigFile {
+        path: format!("/etc/systemd/network/25-wireless-{}.network", interface),
+        contents: format!(
+            "[Match]\nName={}\nType=wlan\n\n[Network]\nDHCP=yes\n",
+            interface
+        )
+        .into(),
+        private: false,
+    })
+}
+
+/// Check that the name could be a Linux network interface, which are at most 15 bytes long.
+fn validate_interface(interface: &str) -> Result<(), ValidationError> {
+    if (1..=15).contains(&interface.len())
+        && interface
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || ['-', '_', '.'].contains(&c))
+        && interface != "."
+        && interface != ".."
+    {
+        Ok(())
+    } else {
+        Err(ValidationError::InterfaceName)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_writes_the_supplicant_configuration_for_the_interface() {
+        let wifi_credentials = WifiCredentials::open("test ssid");
+        let config_files = encode(&wifi_credentials, "wlp2s0").unwrap();
+        assert_eq!(2, config_files.len());
+        assert_eq!(
+            "ctrl_interface=DIR=/run/wpa_supplicant GROUP=netdev\nupdate_config=1\n\nnetwork={\n\tssid=\"test ssid\"\n\tkey_mgmt=NONE\n}\n",
+            config_files[0].contents
+        );
+        assert_eq!(network_file("wlp2s0").unwrap(), config_files[1]);
+        for interface in ["", "..", "../../etc/x", "wlan0\nName=*", "wlan0123456789ab"] {
+            assert_eq!(
+                Err(ValidationError::InterfaceName),
+                encode(&wifi_credentials, interface)
+            );
+        }
+    }
+
+    #[test]
+    fn it_writes_config_files_below_a_root_directory() {
+        let root =
+            std::env::temp_dir().join(format!("wifi-qr-code-systemd-{}", std::process::id()));
+        let config_file = &encode(
+            &WifiCredentials::wpa2("test ssid", "test password"),
+            "wlan0",
+        )
+        .unwrap()[0];
+        // An existing file that anyone can read is restricted before the password is written to it.
+        let existing = root.join("etc/wpa_supplicant/wpa_supplicant-wlan0.conf");
+        std::fs::create_dir_all(existing.parent().unwrap()).unwrap();
+        std::fs::write(&existing, "").unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&existing, std::fs::Permissions::from_mode(0o644)).unwrap();
+        }
+        let path = config_file
+            .write_to(&root)
+            .expect("File should be written.");
+        assert_eq!(
+            root.join("etc/wpa_supplicant/wpa_supplicant-wlan0.conf"),
+            path
+        );
+        assert_eq!(
+            config_file.contents,
+            std::fs::read_to_string(&path).unwrap()
+        );
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            let mode = std::fs::metadata(&path).unwrap().permissions().mode();
+            assert_eq!(0o600, mode & 0o777);
+        }
+        let outside = ConfigFile {
+            path: String::from("/etc/../../outside.conf"),
+            ..config_file.clone()
+        };
+        assert_eq!(
+            std::io::ErrorKind::InvalidInput,
+            outside.write_to(&root).unwrap_err().kind()
+        );
+        assert!(!root.join("../outside.conf").exists());
+        std::fs::remove_dir_all(&root).unwrap();
+    }
+}
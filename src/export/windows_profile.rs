@@ -0,0 +1,155 @@
+//! Windows WLAN profiles that can be imported with `netsh wlan add profile filename=<profile>.xml`.
+
+use super::{escape_xml, escape_xml_secret};
+use crate::{AuthenticationType, Secret, ValidationError, Visibility, WifiCredentials};
+
+/// Validate the credentials, then encode them as a WLAN profile. The profile is named after the SSID and is configured to connect automatically.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::windows_profile;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let profile = windows_profile::encode(&wifi_credentials)
+///     .expect("Failed to validate example credentials.");
+/// assert!(profile.contains("<authentication>WPA2PSK</authentication>"));
+/// ```
+pub fn encode(wifi_credentials: &WifiCredentials) -> Result<Secret<String>, ValidationError> {
+    wifi_credentials.validate()?;
+    let ssid = escape_xml(&wifi_credentials.ssid);
+    Ok(Secret::format(format_args!(
+        r#"<?xml version="1.0"?>
+<WLANProfile xmlns="http://www.microsoft.com/networking/WLAN/profile/v1">
+	<name>{ssid}</name>
+	<SSIDConfig>
+		<SSID>
+			<hex>{hex}</hex>
+			<name>{ssid}</name>
+		</SSID>
+		<nonBroadcast>{non_broadcast}</nonBroadcast>
+	</SSIDConfig>
+	<connectionType>ESS</connectionType>
+	<connectionMode>auto</connectionMode>
+	<MSM>
+		<security>
+{security}		</security>
+	</MSM>
+</WLANProfile>
+"#,
+        ssid = ssid,
+        hex = encode_hex(&wifi_credentials.ssid),
+        non_broadcast = encode_visibility(&wifi_credentials.visibility),
+        security = encode_authentication_type(&wifi_credentials.authentication_type).as_str(),
+    )))
+}
+
+fn encode_hex(input: &str) -> String {
+    input.bytes().map(|byte| format!("{:02X}", byte)).collect()
+}
+
+fn encode_visibility(visibility: &Visibility) -> &'static str {
+    match visibility {
+        Visibility::Visible => "false",
+        Visibility::Hidden => "true",
+    }
+}
+
+fn encode_authentication_type(authentication_type: &AuthenticationType) -> Secret<String> {
+    match authentication_type {
+        AuthenticationType::WEP(password) => Secret::format(format_args!(
+            "{}{}",
+            encode_auth_encryption("open", "WEP"),
+            encode_shared_key("networkKey", password).as_str()
+        )),
+        AuthenticationType::WPA(password) => Secret::format(format_args!(
+            "{}{}",
+            encode_auth_encryption("WPA2PSK", "AES"),
+            encode_shared_key("passPhrase", password).as_str()
+        )),
+        AuthenticationType::WpaPsk(psk) => Secret::format(format_args!(
+            "{}{}",
+            encode_auth_encryption("WPA2PSK", "AES"),
+            encode_shared_key("networkKey", &crate::encode_hex(&**psk)).as_str()
+        )),
+        AuthenticationType::NoPassword => encode_auth_encryption("open", "none").into(),
+    }
+}
+
+fn encode_auth_encryption(authentication: &str, encryption: &str) -> String {
+    format!(
+        "\t\t\t<authEncryption>\n\t\t\t\t<authentication>{}</authentication>\n\t\t\t\t<encryption>{}</encryption>\n\t\t\t\t<useOneX>false</useOneX>\n\t\t\t</authEncryption>\n",
+        authentication, encryption
+    )
+}
+
+fn encode_shared_key(key_type: &str, password: &str) -> Secret<String> {
+    Secret::format(format_args!(
+        "\t\t\t<sharedKey>\n\t\t\t\t<keyType>{}</keyType>\n\t\t\t\t<protected>false</protected>\n\t\t\t\t<keyMaterial>{}</keyMaterial>\n\t\t\t</sharedKey>\n",
+        key_type,
+        escape_xml_secret(password).as_str()
+    ))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_encodes_a_wpa_profile() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test&ssid"),
+            authentication_type: AuthenticationType::WPA("test <password>".into()),
+            visibility: Visibility::Hidden,
+        };
+        let profile = encode(&wifi_credentials).unwrap();
+        assert!(profile.contains("\t<name>test&amp;ssid</name>\n"));
+        assert!(profile.contains("\t\t\t<hex>746573742673736964</hex>\n"));
+        assert!(profile.contains("\t\t<nonBroadcast>true</nonBroadcast>\n"));
+        assert!(profile.contains("\t\t\t\t<authentication>WPA2PSK</authentication>\n\t\t\t\t<encryption>AES</encryption>\n"));
+        assert!(profile.contains("\t\t\t\t<keyType>passPhrase</keyType>\n"));
+        assert!(profile.contains("\t\t\t\t<keyMaterial>test &lt;password&gt;</keyMaterial>\n"));
+    }
+
+    #[test]
+    fn it_encodes_wep_and_open_profiles() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        let profile = encode(&wifi_credentials).unwrap();
+        assert!(profile.contains(
+            "\t\t\t\t<authentication>open</authentication>\n\t\t\t\t<encryption>WEP</encryption>\n"
+        ));
+        assert!(profile.contains("\t\t\t\t<keyType>networkKey</keyType>\n"));
+        assert!(profile.contains("\t\t<nonBroadcast>false</nonBroadcast>\n"));
+
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Visible,
+        };
+        let profile = encode(&wifi_credentials).unwrap();
+        assert!(profile.contains("\t\t\t\t<authentication>open</authentication>\n\t\t\t\t<encryption>none</encryption>\n"));
+        assert!(!profile.contains("<sharedKey>"));
+    }
+
+    #[test]
+    fn it_rejects_control_characters() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("ab\u{1b}cd".into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            Err(ValidationError::WepKeyFormat),
+            encode(&wifi_credentials)
+        );
+    }
+}
@@ -0,0 +1,207 @@
+//! `network={ ... }` blocks for `wpa_supplicant.conf`, as used by Raspberry Pi OS and many embedded Linux images.
+//!
+//! SSIDs are written as quoted strings when they only contain printable ASCII, otherwise they are written as hex so that any byte sequence survives the configuration parser. The credentials are validated first, which limits passphrases and ASCII WEP keys to printable ASCII, so a password cannot end its line and add directives of its own to the configuration.
+
+use crate::{
+    AuthenticationType, Secret, ValidationError, Visibility, WepKeyFormat, WifiCredentials,
+};
+
+/// Validate the credentials, then encode them as a `wpa_supplicant.conf` network block. WPA passphrases are written in plain text; use [`encode_with_derived_psk`] to avoid storing the passphrase on the device.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::wpa_supplicant;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// assert_eq!(
+///     "network={\n\tssid=\"example ssid\"\n\tscan_ssid=1\n\tkey_mgmt=WPA-PSK\n\tpsk=\"example password\"\n}\n",
+///     wpa_supplicant::encode(&wifi_credentials).expect("Failed to validate example credentials.")
+/// );
+/// ```
+pub fn encode(wifi_credentials: &WifiCredentials) -> Result<Secret<String>, ValidationError> {
+    encode_network(wifi_credentials, |password| {
+        Secret::format(format_args!("\"{}\"", password))
+    })
+}
+
+/// Validate the credentials, then encode them as a `wpa_supplicant.conf` network block where the WPA passphrase has been replaced by the 256-bit pre-shared key derived from it, the same way `wpa_passphrase` does.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::export::wpa_supplicant;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("IEEE"),
+///     authentication_type: AuthenticationType::WPA("password".into()),
+///     visibility: Visibility::Visible,
+/// };
+/// assert!(wpa_supplicant::encode_with_derived_psk(&wifi_credentials)
+///     .expect("Failed to validate example credentials.")
+///     .contains("\tpsk=f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e\n"));
+/// ```
+#[cfg(feature = "psk")]
+pub fn encode_with_derived_psk(
+    wifi_credentials: &WifiCredentials,
+) -> Result<Secret<String>, ValidationError> {
+    encode_network(wifi_credentials, |password| {
+        crate::encode_hex(&*derive_psk(&wifi_credentials.ssid, password))
+    })
+}
+
+/// Derive the 256-bit WPA pre-shared key for a passphrase (PBKDF2-HMAC-SHA1 with the SSID as the salt and 4096 iterations).
+#[cfg(feature = "psk")]
+pub fn derive_psk(ssid: &str, passphrase: &str) -> Secret<[u8; 32]> {
+    let mut psk = Secret::new([0u8; 32]);
+    pbkdf2::pbkdf2_hmac::<sha1::Sha1>(passphrase.as_bytes(), ssid.as_bytes(), 4096, &mut *psk);
+    psk
+}
+
+fn encode_network(
+    wifi_credentials: &WifiCredentials,
+    encode_psk: impl Fn(&str) -> Secret<String>,
+) -> Result<Secret<String>, ValidationError> {
+    wifi_credentials.validate()?;
+    let authentication_type = match &wifi_credentials.authentication_type {
+        AuthenticationType::WEP(key) => Secret::format(format_args!(
+            "\tkey_mgmt=NONE\n\twep_key0={}\n\twep_tx_keyidx=0\n",
+            encode_wep_key(key).as_str()
+        )),
+        AuthenticationType::WPA(password) => Secret::format(format_args!(
+            "\tkey_mgmt=WPA-PSK\n\tpsk={}\n",
+            encode_psk(password).as_str()
+        )),
+        AuthenticationType::WpaPsk(psk) => Secret::format(format_args!(
+            "\tkey_mgmt=WPA-PSK\n\tpsk={}\n",
+            crate::encode_hex(&**psk).as_str()
+        )),
+        AuthenticationType::NoPassword => "\tkey_mgmt=NONE\n".into(),
+    };
+    Ok(Secret::format(format_args!(
+        "network={{\n\tssid={}\n{}{}}}\n",
+        encode_ssid(&wifi_credentials.ssid),
+        encode_visibility(&wifi_credentials.visibility),
+        authentication_type.as_str()
+    )))
+}
+
+fn encode_ssid(ssid: &str) -> String {
+    if ssid.chars().all(|c| c.is_ascii_graphic() || c == ' ') && !ssid.contains('"') {
+        format!("\"{}\"", ssid)
+    } else {
+        ssid.bytes().map(|byte| format!("{:02x}", byte)).collect()
+    }
+}
+
+/// Hex WEP keys are written unquoted, while ASCII keys are quoted, unless they contain a quote, in which case they are written as hex too.
+fn encode_wep_key(key: &str) -> Secret<String> {
+    match WepKeyFormat::detect(key) {
+        Some(WepKeyFormat::Hex) => key.into(),
+        _ if key.contains('"') => crate::encode_hex(key.as_bytes()),
+        _ => Secret::format(format_args!("\"{}\"", key)),
+    }
+}
+
+fn encode_visibility(visibility: &Visibility) -> &'static str {
+    match visibility {
+        Visibility::Visible => "",
+        Visibility::Hidden => "\tscan_ssid=1\n",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_encodes_wep_and_open_networks() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            "network={\n\tssid=\"test ssid\"\n\tkey_mgmt=NONE\n\twep_key0=\"test password\"\n\twep_tx_keyidx=0\n}\n",
+            encode(&wifi_credentials).unwrap()
+        );
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Hidden,
+        };
+        assert_eq!(
+            "network={\n\tssid=\"test ssid\"\n\tscan_ssid=1\n\tkey_mgmt=NONE\n}\n",
+            encode(&wifi_credentials).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_encodes_pre_shared_keys_unquoted() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WpaPsk([0xab; 32].into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            format!(
+                "network={{\n\tssid=\"test ssid\"\n\tkey_mgmt=WPA-PSK\n\tpsk={}\n}}\n",
+                "ab".repeat(32)
+            ),
+            encode(&wifi_credentials).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_quotes_only_ascii_wep_keys() {
+        assert_eq!("0123456789", encode_wep_key("0123456789"));
+        assert_eq!("\"abcde\"", encode_wep_key("abcde"));
+        assert_eq!("6162226364", encode_wep_key("ab\"cd"));
+    }
+
+    #[test]
+    fn it_rejects_passwords_that_would_add_lines() {
+        let wifi_credentials = WifiCredentials::wpa2(
+            "test ssid",
+            "test password\"\n}\nnetwork={\n\tssid=\"other\"",
+        );
+        assert_eq!(
+            Err(ValidationError::WpaPassphraseCharacters),
+            encode(&wifi_credentials)
+        );
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("ab\ncd".into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            Err(ValidationError::WepKeyFormat),
+            encode(&wifi_credentials)
+        );
+    }
+
+    #[test]
+    fn it_encodes_unprintable_ssids_as_hex() {
+        assert_eq!("2274657374c3a922", encode_ssid("\"testé\""));
+    }
+
+    #[cfg(feature = "psk")]
+    #[test]
+    fn it_derives_the_pre_shared_key() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("ThisIsASSID"),
+            authentication_type: AuthenticationType::WPA("ThisIsAPassword".into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            "network={\n\tssid=\"ThisIsASSID\"\n\tkey_mgmt=WPA-PSK\n\tpsk=0dc0d6eb90555ed6419756b9a15ec3e3209b63df707dd508d14581f8982721af\n}\n",
+            encode_with_derived_psk(&wifi_credentials).unwrap()
+        );
+    }
+}
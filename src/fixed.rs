@@ -0,0 +1,306 @@
+#[cfg(feature = "std")]
+use crate::WifiCredentials;
+use crate::{EscapingMode, QrCodeEcc, QrMatrix, ValidationError, WifiCredentialsRef};
+
+use core::error;
+use core::fmt::{self, Display, Formatter};
+
+/// The longest payload of valid credentials: a 32 byte SSID and a 63 character WPA passphrase that consist only of characters escaped with a backslash, on a visible network.
+const MAX_PAYLOAD_LEN: usize = "WIFI:S:;T:WPA;P:;H:false;;".len() + 2 * 32 + 2 * 63;
+
+/// The buffer passed to [`WifiCredentialsRef::encode_to_slice`], or the capacity of the string returned by `WifiCredentialsRef::encode_to_heapless`, is too small for the payload. For [`WifiCredentialsRef::encode_to_qr_matrix`], the capacity is the number of bytes the largest QR code version of the matrix holds.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub struct CapacityError {
+    /// The length of the payload in bytes.
+    pub required: usize,
+    /// The size of the buffer in bytes.
+    pub capacity: usize,
+}
+
+impl Display for CapacityError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "the payload is {} bytes long, but the buffer only holds {}",
+            self.required, self.capacity
+        )
+    }
+}
+
+impl error::Error for CapacityError {}
+
+/// Errors from [`WifiCredentialsRef::encode_to_qr_matrix`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum QrMatrixError {
+    /// The credentials do not meet the requirements of the wifi standards, so devices would fail to join the network.
+    Validation(ValidationError),
+    /// The payload does not fit into the largest QR code version of the matrix.
+    Capacity(CapacityError),
+}
+
+impl Display for QrMatrixError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Validation(error) => write!(f, "invalid credentials: {}", error),
+            Self::Capacity(error) => {
+                write!(f, "the QR code does not fit into the matrix: {}", error)
+            }
+        }
+    }
+}
+
+impl error::Error for QrMatrixError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Validation(error) => Some(error),
+            Self::Capacity(error) => Some(error),
+        }
+    }
+}
+
+impl From<ValidationError> for QrMatrixError {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+impl From<CapacityError> for QrMatrixError {
+    fn from(error: CapacityError) -> Self {
+        Self::Capacity(error)
+    }
+}
+
+impl WifiCredentialsRef<'_> {
+    /// Encode the credentials like [`WifiCredentials::encode`] into the start of `buffer`, returning the length of the payload, for firmware without a global allocator. [`ConstPayload`](crate::ConstPayload) is the equivalent for credentials known at compile time. If the buffer is too small, its contents are unspecified, and the error has the length that would have been needed.
+    ///
+    /// The credentials are not validated, so check them with [`WifiCredentialsRef::validate`] first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationTypeRef, CapacityError, Visibility, WifiCredentialsRef};
+    ///
+    /// let wifi_credentials = WifiCredentialsRef {
+    ///     ssid: "example ssid",
+    ///     authentication_type: AuthenticationTypeRef::WPA("example password"),
+    ///     visibility: Visibility::Visible,
+    /// };
+    /// let mut buffer = [0; 64];
+    /// let len = wifi_credentials.encode_to_slice(&mut buffer).expect("The buffer is too small.");
+    /// assert_eq!(b"WIFI:S:example ssid;T:WPA;P:example password;H:false;;", &buffer[..len]);
+    /// assert_eq!(
+    ///     Err(CapacityError { required: 54, capacity: 32 }),
+    ///     wifi_credentials.encode_to_slice(&mut buffer[..32])
+    /// );
+    /// ```
+    pub fn encode_to_slice(&self, buffer: &mut [u8]) -> Result<usize, CapacityError> {
+        let mut writer = SliceWriter { buffer, len: 0 };
+        self.encode_to_with(&mut writer, EscapingMode::Spec)
+            .expect("Writing to a slice only counts the bytes that do not fit.");
+        if writer.len > writer.buffer.len() {
+            return Err(CapacityError {
+                required: writer.len,
+                capacity: writer.buffer.len(),
+            });
+        }
+        Ok(writer.len)
+    }
+
+    /// Encode the credentials like [`WifiCredentials::encode`] into a [`heapless::String`](https://docs.rs/heapless) with a capacity of `N` bytes, for firmware without a global allocator.
+    ///
+    /// The credentials are not validated, so check them with [`WifiCredentialsRef::validate`] first.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationTypeRef, Visibility, WifiCredentialsRef};
+    ///
+    /// let wifi_credentials = WifiCredentialsRef {
+    ///     ssid: "example ssid",
+    ///     authentication_type: AuthenticationTypeRef::NoPassword,
+    ///     visibility: Visibility::Visible,
+    /// };
+    /// let payload = wifi_credentials
+    ///     .encode_to_heapless::<64>()
+    ///     .expect("The string is too small.");
+    /// assert_eq!("WIFI:S:example ssid;T:nopass;H:false;;", payload.as_str());
+    /// ```
+    #[cfg(feature = "heapless")]
+    pub fn encode_to_heapless<const N: usize>(&self) -> Result<heapless::String<N>, CapacityError> {
+        let mut buffer = heapless::Vec::<u8, N>::new();
+        // The buffer is filled to its capacity, and then truncated to the payload.
+        buffer
+            .resize_default(N)
+            .expect("Resizing to the capacity cannot fail.");
+        let len = self.encode_to_slice(&mut buffer)?;
+        buffer.truncate(len);
+        Ok(heapless::String::from_utf8(buffer)
+            .expect("Encoding only writes whole characters of the values."))
+    }
+
+    /// Validate the credentials and replace the contents of `matrix` with their QR code, without allocating, for firmware without a global allocator or the `std` feature. The symbol is the same as the one [`QrMatrix::encode`] generates, in the smallest version up to [`QrMatrix::MAX_VERSION`] that holds the payload. If the credentials are invalid or do not fit, the matrix is left empty.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationTypeRef, QrCodeEcc, QrMatrix, Visibility, WifiCredentialsRef};
+    ///
+    /// let wifi_credentials = WifiCredentialsRef {
+    ///     ssid: "example ssid",
+    ///     authentication_type: AuthenticationTypeRef::WPA("example password"),
+    ///     visibility: Visibility::Visible,
+    /// };
+    /// let mut matrix = QrMatrix::<{ wifi_qr_code::qr_matrix_side(5) }>::new();
+    /// wifi_credentials
+    ///     .encode_to_qr_matrix(&mut matrix, QrCodeEcc::Medium)
+    ///     .expect("The credentials do not fit into a version 5 QR code.");
+    /// assert_eq!(33, matrix.size());
+    /// ```
+    pub fn encode_to_qr_matrix<const SIDE: usize>(
+        &self,
+        matrix: &mut QrMatrix<SIDE>,
+        qr_code_error_checking: QrCodeEcc,
+    ) -> Result<(), QrMatrixError> {
+        *matrix = QrMatrix::new();
+        self.validate()?;
+        let mut payload = [0; MAX_PAYLOAD_LEN];
+        let len = self.encode_to_slice(&mut payload)?;
+        Ok(matrix.encode_bytes(&payload[..len], qr_code_error_checking)?)
+    }
+}
+
+#[cfg(feature = "std")]
+impl WifiCredentials {
+    /// Encode the credentials into the start of `buffer` without allocating, like [`WifiCredentialsRef::encode_to_slice`].
+    pub fn encode_to_slice(&self, buffer: &mut [u8]) -> Result<usize, CapacityError> {
+        self.as_borrowed().encode_to_slice(buffer)
+    }
+
+    /// Encode the credentials into a `heapless::String` without allocating, like [`WifiCredentialsRef::encode_to_heapless`].
+    #[cfg(feature = "heapless")]
+    pub fn encode_to_heapless<const N: usize>(&self) -> Result<heapless::String<N>, CapacityError> {
+        self.as_borrowed().encode_to_heapless()
+    }
+
+    /// Validate the credentials and replace the contents of `matrix` with their QR code without allocating, like [`WifiCredentialsRef::encode_to_qr_matrix`].
+    pub fn encode_to_qr_matrix<const SIDE: usize>(
+        &self,
+        matrix: &mut QrMatrix<SIDE>,
+        qr_code_error_checking: QrCodeEcc,
+    ) -> Result<(), QrMatrixError> {
+        self.as_borrowed()
+            .encode_to_qr_matrix(matrix, qr_code_error_checking)
+    }
+}
+
+/// Writes to the start of a slice, and keeps counting the bytes that do not fit once it is full.
+struct SliceWriter<'a> {
+    buffer: &'a mut [u8],
+    /// The number of bytes written, including those that did not fit.
+    len: usize,
+}
+
+impl fmt::Write for SliceWriter<'_> {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        let end = self.len + s.len();
+        if let Some(destination) = self.buffer.get_mut(self.len..end) {
+            destination.copy_from_slice(s.as_bytes());
+        }
+        self.len = end;
+        Ok(())
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+    #[test]
+    fn it_writes_the_same_payload_as_encode() {
+        let wifi_credentials = WifiCredentials::wpa2("test;ssid", "pass\\word");
+        let payload = wifi_credentials.encode();
+        let mut buffer = [0xff; 64];
+        let len = wifi_credentials.encode_to_slice(&mut buffer).unwrap();
+        assert_eq!(payload.as_str().as_bytes(), &buffer[..len]);
+        assert!(buffer[len..].iter().all(|&byte| byte == 0xff));
+        assert_eq!(
+            Ok(len),
+            wifi_credentials.encode_to_slice(&mut buffer[..len])
+        );
+        assert_eq!(
+            Err(CapacityError {
+                required: len,
+                capacity: len - 1
+            }),
+            wifi_credentials.encode_to_slice(&mut buffer[..len - 1])
+        );
+        assert_eq!(
+            Err(CapacityError {
+                required: len,
+                capacity: 10
+            }),
+            wifi_credentials.encode_to_slice(&mut buffer[..10])
+        );
+    }
+
+    #[cfg(feature = "heapless")]
+    #[test]
+    fn it_fills_heapless_strings_up_to_their_capacity() {
+        let wifi_credentials = WifiCredentials::open("test ssid");
+        let payload = wifi_credentials.encode_to_heapless::<35>().unwrap();
+        assert_eq!(wifi_credentials.encode(), payload.as_str());
+        assert_eq!(
+            Err(CapacityError {
+                required: 35,
+                capacity: 34
+            }),
+            wifi_credentials.encode_to_heapless::<34>()
+        );
+    }
+
+    #[test]
+    fn it_draws_the_same_matrix_as_encode() {
+        let wifi_credentials = WifiCredentials::wpa2("test;ssid", "pass\\word");
+        let mut expected = QrMatrix::<{ crate::qr_matrix_side(5) }>::new();
+        expected
+            .encode(&wifi_credentials, QrCodeEcc::Medium)
+            .unwrap();
+        let mut matrix = QrMatrix::new();
+        wifi_credentials
+            .encode_to_qr_matrix(&mut matrix, QrCodeEcc::Medium)
+            .unwrap();
+        assert_eq!(expected, matrix);
+
+        let mut matrix = QrMatrix::<{ crate::qr_matrix_side(2) }>::new();
+        assert_eq!(
+            Err(QrMatrixError::Capacity(CapacityError {
+                required: wifi_credentials.encode().as_str().len(),
+                capacity: 32
+            })),
+            wifi_credentials.encode_to_qr_matrix(&mut matrix, QrCodeEcc::Low)
+        );
+        assert_eq!(
+            Ok(()),
+            WifiCredentials::open("a").encode_to_qr_matrix(&mut matrix, QrCodeEcc::Low)
+        );
+        assert_eq!(
+            Err(QrMatrixError::Validation(ValidationError::EmptySsid)),
+            WifiCredentials::open("").encode_to_qr_matrix(&mut matrix, QrCodeEcc::Low)
+        );
+        assert_eq!(0, matrix.size());
+    }
+
+    #[test]
+    fn it_fits_the_longest_valid_payload_into_the_buffer() {
+        let wifi_credentials = WifiCredentials {
+            ssid: ";".repeat(32),
+            authentication_type: AuthenticationType::WPA("\\".repeat(63).into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(Ok(()), wifi_credentials.validate());
+        assert_eq!(MAX_PAYLOAD_LEN, wifi_credentials.encode().as_str().len());
+    }
+}
@@ -0,0 +1,167 @@
+use crate::{options, EncodeOptions, Error, OutputStage, QrPayload};
+
+use qrcode_generator::qrcodegen::QrCode;
+use std::fs::{self, OpenOptions};
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+
+/// The quiet zone around the QR code, in modules, which the QR code standard requires to be at least 4 modules wide.
+const QUIET_ZONE: usize = 4;
+
+/// Show the QR code full-screen on a Linux framebuffer device such as `/dev/fb0`, for kiosk boards that show the network on boot without running X or Wayland. The code is drawn in black, as large as a whole number of pixels per module allows and centered, with the rest of the screen white.
+///
+/// The size and pixel format of the screen are read from sysfs, e.g. `/sys/class/graphics/fb0`. Every framebuffer depth is supported, since black and white are all bits cleared and all bits set in any true color format. Writing to the device usually requires membership in the `video` group, and a text console on the same screen may draw over the code, e.g. its blinking cursor, unless it is switched off with `setterm --cursor off`.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wifi_qr_code::EncodeOptions;
+/// use wifi_qr_code::WifiCredentials;
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// wifi_qr_code::encode_to_framebuffer(&wifi_credentials, "/dev/fb0", &EncodeOptions::default())
+///     .expect("Failed to show the QR code on the framebuffer.");
+/// ```
+pub fn encode_to_framebuffer(
+    payload: &impl QrPayload,
+    device: impl AsRef<Path>,
+    options: &EncodeOptions,
+) -> Result<(), Error> {
+    let device = device.as_ref();
+    let qr_code = options::generate(payload, options)?;
+    let io_error = |source| Error::Io {
+        stage: OutputStage::Framebuffer,
+        source,
+    };
+    let geometry = Geometry::read(&sysfs_directory(device)).map_err(io_error)?;
+    OpenOptions::new()
+        .write(true)
+        .open(device)
+        .and_then(|mut framebuffer| framebuffer.write_all(&frame(&qr_code, &geometry)))
+        .map_err(io_error)
+}
+
+/// The sysfs directory describing a framebuffer device, which has the same name as the device node.
+fn sysfs_directory(device: &Path) -> PathBuf {
+    Path::new("/sys/class/graphics").join(device.file_name().unwrap_or_default())
+}
+
+/// The layout of a framebuffer in memory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Geometry {
+    width: usize,
+    height: usize,
+    bytes_per_pixel: usize,
+    /// The number of bytes per row, which may include padding after the last pixel.
+    stride: usize,
+}
+
+impl Geometry {
+    /// Read the layout of a framebuffer from its sysfs directory.
+    fn read(directory: &Path) -> io::Result<Self> {
+        let attribute =
+            |name| fs::read_to_string(directory.join(name)).map(|value| value.trim().to_string());
+        let invalid = |name: &str, value: &str| {
+            io::Error::new(
+                io::ErrorKind::InvalidData,
+                format!("unexpected framebuffer {}: {}", name, value),
+            )
+        };
+        let virtual_size = attribute("virtual_size")?;
+        let (width, height) = virtual_size
+            .split_once(',')
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+            .ok_or_else(|| invalid("size", &virtual_size))?;
+        let bits_per_pixel = attribute("bits_per_pixel")?;
+        let bytes_per_pixel = match bits_per_pixel.parse::<usize>() {
+            Ok(bits @ (8 | 16 | 24 | 32)) => bits / 8,
+            _ => return Err(invalid("depth", &bits_per_pixel)),
+        };
+        let stride = attribute("stride")?;
+        let stride = stride
+            .parse()
+            .ok()
+            .filter(|&stride| stride >= width * bytes_per_pixel)
+            .ok_or_else(|| invalid("stride", &stride))?;
+        Ok(Geometry {
+            width,
+            height,
+            bytes_per_pixel,
+            stride,
+        })
+    }
+}
+
+/// The contents of the framebuffer with the QR code centered on a white screen.
+fn frame(qr_code: &QrCode, geometry: &Geometry) -> Vec<u8> {
+    let modules = qr_code.size() as usize + 2 * QUIET_ZONE;
+    let module_size = (geometry.width.min(geometry.height) / modules).max(1);
+    let side = modules * module_size;
+    let left = geometry.width.saturating_sub(side) / 2;
+    let top = geometry.height.saturating_sub(side) / 2;
+    let mut frame = vec![0xff; geometry.stride * geometry.height];
+    // Modules of the quiet zone and outside of it are negative or past the end of the code, where there are no dark modules.
+    let module = |pixel: usize, start: usize| {
+        (pixel as i32 - start as i32).div_euclid(module_size as i32) - QUIET_ZONE as i32
+    };
+    for (y, row) in frame.chunks_exact_mut(geometry.stride).enumerate() {
+        for x in left..(left + side).min(geometry.width) {
+            if qr_code.get_module(module(x, left), module(y, top)) {
+                row[x * geometry.bytes_per_pixel..(x + 1) * geometry.bytes_per_pixel].fill(0);
+            }
+        }
+    }
+    frame
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::WifiCredentials;
+
+    #[test]
+    fn it_centers_the_code_on_a_white_screen() {
+        let directory =
+            std::env::temp_dir().join(format!("wifi-qr-code-fb-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        fs::write(directory.join("virtual_size"), "200,100\n").unwrap();
+        fs::write(directory.join("bits_per_pixel"), "16\n").unwrap();
+        fs::write(directory.join("stride"), "416\n").unwrap();
+        let geometry = Geometry::read(&directory).unwrap();
+        fs::write(directory.join("bits_per_pixel"), "12\n").unwrap();
+        assert_eq!(
+            io::ErrorKind::InvalidData,
+            Geometry::read(&directory).unwrap_err().kind()
+        );
+        fs::remove_dir_all(&directory).unwrap();
+        assert_eq!(
+            Geometry {
+                width: 200,
+                height: 100,
+                bytes_per_pixel: 2,
+                stride: 416,
+            },
+            geometry
+        );
+
+        let qr_code = options::generate(
+            &WifiCredentials::wpa2("test ssid", "test password"),
+            &EncodeOptions::default(),
+        )
+        .unwrap();
+        let frame = frame(&qr_code, &geometry);
+        assert_eq!(416 * 100, frame.len());
+        // 33 modules and the quiet zone fit 2 pixels per module, 82 pixels wide, leaving 59 pixels to the left and 9 above.
+        let pixel = |x: usize, y: usize| &frame[y * 416 + x * 2..y * 416 + x * 2 + 2];
+        for y in 0..100 {
+            for x in 0..200 {
+                let dark = (67..133).contains(&x)
+                    && (17..83).contains(&y)
+                    && qr_code.get_module((x as i32 - 67) / 2, (y as i32 - 17) / 2);
+                assert_eq!(if dark { [0, 0] } else { [0xff, 0xff] }, pixel(x, y));
+            }
+        }
+        // The row padding is left white too.
+        assert!(frame[400..416].iter().all(|&byte| byte == 0xff));
+    }
+}
@@ -0,0 +1,682 @@
+//! A QR code generator that draws byte mode payloads straight into the array of a [`QrMatrix`](crate::QrMatrix), for builds without the `std` feature. It keeps no buffers besides the matrix and a few bytes on the stack, and takes the same steps as the QR code library used with `std`, so both generate the same symbols.
+
+use crate::CapacityError;
+#[cfg(feature = "std")]
+use crate::QrCodeEcc;
+
+/// The error correction level of a QR code. With the `std` feature, this is the type of the same name from the [qrcode-generator](https://docs.rs/qrcode-generator) crate, which has the same variants.
+#[cfg(not(feature = "std"))]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum QrCodeEcc {
+    /// About 7% of the codewords can be restored.
+    Low,
+    /// About 15% of the codewords can be restored.
+    Medium,
+    /// About 25% of the codewords can be restored.
+    Quartile,
+    /// About 30% of the codewords can be restored.
+    High,
+}
+
+/// The number of error correction codewords in each block, by error correction level and version.
+const ECC_CODEWORDS_PER_BLOCK: [[u8; 41]; 4] = [
+    [
+        0, 7, 10, 15, 20, 26, 18, 20, 24, 30, 18, 20, 24, 26, 30, 22, 24, 28, 30, 28, 28, 28, 28,
+        30, 30, 26, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 10, 16, 26, 18, 24, 16, 18, 22, 22, 26, 30, 22, 22, 24, 24, 28, 28, 26, 26, 26, 26, 28,
+        28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28, 28,
+    ],
+    [
+        0, 13, 22, 18, 26, 18, 24, 18, 22, 20, 24, 28, 26, 24, 20, 30, 24, 28, 28, 26, 30, 28, 30,
+        30, 30, 30, 28, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+    [
+        0, 17, 28, 22, 16, 22, 28, 26, 26, 24, 28, 24, 28, 22, 24, 24, 30, 28, 28, 26, 28, 30, 24,
+        30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30, 30,
+    ],
+];
+
+/// The number of error correction blocks the codewords are split into, by error correction level and version.
+const NUM_ERROR_CORRECTION_BLOCKS: [[u8; 41]; 4] = [
+    [
+        0, 1, 1, 1, 1, 1, 2, 2, 2, 2, 4, 4, 4, 4, 4, 6, 6, 6, 6, 7, 8, 8, 9, 9, 10, 12, 12, 12, 13,
+        14, 15, 16, 17, 18, 19, 19, 20, 21, 22, 24, 25,
+    ],
+    [
+        0, 1, 1, 1, 2, 2, 4, 4, 4, 5, 5, 5, 8, 9, 9, 10, 10, 11, 13, 14, 16, 17, 17, 18, 20, 21,
+        23, 25, 26, 28, 29, 31, 33, 35, 37, 38, 40, 43, 45, 47, 49,
+    ],
+    [
+        0, 1, 1, 2, 2, 4, 4, 6, 6, 8, 8, 8, 10, 12, 16, 12, 17, 16, 18, 21, 20, 23, 23, 25, 27, 29,
+        34, 34, 35, 38, 40, 43, 45, 48, 51, 53, 56, 59, 62, 65, 68,
+    ],
+    [
+        0, 1, 1, 2, 4, 4, 4, 5, 6, 8, 8, 11, 11, 16, 16, 18, 16, 19, 21, 25, 25, 25, 34, 30, 32,
+        35, 37, 40, 42, 45, 48, 51, 54, 57, 60, 63, 66, 70, 74, 77, 81,
+    ],
+];
+
+/// The largest number of error correction codewords in a block, at any version and level.
+const MAX_ECC_CODEWORDS: usize = 30;
+
+/// The penalties the QR code standard scores masked symbols with: for runs of five or more modules of the same color, for 2 by 2 blocks of the same color, for patterns that look like finder patterns, and for an imbalance of dark and light modules.
+const PENALTY_N1: i32 = 3;
+const PENALTY_N2: i32 = 3;
+const PENALTY_N3: i32 = 40;
+const PENALTY_N4: i32 = 10;
+
+/// The row of the tables for the error correction level.
+fn ordinal(ecc: QrCodeEcc) -> usize {
+    match ecc {
+        QrCodeEcc::Low => 0,
+        QrCodeEcc::Medium => 1,
+        QrCodeEcc::Quartile => 2,
+        QrCodeEcc::High => 3,
+    }
+}
+
+/// The two bits the format information stores the error correction level as.
+fn format_bits(ecc: QrCodeEcc) -> u32 {
+    match ecc {
+        QrCodeEcc::Low => 1,
+        QrCodeEcc::Medium => 0,
+        QrCodeEcc::Quartile => 3,
+        QrCodeEcc::High => 2,
+    }
+}
+
+/// The number of modules of the version that are left for codewords once the function patterns are drawn, including the remainder bits that do not make up a whole codeword.
+fn raw_modules(version: u8) -> usize {
+    let version = usize::from(version);
+    let mut result = (16 * version + 128) * version + 64;
+    if version >= 2 {
+        let alignment_patterns = version / 7 + 2;
+        result -= (25 * alignment_patterns - 10) * alignment_patterns - 55;
+        if version >= 7 {
+            result -= 36;
+        }
+    }
+    result
+}
+
+/// The number of codewords of the version and level that hold data rather than error correction.
+fn data_codewords(version: u8, ecc: QrCodeEcc) -> usize {
+    let (level, version) = (ordinal(ecc), usize::from(version));
+    raw_modules(version as u8) / 8
+        - usize::from(ECC_CODEWORDS_PER_BLOCK[level][version])
+            * usize::from(NUM_ERROR_CORRECTION_BLOCKS[level][version])
+}
+
+/// The width of the character count of a byte mode segment.
+fn count_bits(version: u8) -> usize {
+    if version < 10 {
+        8
+    } else {
+        16
+    }
+}
+
+/// The number of payload bytes a QR code of the version and level holds, after the mode indicator and character count.
+pub(crate) fn byte_capacity(version: u8, ecc: QrCodeEcc) -> usize {
+    (data_codewords(version, ecc) * 8 - 4 - count_bits(version)) / 8
+}
+
+/// Generate the QR code of `payload` into the top left corner of `modules`, which must be all light, in the smallest version up to `max_version` that holds it, and return the width of the code in modules. The error correction level is raised while the payload still fits into that version, and the mask is chosen automatically.
+pub(crate) fn generate<const SIDE: usize>(
+    modules: &mut [[bool; SIDE]; SIDE],
+    max_version: u8,
+    payload: &[u8],
+    ecc: QrCodeEcc,
+) -> Result<usize, CapacityError> {
+    let version = (1..=max_version)
+        .find(|&version| payload.len() <= byte_capacity(version, ecc))
+        .ok_or(CapacityError {
+            required: payload.len(),
+            capacity: match max_version {
+                0 => 0,
+                max_version => byte_capacity(max_version, ecc),
+            },
+        })?;
+    let ecc = [QrCodeEcc::Medium, QrCodeEcc::Quartile, QrCodeEcc::High]
+        .iter()
+        .copied()
+        .filter(|&higher| payload.len() <= byte_capacity(version, higher))
+        .fold(ecc, |ecc, higher| ecc.max(higher));
+
+    let mut symbol = Symbol::new(modules, version);
+    symbol.draw_function_patterns(ecc);
+    symbol.draw_codewords(&Codewords::new(payload, version, ecc));
+    let mut best = (i32::MAX, 0);
+    for mask in 0..8 {
+        symbol.apply_mask(mask);
+        symbol.draw_format_bits(ecc, mask);
+        let penalty = symbol.penalty();
+        if penalty < best.0 {
+            best = (penalty, mask);
+        }
+        // Masks are applied with XOR, so applying it again removes it.
+        symbol.apply_mask(mask);
+    }
+    symbol.apply_mask(best.1);
+    symbol.draw_format_bits(ecc, best.1);
+    Ok(symbol.size as usize)
+}
+
+/// The data and error correction codewords of a byte mode payload. They are computed as they are placed rather than kept in a buffer, which costs recomputing the error correction of a block for each of its codewords.
+struct Codewords<'a> {
+    payload: &'a [u8],
+    /// The number of nibbles before the payload, for the mode indicator and character count.
+    header: usize,
+    /// The number of data codewords.
+    data_len: usize,
+    /// The number of blocks the codewords are split into.
+    blocks: usize,
+    /// The number of blocks with one data codeword less than the others, which come first.
+    short_blocks: usize,
+    /// The number of data codewords in a short block.
+    short_data_len: usize,
+    /// The number of error correction codewords in each block.
+    ecc_len: usize,
+    /// The Reed-Solomon generator polynomial, without its leading coefficient.
+    divisor: [u8; MAX_ECC_CODEWORDS],
+}
+
+impl<'a> Codewords<'a> {
+    fn new(payload: &'a [u8], version: u8, ecc: QrCodeEcc) -> Self {
+        let level = ordinal(ecc);
+        let blocks = usize::from(NUM_ERROR_CORRECTION_BLOCKS[level][usize::from(version)]);
+        let ecc_len = usize::from(ECC_CODEWORDS_PER_BLOCK[level][usize::from(version)]);
+        let raw_codewords = raw_modules(version) / 8;
+        Codewords {
+            payload,
+            header: 1 + count_bits(version) / 4,
+            data_len: data_codewords(version, ecc),
+            blocks,
+            short_blocks: blocks - raw_codewords % blocks,
+            short_data_len: raw_codewords / blocks - ecc_len,
+            ecc_len,
+            divisor: divisor(ecc_len),
+        }
+    }
+
+    /// The nibble at `index` of the bit stream, which is zero past the payload, for the terminator and the padding to a whole byte.
+    fn nibble(&self, index: usize) -> u8 {
+        if index == 0 {
+            // The mode indicator of byte mode.
+            0b0100
+        } else if index < self.header {
+            (self.payload.len() >> (4 * (self.header - 1 - index))) as u8 & 0xf
+        } else {
+            let index = index - self.header;
+            match self.payload.get(index / 2) {
+                Some(byte) if index.is_multiple_of(2) => byte >> 4,
+                Some(byte) => byte & 0xf,
+                None => 0,
+            }
+        }
+    }
+
+    /// The data codeword at `index`, in the order of the bit stream.
+    fn data(&self, index: usize) -> u8 {
+        // The payload ends with a nibble of terminator, and another one if that ends in the middle of a byte, and the rest of the data codewords alternate between two pad bytes.
+        let padding = (self.header + 2 * self.payload.len() + 2) / 2;
+        if index < padding {
+            self.nibble(2 * index) << 4 | self.nibble(2 * index + 1)
+        } else if (index - padding).is_multiple_of(2) {
+            0xec
+        } else {
+            0x11
+        }
+    }
+
+    /// The index of the first data codeword of the block.
+    fn block_start(&self, block: usize) -> usize {
+        block * self.short_data_len + block.saturating_sub(self.short_blocks)
+    }
+
+    /// The error correction codeword at `index` of the block.
+    fn ecc(&self, block: usize, index: usize) -> u8 {
+        let mut remainder = [0; MAX_ECC_CODEWORDS];
+        let remainder = &mut remainder[..self.ecc_len];
+        let start = self.block_start(block);
+        for codeword in start..self.block_start(block + 1) {
+            let factor = self.data(codeword) ^ remainder[0];
+            remainder.copy_within(1.., 0);
+            remainder[self.ecc_len - 1] = 0;
+            for (coefficient, divisor) in remainder.iter_mut().zip(&self.divisor) {
+                *coefficient ^= multiply(*divisor, factor);
+            }
+        }
+        remainder[index]
+    }
+
+    /// The codeword at `index` in the order they are placed, which interleaves the blocks: the first codeword of each block in turn, then the second, and so on, with the data codewords of all blocks before their error correction codewords.
+    fn get(&self, index: usize) -> u8 {
+        if index >= self.data_len {
+            let index = index - self.data_len;
+            return self.ecc(index % self.blocks, index / self.blocks);
+        }
+        let (block, offset) = if index < self.short_data_len * self.blocks {
+            (index % self.blocks, index / self.blocks)
+        } else {
+            // Only the long blocks have a codeword in the last column of data.
+            let long_block = index - self.short_data_len * self.blocks;
+            (self.short_blocks + long_block, self.short_data_len)
+        };
+        self.data(self.block_start(block) + offset)
+    }
+}
+
+/// The Reed-Solomon generator polynomial of the degree, highest coefficient first, without the leading coefficient of 1.
+fn divisor(degree: usize) -> [u8; MAX_ECC_CODEWORDS] {
+    let mut result = [0; MAX_ECC_CODEWORDS];
+    result[degree - 1] = 1;
+    let mut root = 1;
+    for _ in 0..degree {
+        for j in 0..degree {
+            result[j] = multiply(result[j], root);
+            if j + 1 < degree {
+                result[j] ^= result[j + 1];
+            }
+        }
+        root = multiply(root, 0x02);
+    }
+    result
+}
+
+/// Multiply in the Galois field GF(2^8) with the polynomial the QR code standard uses.
+fn multiply(x: u8, y: u8) -> u8 {
+    let mut z: u8 = 0;
+    for i in (0..8).rev() {
+        z = (z << 1) ^ ((z >> 7) * 0x1d);
+        z ^= ((y >> i) & 1) * x;
+    }
+    z
+}
+
+/// A QR code of one version being drawn into the top left corner of the array.
+struct Symbol<'a, const SIDE: usize> {
+    modules: &'a mut [[bool; SIDE]; SIDE],
+    version: u8,
+    size: i32,
+    /// The centers of the alignment patterns along either axis, in the first `alignment_count` entries.
+    alignment: [i32; 7],
+    alignment_count: usize,
+}
+
+impl<'a, const SIDE: usize> Symbol<'a, SIDE> {
+    fn new(modules: &'a mut [[bool; SIDE]; SIDE], version: u8) -> Self {
+        let size = 17 + 4 * i32::from(version);
+        let mut alignment = [0; 7];
+        let alignment_count = match version {
+            1 => 0,
+            _ => usize::from(version / 7 + 2),
+        };
+        if alignment_count > 0 {
+            let count = alignment_count as i32;
+            let step = match version {
+                32 => 26,
+                _ => (i32::from(version) * 4 + count * 2 + 1) / (count * 2 - 2) * 2,
+            };
+            alignment[0] = 6;
+            for (i, position) in (1..).zip(&mut alignment[1..alignment_count]) {
+                *position = size - 7 - (count - 1 - i) * step;
+            }
+        }
+        Symbol {
+            modules,
+            version,
+            size,
+            alignment,
+            alignment_count,
+        }
+    }
+
+    fn get(&self, x: i32, y: i32) -> bool {
+        self.modules[y as usize][x as usize]
+    }
+
+    fn set(&mut self, x: i32, y: i32, dark: bool) {
+        self.modules[y as usize][x as usize] = dark;
+    }
+
+    /// Whether the module is part of a function pattern, the format or version information, rather than of the codewords.
+    fn is_function(&self, x: i32, y: i32) -> bool {
+        let size = self.size;
+        // The timing patterns, and the finder patterns with their separators and the format information next to them.
+        if x == 6
+            || y == 6
+            || (x <= 8 && y <= 8)
+            || (x >= size - 8 && y <= 8)
+            || (x <= 8 && y >= size - 8)
+        {
+            return true;
+        }
+        let version_information = |a: i32, b: i32| (size - 11..size - 8).contains(&a) && b < 6;
+        if self.version >= 7 && (version_information(x, y) || version_information(y, x)) {
+            return true;
+        }
+        let alignment = &self.alignment[..self.alignment_count];
+        let near = |c: i32| alignment.iter().position(|&center| (c - center).abs() <= 2);
+        match (near(x), near(y)) {
+            (Some(i), Some(j)) => !self.overlaps_finder(i, j),
+            _ => false,
+        }
+    }
+
+    /// Whether the alignment pattern at the `i`th center horizontally and `j`th vertically would overlap a finder pattern, so it is left out.
+    fn overlaps_finder(&self, i: usize, j: usize) -> bool {
+        let last = self.alignment_count - 1;
+        (i == 0 && (j == 0 || j == last)) || (i == last && j == 0)
+    }
+
+    fn draw_function_patterns(&mut self, ecc: QrCodeEcc) {
+        let size = self.size;
+        for i in 0..size {
+            self.set(6, i, i % 2 == 0);
+            self.set(i, 6, i % 2 == 0);
+        }
+        self.draw_finder_pattern(3, 3);
+        self.draw_finder_pattern(size - 4, 3);
+        self.draw_finder_pattern(3, size - 4);
+        for i in 0..self.alignment_count {
+            for j in 0..self.alignment_count {
+                if !self.overlaps_finder(i, j) {
+                    self.draw_alignment_pattern(self.alignment[i], self.alignment[j]);
+                }
+            }
+        }
+        // The mask is chosen once the codewords are drawn, which overwrites these format bits.
+        self.draw_format_bits(ecc, 0);
+        self.draw_version();
+    }
+
+    fn draw_finder_pattern(&mut self, x: i32, y: i32) {
+        for dy in -4..=4 {
+            for dx in -4..=4 {
+                let (xx, yy) = (x + dx, y + dy);
+                if (0..self.size).contains(&xx) && (0..self.size).contains(&yy) {
+                    let distance = dx.abs().max(dy.abs());
+                    self.set(xx, yy, distance != 2 && distance != 4);
+                }
+            }
+        }
+    }
+
+    fn draw_alignment_pattern(&mut self, x: i32, y: i32) {
+        for dy in -2..=2 {
+            for dx in -2..=2 {
+                self.set(x + dx, y + dy, dx.abs().max(dy.abs()) != 1);
+            }
+        }
+    }
+
+    fn draw_format_bits(&mut self, ecc: QrCodeEcc, mask: u8) {
+        let data = format_bits(ecc) << 3 | u32::from(mask);
+        let mut remainder = data;
+        for _ in 0..10 {
+            remainder = (remainder << 1) ^ ((remainder >> 9) * 0x537);
+        }
+        let bits = (data << 10 | remainder) ^ 0x5412;
+        let bit = |i: i32| (bits >> i) & 1 != 0;
+        for i in 0..6 {
+            self.set(8, i, bit(i));
+        }
+        self.set(8, 7, bit(6));
+        self.set(8, 8, bit(7));
+        self.set(7, 8, bit(8));
+        for i in 9..15 {
+            self.set(14 - i, 8, bit(i));
+        }
+        let size = self.size;
+        for i in 0..8 {
+            self.set(size - 1 - i, 8, bit(i));
+        }
+        for i in 8..15 {
+            self.set(8, size - 15 + i, bit(i));
+        }
+        // The dark module next to the bottom left finder pattern.
+        self.set(8, size - 8, true);
+    }
+
+    fn draw_version(&mut self) {
+        if self.version < 7 {
+            return;
+        }
+        let data = u32::from(self.version);
+        let mut remainder = data;
+        for _ in 0..12 {
+            remainder = (remainder << 1) ^ ((remainder >> 11) * 0x1f25);
+        }
+        let bits = data << 12 | remainder;
+        for i in 0..18 {
+            let dark = (bits >> i) & 1 != 0;
+            let (a, b) = (self.size - 11 + i % 3, i / 3);
+            self.set(a, b, dark);
+            self.set(b, a, dark);
+        }
+    }
+
+    /// Place the codewords in the zigzag of two module wide columns from the bottom right corner, skipping the function patterns. The remainder bits are left light.
+    fn draw_codewords(&mut self, codewords: &Codewords<'_>) {
+        let bits = raw_modules(self.version) / 8 * 8;
+        let mut i = 0;
+        let mut codeword = 0;
+        let mut right = self.size - 1;
+        while right >= 1 {
+            // The vertical timing pattern takes up a whole column.
+            if right == 6 {
+                right = 5;
+            }
+            for vertical in 0..self.size {
+                for j in 0..2 {
+                    let x = right - j;
+                    let upward = (right + 1) & 2 == 0;
+                    let y = if upward {
+                        self.size - 1 - vertical
+                    } else {
+                        vertical
+                    };
+                    if !self.is_function(x, y) && i < bits {
+                        if i % 8 == 0 {
+                            codeword = codewords.get(i / 8);
+                        }
+                        self.set(x, y, (codeword >> (7 - i % 8)) & 1 != 0);
+                        i += 1;
+                    }
+                }
+            }
+            right -= 2;
+        }
+    }
+
+    fn apply_mask(&mut self, mask: u8) {
+        for y in 0..self.size {
+            for x in 0..self.size {
+                let invert = match mask {
+                    0 => (x + y) % 2 == 0,
+                    1 => y % 2 == 0,
+                    2 => x % 3 == 0,
+                    3 => (x + y) % 3 == 0,
+                    4 => (x / 3 + y / 2) % 2 == 0,
+                    5 => x * y % 2 + x * y % 3 == 0,
+                    6 => (x * y % 2 + x * y % 3) % 2 == 0,
+                    _ => ((x + y) % 2 + x * y % 3) % 2 == 0,
+                };
+                if invert && !self.is_function(x, y) {
+                    let dark = self.get(x, y);
+                    self.set(x, y, !dark);
+                }
+            }
+        }
+    }
+
+    /// Score the masked symbol by how hard it is to scan, lower being better.
+    fn penalty(&self) -> i32 {
+        let size = self.size;
+        let mut result = 0;
+        // Runs and finder-like patterns in the rows, then in the columns.
+        for transposed in [false, true].iter().copied() {
+            let module = |along: i32, across: i32| match transposed {
+                false => self.get(along, across),
+                true => self.get(across, along),
+            };
+            for across in 0..size {
+                let mut run_color = false;
+                let mut run_length = 0;
+                let mut history = RunHistory::new(size);
+                for along in 0..size {
+                    if module(along, across) == run_color {
+                        run_length += 1;
+                        if run_length == 5 {
+                            result += PENALTY_N1;
+                        } else if run_length > 5 {
+                            result += 1;
+                        }
+                    } else {
+                        history.add(run_length);
+                        if !run_color {
+                            result += history.count_patterns() * PENALTY_N3;
+                        }
+                        run_color = module(along, across);
+                        run_length = 1;
+                    }
+                }
+                result += history.terminate_and_count(run_color, run_length) * PENALTY_N3;
+            }
+        }
+        let mut dark = 0;
+        for y in 0..size {
+            for x in 0..size {
+                let color = self.get(x, y);
+                dark += i32::from(color);
+                if x < size - 1
+                    && y < size - 1
+                    && color == self.get(x + 1, y)
+                    && color == self.get(x, y + 1)
+                    && color == self.get(x + 1, y + 1)
+                {
+                    result += PENALTY_N2;
+                }
+            }
+        }
+        // Every step of 5% that the share of dark modules is away from half.
+        let total = size * size;
+        let k = ((dark * 20 - total * 10).abs() + total - 1) / total - 1;
+        result + k * PENALTY_N4
+    }
+}
+
+/// The lengths of the last seven runs of modules in a row or column, most recent first, for finding patterns that look like the 1:1:3:1:1 finder patterns.
+struct RunHistory {
+    size: i32,
+    runs: [i32; 7],
+}
+
+impl RunHistory {
+    fn new(size: i32) -> Self {
+        RunHistory { size, runs: [0; 7] }
+    }
+
+    fn add(&mut self, mut run_length: i32) {
+        // The light quiet zone counts towards the first run.
+        if self.runs[0] == 0 {
+            run_length += self.size;
+        }
+        self.runs.copy_within(..6, 1);
+        self.runs[0] = run_length;
+    }
+
+    /// The number of finder-like patterns ending at the most recent light run, with at least four light modules on either side.
+    fn count_patterns(&self) -> i32 {
+        let runs = &self.runs;
+        let n = runs[1];
+        let core = n > 0 && runs[2] == n && runs[3] == n * 3 && runs[4] == n && runs[5] == n;
+        i32::from(core && runs[0] >= n * 4 && runs[6] >= n)
+            + i32::from(core && runs[6] >= n * 4 && runs[0] >= n)
+    }
+
+    fn terminate_and_count(mut self, run_color: bool, mut run_length: i32) -> i32 {
+        if run_color {
+            self.add(run_length);
+            run_length = 0;
+        }
+        // The light quiet zone counts towards the last run.
+        self.add(run_length + self.size);
+        self.count_patterns()
+    }
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+
+    use qrcode_generator::qrcodegen::QrCode;
+
+    #[test]
+    fn it_generates_the_same_symbols_as_the_std_library() {
+        let levels = [
+            QrCodeEcc::Low,
+            QrCodeEcc::Medium,
+            QrCodeEcc::Quartile,
+            QrCodeEcc::High,
+        ];
+        for ecc in levels.iter().copied() {
+            for version in 1..=40 {
+                let mut modules = [[false; 177]; 177];
+                // Both full codes and codes with pad bytes, and payloads that end halfway through a byte of the bit stream.
+                let len = byte_capacity(version, ecc) - usize::from(version % 3);
+                let payload: Vec<u8> = (0..len).map(|i| (i * 37 + 11) as u8).collect();
+                let expected = QrCode::encode_binary(&payload, ecc).unwrap();
+                let size = generate(&mut modules, 40, &payload, ecc).unwrap();
+                assert_eq!(
+                    expected.size() as usize,
+                    size,
+                    "{:?}, version {}",
+                    ecc,
+                    version
+                );
+                for (y, row) in modules.iter().enumerate() {
+                    for (x, module) in row.iter().enumerate() {
+                        assert_eq!(
+                            expected.get_module(x as i32, y as i32),
+                            *module,
+                            "{:?}, version {}, {}, {}",
+                            ecc,
+                            version,
+                            x,
+                            y
+                        );
+                    }
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn it_reports_the_capacity_of_the_largest_version() {
+        let mut modules = [[false; 29]; 29];
+        let payload = [b'a'; 54];
+        assert_eq!(53, byte_capacity(3, QrCodeEcc::Low));
+        assert_eq!(
+            Err(CapacityError {
+                required: 54,
+                capacity: 53
+            }),
+            generate(&mut modules, 3, &payload, QrCodeEcc::Low)
+        );
+        assert_eq!(
+            Ok(29),
+            generate(&mut modules, 3, &payload[..53], QrCodeEcc::Low)
+        );
+        assert_eq!(
+            Err(CapacityError {
+                required: 1,
+                capacity: 0
+            }),
+            generate(&mut modules, 0, &payload[..1], QrCodeEcc::Low)
+        );
+    }
+}
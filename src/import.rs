@@ -0,0 +1,33 @@
+//! Import credentials from the configuration formats used by routers and access points, so codes can be generated for every configured network without re-typing passwords.
+
+#[cfg(feature = "fritzbox")]
+pub mod fritzbox;
+pub mod mikrotik;
+pub mod openwrt;
+#[cfg(feature = "unifi")]
+pub mod unifi;
+
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+
+/// Errors when importing credentials.
+#[derive(Debug)]
+pub enum ImportError {
+    /// The input does not follow the expected syntax. The line number is 1-based.
+    Syntax {
+        /// The line that could not be parsed.
+        line: usize,
+        /// A description of what was wrong with the line.
+        reason: String,
+    },
+}
+
+impl Display for ImportError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax { line, reason } => write!(f, "syntax error on line {}: {}", line, reason),
+        }
+    }
+}
+
+impl Error for ImportError {}
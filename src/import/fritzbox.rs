@@ -0,0 +1,458 @@
+//! Read the WLANs configured on an AVM Fritz!Box, including the guest WLAN, over its TR-064 interface.
+//!
+//! TR-064 has to be enabled on the box under "Home Network > Network > Network Settings > Allow access for applications", and the user needs the "Fritz!Box settings" permission. Every WLAN is a `WLANConfiguration` service: usually the 2.4 GHz band, then the 5 GHz band, then the guest WLAN. Disabled WLANs and WPA-Enterprise WLANs are skipped, and bands that share the same credentials are only returned once.
+
+use crate::{AuthenticationType, Secret, Visibility, WifiCredentials};
+
+use md5::{Digest, Md5};
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// The number of `WLANConfiguration` services that are queried, which covers tri-band boxes with a guest WLAN.
+const SERVICES: u8 = 4;
+
+/// Errors when reading the WLANs of a Fritz!Box.
+#[derive(Debug)]
+pub enum FritzBoxError {
+    /// The box could not be reached.
+    Http(Box<ureq::Error>),
+    /// The response could not be read.
+    Io(io::Error),
+    /// The box rejected the username or password, or the user is not allowed to read the WLAN settings.
+    Unauthorized,
+    /// The box returned a SOAP fault for an action.
+    Fault {
+        /// The action that failed, such as `GetSecurityKeys`.
+        action: &'static str,
+        /// The UPnP error code.
+        code: String,
+        /// The description of the error.
+        description: String,
+    },
+    /// A response did not contain an expected value.
+    UnexpectedResponse(String),
+}
+
+impl Display for FritzBoxError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(error) => write!(f, "failed to query the Fritz!Box: {}", error),
+            Self::Io(error) => write!(f, "failed to read the response: {}", error),
+            Self::Unauthorized => f.write_str("the Fritz!Box rejected the username or password"),
+            Self::Fault {
+                action,
+                code,
+                description,
+            } => write!(f, "{} failed with error {}: {}", action, code, description),
+            Self::UnexpectedResponse(reason) => {
+                write!(f, "unexpected response from the Fritz!Box: {}", reason)
+            }
+        }
+    }
+}
+
+impl error::Error for FritzBoxError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Http(error) => Some(error.as_ref()),
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for FritzBoxError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// The TR-064 interface of a Fritz!Box.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FritzBox {
+    /// The base URL of the TR-064 interface, `http://fritz.box:49000` by default.
+    pub url: String,
+    /// The username to log in with. Boxes that log in with only a password accept an empty username.
+    pub username: String,
+    /// The password to log in with.
+    pub password: String,
+}
+
+impl FritzBox {
+    /// Log in to the box at `fritz.box` with the username and password.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::import::fritzbox::FritzBox;
+    ///
+    /// let fritz_box = FritzBox {
+    ///     url: String::from("http://192.168.178.1:49000"),
+    ///     ..FritzBox::new("example user", "example password")
+    /// };
+    /// ```
+    pub fn new(username: impl Into<String>, password: impl Into<String>) -> Self {
+        FritzBox {
+            url: String::from("http://fritz.box:49000"),
+            username: username.into(),
+            password: password.into(),
+        }
+    }
+
+    /// Read the credentials of every enabled WLAN, with the guest WLAN last.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wifi_qr_code::import::fritzbox::FritzBox;
+    /// use wifi_qr_code::QrCodeEcc;
+    ///
+    /// let fritz_box = FritzBox::new("example user", "example password");
+    /// let wifi_credentials = fritz_box.credentials().expect("Failed to read the WLANs.");
+    /// let guest = wifi_credentials.last().expect("No WLAN is enabled.");
+    /// guest
+    ///     .qr(QrCodeEcc::Medium)
+    ///     .png(std::io::stdout())
+    ///     .expect("Failed to write PNG.");
+    /// ```
+    pub fn credentials(&self) -> Result<Vec<WifiCredentials>, FritzBoxError> {
+        let mut session = Session {
+            fritz_box: self,
+            agent: ureq::AgentBuilder::new()
+                .timeout(Duration::from_secs(10))
+                .build(),
+            challenge: None,
+            count: 0,
+        };
+        let mut wifi_credentials: Vec<WifiCredentials> = Vec::new();
+        for service in 1..=SERVICES {
+            let info = match session.call(service, "GetInfo")? {
+                Some(info) => info,
+                None => continue,
+            };
+            if value(&info, "NewEnable")? != "1" {
+                continue;
+            }
+            let keys = session
+                .call(service, "GetSecurityKeys")?
+                .ok_or_else(|| unexpected("GetSecurityKeys is not supported"))?;
+            let beacon = session.call(service, "GetBeaconAdvertisement")?;
+            if let Some(credentials) = parse_wlan(&info, &keys, beacon.as_deref())? {
+                if !wifi_credentials.contains(&credentials) {
+                    wifi_credentials.push(credentials);
+                }
+            }
+        }
+        Ok(wifi_credentials)
+    }
+}
+
+/// The parameters of an HTTP digest authentication challenge.
+struct Challenge {
+    realm: String,
+    nonce: String,
+    opaque: Option<String>,
+}
+
+/// A connection that reuses the last digest challenge until the box sends a new one.
+struct Session<'a> {
+    fritz_box: &'a FritzBox,
+    agent: ureq::Agent,
+    challenge: Option<Challenge>,
+    count: u32,
+}
+
+impl Session<'_> {
+    /// Call the action of the `WLANConfiguration` service with the number, returning the response body, or `None` if the box does not have the service or action.
+    fn call(&mut self, service: u8, action: &'static str) -> Result<Option<String>, FritzBoxError> {
+        let path = format!("/upnp/control/wlanconfig{}", service);
+        let service_type = format!("urn:dslforum-org:service:WLANConfiguration:{}", service);
+        let body = format!(
+            "<?xml version=\"1.0\" encoding=\"utf-8\"?>\n<s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\" s:encodingStyle=\"http://schemas.xmlsoap.org/soap/encoding/\"><s:Body><u:{action} xmlns:u=\"{service_type}\"/></s:Body></s:Envelope>",
+            action = action,
+            service_type = service_type
+        );
+        for attempt in 0..2 {
+            let mut request = self
+                .agent
+                .post(&format!("{}{}", self.fritz_box.url, path))
+                .set("Content-Type", "text/xml; charset=\"utf-8\"")
+                .set("SOAPAction", &format!("{}#{}", service_type, action));
+            if let Some(challenge) = &self.challenge {
+                self.count += 1;
+                request = request.set(
+                    "Authorization",
+                    &authorization(
+                        challenge,
+                        &self.fritz_box.username,
+                        &self.fritz_box.password,
+                        &path,
+                        self.count,
+                        &cnonce(),
+                    ),
+                );
+            }
+            match request.send_string(&body) {
+                Ok(response) => return Ok(Some(response.into_string()?)),
+                Err(ureq::Error::Status(401, response)) if attempt == 0 => {
+                    self.challenge = response
+                        .header("WWW-Authenticate")
+                        .and_then(parse_challenge);
+                    self.count = 0;
+                    if self.challenge.is_none() {
+                        return Err(FritzBoxError::Unauthorized);
+                    }
+                }
+                Err(ureq::Error::Status(401, _)) => return Err(FritzBoxError::Unauthorized),
+                Err(ureq::Error::Status(404, _)) => return Ok(None),
+                Err(ureq::Error::Status(500, response)) => {
+                    let fault = response.into_string()?;
+                    let code = element(&fault, "errorCode").unwrap_or_default();
+                    // 401 is UPnP's "Invalid Action", for actions that older boxes do not have.
+                    if code == "401" {
+                        return Ok(None);
+                    }
+                    return Err(FritzBoxError::Fault {
+                        action,
+                        code,
+                        description: element(&fault, "errorDescription").unwrap_or_default(),
+                    });
+                }
+                Err(error) => return Err(FritzBoxError::Http(Box::new(error))),
+            }
+        }
+        Err(FritzBoxError::Unauthorized)
+    }
+}
+
+/// Parse a `WWW-Authenticate: Digest ...` header.
+fn parse_challenge(header: &str) -> Option<Challenge> {
+    let parameters = header.trim().strip_prefix("Digest ")?;
+    let mut realm = None;
+    let mut nonce = None;
+    let mut opaque = None;
+    let mut rest = parameters.trim_start();
+    while !rest.is_empty() {
+        let (name, value) = rest.split_once('=')?;
+        let (value, remainder) = match value.strip_prefix('"') {
+            Some(quoted) => {
+                let end = quoted.find('"')?;
+                (&quoted[..end], &quoted[end + 1..])
+            }
+            None => value.split_at(value.find(',').unwrap_or(value.len())),
+        };
+        match name.trim() {
+            "realm" => realm = Some(String::from(value)),
+            "nonce" => nonce = Some(String::from(value)),
+            "opaque" => opaque = Some(String::from(value)),
+            _ => {}
+        }
+        rest = remainder.trim_start_matches([',', ' ']);
+    }
+    Some(Challenge {
+        realm: realm?,
+        nonce: nonce?,
+        opaque,
+    })
+}
+
+/// The `Authorization` header answering the challenge with MD5 and `qop=auth`, as described in RFC 2617.
+fn authorization(
+    challenge: &Challenge,
+    username: &str,
+    password: &str,
+    uri: &str,
+    count: u32,
+    cnonce: &str,
+) -> String {
+    let md5 = |text: Secret<String>| crate::encode_hex(&Md5::digest(text.as_bytes()));
+    let ha1 = md5(Secret::format(format_args!(
+        "{}:{}:{}",
+        username, challenge.realm, password
+    )));
+    let ha2 = md5(Secret::format(format_args!("POST:{}", uri)));
+    let count = format!("{:08x}", count);
+    let response = md5(Secret::format(format_args!(
+        "{}:{}:{}:{}:auth:{}",
+        ha1.as_str(),
+        challenge.nonce,
+        count,
+        cnonce,
+        ha2.as_str()
+    )));
+    let mut header = format!(
+        "Digest username=\"{}\", realm=\"{}\", nonce=\"{}\", uri=\"{}\", algorithm=MD5, qop=auth, nc={}, cnonce=\"{}\", response=\"{}\"",
+        username,
+        challenge.realm,
+        challenge.nonce,
+        uri,
+        count,
+        cnonce,
+        response.as_str()
+    );
+    if let Some(opaque) = &challenge.opaque {
+        header.push_str(&format!(", opaque=\"{}\"", opaque));
+    }
+    header
+}
+
+/// A client nonce, which only has to differ between requests.
+fn cnonce() -> String {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_nanos())
+        .unwrap_or(0);
+    format!("{:016x}", nanos as u64)
+}
+
+/// Turn the `GetInfo`, `GetSecurityKeys`, and `GetBeaconAdvertisement` responses of a WLAN into credentials, or `None` for WPA-Enterprise WLANs. The SSID is assumed to be visible if the box does not support `GetBeaconAdvertisement`.
+fn parse_wlan(
+    info: &str,
+    keys: &str,
+    beacon: Option<&str>,
+) -> Result<Option<WifiCredentials>, FritzBoxError> {
+    let ssid = value(info, "NewSSID")?;
+    let authentication_type = match value(info, "NewBeaconType")?.as_str() {
+        "None" | "OWE" => AuthenticationType::NoPassword,
+        "Basic" => AuthenticationType::WEP(Secret::new(value(keys, "NewWEPKey0")?)),
+        _ if ["NewWPAAuthenticationMode", "NewIEEE11iAuthenticationMode"]
+            .iter()
+            .any(|name| value(info, name).is_ok_and(|mode| mode.starts_with("EAP"))) =>
+        {
+            return Ok(None)
+        }
+        _ => AuthenticationType::WPA(Secret::new(value(keys, "NewKeyPassphrase")?)),
+    };
+    let visibility = match beacon
+        .map(|beacon| value(beacon, "NewBeaconAdvertisementEnabled"))
+        .transpose()?
+    {
+        Some(enabled) if enabled == "0" => Visibility::Hidden,
+        _ => Visibility::Visible,
+    };
+    Ok(Some(WifiCredentials {
+        ssid,
+        authentication_type,
+        visibility,
+    }))
+}
+
+fn value(response: &str, name: &str) -> Result<String, FritzBoxError> {
+    element(response, name).ok_or_else(|| unexpected(&format!("{} is missing", name)))
+}
+
+/// The unescaped text of the first `<name>` element, which is enough for the flat structure of TR-064 responses.
+fn element(xml: &str, name: &str) -> Option<String> {
+    let start = xml.find(&format!("<{}>", name))? + name.len() + 2;
+    let end = xml[start..].find(&format!("</{}>", name))?;
+    Some(
+        xml[start..start + end]
+            .replace("&lt;", "<")
+            .replace("&gt;", ">")
+            .replace("&quot;", "\"")
+            .replace("&apos;", "'")
+            .replace("&amp;", "&"),
+    )
+}
+
+fn unexpected(reason: &str) -> FritzBoxError {
+    FritzBoxError::UnexpectedResponse(String::from(reason))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn response(action: &str, values: &[(&str, &str)]) -> String {
+        format!(
+            "<?xml version=\"1.0\"?>\n<s:Envelope xmlns:s=\"http://schemas.xmlsoap.org/soap/envelope/\"><s:Body><u:{action}Response xmlns:u=\"urn:dslforum-org:service:WLANConfiguration:3\">{values}</u:{action}Response></s:Body></s:Envelope>",
+            action = action,
+            values = values
+                .iter()
+                .map(|(name, value)| format!("<{name}>{value}</{name}>", name = name, value = value))
+                .collect::<String>()
+        )
+    }
+
+    #[test]
+    fn it_parses_wlan_responses() {
+        let info = response(
+            "GetInfo",
+            &[
+                ("NewEnable", "1"),
+                ("NewSSID", "FRITZ!Box Gastzugang &amp; Café"),
+                ("NewBeaconType", "11i"),
+                ("NewIEEE11iAuthenticationMode", "PSKAuthentication"),
+            ],
+        );
+        let keys = response(
+            "GetSecurityKeys",
+            &[
+                ("NewWEPKey0", ""),
+                ("NewKeyPassphrase", "test &lt;password&gt;"),
+            ],
+        );
+        let beacon = response(
+            "GetBeaconAdvertisement",
+            &[("NewBeaconAdvertisementEnabled", "0")],
+        );
+        assert_eq!(
+            WifiCredentials {
+                ssid: String::from("FRITZ!Box Gastzugang & Café"),
+                authentication_type: AuthenticationType::WPA("test <password>".into()),
+                visibility: Visibility::Hidden,
+            },
+            parse_wlan(&info, &keys, Some(&beacon)).unwrap().unwrap()
+        );
+        assert_eq!(
+            Visibility::Visible,
+            parse_wlan(&info, &keys, None).unwrap().unwrap().visibility
+        );
+
+        let info = response(
+            "GetInfo",
+            &[
+                ("NewSSID", "test ssid"),
+                ("NewBeaconType", "WPAand11i"),
+                ("NewWPAAuthenticationMode", "EAPAuthentication"),
+            ],
+        );
+        assert!(parse_wlan(&info, &keys, None).unwrap().is_none());
+        assert!(matches!(
+            parse_wlan(&response("GetInfo", &[]), &keys, None),
+            Err(FritzBoxError::UnexpectedResponse(_))
+        ));
+    }
+
+    #[test]
+    fn it_answers_digest_challenges() {
+        // The example from RFC 2617, section 3.5, with the method changed to POST.
+        let challenge = parse_challenge(
+            "Digest realm=\"testrealm@host.com\", qop=\"auth,auth-int\", nonce=\"dcd98b7102dd2f0e8b11d0f600bfb0c093\", opaque=\"5ccc069c403ebaf9f0171e9517f40e41\"",
+        )
+        .expect("Challenge should parse.");
+        assert_eq!("testrealm@host.com", challenge.realm);
+        let header = authorization(
+            &challenge,
+            "Mufasa",
+            "Circle Of Life",
+            "/dir/index.html",
+            1,
+            "0a4f113b",
+        );
+        assert!(header.starts_with("Digest username=\"Mufasa\", realm=\"testrealm@host.com\""));
+        assert!(header.contains(", nc=00000001, cnonce=\"0a4f113b\""));
+        assert!(header.contains("response=\"440c5a7b9ed304fecd2ddd39c9c7b726\""));
+        assert!(header.ends_with(", opaque=\"5ccc069c403ebaf9f0171e9517f40e41\""));
+
+        let challenge = parse_challenge(
+            "Digest realm=\"F!Box SOAP-Auth\",nonce=\"A1B2C3\",algorithm=MD5,qop=\"auth\"",
+        )
+        .expect("Challenge should parse.");
+        assert_eq!("A1B2C3", challenge.nonce);
+        assert!(challenge.opaque.is_none());
+        assert!(parse_challenge("Basic realm=\"test\"").is_none());
+    }
+}
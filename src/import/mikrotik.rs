@@ -0,0 +1,322 @@
+//! MikroTik RouterOS configuration exports (the output of `/export`).
+//!
+//! Security profiles in `/interface wireless security-profiles` hold the passwords, while `/interface wireless` holds the SSIDs that reference them by name, so both sections are read and joined. Interfaces in station mode, disabled interfaces, and profiles that only allow EAP authentication are skipped.
+
+use super::ImportError;
+use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+use std::collections::HashMap;
+
+const SECURITY_PROFILES_MENU: &str = "/interface wireless security-profiles";
+const INTERFACES_MENU: &str = "/interface wireless";
+
+/// Parse a RouterOS export into one set of credentials per SSID, in the order the interfaces are configured.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::import::mikrotik;
+///
+/// let export = r#"
+/// /interface wireless security-profiles
+/// add authentication-types=wpa2-psk mode=dynamic-keys name=guest \
+///     wpa2-pre-shared-key="example password"
+/// /interface wireless
+/// set [ find default-name=wlan1 ] mode=ap-bridge security-profile=guest ssid="example ssid"
+/// "#;
+/// let wifi_credentials = mikrotik::parse(export).expect("Failed to parse example export.");
+/// assert_eq!(
+///     "WIFI:S:example ssid;T:WPA;P:example password;H:false;;",
+///     wifi_credentials[0].encode()
+/// );
+/// ```
+pub fn parse(input: &str) -> Result<Vec<WifiCredentials>, ImportError> {
+    let mut profiles: HashMap<String, Properties> = HashMap::new();
+    profiles.insert(String::from("default"), Properties::new());
+    let mut interfaces: Vec<Properties> = Vec::new();
+    let mut menu = String::new();
+
+    for (line_number, line) in join_continued_lines(input) {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        if line.starts_with('/') {
+            menu = line.split_whitespace().collect::<Vec<_>>().join(" ");
+            continue;
+        }
+        let command = parse_command(line).map_err(|reason| ImportError::Syntax {
+            line: line_number,
+            reason: String::from(reason),
+        })?;
+        if menu == SECURITY_PROFILES_MENU {
+            let name = match command.verb.as_str() {
+                "add" => command.properties.get("name").cloned(),
+                "set" => command.selected("name").or_else(|| {
+                    command
+                        .selected("default")
+                        .filter(|default| default == "yes")
+                        .map(|_| String::from("default"))
+                }),
+                _ => None,
+            };
+            if let Some(name) = name {
+                profiles.entry(name).or_default().extend(command.properties);
+            }
+        } else if menu == INTERFACES_MENU {
+            match command.verb.as_str() {
+                "add" => interfaces.push(command.properties),
+                "set" => {
+                    let default_name = command.selected("default-name");
+                    let existing = interfaces.iter_mut().find(|interface| {
+                        default_name.is_some()
+                            && interface.get("default-name") == default_name.as_ref()
+                    });
+                    match existing {
+                        Some(interface) => interface.extend(command.properties),
+                        None => {
+                            let mut interface = command.properties;
+                            if let Some(default_name) = default_name {
+                                interface.insert(String::from("default-name"), default_name);
+                            }
+                            interfaces.push(interface);
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+
+    let mut wifi_credentials: Vec<WifiCredentials> = Vec::new();
+    for interface in &interfaces {
+        if let Some(credentials) = to_credentials(interface, &profiles) {
+            if !wifi_credentials
+                .iter()
+                .any(|existing| existing.ssid == credentials.ssid)
+            {
+                wifi_credentials.push(credentials);
+            }
+        }
+    }
+    Ok(wifi_credentials)
+}
+
+type Properties = HashMap<String, String>;
+
+struct Command {
+    verb: String,
+    selector: Properties,
+    properties: Properties,
+}
+
+impl Command {
+    fn selected(&self, name: &str) -> Option<String> {
+        self.selector.get(name).cloned()
+    }
+}
+
+fn to_credentials(
+    interface: &Properties,
+    profiles: &HashMap<String, Properties>,
+) -> Option<WifiCredentials> {
+    let property = |name: &str| interface.get(name).map(String::as_str);
+    if property("disabled") == Some("yes")
+        || property("mode").is_some_and(|mode| mode.starts_with("station"))
+    {
+        return None;
+    }
+    let ssid = property("ssid")?;
+    let profile = profiles.get(property("security-profile").unwrap_or("default"))?;
+    let profile_property = |name: &str| profile.get(name).map(String::as_str);
+    let authentication_type = match profile_property("mode").unwrap_or("none") {
+        "none" => AuthenticationType::NoPassword,
+        "static-keys-required" | "static-keys-optional" => {
+            let key_index = profile_property("static-transmit-key")
+                .and_then(|key| key.strip_prefix("key-"))
+                .unwrap_or("0");
+            AuthenticationType::WEP(profile_property(&format!("static-key-{}", key_index))?.into())
+        }
+        "dynamic-keys" => {
+            let authentication_types = profile_property("authentication-types").unwrap_or("");
+            let password = if authentication_types
+                .split(',')
+                .any(|authentication_type| authentication_type == "wpa2-psk")
+            {
+                profile_property("wpa2-pre-shared-key")
+            } else if authentication_types
+                .split(',')
+                .any(|authentication_type| authentication_type == "wpa-psk")
+            {
+                profile_property("wpa-pre-shared-key")
+            } else {
+                None
+            };
+            AuthenticationType::WPA(password?.into())
+        }
+        _ => return None,
+    };
+    let visibility = if property("hide-ssid") == Some("yes") {
+        Visibility::Hidden
+    } else {
+        Visibility::Visible
+    };
+    Some(WifiCredentials {
+        ssid: String::from(ssid),
+        authentication_type,
+        visibility,
+    })
+}
+
+/// Long lines in exports are wrapped with a trailing backslash. Joined lines are reported with the line number they start on.
+fn join_continued_lines(input: &str) -> Vec<(usize, String)> {
+    let mut lines: Vec<(usize, String)> = Vec::new();
+    let mut continuing = false;
+    for (index, line) in input.lines().enumerate() {
+        let (content, continues) = match line.strip_suffix('\\') {
+            Some(content) => (content, true),
+            None => (line, false),
+        };
+        match lines.last_mut() {
+            Some((_, joined)) if continuing => joined.push_str(content.trim_start()),
+            _ => lines.push((index + 1, String::from(content))),
+        }
+        continuing = continues;
+    }
+    lines
+}
+
+fn parse_command(line: &str) -> Result<Command, &'static str> {
+    let mut chars = line.chars().peekable();
+    let mut verb = String::new();
+    while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+        verb.push(c);
+    }
+    let mut selector = Properties::new();
+    let mut properties = Properties::new();
+    loop {
+        while chars.next_if(|c| c.is_whitespace()).is_some() {}
+        match chars.peek() {
+            None => break,
+            Some('[') => {
+                chars.next();
+                let mut expression = String::new();
+                loop {
+                    match chars.next() {
+                        Some(']') => break,
+                        Some(c) => expression.push(c),
+                        None => return Err("unterminated [ find ] expression"),
+                    }
+                }
+                selector.extend(parse_command(&expression)?.properties);
+            }
+            Some(_) => {
+                let mut name = String::new();
+                while let Some(c) = chars.next_if(|c| !c.is_whitespace() && *c != '=') {
+                    name.push(c);
+                }
+                let value = if chars.next_if_eq(&'=').is_some() {
+                    parse_value(&mut chars)?
+                } else {
+                    String::new()
+                };
+                properties.insert(name, value);
+            }
+        }
+    }
+    Ok(Command {
+        verb,
+        selector,
+        properties,
+    })
+}
+
+/// Values are either bare words or double quoted. Quoted values escape special characters with a backslash, and non-ASCII bytes as two hex digits (e.g. `\C3\A9`).
+fn parse_value(chars: &mut std::iter::Peekable<std::str::Chars>) -> Result<String, &'static str> {
+    if chars.next_if_eq(&'"').is_none() {
+        let mut value = String::new();
+        while let Some(c) = chars.next_if(|c| !c.is_whitespace()) {
+            value.push(c);
+        }
+        return Ok(value);
+    }
+    let mut bytes: Vec<u8> = Vec::new();
+    loop {
+        match chars.next() {
+            Some('"') => break,
+            Some('\\') => {
+                let escaped = chars.next().ok_or("unterminated double quote")?;
+                match chars
+                    .peek()
+                    .and_then(|next| u8::from_str_radix(&format!("{}{}", escaped, next), 16).ok())
+                {
+                    Some(byte) => {
+                        chars.next();
+                        bytes.push(byte);
+                    }
+                    None => bytes.extend(escaped.to_string().as_bytes()),
+                }
+            }
+            Some(c) => bytes.extend(c.to_string().as_bytes()),
+            None => return Err("unterminated double quote"),
+        }
+    }
+    Ok(String::from_utf8_lossy(&bytes).into_owned())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const EXPORT: &str = r#"# jan/02/1970 00:00:00 by RouterOS 6.49
+# software id = ABCD-1234
+#
+/interface wireless security-profiles
+set [ find default=yes ] supplicant-identity=MikroTik
+add authentication-types=wpa-psk,wpa2-psk mode=dynamic-keys name=home \
+    supplicant-identity="" wpa-pre-shared-key="home password" \
+    wpa2-pre-shared-key="home password"
+add authentication-types=wpa-psk mode=dynamic-keys name=legacy-wpa \
+    wpa-pre-shared-key="legacy \"password\""
+add mode=static-keys-required name=wep static-key-0=first static-key-1=second \
+    static-transmit-key=key-1
+add authentication-types=wpa2-eap mode=dynamic-keys name=enterprise
+/interface wireless
+set [ find default-name=wlan1 ] band=2ghz-b/g/n disabled=no mode=ap-bridge \
+    security-profile=home ssid="Caf\C3\A9 Network"
+set [ find default-name=wlan2 ] disabled=no mode=ap-bridge security-profile=home \
+    ssid="Caf\C3\A9 Network"
+add master-interface=wlan1 name=wlan3 security-profile=legacy-wpa ssid=Legacy hide-ssid=yes
+add master-interface=wlan1 name=wlan4 security-profile=wep ssid=WEP
+add master-interface=wlan1 name=wlan5 ssid=Open
+add master-interface=wlan1 name=wlan6 security-profile=enterprise ssid=Enterprise
+add master-interface=wlan1 disabled=yes name=wlan7 ssid=Disabled
+add mode=station name=wlan8 ssid=Upstream
+"#;
+
+    #[test]
+    fn it_joins_interfaces_with_their_security_profiles() {
+        let wifi_credentials = parse(EXPORT).expect("Export should parse.");
+        let encoded: Vec<String> = wifi_credentials
+            .iter()
+            .map(|wifi_credentials| wifi_credentials.encode().into_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "WIFI:S:Café Network;T:WPA;P:home password;H:false;;",
+                r#"WIFI:S:Legacy;T:WPA;P:legacy \"password\";H:true;;"#,
+                "WIFI:S:WEP;T:WEP;P:second;H:false;;",
+                "WIFI:S:Open;T:nopass;H:false;;",
+            ],
+            encoded
+        );
+    }
+
+    #[test]
+    fn it_reports_syntax_errors_with_line_numbers() {
+        match parse("/interface wireless\nadd ssid=\"unterminated\n") {
+            Err(ImportError::Syntax { line, .. }) => assert_eq!(2, line),
+            _ => panic!("Expected a syntax error."),
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! OpenWrt wireless configuration (`/etc/config/wireless`) in UCI syntax.
+//!
+//! Every enabled `wifi-iface` section in access point mode produces credentials. Interfaces that share an SSID (e.g. the same network on the 2.4 GHz and 5 GHz radios) are only reported once, and enterprise (802.1X) networks are skipped because they cannot be represented as a password.
+
+use super::ImportError;
+use crate::{AuthenticationType, Secret, Visibility, WifiCredentials};
+
+use std::collections::HashMap;
+
+/// Parse a UCI wireless configuration into one set of credentials per SSID, in the order they are configured.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::import::openwrt;
+///
+/// let config = "
+/// config wifi-iface 'default_radio0'
+///     option device 'radio0'
+///     option mode 'ap'
+///     option ssid 'example ssid'
+///     option encryption 'psk2'
+///     option key 'example password'
+/// ";
+/// let wifi_credentials = openwrt::parse(config).expect("Failed to parse example config.");
+/// assert_eq!(
+///     "WIFI:S:example ssid;T:WPA;P:example password;H:false;;",
+///     wifi_credentials[0].encode()
+/// );
+/// ```
+pub fn parse(input: &str) -> Result<Vec<WifiCredentials>, ImportError> {
+    let mut wifi_credentials: Vec<WifiCredentials> = Vec::new();
+    for section in parse_sections(input)? {
+        if section.section_type != "wifi-iface" {
+            continue;
+        }
+        if let Some(credentials) = section.to_credentials() {
+            if !wifi_credentials
+                .iter()
+                .any(|existing| existing.ssid == credentials.ssid)
+            {
+                wifi_credentials.push(credentials);
+            }
+        }
+    }
+    Ok(wifi_credentials)
+}
+
+struct Section {
+    section_type: String,
+    options: HashMap<String, String>,
+}
+
+impl Section {
+    fn option(&self, name: &str) -> Option<&str> {
+        self.options.get(name).map(String::as_str)
+    }
+
+    fn to_credentials(&self) -> Option<WifiCredentials> {
+        if self.option("mode").unwrap_or("ap") != "ap" || self.option("disabled") == Some("1") {
+            return None;
+        }
+        let ssid = self.option("ssid")?;
+        let encryption = self.option("encryption").unwrap_or("none");
+        let authentication_type = if encryption == "none" || encryption.starts_with("owe") {
+            AuthenticationType::NoPassword
+        } else if encryption.starts_with("psk") || encryption.starts_with("sae") {
+            AuthenticationType::WPA(self.option("key")?.into())
+        } else if encryption.starts_with("wep") {
+            AuthenticationType::WEP(Secret::new(self.wep_key()?))
+        } else {
+            return None;
+        };
+        let visibility = if self.option("hidden") == Some("1") {
+            Visibility::Hidden
+        } else {
+            Visibility::Visible
+        };
+        Some(WifiCredentials {
+            ssid: String::from(ssid),
+            authentication_type,
+            visibility,
+        })
+    }
+
+    /// WEP networks select one of `key1` through `key4` by index, and prefix ASCII keys with `s:`.
+    fn wep_key(&self) -> Option<String> {
+        let key = match self.option("key").unwrap_or("1") {
+            index @ ("1" | "2" | "3" | "4") => self.option(&format!("key{}", index))?,
+            key => key,
+        };
+        Some(String::from(key.strip_prefix("s:").unwrap_or(key)))
+    }
+}
+
+fn parse_sections(input: &str) -> Result<Vec<Section>, ImportError> {
+    let mut sections: Vec<Section> = Vec::new();
+    for (index, line) in input.lines().enumerate() {
+        let line_number = index + 1;
+        let syntax_error = |reason: &str| ImportError::Syntax {
+            line: line_number,
+            reason: String::from(reason),
+        };
+        let tokens = tokenize(line).map_err(syntax_error)?;
+        match tokens.first().map(String::as_str) {
+            None | Some("package") | Some("list") => {}
+            Some("config") => {
+                let section_type = tokens
+                    .get(1)
+                    .ok_or_else(|| syntax_error("config is missing a section type"))?;
+                sections.push(Section {
+                    section_type: section_type.clone(),
+                    options: HashMap::new(),
+                });
+            }
+            Some("option") => {
+                let (name, value) = match (tokens.get(1), tokens.get(2)) {
+                    (Some(name), Some(value)) => (name, value),
+                    _ => return Err(syntax_error("option requires a name and a value")),
+                };
+                let section = sections
+                    .last_mut()
+                    .ok_or_else(|| syntax_error("option appears before any config section"))?;
+                section.options.insert(name.clone(), value.clone());
+            }
+            Some(_) => return Err(syntax_error("expected config, option, or list")),
+        }
+    }
+    Ok(sections)
+}
+
+fn tokenize(line: &str) -> Result<Vec<String>, &'static str> {
+    let mut tokens = Vec::new();
+    let mut token: Option<String> = None;
+    let mut chars = line.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '#' => break,
+            c if c.is_whitespace() => tokens.extend(token.take()),
+            '\'' => {
+                let token = token.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('\'') => break,
+                        Some(c) => token.push(c),
+                        None => return Err("unterminated single quote"),
+                    }
+                }
+            }
+            '"' => {
+                let token = token.get_or_insert_with(String::new);
+                loop {
+                    match chars.next() {
+                        Some('"') => break,
+                        Some('\\') => token.push(chars.next().ok_or("unterminated double quote")?),
+                        Some(c) => token.push(c),
+                        None => return Err("unterminated double quote"),
+                    }
+                }
+            }
+            c => token.get_or_insert_with(String::new).push(c),
+        }
+    }
+    tokens.extend(token);
+    Ok(tokens)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+config wifi-device 'radio0'
+	option type 'mac80211'
+	option channel '36'
+
+config wifi-iface 'default_radio0'
+	option device 'radio0'
+	option network 'lan'
+	option mode 'ap'
+	option ssid 'Home Network'
+	option encryption 'sae-mixed'
+	option key 'home password'
+
+config wifi-iface 'default_radio1'
+	option device 'radio1'
+	option network 'lan'
+	option mode 'ap'
+	option ssid 'Home Network'
+	option encryption 'psk2'
+	option key 'home password'
+
+config wifi-iface 'guest'
+	option device 'radio0'
+	option mode 'ap'
+	option ssid "Guest \"Network\"" # comment
+	option hidden '1'
+
+config wifi-iface 'legacy'
+	option device 'radio1'
+	option ssid legacy
+	option encryption 'wep-open'
+	option key '2'
+	option key1 's:first'
+	option key2 's:second'
+
+config wifi-iface 'disabled'
+	option device 'radio1'
+	option ssid 'Disabled Network'
+	option disabled '1'
+
+config wifi-iface 'enterprise'
+	option device 'radio1'
+	option ssid 'Enterprise Network'
+	option encryption 'wpa2'
+
+config wifi-iface 'uplink'
+	option device 'radio1'
+	option mode 'sta'
+	option ssid 'Upstream Network'
+"#;
+
+    #[test]
+    fn it_imports_each_access_point_ssid_once() {
+        let wifi_credentials = parse(CONFIG).expect("Config should parse.");
+        let encoded: Vec<String> = wifi_credentials
+            .iter()
+            .map(|wifi_credentials| wifi_credentials.encode().into_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "WIFI:S:Home Network;T:WPA;P:home password;H:false;;",
+                r#"WIFI:S:Guest \"Network\";T:nopass;H:true;;"#,
+                "WIFI:S:legacy;T:WEP;P:second;H:false;;",
+            ],
+            encoded
+        );
+    }
+
+    #[test]
+    fn it_reports_syntax_errors_with_line_numbers() {
+        match parse("config wifi-iface\n\toption ssid 'unterminated\n") {
+            Err(ImportError::Syntax { line, .. }) => assert_eq!(2, line),
+            _ => panic!("Expected a syntax error."),
+        }
+        assert!(parse("option ssid 'orphan'").is_err());
+    }
+}
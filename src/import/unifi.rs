@@ -0,0 +1,134 @@
+//! UniFi Network controller WLAN configuration, as returned by the controller's `GET /api/s/<site>/rest/wlanconf` endpoint.
+//!
+//! The controller requires an authenticated session, so fetching the configuration is left to the caller (e.g. `curl` with a session cookie). Disabled WLANs and enterprise (802.1X) WLANs are skipped.
+
+use super::ImportError;
+use crate::{AuthenticationType, Secret, Visibility, WifiCredentials};
+
+use serde::Deserialize;
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum Response {
+    Wrapped { data: Vec<Wlan> },
+    Bare(Vec<Wlan>),
+}
+
+#[derive(Deserialize)]
+struct Wlan {
+    name: String,
+    #[serde(default = "enabled_by_default")]
+    enabled: bool,
+    #[serde(default)]
+    hide_ssid: bool,
+    #[serde(default)]
+    security: String,
+    x_passphrase: Option<String>,
+    x_wep: Option<String>,
+}
+
+fn enabled_by_default() -> bool {
+    true
+}
+
+/// Parse a `wlanconf` response (either the full `{"meta": ..., "data": [...]}` envelope or the bare array) into one set of credentials per SSID.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::import::unifi;
+///
+/// let response = r#"{
+///     "meta": { "rc": "ok" },
+///     "data": [
+///         { "name": "example ssid", "enabled": true, "security": "wpapsk", "x_passphrase": "example password" }
+///     ]
+/// }"#;
+/// let wifi_credentials = unifi::parse(response).expect("Failed to parse example response.");
+/// assert_eq!(
+///     "WIFI:S:example ssid;T:WPA;P:example password;H:false;;",
+///     wifi_credentials[0].encode()
+/// );
+/// ```
+pub fn parse(input: &str) -> Result<Vec<WifiCredentials>, ImportError> {
+    let wlans = match serde_json::from_str(input).map_err(|error| ImportError::Syntax {
+        line: error.line(),
+        reason: error.to_string(),
+    })? {
+        Response::Wrapped { data } => data,
+        Response::Bare(wlans) => wlans,
+    };
+    let mut wifi_credentials: Vec<WifiCredentials> = Vec::new();
+    for wlan in wlans {
+        if let Some(credentials) = wlan.into_credentials() {
+            if !wifi_credentials
+                .iter()
+                .any(|existing| existing.ssid == credentials.ssid)
+            {
+                wifi_credentials.push(credentials);
+            }
+        }
+    }
+    Ok(wifi_credentials)
+}
+
+impl Wlan {
+    fn into_credentials(self) -> Option<WifiCredentials> {
+        if !self.enabled {
+            return None;
+        }
+        let authentication_type = match self.security.as_str() {
+            "" | "open" => AuthenticationType::NoPassword,
+            "wep" => AuthenticationType::WEP(Secret::new(self.x_wep?)),
+            "wpapsk" => AuthenticationType::WPA(Secret::new(self.x_passphrase?)),
+            _ => return None,
+        };
+        let visibility = if self.hide_ssid {
+            Visibility::Hidden
+        } else {
+            Visibility::Visible
+        };
+        Some(WifiCredentials {
+            ssid: self.name,
+            authentication_type,
+            visibility,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_imports_enabled_wlans() {
+        let response = r#"[
+            { "_id": "1", "name": "Home", "enabled": true, "security": "wpapsk", "wpa_mode": "wpa2", "x_passphrase": "home password" },
+            { "_id": "2", "name": "Guest", "security": "open", "hide_ssid": true, "is_guest": true },
+            { "_id": "3", "name": "Legacy", "security": "wep", "wep_idx": 1, "x_wep": "legacy key" },
+            { "_id": "4", "name": "Disabled", "enabled": false, "security": "wpapsk", "x_passphrase": "disabled password" },
+            { "_id": "5", "name": "Enterprise", "security": "wpaeap" }
+        ]"#;
+        let wifi_credentials = parse(response).expect("Response should parse.");
+        let encoded: Vec<String> = wifi_credentials
+            .iter()
+            .map(|wifi_credentials| wifi_credentials.encode().into_string())
+            .collect();
+        assert_eq!(
+            vec![
+                "WIFI:S:Home;T:WPA;P:home password;H:false;;",
+                "WIFI:S:Guest;T:nopass;H:true;;",
+                "WIFI:S:Legacy;T:WEP;P:legacy key;H:false;;",
+            ],
+            encoded
+        );
+    }
+
+    #[test]
+    fn it_reports_malformed_responses() {
+        match parse("{\n\"data\": [\n{ \"name\": }\n]\n}") {
+            Err(ImportError::Syntax { line, .. }) => assert_eq!(3, line),
+            _ => panic!("Expected a syntax error."),
+        }
+    }
+}
@@ -0,0 +1,127 @@
+//! Store wifi passwords in the operating system's keyring, keyed by SSID, so codes can be generated again without asking for the password or keeping it in a plain text configuration file. The macOS keychain, the Windows credential manager, and the Secret Service (GNOME Keyring or KWallet) on Linux are used through the [keyring](https://docs.rs/keyring) crate.
+
+use crate::WifiCredentials;
+
+use std::error;
+use std::fmt::{self, Display, Formatter};
+
+/// The keyring service that passwords are stored under.
+const SERVICE: &str = "wifi-qr-code";
+
+/// Errors when storing or retrieving passwords.
+#[derive(Debug)]
+pub enum KeyringError {
+    /// The credentials are for an open network, which has no password to store.
+    NoPassword,
+    /// No password is stored for the SSID.
+    NotFound(String),
+    /// The keyring could not be accessed, e.g. because it is locked or no keyring service is running.
+    Keyring(::keyring::Error),
+}
+
+impl Display for KeyringError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoPassword => f.write_str("open networks do not have a password"),
+            Self::NotFound(ssid) => write!(f, "no password is stored for {:?}", ssid),
+            Self::Keyring(error) => write!(f, "failed to access the keyring: {}", error),
+        }
+    }
+}
+
+impl error::Error for KeyringError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::NoPassword | Self::NotFound(_) => None,
+            Self::Keyring(error) => Some(error),
+        }
+    }
+}
+
+impl From<::keyring::Error> for KeyringError {
+    fn from(error: ::keyring::Error) -> Self {
+        Self::Keyring(error)
+    }
+}
+
+fn entry(ssid: &str) -> Result<::keyring::Entry, KeyringError> {
+    Ok(::keyring::Entry::new(SERVICE, ssid)?)
+}
+
+fn not_found(ssid: &str) -> impl FnOnce(::keyring::Error) -> KeyringError + '_ {
+    move |error| match error {
+        ::keyring::Error::NoEntry => KeyringError::NotFound(String::from(ssid)),
+        error => KeyringError::Keyring(error),
+    }
+}
+
+/// Store the password of the credentials under their SSID, replacing any password stored before. Pre-shared keys are stored as 64 hex digits.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wifi_qr_code::WifiCredentials;
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// wifi_qr_code::keyring::store(&wifi_credentials).expect("Failed to store the password.");
+/// ```
+pub fn store(wifi_credentials: &WifiCredentials) -> Result<(), KeyringError> {
+    let password = wifi_credentials
+        .authentication_type
+        .password_text()
+        .ok_or(KeyringError::NoPassword)?;
+    Ok(entry(&wifi_credentials.ssid)?.set_password(&password)?)
+}
+
+/// Retrieve the password stored for the SSID by [`store`].
+///
+/// # Examples
+///
+/// ```no_run
+/// use wifi_qr_code::{QrCodeEcc, WifiCredentials};
+///
+/// let password = wifi_qr_code::keyring::password("example ssid")
+///     .expect("Failed to retrieve the password.");
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", password);
+/// wifi_credentials
+///     .qr(QrCodeEcc::Medium)
+///     .png(std::io::stdout())
+///     .expect("Failed to write PNG.");
+/// ```
+pub fn password(ssid: &str) -> Result<String, KeyringError> {
+    entry(ssid)?.get_password().map_err(not_found(ssid))
+}
+
+/// Remove the password stored for the SSID, e.g. after the network has been taken down.
+///
+/// # Examples
+///
+/// ```no_run
+/// wifi_qr_code::keyring::delete("example ssid").expect("Failed to delete the password.");
+/// ```
+pub fn delete(ssid: &str) -> Result<(), KeyringError> {
+    entry(ssid)?.delete_credential().map_err(not_found(ssid))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reports_missing_passwords() {
+        ::keyring::set_default_credential_builder(::keyring::mock::default_credential_builder());
+        assert!(matches!(
+            store(&WifiCredentials::open("test ssid")),
+            Err(KeyringError::NoPassword)
+        ));
+        assert!(matches!(
+            password("test ssid"),
+            Err(KeyringError::NotFound(ssid)) if ssid == "test ssid"
+        ));
+        assert!(matches!(
+            delete("test ssid"),
+            Err(KeyringError::NotFound(_))
+        ));
+        assert!(store(&WifiCredentials::wpa2("test ssid", "test password")).is_ok());
+    }
+}
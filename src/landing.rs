@@ -0,0 +1,212 @@
+//! Static landing pages for joining a network: a complete HTML document with the QR code, the network name, instructions for each operating system, and the password, ready to put on an internal web server or open in a kiosk browser.
+//!
+//! Pages have no external resources or scripts, so they work offline and under strict content security policies. The password can be hidden behind a button, which is a `<details>` element and works without JavaScript.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wifi_qr_code::landing::LandingPage;
+//! use wifi_qr_code::print::Locale;
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! use std::fs::File;
+//!
+//! let wifi_credentials = WifiCredentials::wpa2("example guest network", "example password");
+//! let mut landing_page = LandingPage::new();
+//! landing_page.locale = Locale::German;
+//! landing_page
+//!     .write_html(
+//!         &wifi_credentials,
+//!         File::create("index.html").expect("Failed to create the page."),
+//!     )
+//!     .expect("Failed to write the landing page.");
+//! ```
+
+use crate::export::{escape_xml, escape_xml_secret};
+use crate::print::Locale;
+use crate::{render, Error, OutputStage, QrCodeEcc, SvgAttributes, WifiCredentials};
+
+use std::io::Write;
+
+/// The style sheet of every page, which centers the content in a column and follows the light or dark mode of the browser. The QR code keeps its white background in dark mode, since many scanners cannot read inverted codes.
+const STYLE: &str = "body{margin:0;font-family:system-ui,sans-serif;line-height:1.5;color:#111;background:#fff}\
+main{max-width:32rem;margin:0 auto;padding:2rem 1rem;text-align:center}\
+h1{margin:0 0 .25rem}\
+.qr-code{width:100%;max-width:20rem;height:auto}\
+.network{font-size:1.25rem;font-weight:600}\
+.password code{font-size:1.25rem;word-break:break-all}\
+summary{display:inline-block;padding:.5rem 1rem;border:1px solid currentColor;border-radius:.5rem;cursor:pointer}\
+.platforms{padding:0;list-style:none;text-align:left}\
+.platforms li{margin:.75rem 0}\
+@media (prefers-color-scheme:dark){body{color:#eee;background:#111}}";
+
+/// How the password is shown on a landing page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PasswordDisplay {
+    /// Written out under the network name, like on printed cards.
+    Visible,
+    /// Behind a button that shows it, so it is not readable from across the room.
+    Reveal,
+    /// Left out, for pages where guests should only scan the code.
+    Omitted,
+}
+
+/// The settings of a landing page.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LandingPage {
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The language of the text.
+    pub locale: Locale,
+    /// How the password is shown.
+    pub password: PasswordDisplay,
+}
+
+impl LandingPage {
+    /// An English landing page with a QR code using medium error correction, and the password behind a button.
+    pub fn new() -> Self {
+        LandingPage {
+            qr_code_error_checking: QrCodeEcc::Medium,
+            locale: Locale::English,
+            password: PasswordDisplay::Reveal,
+        }
+    }
+
+    /// The landing page for the network, as an HTML document.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::landing::LandingPage;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let html = LandingPage::new()
+    ///     .render(&wifi_credentials)
+    ///     .expect("Failed to render the landing page.");
+    /// assert!(html.starts_with("<!DOCTYPE html>"));
+    /// assert!(html.contains("<summary>Show password</summary>"));
+    /// ```
+    pub fn render(&self, wifi_credentials: &WifiCredentials) -> Result<String, Error> {
+        let qr_code = crate::generate_symbol(
+            &crate::encode_payload(wifi_credentials)?,
+            self.qr_code_error_checking,
+        )?;
+        let message = |id: &str| escape_xml(&self.locale.message(id, &[]));
+        let title = message("title");
+        let ssid = [("ssid", wifi_credentials.ssid.as_str())];
+        let [network, password_caption] = crate::print::captions(wifi_credentials, self.locale);
+        let svg = render::svg_element(
+            &qr_code,
+            // Scaled down to fit with CSS, so this only determines the resolution of the path coordinates.
+            (qr_code.size() as usize + 2) * 8,
+            &SvgAttributes {
+                class: Some(String::from("qr-code")),
+                title: Some(self.locale.message("instructions", &[])),
+                ..SvgAttributes::default()
+            },
+        )?;
+
+        let mut html = format!(
+            "<!DOCTYPE html>\n<html lang=\"{}\">\n<head>\n<meta charset=\"utf-8\">\n<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n<title>{}: {}</title>\n<style>{}</style>\n</head>\n<body>\n<main>\n<h1>{}</h1>\n<p>{}</p>\n{}\n<p class=\"network\">{}</p>\n",
+            self.locale.tag(),
+            title,
+            escape_xml(&wifi_credentials.ssid),
+            STYLE,
+            title,
+            message("instructions"),
+            svg,
+            escape_xml(&network),
+        );
+        match (
+            self.password,
+            wifi_credentials.authentication_type.password_text(),
+        ) {
+            (PasswordDisplay::Omitted, _) => {}
+            (PasswordDisplay::Reveal, Some(password)) => html.push_str(&format!(
+                "<details class=\"password\">\n<summary>{}</summary>\n<p><code>{}</code></p>\n</details>\n",
+                message("show-password"),
+                escape_xml_secret(&password).as_str()
+            )),
+            _ => html.push_str(&format!(
+                "<p class=\"password\">{}</p>\n",
+                escape_xml(&password_caption)
+            )),
+        }
+        html.push_str("<ul class=\"platforms\">\n");
+        for id in &[
+            "instructions-ios",
+            "instructions-android",
+            "instructions-windows",
+            "instructions-macos",
+        ] {
+            html.push_str(&format!(
+                "<li>{}</li>\n",
+                escape_xml(&self.locale.message(id, &ssid))
+            ));
+        }
+        html.push_str("</ul>\n</main>\n</body>\n</html>\n");
+        Ok(html)
+    }
+
+    /// Write the landing page for the network as an HTML document, like [`LandingPage::render`].
+    pub fn write_html(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        mut writer: impl Write,
+    ) -> Result<(), Error> {
+        let html = self.render(wifi_credentials)?;
+        writer
+            .write_all(html.as_bytes())
+            .map_err(|source| Error::Io {
+                stage: OutputStage::Html,
+                source,
+            })
+    }
+}
+
+impl Default for LandingPage {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_escapes_the_credentials() {
+        let wifi_credentials = WifiCredentials::wpa2("Café <guest>", "pass\"word&more");
+        let html = LandingPage::new().render(&wifi_credentials).unwrap();
+        assert!(html.contains("<title>Wi-Fi: Café &lt;guest&gt;</title>"));
+        assert!(html.contains("<p class=\"network\">Network: Café &lt;guest&gt;</p>"));
+        assert!(html.contains("<p><code>pass&quot;word&amp;more</code></p>"));
+        assert!(html.contains(
+            "<li>Windows: choose Café &lt;guest&gt; in the Wi-Fi list of the taskbar.</li>"
+        ));
+        assert!(!html.contains("<guest>"));
+    }
+
+    #[test]
+    fn it_shows_the_password_as_configured() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        let mut landing_page = LandingPage::new();
+        landing_page.locale = Locale::German;
+        landing_page.password = PasswordDisplay::Visible;
+        let html = landing_page.render(&wifi_credentials).unwrap();
+        assert!(html.contains("<html lang=\"de\">"));
+        assert!(html.contains("<p class=\"password\">Passwort: test password</p>"));
+        assert!(!html.contains("<details"));
+
+        landing_page.password = PasswordDisplay::Omitted;
+        let html = landing_page.render(&wifi_credentials).unwrap();
+        assert!(!html.contains("test password"));
+
+        landing_page.password = PasswordDisplay::Reveal;
+        let html = landing_page
+            .render(&WifiCredentials::open("test ssid"))
+            .unwrap();
+        assert!(html.contains("<p class=\"password\">Kein Passwort</p>"));
+    }
+}
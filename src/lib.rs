@@ -1,3 +1,4 @@
+#![cfg_attr(not(feature = "std"), no_std)]
 #![deny(clippy::all)]
 #![deny(missing_docs)]
 #![deny(warnings)]
@@ -5,11 +6,262 @@
 //! Wifi QR codes are a way to encode wifi connection information and credentials into a QR code so that it can be scanned. They are supported via the latest Android and iOS phones, as well as other platforms.
 //!
 //! It is important to take into account that QR codes do not provide any security mechanisms that would prevent someone from just reading the code and recovering the password for the network. Android requires that you re-authenticate before it will display the QR code on the screen to make sure the user is allowed to share that information, for example.
+//!
+//! # Features
+//!
+//! Encoding payloads, matrices, and raw image data needs only the `std` feature. With `default-features = false, features = ["std"]`, nothing else is compiled in, which keeps WASM bundles small. Without `std`, the crate is `no_std` and needs no allocator: it validates [`WifiCredentialsRef`], writes their payload with [`WifiCredentialsRef::encode_to_slice`], and draws their QR code into a [`QrMatrix`] with [`WifiCredentialsRef::encode_to_qr_matrix`], e.g. for firmware on an ESP32.
+//!
+//! * `png` (default) - write PNG images, e.g. with `encode_as_png_streamed` or [`QrCodeBuilder::png`], using the [png](https://docs.rs/png) crate.
+//! * `svg` (default) - write SVG images, e.g. with `encode_as_svg_with_report` or [`QrCodeBuilder::svg`], bare `<svg>` elements for HTML pages with [`QrCodeBuilder::svg_element`], printable cards with the SSID and password written under the code via `WifiCredentials::write_svg_card`, and static HTML landing pages with instructions for each operating system via `landing::LandingPage`.
+//! * `decode` - read QR codes with an embedded decoder, to check rendered codes with `WifiCredentials::verify_roundtrip` or to read existing PNG and JPEG images with `decode_from_image`.
+//! * `camera` - scan wifi QR codes with the default camera via `decode_from_camera`, giving up after a timeout, using [nokhwa](https://docs.rs/nokhwa). Implies `decode`. Building it on Linux requires libclang for the Video4Linux bindings.
+//! * `parallel` - render batches of credentials to PNG images on all cores with `encode_batch_parallel`, using [rayon](https://docs.rs/rayon). Implies `png`.
+//! * `schemars` - derive [`schemars::JsonSchema`](https://docs.rs/schemars) for the credential types so that input can be validated against a JSON Schema before it reaches this crate.
+//! * `image` - return the QR code as an [`image::DynamicImage`](https://docs.rs/image/0.24) via `encode_as_dynamic_image`.
+//! * `psk` - derive WPA pre-shared keys from passphrases, e.g. for [`export::wpa_supplicant::encode_with_derived_psk`].
+//! * `zeroize` - wipe passwords from memory when credentials are dropped, along with the payload passed to the QR code generator once rendering is finished and the configuration files written by the `export` module. Passwords are held in a [`Secret`], which is the same type without the feature, so enabling it does not change the API. Buffers allocated by the underlying QR code library are not wiped.
+//! * `unicode-normalization` - normalize SSIDs and passwords to Unicode NFC with `WifiCredentials::normalize_nfc`, for credentials typed on systems that produce decomposed accents.
+//! * `prompt` - ask for credentials on the terminal, with a masked password, via `prompt_for_credentials`, using [rpassword](https://docs.rs/rpassword).
+//! * `unifi` - import WLANs from a UniFi Network controller via `import::unifi`.
+//! * `fritzbox` - read the WLANs and guest WLAN configured on an AVM Fritz!Box over TR-064 via `import::fritzbox`, using [ureq](https://docs.rs/ureq) and HTTP digest authentication.
+//! * `apply` - join the network described by credentials on the local machine with `apply::join`, using `nmcli` on Linux, `networksetup` on macOS, and `netsh` on Windows, which reads the profile from a file created with [tempfile](https://docs.rs/tempfile). The module is only available on those platforms.
+//! * `keyring` - store passwords in the operating system's keyring, keyed by SSID, with `keyring::store` and retrieve them with `keyring::password`, using the [keyring](https://docs.rs/keyring) crate.
+//! * `label` - encode Brother QL and DYMO LabelWriter print jobs laid out at the raster size of 62 mm endless tape or DYMO 99012 labels, via `print::label::Label`, using the [font8x8](https://docs.rs/font8x8) font for the captions.
+//! * `mqtt` - publish the QR code as a base64 encoded PNG to an MQTT broker, with Home Assistant discovery messages for an image entity and an SSID sensor, via `mqtt::HomeAssistant` and `mqtt::publish`, using [rumqttc](https://docs.rs/rumqttc). Implies `png`.
+//! * `platform` - read the wifi networks saved on the local machine, including their passwords where the user is allowed to, through the `platform::SavedNetworks` backends for NetworkManager, the macOS keychain, and Windows WLAN profiles. The module is only available on Linux, macOS, and Windows.
+//! * `random` - generate passphrases with the operating system's random number generator via `generate_passphrase` and `WifiCredentials::wpa2_random`, using [getrandom](https://docs.rs/getrandom).
+//! * `hotspot` - share the machine's connection through a hotspot with a generated passphrase, and get the QR code for it, via `apply::create_hotspot` on Linux and Windows. Implies `apply` and `random`.
+//! * `rotation` - rotate the passphrase of a guest network via `rotation::Rotation`, which configures the access point through a pluggable `rotation::AccessPoint` backend, then writes the new QR code and card and archives the previous ones. Implies `random` and `svg`.
+//! * `pdf` - write sheets of sticker labels as PDF documents via `print::sheet::Sheet`, with predefined Avery layouts such as `print::sheet::SheetLayout::AVERY_5160` and `print::sheet::SheetLayout::AVERY_L7160`, and foldable table tents via `print::tent::TableTent`.
+//! * `template` - fill in user supplied SVG or HTML templates containing `{{qr}}`, `{{ssid}}`, and `{{password}}` placeholders via `template::Template`, using [handlebars](https://docs.rs/handlebars). Implies `svg`.
+//! * `fonts` - set the text of SVG cards in a TrueType or OpenType font, embedded into the card or converted into paths, via `print::font::Font` and `WifiCredentials::write_svg_card_with_font`, using [ttf-parser](https://docs.rs/ttf-parser). Implies `svg`.
+//! * `ipp` - submit print jobs, such as sheets or ESC/POS receipts, straight to network printers over IPP via `print::ipp::Printer`, using [ureq](https://docs.rs/ureq).
+//! * `axum` and `actix` - return QR codes from [axum](https://docs.rs/axum) and [actix-web](https://docs.rs/actix-web) handlers as `web::WifiQrPng` and `web::WifiQrSvg` responses, with the content type set and caching disabled.
+//! * `serve` - serve the QR code of a network over HTTP via `serve::Server`, limited to loopback addresses and Unix sockets by default, with optional bearer tokens and per-client rate limits. It also has a JSON API for generating and decoding the QR codes of any network, described by an OpenAPI document at `/openapi.json`. Prometheus metrics are served at `/metrics`. Implies `png` and `svg`.
+//! * `guest` - hand out short-lived guest passphrases at `POST /guest` via `serve::Server::guest`, which configures the access point through a `rotation::AccessPoint` backend and replaces each passphrase once it expires. Implies `rotation` and `serve`.
+//! * `tls` - serve `serve::Server` over HTTPS with `serve::Server::tls`, using [rustls](https://docs.rs/rustls) with certificates from PEM files, which are reloaded when an ACME client renews them. Implies `serve`.
+//! * `wasm` - draw QR codes straight onto a `<canvas>` from JavaScript via `wasm::draw_to_canvas`, exported as `drawToCanvas`, with the colors, quiet zone, and error correction set by `wasm::CanvasStyle`, using [wasm-bindgen](https://docs.rs/wasm-bindgen) and [web-sys](https://docs.rs/web-sys).
+//! * `epd` - show QR codes on black and white Waveshare e-paper displays via `display::epd::EpdRenderer`, rotated to the orientation of the panel, with full or partial refreshes, using [epd-waveshare](https://docs.rs/epd-waveshare). It also adds `display::draw_bitmatrix`, which draws onto any [embedded-graphics](https://docs.rs/embedded-graphics) target.
+//! * `oled` - show QR codes on 128 by 64 monochrome OLED displays, such as SSD1306 and SH1106 modules connected over I2C or SPI, via `display::oled::OledRenderer`, scaled to whole pixels per module and centered on the screen. It works with any embedded-graphics target with `BinaryColor` pixels, and also adds `display::draw_bitmatrix`.
+//! * `framebuffer` - show the QR code full-screen on a Linux framebuffer device such as `/dev/fb0` with `encode_to_framebuffer`, for kiosk boards without X or Wayland. The function is only available on Linux.
+//! * `hub75` - show QR codes on RGB LED matrix panels with a HUB75 connector via `display::hub75::Hub75Renderer`, with adjustable color, brightness, and contrast, and a check that rejects settings too dim or too low in contrast to scan. It works with any embedded-graphics target with RGB pixels, and also adds `display::draw_bitmatrix`.
+//! * `tft` - show QR codes on color TFT displays such as ST7789, ILI9341, and round GC9A01 modules via `display::tft::TftRenderer`, driven by [mipidsi](https://docs.rs/mipidsi) or any other embedded-graphics target with RGB pixels, with a margin for rounded corners and bezels. It also adds `display::draw_bitmatrix`.
+//! * `heapless` - encode the payload into a fixed capacity [`heapless::String`](https://docs.rs/heapless) with `WifiCredentials::encode_to_heapless`, for firmware without a global allocator. Writing the payload into a byte buffer with [`WifiCredentialsRef::encode_to_slice`] is always available.
+//! * `defmt` - implement [`defmt::Format`](https://docs.rs/defmt) for the errors and credential types, for readable logs over RTT on embedded targets. Passwords are redacted like in the `Debug` implementations.
+//! * `micro` - encode the credentials of open networks with very short SSIDs as M4 Micro QR codes via `encode_as_micro_matrix`, for labels too small for a regular QR code, using the [qrcode](https://docs.rs/qrcode) crate.
+//! * `std` (default) - everything that needs the standard library or an allocator: the owned [`WifiCredentials`], the `encode_as_*` functions, and every other feature except `heapless`, `defmt`, and `embedded-graphics`. Without it, QR codes are generated by a port of the qrcodegen library in the crate that writes straight into the array of a [`QrMatrix`].
+//! * `embedded-graphics` - draw a [`QrMatrix`] onto any [embedded-graphics](https://docs.rs/embedded-graphics) target with `display::draw_qr_matrix`, without `std`. The display renderers above imply it.
 
+#[cfg(not(feature = "std"))]
+pub use generator::QrCodeEcc;
+#[cfg(feature = "std")]
 pub use qrcode_generator::{QRCodeError, QrCodeEcc};
 
+#[cfg(feature = "parallel")]
+pub use batch::encode_batch_parallel;
+#[cfg(feature = "std")]
+pub use batch::BatchEncoder;
+#[cfg(feature = "std")]
+pub use bitmatrix::BitMatrix;
+pub use borrowed::{AuthenticationTypeRef, WifiCredentialsRef};
+#[cfg(feature = "std")]
+pub use builder::QrCodeBuilder;
+#[cfg(feature = "camera")]
+pub use camera::decode_from_camera;
+#[doc(hidden)]
+pub use const_payload::payload_capacity;
+pub use const_payload::ConstPayload;
+#[cfg(feature = "std")]
+pub use control_characters::ControlCharacterPolicy;
+#[cfg(feature = "decode")]
+pub use decode::{decode_from_image, DecodeError, VerificationError};
+pub use error::{CredentialField, ValidationError};
+#[cfg(feature = "std")]
+pub use error::{Error, OutputStage, Result};
+#[cfg(feature = "std")]
+pub use escaping::EscapedCredentials;
+pub use escaping::EscapingMode;
+pub use fixed::{CapacityError, QrMatrixError};
+#[cfg(all(feature = "framebuffer", target_os = "linux"))]
+pub use framebuffer::encode_to_framebuffer;
+#[cfg(feature = "std")]
+pub use lint::{lint, Diagnostic, LintKind, Severity};
+#[cfg(feature = "micro")]
+pub use micro::encode_as_micro_matrix;
+#[cfg(feature = "std")]
+pub use modules::Modules;
+#[cfg(feature = "std")]
+pub use options::{EncodeOptions, MaskSelection};
+#[cfg(feature = "std")]
+pub use parse::{ParseError, RecoveredCredentials};
+#[cfg(feature = "random")]
+pub use passphrase::generate_passphrase;
+#[cfg(feature = "std")]
+pub use payload::EncodedPayload;
+#[cfg(feature = "prompt")]
+pub use prompt::{prompt_for_credentials, PromptError};
+#[cfg(feature = "std")]
+pub use qr_code::WifiQrCode;
+#[cfg(feature = "std")]
+pub use qr_matrix::encode_as_qr_matrix;
+pub use qr_matrix::{qr_matrix_side, QrMatrix};
+#[cfg(feature = "svg")]
+pub use render::SvgAttributes;
+#[cfg(feature = "std")]
+pub use report::EncodeReport;
+#[cfg(feature = "std")]
+pub use roundtrip::RoundtripError;
+#[cfg(feature = "std")]
+pub use secret::Secret;
+#[cfg(feature = "std")]
+pub use strength::{Strength, StrengthRating};
+
+#[cfg(all(
+    feature = "apply",
+    any(target_os = "linux", target_os = "macos", target_os = "windows")
+))]
+pub mod apply;
+#[cfg(feature = "std")]
+mod batch;
+#[cfg(feature = "std")]
+mod bitmatrix;
+mod borrowed;
+#[cfg(feature = "std")]
+mod builder;
+#[cfg(feature = "camera")]
+mod camera;
+#[cfg(feature = "svg")]
+mod card;
+mod const_payload;
+#[cfg(feature = "std")]
+mod control_characters;
+#[cfg(feature = "decode")]
+mod decode;
+#[cfg(feature = "embedded-graphics")]
+pub mod display;
+#[cfg(feature = "std")]
+pub mod dpp;
+#[cfg(feature = "std")]
+pub mod enterprise;
+mod error;
+mod escaping;
+#[cfg(feature = "std")]
+pub mod export;
+mod fixed;
+#[cfg(all(feature = "framebuffer", target_os = "linux"))]
+mod framebuffer;
+mod generator;
+#[cfg(feature = "std")]
+pub mod import;
+#[cfg(feature = "keyring")]
+pub mod keyring;
+#[cfg(feature = "svg")]
+pub mod landing;
+#[cfg(feature = "std")]
+mod lint;
+#[cfg(feature = "micro")]
+mod micro;
+#[cfg(feature = "std")]
+mod modules;
+#[cfg(feature = "mqtt")]
+pub mod mqtt;
+#[cfg(feature = "unicode-normalization")]
+mod normalization;
+#[cfg(feature = "std")]
+mod options;
+#[cfg(feature = "std")]
+mod parse;
+#[cfg(feature = "random")]
+mod passphrase;
+#[cfg(feature = "std")]
+mod payload;
+#[cfg(all(
+    feature = "platform",
+    any(target_os = "linux", target_os = "macos", target_os = "windows")
+))]
+pub mod platform;
+#[cfg(feature = "std")]
+pub mod print;
+#[cfg(feature = "prompt")]
+mod prompt;
+#[cfg(feature = "std")]
+mod qr_code;
+mod qr_matrix;
+#[cfg(feature = "std")]
+mod redact;
+#[cfg(feature = "std")]
+pub mod registry;
+#[cfg(feature = "std")]
+mod render;
+#[cfg(feature = "std")]
+mod report;
+#[cfg(feature = "rotation")]
+pub mod rotation;
+#[cfg(feature = "std")]
+mod roundtrip;
+#[cfg(feature = "std")]
+mod secret;
+#[cfg(feature = "serve")]
+pub mod serve;
+#[cfg(feature = "std")]
+mod strength;
+#[cfg(feature = "std")]
+pub mod structured_append;
+#[cfg(feature = "template")]
+pub mod template;
+#[cfg(all(
+    any(feature = "apply", feature = "platform"),
+    any(target_os = "linux", target_os = "macos", target_os = "windows")
+))]
+mod tool;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(all(
+    any(feature = "actix", feature = "axum"),
+    any(feature = "png", feature = "svg")
+))]
+pub mod web;
+
+#[cfg(feature = "std")]
+use core::fmt;
+#[cfg(any(feature = "png", feature = "svg"))]
 use std::io::Write;
 
+/// Data that can be rendered as a QR code by the `encode_as_*` functions, such as [`WifiCredentials`], [`enterprise::EnterpriseCredentials`], or [`dpp::DppCredentials`].
+#[cfg(feature = "std")]
+pub trait QrPayload {
+    /// Encode the data as the text stored in the QR code.
+    fn encode(&self) -> String;
+
+    /// Check that the data can be used by devices that scan the QR code. The `encode_as_*` functions refuse to render data that fails validation.
+    fn validate(&self) -> Result<(), ValidationError> {
+        Ok(())
+    }
+}
+
+/// The validated payload handed to the QR code generator. With the `zeroize` feature, it is wiped from memory once rendering is finished.
+#[cfg(feature = "zeroize")]
+fn encode_payload(payload: &impl QrPayload) -> Result<zeroize::Zeroizing<String>, Error> {
+    payload.validate()?;
+    Ok(zeroize::Zeroizing::new(payload.encode()))
+}
+
+#[cfg(all(feature = "std", not(feature = "zeroize")))]
+fn encode_payload(payload: &impl QrPayload) -> Result<String, Error> {
+    payload.validate()?;
+    Ok(payload.encode())
+}
+
+/// Generate the same QR code as the `qrcode_generator` functions, for output that they do not support.
+#[cfg(feature = "std")]
+fn generate_symbol(
+    payload: &str,
+    qr_code_error_checking: QrCodeEcc,
+) -> Result<qrcode_generator::qrcodegen::QrCode, Error> {
+    qrcode_generator::qrcodegen::QrCode::encode_text(payload, qr_code_error_checking)
+        .map_err(|_| Error::QrCode(QRCodeError::DataTooLong))
+}
+
 /// Encode credentials as a matrix of boolean values. This is useful when manually generating an image.
 ///
 /// # Examples
@@ -20,16 +272,174 @@ use std::io::Write;
 ///
 /// let wifi_credentials = WifiCredentials {
 ///     ssid: String::from("example ssid"),
-///     authentication_type: AuthenticationType::WPA(String::from("example password")),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
 ///     visibility: Visibility::Hidden,
 /// };
 /// wifi_qr_code::encode_as_matrix(&wifi_credentials, QrCodeEcc::Medium);
 /// ```
+#[cfg(feature = "std")]
+#[deprecated(
+    note = "use `wifi_credentials.qr(ecc).matrix()`, or `QrCodeBuilder::new` for other payloads, instead"
+)]
 pub fn encode_as_matrix(
-    wifi_credentials: &WifiCredentials,
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+) -> Result<Vec<Vec<bool>>, Error> {
+    Ok(qrcode_generator::to_matrix(
+        encode_payload(payload)?,
+        qr_code_error_checking,
+    )?)
+}
+
+/// Encode credentials as a single buffer of boolean values, row by row, along with the width and height of the QR code in modules. The module at column `x` and row `y` is at index `y * size + x`. Unlike [`encode_as_matrix`], this avoids an allocation per row, and the matrix can be handed across FFI or WASM boundaries as one contiguous buffer.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let (modules, size) = wifi_qr_code::encode_as_flat_matrix(&wifi_credentials, QrCodeEcc::Medium)
+///     .expect("Failed to encode example matrix.");
+/// assert_eq!(size * size, modules.len());
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_as_flat_matrix(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+) -> Result<(Vec<bool>, usize), Error> {
+    Ok(render::to_flat_matrix(&generate_symbol(
+        &encode_payload(payload)?,
+        qr_code_error_checking,
+    )?))
+}
+
+/// Encode credentials as a matrix of boolean values surrounded by `quiet_zone` light modules on every side. This is useful for renderers that draw the matrix as-is, which would otherwise produce a code without the light border scanners need to find it. The QR code standard calls for a quiet zone of 4 modules, though most scanners manage with 2.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let matrix = wifi_qr_code::encode_as_matrix(&wifi_credentials, QrCodeEcc::Medium)
+///     .expect("Failed to encode example matrix.");
+/// let padded = wifi_qr_code::encode_as_matrix_with_quiet_zone(&wifi_credentials, QrCodeEcc::Medium, 4)
+///     .expect("Failed to encode example matrix.");
+/// assert_eq!(matrix.len() + 8, padded.len());
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_as_matrix_with_quiet_zone(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+    quiet_zone: usize,
+) -> Result<Vec<Vec<bool>>, Error> {
+    Ok(render::with_quiet_zone(
+        render::to_matrix(&generate_symbol(
+            &encode_payload(payload)?,
+            qr_code_error_checking,
+        )?),
+        quiet_zone,
+    ))
+}
+
+/// Encode credentials as a [`BitMatrix`], packed one bit per module. This is useful on microcontrollers driving small displays, where a `Vec<Vec<bool>>` takes eight times the memory.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let bitmatrix = wifi_qr_code::encode_as_bitmatrix(&wifi_credentials, QrCodeEcc::Medium)
+///     .expect("Failed to encode example bitmatrix.");
+/// assert_eq!(bitmatrix.size * bitmatrix.stride, bitmatrix.data.len());
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_as_bitmatrix(
+    payload: &impl QrPayload,
     qr_code_error_checking: QrCodeEcc,
-) -> Result<Vec<Vec<bool>>, QRCodeError> {
-    qrcode_generator::to_matrix(wifi_credentials.encode(), qr_code_error_checking)
+) -> Result<BitMatrix, Error> {
+    Ok(BitMatrix::new(&generate_symbol(
+        &encode_payload(payload)?,
+        qr_code_error_checking,
+    )?))
+}
+
+/// Encode credentials as an iterator of `(x, y, dark)` modules. This is useful for renderers such as plotters, engravers, or LED matrices that consume one module at a time, since the matrix is never materialized.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let modules = wifi_qr_code::encode_as_modules(&wifi_credentials, QrCodeEcc::Medium)
+///     .expect("Failed to encode example modules.");
+/// for (x, y, dark) in modules {
+///     if dark {
+///         // Draw the module at (x, y).
+///     }
+/// }
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_as_modules(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+) -> Result<Modules, Error> {
+    Ok(Modules::new(generate_symbol(
+        &encode_payload(payload)?,
+        qr_code_error_checking,
+    )?))
+}
+
+/// Encode credentials as a matrix of boolean values, with control over how the QR code is generated.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::EncodeOptions;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let options = EncodeOptions {
+///     min_version: 5,
+///     ..EncodeOptions::default()
+/// };
+/// let matrix = wifi_qr_code::encode_as_matrix_with_options(&wifi_credentials, &options)
+///     .expect("Failed to encode example matrix.");
+/// assert_eq!(37, matrix.len());
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_as_matrix_with_options(
+    payload: &impl QrPayload,
+    options: &EncodeOptions,
+) -> Result<Vec<Vec<bool>>, Error> {
+    Ok(render::to_matrix(&options::generate(payload, options)?))
 }
 
 /// Encode credentials as raw image data. This is useful when generating the QR code and then manipulating it with an image library.
@@ -42,20 +452,150 @@ pub fn encode_as_matrix(
 ///
 /// let wifi_credentials = WifiCredentials {
 ///     ssid: String::from("example ssid"),
-///     authentication_type: AuthenticationType::WPA(String::from("example password")),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
 ///     visibility: Visibility::Hidden,
 /// };
 /// wifi_qr_code::encode_as_image(&wifi_credentials, QrCodeEcc::Medium, 100);
 /// ```
+#[cfg(feature = "std")]
+#[deprecated(
+    note = "use `wifi_credentials.qr(ecc).size(image_size).image()`, or `QrCodeBuilder::new` for other payloads, instead"
+)]
 pub fn encode_as_image(
-    wifi_credentials: &WifiCredentials,
+    payload: &impl QrPayload,
     qr_code_error_checking: QrCodeEcc,
     image_size: usize,
-) -> Result<Vec<u8>, QRCodeError> {
-    qrcode_generator::to_image(
-        wifi_credentials.encode(),
+) -> Result<Vec<u8>, Error> {
+    Ok(qrcode_generator::to_image(
+        encode_payload(payload)?,
         qr_code_error_checking,
         image_size,
+    )?)
+}
+
+/// Encode credentials as raw image data drawn at exactly `pixels_per_module` pixels per module, with a light margin of `margin` modules, returning the image along with its side length in pixels. Unlike [`encode_as_image`], which fits the QR code into a given image size, every module is the same whole number of pixels, so the code stays crisp when displayed or printed at its native resolution.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let (image, image_size) = wifi_qr_code::encode_as_image_with_module_size(&wifi_credentials, QrCodeEcc::Medium, 4, 4)
+///     .expect("Failed to encode example image.");
+/// assert_eq!(0, image_size % 4);
+/// assert_eq!(image_size * image_size, image.len());
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_as_image_with_module_size(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+    pixels_per_module: usize,
+    margin: usize,
+) -> Result<(Vec<u8>, usize), Error> {
+    render::to_scaled_image(
+        &generate_symbol(&encode_payload(payload)?, qr_code_error_checking)?,
+        pixels_per_module,
+        margin,
+    )
+}
+
+/// Encode credentials as raw image data, along with an [`EncodeReport`] describing the QR code.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let (image, report) = wifi_qr_code::encode_as_image_with_report(&wifi_credentials, QrCodeEcc::Medium, 100)
+///     .expect("Failed to encode example image.");
+/// assert_eq!(image.len(), report.output_bytes);
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_as_image_with_report(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+    image_size: usize,
+) -> Result<(Vec<u8>, EncodeReport), Error> {
+    let encoded = encode_payload(payload)?;
+    let qr_code = generate_symbol(&encoded, qr_code_error_checking)?;
+    let image = render::to_image(&qr_code, image_size)?;
+    let report = EncodeReport::new(&qr_code, encoded.len(), image.len());
+    Ok((image, report))
+}
+
+/// Encode credentials as raw image data, with control over how the QR code is generated.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::EncodeOptions;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let options = EncodeOptions {
+///     min_version: 5,
+///     max_version: 5,
+///     ..EncodeOptions::default()
+/// };
+/// wifi_qr_code::encode_as_image_with_options(&wifi_credentials, &options, 100);
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_as_image_with_options(
+    payload: &impl QrPayload,
+    options: &EncodeOptions,
+    image_size: usize,
+) -> Result<Vec<u8>, Error> {
+    render::to_image(&options::generate(payload, options)?, image_size)
+}
+
+/// Encode credentials as an [`image::DynamicImage`](https://docs.rs/image/0.24/image/enum.DynamicImage.html). This is useful when post-processing the QR code (resizing, compositing, watermarking) with the image crate, since it avoids decoding a freshly encoded PNG.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let image = wifi_qr_code::encode_as_dynamic_image(&wifi_credentials, QrCodeEcc::Medium, 100)
+///     .expect("Failed to encode example image.");
+/// assert_eq!(100, image.width());
+/// ```
+#[cfg(feature = "image")]
+#[deprecated(
+    note = "use `wifi_credentials.qr(ecc).size(image_size).dynamic_image()`, or `QrCodeBuilder::new` for other payloads, instead"
+)]
+pub fn encode_as_dynamic_image(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+    image_size: usize,
+) -> Result<image::DynamicImage, Error> {
+    render::to_dynamic_image(
+        render::to_image(
+            &generate_symbol(&encode_payload(payload)?, qr_code_error_checking)?,
+            image_size,
+        )?,
+        image_size,
     )
 }
 
@@ -71,26 +611,99 @@ pub fn encode_as_image(
 ///
 /// let wifi_credentials = WifiCredentials {
 ///     ssid: String::from("example ssid"),
-///     authentication_type: AuthenticationType::WPA(String::from("example password")),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
 ///     visibility: Visibility::Hidden,
 /// };
-/// let png_file = File::create("wifi_qr.png").expect("Failed to create example PNG file.");
+/// let png_file = File::create(std::env::temp_dir().join("wifi_qr.png")).expect("Failed to create example PNG file.");
 /// wifi_qr_code::encode_as_png(&wifi_credentials, QrCodeEcc::Medium, 100, png_file);
 /// ```
+#[cfg(feature = "png")]
+#[deprecated(
+    note = "use `wifi_credentials.qr(ecc).size(image_size).png(writer)`, or `QrCodeBuilder::new` for other payloads, instead"
+)]
 pub fn encode_as_png(
-    wifi_credentials: &WifiCredentials,
+    payload: &impl QrPayload,
     qr_code_error_checking: QrCodeEcc,
     image_size: usize,
-    mut writer: impl Write,
-) -> Result<(), QRCodeError> {
-    qrcode_generator::to_png_to_writer(
-        wifi_credentials.encode(),
-        qr_code_error_checking,
+    writer: impl Write,
+) -> Result<(), Error> {
+    let qr_code = generate_symbol(&encode_payload(payload)?, qr_code_error_checking)?;
+    render::write_png(&render::to_image(&qr_code, image_size)?, image_size, writer)
+}
+
+/// Encode credentials as a PNG image, streamed to the writer one pixel row at a time. Unlike [`encode_as_png`], the full image is never held in memory, so large posters can be rendered on small hosts.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// use std::fs::File;
+/// use std::io::BufWriter;
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let png_file = File::create(std::env::temp_dir().join("wifi_qr.png")).expect("Failed to create example PNG file.");
+/// wifi_qr_code::encode_as_png_streamed(&wifi_credentials, QrCodeEcc::Medium, 4096, BufWriter::new(png_file))
+///     .expect("Failed to encode example PNG.");
+/// ```
+#[cfg(feature = "png")]
+pub fn encode_as_png_streamed(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+    image_size: usize,
+    writer: impl Write,
+) -> Result<(), Error> {
+    render::write_png_streamed(
+        &generate_symbol(&encode_payload(payload)?, qr_code_error_checking)?,
         image_size,
-        &mut writer,
+        writer,
     )
 }
 
+/// Encode credentials as a PNG image, returning an [`EncodeReport`] describing the QR code.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let mut png = Vec::new();
+/// let report = wifi_qr_code::encode_as_png_with_report(&wifi_credentials, QrCodeEcc::Medium, 100, &mut png)
+///     .expect("Failed to encode example PNG.");
+/// assert_eq!(png.len(), report.output_bytes);
+/// ```
+#[cfg(feature = "png")]
+pub fn encode_as_png_with_report(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+    image_size: usize,
+    writer: impl Write,
+) -> Result<EncodeReport, Error> {
+    let encoded = encode_payload(payload)?;
+    let qr_code = generate_symbol(&encoded, qr_code_error_checking)?;
+    let mut writer = report::CountingWriter {
+        inner: writer,
+        count: 0,
+    };
+    render::write_png(
+        &render::to_image(&qr_code, image_size)?,
+        image_size,
+        &mut writer,
+    )?;
+    Ok(EncodeReport::new(&qr_code, encoded.len(), writer.count))
+}
+
 /// Encode credentials as an SVG image.
 ///
 /// # Examples
@@ -103,66 +716,265 @@ pub fn encode_as_png(
 ///
 /// let wifi_credentials = WifiCredentials {
 ///     ssid: String::from("example ssid"),
-///     authentication_type: AuthenticationType::WPA(String::from("example password")),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
 ///     visibility: Visibility::Hidden,
 /// };
-/// let svg_file = File::create("wifi_qr.svg").expect("Failed to create example SVG file.");
+/// let svg_file = File::create(std::env::temp_dir().join("wifi_qr.svg")).expect("Failed to create example SVG file.");
 /// wifi_qr_code::encode_as_svg(&wifi_credentials, QrCodeEcc::Medium, 100, svg_file);
 /// ```
+#[cfg(feature = "svg")]
+#[deprecated(
+    note = "use `wifi_credentials.qr(ecc).size(image_size).svg(writer)`, or `QrCodeBuilder::new` for other payloads, instead"
+)]
 pub fn encode_as_svg(
-    wifi_credentials: &WifiCredentials,
+    payload: &impl QrPayload,
     qr_code_error_checking: QrCodeEcc,
     image_size: usize,
-    mut writer: impl Write,
-) -> Result<(), QRCodeError> {
-    qrcode_generator::to_png_to_writer(
-        wifi_credentials.encode(),
-        qr_code_error_checking,
+    writer: impl Write,
+) -> Result<(), Error> {
+    render::write_svg(
+        &generate_symbol(&encode_payload(payload)?, qr_code_error_checking)?,
         image_size,
-        &mut writer,
+        writer,
     )
 }
 
-/// Declare whether the network is authenticated via WEP with a password, WPA with a password, or if the network is open.
+/// Encode credentials as an SVG image, returning an [`EncodeReport`] describing the QR code.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let mut svg = Vec::new();
+/// let report = wifi_qr_code::encode_as_svg_with_report(&wifi_credentials, QrCodeEcc::Medium, 100, &mut svg)
+///     .expect("Failed to encode example SVG.");
+/// assert_eq!(svg.len(), report.output_bytes);
+/// ```
+#[cfg(feature = "svg")]
+pub fn encode_as_svg_with_report(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+    image_size: usize,
+    writer: impl Write,
+) -> Result<EncodeReport, Error> {
+    let encoded = encode_payload(payload)?;
+    let qr_code = generate_symbol(&encoded, qr_code_error_checking)?;
+    let mut writer = report::CountingWriter {
+        inner: writer,
+        count: 0,
+    };
+    render::write_svg(&qr_code, image_size, &mut writer)?;
+    Ok(EncodeReport::new(&qr_code, encoded.len(), writer.count))
+}
+
+/// Declare whether the network is authenticated via WEP with a password, WPA with a password, or if the network is open. More authentication types may be added in the future, so matches outside this crate need a wildcard arm.
+#[cfg(feature = "std")]
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub enum AuthenticationType {
-    /// WEP authentication is an older family of protocols. It is not particularly secure and wireless access points should use a more modern methods such as the WPA family of authentication protocols.
-    WEP(String),
-    /// WPA authentication is a more modern family of protocols. Typically, wireless networks will use WPA2 as their protocol implementation.
-    WPA(String),
+    /// WEP authentication is an older family of protocols. It is not particularly secure and wireless access points should use a more modern methods such as the WPA family of authentication protocols. The key must be in one of the [`WepKeyFormat`]s.
+    WEP(Secret<String>),
+    /// WPA authentication is a more modern family of protocols. Typically, wireless networks will use WPA2 as their protocol implementation. The passphrase must be 8 to 63 printable ASCII characters.
+    WPA(Secret<String>),
+    /// WPA authentication with the 256-bit pre-shared key itself rather than the passphrase it is derived from. It is encoded as 64 hex digits, which devices accept in place of a passphrase.
+    WpaPsk(Secret<[u8; 32]>),
     /// No password / open access is particularly rare because it is possible for malicious actors to read all unencrypted traffic going across the network.
     NoPassword,
 }
 
+#[cfg(feature = "std")]
 impl AuthenticationType {
-    fn encode(&self) -> String {
+    /// Replace the password, e.g. after parsing the authentication type from user input with [`str::parse`]. WPA pre-shared keys are replaced by the passphrase, and open networks are unchanged.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::AuthenticationType;
+    ///
+    /// let authentication_type: AuthenticationType = "wpa2".parse().expect("Failed to parse example type.");
+    /// assert_eq!(
+    ///     AuthenticationType::WPA("example password".into()),
+    ///     authentication_type.with_password("example password")
+    /// );
+    /// ```
+    pub fn with_password(self, password: impl Into<String>) -> Self {
+        match self {
+            Self::WEP(_) => Self::WEP(Secret::new(password.into())),
+            Self::WPA(_) | Self::WpaPsk(_) => Self::WPA(Secret::new(password.into())),
+            Self::NoPassword => Self::NoPassword,
+        }
+    }
+
+    /// Create a WPA pre-shared key authentication type from 64 hex digits, such as the output of `wpa_passphrase`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, ValidationError};
+    ///
+    /// let psk = "f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e";
+    /// assert!(AuthenticationType::wpa_psk_from_hex(psk).is_ok());
+    /// assert_eq!(
+    ///     Some(ValidationError::WpaPskFormat),
+    ///     AuthenticationType::wpa_psk_from_hex("f42c").err()
+    /// );
+    /// ```
+    pub fn wpa_psk_from_hex(hex: &str) -> Result<Self, ValidationError> {
+        if hex.len() != 64 || !hex.bytes().all(|byte| byte.is_ascii_hexdigit()) {
+            return Err(ValidationError::WpaPskFormat);
+        }
+        let mut psk = Secret::new([0u8; 32]);
+        for (byte, digits) in psk.iter_mut().zip(hex.as_bytes().chunks(2)) {
+            let digits = std::str::from_utf8(digits).map_err(|_| ValidationError::WpaPskFormat)?;
+            *byte = u8::from_str_radix(digits, 16).map_err(|_| ValidationError::WpaPskFormat)?;
+        }
+        Ok(Self::WpaPsk(psk))
+    }
+
+    /// The password as it is typed into a device, with pre-shared keys written as 64 hex digits, or `None` for open networks.
+    pub(crate) fn password_text(&self) -> Option<Secret<String>> {
+        match self {
+            Self::WEP(password) | Self::WPA(password) => Some(password.clone()),
+            Self::WpaPsk(psk) => Some(encode_hex(&**psk)),
+            Self::NoPassword => None,
+        }
+    }
+
+    /// Check that the password meets the requirements of the authentication protocol.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, ValidationError};
+    ///
+    /// assert_eq!(Ok(()), AuthenticationType::WPA("example password".into()).validate());
+    /// assert_eq!(
+    ///     Err(ValidationError::WpaPassphraseLength(5)),
+    ///     AuthenticationType::WPA("short".into()).validate()
+    /// );
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.as_borrowed().validate()
+    }
+
+    /// Format the authentication type with its password visible. The `Debug` implementation redacts the password so it does not end up in logs by accident.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::AuthenticationType;
+    ///
+    /// let authentication_type = AuthenticationType::WPA("example password".into());
+    /// assert_eq!("WPA(<redacted>)", format!("{:?}", authentication_type));
+    /// assert_eq!(r#"WPA("example password")"#, format!("{:?}", authentication_type.debug_unredacted()));
+    /// ```
+    pub fn debug_unredacted(&self) -> impl fmt::Debug + '_ {
+        redact::Redactable {
+            value: self,
+            redact: false,
+        }
+    }
+}
+
+/// The ways a WEP key can be written. Keys are either 40 bits (often sold as "64-bit WEP") or 104 bits (often sold as "128-bit WEP"), and devices reject anything else.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+pub enum WepKeyFormat {
+    /// 5 (40-bit) or 13 (104-bit) ASCII characters, used directly as the key bytes.
+    Ascii,
+    /// 10 (40-bit) or 26 (104-bit) hex digits.
+    Hex,
+}
+
+impl WepKeyFormat {
+    /// Determine how a WEP key is written, or `None` if it is not a valid WEP key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::WepKeyFormat;
+    ///
+    /// assert_eq!(Some(WepKeyFormat::Ascii), WepKeyFormat::detect("abcde"));
+    /// assert_eq!(Some(WepKeyFormat::Hex), WepKeyFormat::detect("0123456789"));
+    /// assert_eq!(None, WepKeyFormat::detect("012345678"));
+    /// ```
+    pub fn detect(key: &str) -> Option<Self> {
+        match key.len() {
+            5 | 13 if key.chars().all(|c| c.is_ascii() && !c.is_ascii_control()) => {
+                Some(Self::Ascii)
+            }
+            10 | 26 if key.bytes().all(|byte| byte.is_ascii_hexdigit()) => Some(Self::Hex),
+            _ => None,
+        }
+    }
+}
+
+/// Wipe the password from memory. This is done automatically when the [`Secret`] holding it is dropped.
+#[cfg(feature = "zeroize")]
+impl zeroize::Zeroize for AuthenticationType {
+    fn zeroize(&mut self) {
         match self {
-            Self::WEP(password) => format!("T:WEP;P:{};", escape(password)),
-            Self::WPA(password) => format!("T:WPA;P:{};", escape(password)),
-            Self::NoPassword => String::from("T:nopass;"),
+            Self::WEP(password) | Self::WPA(password) => password.zeroize(),
+            Self::WpaPsk(psk) => psk.zeroize(),
+            Self::NoPassword => {}
         }
     }
 }
 
-/// Declare whether the network is broadcasting its availability.
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for AuthenticationType {}
+
+/// Declare whether the network is broadcasting its availability. Most networks are visible, so that is the default.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::NoPassword,
+///     visibility: Visibility::default(),
+/// };
+/// assert_eq!("WIFI:S:example ssid;T:nopass;H:false;;", wifi_credentials.encode());
+/// # }
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "defmt", derive(defmt::Format))]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
+#[non_exhaustive]
 pub enum Visibility {
     /// Visible wifi networks display in lists of networks when a device scans an area.
+    #[default]
     Visible,
     /// Hidden wifi networks do not show up on scans and must be known by their SSID to be accessed.
     Hidden,
 }
 
 impl Visibility {
-    fn encode(&self) -> String {
+    fn encode(&self) -> &'static str {
         match self {
-            Self::Visible => String::from("H:false;"),
-            Self::Hidden => String::from("H:true;"),
+            Self::Visible => "H:false;",
+            Self::Hidden => "H:true;",
         }
     }
 }
 
 /// The credentials needed to completely connect to a wifi network.
+#[cfg(feature = "std")]
+#[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "schemars", derive(schemars::JsonSchema))]
 pub struct WifiCredentials {
-    /// The SSID of a wifi network is the name used to access it.
+    /// The SSID of a wifi network is the name used to access it. It must be between 1 and 32 bytes long when encoded as UTF-8.
     pub ssid: String,
     /// The authentication type of a wifi network determines the protocol used to access it and the password required to properly authenticate to it.
     pub authentication_type: AuthenticationType,
@@ -170,7 +982,65 @@ pub struct WifiCredentials {
     pub visibility: Visibility,
 }
 
+#[cfg(feature = "std")]
 impl WifiCredentials {
+    /// Credentials for a visible WPA/WPA2 network, the most common setup.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// assert_eq!("WIFI:S:example ssid;T:WPA;P:example password;H:false;;", wifi_credentials.encode());
+    /// ```
+    pub fn wpa2(ssid: impl Into<String>, password: impl Into<String>) -> Self {
+        WifiCredentials {
+            ssid: ssid.into(),
+            authentication_type: AuthenticationType::WPA(Secret::new(password.into())),
+            visibility: Visibility::Visible,
+        }
+    }
+
+    /// Credentials for a visible network without a password, such as a guest network with a captive portal.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::open("example ssid");
+    /// assert_eq!("WIFI:S:example ssid;T:nopass;H:false;;", wifi_credentials.encode());
+    /// ```
+    pub fn open(ssid: impl Into<String>) -> Self {
+        WifiCredentials {
+            ssid: ssid.into(),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Visible,
+        }
+    }
+
+    /// Credentials for a network that does not broadcast its SSID.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials::hidden(
+    ///     "example ssid",
+    ///     AuthenticationType::WPA("example password".into()),
+    /// );
+    /// assert_eq!("WIFI:S:example ssid;T:WPA;P:example password;H:true;;", wifi_credentials.encode());
+    /// ```
+    pub fn hidden(ssid: impl Into<String>, authentication_type: AuthenticationType) -> Self {
+        WifiCredentials {
+            ssid: ssid.into(),
+            authentication_type,
+            visibility: Visibility::Hidden,
+        }
+    }
+
     /// Encode the credentials into the form expected for a wifi QR Code. Special characters (i.e. ";,:\) will be escaped in the output.
     ///
     /// # Examples
@@ -180,35 +1050,100 @@ impl WifiCredentials {
     ///
     /// let wifi_credentials = WifiCredentials {
     ///     ssid: String::from("example ssid"),
-    ///     authentication_type: AuthenticationType::WPA(String::from("example password")),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
     ///     visibility: Visibility::Hidden,
     /// };
     /// assert_eq!("WIFI:S:example ssid;T:WPA;P:example password;H:true;;", wifi_credentials.encode());
     /// ```
-    pub fn encode(&self) -> String {
-        format!(
-            "WIFI:{}{}{};",
-            self.encode_ssid(),
-            self.authentication_type.encode(),
-            self.visibility.encode()
-        )
+    pub fn encode(&self) -> EncodedPayload {
+        self.encode_with(EscapingMode::Spec)
     }
 
-    fn encode_ssid(&self) -> String {
-        format!("S:{};", escape(&self.ssid))
+    /// Write the encoded credentials to `writer` without building intermediate strings, e.g. to reuse one buffer across many credentials.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// let mut payload = String::new();
+    /// wifi_credentials.encode_to(&mut payload).expect("Failed to write example payload.");
+    /// assert_eq!(wifi_credentials.encode(), payload);
+    /// ```
+    pub fn encode_to(&self, writer: &mut impl fmt::Write) -> fmt::Result {
+        self.encode_to_with(writer, EscapingMode::Spec)
+    }
+
+    /// Check that the credentials meet the requirements of the wifi standards. The `encode_as_*` functions perform this check before rendering a QR code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, ValidationError, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("pässword".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// assert_eq!(Err(ValidationError::WpaPassphraseCharacters), wifi_credentials.validate());
+    /// ```
+    pub fn validate(&self) -> Result<(), ValidationError> {
+        self.as_borrowed().validate()
+    }
+
+    /// Format the credentials with the password visible. The `Debug` implementation redacts the password so it does not end up in logs by accident.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// assert!(!format!("{:?}", wifi_credentials).contains("example password"));
+    /// assert!(format!("{:?}", wifi_credentials.debug_unredacted()).contains("example password"));
+    /// ```
+    pub fn debug_unredacted(&self) -> impl fmt::Debug + '_ {
+        redact::Redactable {
+            value: self,
+            redact: false,
+        }
     }
 }
 
-fn escape(input: &str) -> String {
-    String::from(input)
-        .replace('\\', r#"\\"#)
-        .replace('"', r#"\""#)
-        .replace(';', r#"\;"#)
-        .replace(',', r#"\,"#)
-        .replace(':', r#"\:"#)
+#[cfg(feature = "std")]
+impl QrPayload for WifiCredentials {
+    fn encode(&self) -> String {
+        WifiCredentials::encode(self).into_string()
+    }
+
+    fn validate(&self) -> Result<(), ValidationError> {
+        WifiCredentials::validate(self)
+    }
 }
 
-#[cfg(test)]
+/// Write bytes as lowercase hex digits into a [`Secret`], since they are usually a pre-shared key.
+#[cfg(feature = "std")]
+pub(crate) fn encode_hex(bytes: &[u8]) -> Secret<String> {
+    const DIGITS: &[u8; 16] = b"0123456789abcdef";
+    let mut hex = Secret::new(String::with_capacity(bytes.len() * 2));
+    for byte in bytes {
+        hex.push(char::from(DIGITS[usize::from(byte >> 4)]));
+        hex.push(char::from(DIGITS[usize::from(byte & 0xf)]));
+    }
+    hex
+}
+
+#[cfg(all(test, feature = "std"))]
 mod tests {
     use super::*;
 
@@ -217,21 +1152,21 @@ mod tests {
         // WIFI:S:<SSID>;T:<WPA|WEP|>;P:<password>;H:<true|false|>;
         let wifi_credentials = WifiCredentials {
             ssid: String::from("test ssid"),
-            authentication_type: AuthenticationType::WEP(String::from("test password")),
+            authentication_type: AuthenticationType::WEP("test password".into()),
             visibility: Visibility::Visible,
         };
         assert_eq!(
             "WIFI:S:test ssid;T:WEP;P:test password;H:false;;",
-            &wifi_credentials.encode()
+            wifi_credentials.encode()
         );
         let wifi_credentials = WifiCredentials {
             ssid: String::from("test ssid"),
-            authentication_type: AuthenticationType::WPA(String::from("test password")),
+            authentication_type: AuthenticationType::WPA("test password".into()),
             visibility: Visibility::Hidden,
         };
         assert_eq!(
             "WIFI:S:test ssid;T:WPA;P:test password;H:true;;",
-            &wifi_credentials.encode()
+            wifi_credentials.encode()
         );
         let wifi_credentials = WifiCredentials {
             ssid: String::from("test ssid"),
@@ -240,22 +1175,151 @@ mod tests {
         };
         assert_eq!(
             "WIFI:S:test ssid;T:nopass;H:false;;",
-            &wifi_credentials.encode()
+            wifi_credentials.encode()
+        );
+    }
+
+    #[test]
+    fn it_builds_common_setups() {
+        assert_eq!(
+            WifiCredentials {
+                ssid: String::from("test ssid"),
+                authentication_type: AuthenticationType::WPA("test password".into()),
+                visibility: Visibility::Visible,
+            },
+            WifiCredentials::wpa2("test ssid", String::from("test password"))
+        );
+        assert_eq!(
+            "WIFI:S:test ssid;T:nopass;H:true;;",
+            WifiCredentials::hidden("test ssid", AuthenticationType::NoPassword).encode()
+        );
+    }
+
+    #[test]
+    fn it_deduplicates_credentials() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        let mut hidden = wifi_credentials.clone();
+        hidden.visibility = Visibility::Hidden;
+        let networks: std::collections::HashSet<_> =
+            vec![wifi_credentials.clone(), hidden, wifi_credentials]
+                .into_iter()
+                .collect();
+        assert_eq!(2, networks.len());
+    }
+
+    #[test]
+    fn it_encodes_pre_shared_keys_as_hex() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WpaPsk([0x0f; 32].into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            format!("WIFI:S:test ssid;T:WPA;P:{};H:false;;", "0f".repeat(32)),
+            wifi_credentials.encode()
+        );
+    }
+
+    #[test]
+    fn it_refuses_to_render_invalid_credentials() {
+        let too_long = "a".repeat(64);
+        for (passphrase, expected) in [
+            ("short", ValidationError::WpaPassphraseLength(5)),
+            (too_long.as_str(), ValidationError::WpaPassphraseLength(64)),
+            ("tab\tseparated", ValidationError::WpaPassphraseCharacters),
+        ] {
+            let wifi_credentials = WifiCredentials {
+                ssid: String::from("test ssid"),
+                authentication_type: AuthenticationType::WPA(passphrase.into()),
+                visibility: Visibility::Visible,
+            };
+            match wifi_credentials.qr(QrCodeEcc::Medium).matrix() {
+                Err(Error::Validation(error)) => assert_eq!(expected, error),
+                _ => panic!("Expected {:?} to fail validation.", passphrase),
+            }
+        }
+    }
+
+    #[test]
+    fn it_validates_ssid_length() {
+        let mut wifi_credentials = WifiCredentials {
+            ssid: String::new(),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(Err(ValidationError::EmptySsid), wifi_credentials.validate());
+        wifi_credentials.ssid = "a".repeat(32);
+        assert_eq!(Ok(()), wifi_credentials.validate());
+        // 11 three-byte characters exceed the limit even though there are only 11 characters.
+        wifi_credentials.ssid = "€".repeat(11);
+        assert_eq!(
+            Err(ValidationError::SsidLength(33)),
+            wifi_credentials.validate()
         );
+        match wifi_credentials.qr(QrCodeEcc::Medium).matrix() {
+            Err(Error::Validation(ValidationError::SsidLength(33))) => {}
+            _ => panic!("Expected the encoder to reject the SSID."),
+        }
+    }
+
+    #[test]
+    fn it_validates_wep_keys() {
+        for key in [
+            "abcde",
+            "abcdefghijklm",
+            "0123456789",
+            "0123456789abcdefABCDEF0123",
+        ] {
+            assert_eq!(Ok(()), AuthenticationType::WEP(key.into()).validate());
+        }
+        for key in ["abcd", "abcdefghij", "012345678g", "\u{7}bcde"] {
+            assert_eq!(
+                Err(ValidationError::WepKeyFormat),
+                AuthenticationType::WEP(key.into()).validate()
+            );
+        }
     }
 
     #[test]
     fn it_properly_handles_escaped_characters() {
         let wifi_credentials = WifiCredentials {
             ssid: String::from(r#"special_characters ";,:\"#),
-            authentication_type: AuthenticationType::WEP(String::from(
-                r#"special_characters ";,:\"#,
-            )),
+            authentication_type: AuthenticationType::WEP(r#"special_characters ";,:\"#.into()),
             visibility: Visibility::Visible,
         };
         assert_eq!(
             r#"WIFI:S:special_characters \"\;\,\:\\;T:WEP;P:special_characters \"\;\,\:\\;H:false;;"#,
-            &wifi_credentials.encode()
+            wifi_credentials.encode()
         );
     }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn it_wipes_passwords() {
+        use zeroize::Zeroize;
+
+        let mut authentication_type = AuthenticationType::WPA("test password".into());
+        authentication_type.zeroize();
+        match &authentication_type {
+            AuthenticationType::WPA(password) => assert!(password.is_empty()),
+            _ => panic!("Zeroizing should not change the authentication type."),
+        }
+    }
+
+    #[cfg(feature = "schemars")]
+    #[test]
+    fn it_generates_a_json_schema_for_credentials() {
+        let schema = schemars::schema_for!(WifiCredentials);
+        let properties = schema
+            .get("properties")
+            .and_then(|properties| properties.as_object())
+            .expect("Schema should describe the credential fields.");
+        assert!(properties.contains_key("ssid"));
+        assert!(properties.contains_key("authentication_type"));
+        assert!(properties.contains_key("visibility"));
+    }
 }
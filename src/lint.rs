@@ -0,0 +1,480 @@
+use crate::parse::{parse_wpa_password, trim_leading_noise, unescape};
+use crate::{AuthenticationType, AuthenticationTypeRef, ValidationError};
+
+use std::fmt::{self, Display, Formatter};
+use std::ops::Range;
+
+/// A problem found by [`lint`], with the byte range of the payload it is about.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Diagnostic {
+    /// The bytes of the payload the problem is about. Problems about something missing have an empty range where it was expected.
+    pub span: Range<usize>,
+    /// Whether scanners are likely to reject the payload because of the problem.
+    pub severity: Severity,
+    /// What the problem is.
+    pub kind: LintKind,
+}
+
+/// How serious a [`Diagnostic`] is.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Severity {
+    /// The payload deviates from the format, but many scanners accept it anyway.
+    Warning,
+    /// Scanners will misread the payload, or devices will fail to join the network.
+    Error,
+}
+
+/// The problems [`lint`] reports.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum LintKind {
+    /// The payload does not start with `WIFI:`.
+    MissingPrefix,
+    /// Whitespace or a byte order mark comes before the `WIFI:` prefix, as left behind by some OCR pipelines and chat apps.
+    LeadingCharacters,
+    /// The `WIFI:` prefix is not uppercase, which only some scanners accept.
+    LowercasePrefix,
+    /// The payload does not end with the `;;` that terminates the last field and the payload.
+    MissingTerminator,
+    /// The payload continues after the terminating `;;`.
+    TrailingCharacters,
+    /// A `;` inside a value was not escaped, so the rest of the value looks like a field without a key.
+    UnescapedSemicolon,
+    /// A character that should be escaped inside values, such as `:` or `,`, was not.
+    UnescapedCharacter(char),
+    /// A field key is lowercase, which only some scanners accept. Contains the key.
+    LowercaseKey(String),
+    /// A field that is not part of the format. Contains the key.
+    UnknownField(String),
+    /// The same field appeared twice. Contains the key.
+    DuplicateField(String),
+    /// A required field is missing. Contains the key of the field.
+    MissingField(&'static str),
+    /// The `T:` field is not an authentication type. Contains the value.
+    InvalidAuthenticationType(String),
+    /// The `T:` field names an authentication type, such as `WPA2`, in a way only some scanners accept. Contains the value.
+    NonstandardAuthenticationType(String),
+    /// The `H:` field is not `true` or `false`. Contains the value.
+    InvalidVisibility(String),
+    /// A `P:` field was given for an open network, and will be ignored.
+    UnusedPassword,
+    /// The SSID or password do not meet the requirements of the wifi standards.
+    Validation(ValidationError),
+}
+
+impl Display for Severity {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Warning => "warning",
+            Self::Error => "error",
+        })
+    }
+}
+
+impl Display for LintKind {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPrefix => f.write_str("payload does not start with WIFI:"),
+            Self::LeadingCharacters => f.write_str("characters before the WIFI: prefix"),
+            Self::LowercasePrefix => f.write_str("the WIFI: prefix should be uppercase"),
+            Self::MissingTerminator => f.write_str("payload does not end with ;;"),
+            Self::TrailingCharacters => f.write_str("payload continues after the terminating ;;"),
+            Self::UnescapedSemicolon => f.write_str("unescaped ; inside a value"),
+            Self::UnescapedCharacter(c) => write!(f, "unescaped {} inside a value", c),
+            Self::LowercaseKey(key) => write!(f, "field key {}: should be uppercase", key),
+            Self::UnknownField(key) => write!(f, "unknown field {}:", key),
+            Self::DuplicateField(key) => write!(f, "the {}: field appears more than once", key),
+            Self::MissingField(key) => write!(f, "missing the {}: field", key),
+            Self::InvalidAuthenticationType(value) => {
+                write!(f, "unknown authentication type {:?}", value)
+            }
+            Self::NonstandardAuthenticationType(value) => write!(
+                f,
+                "authentication type {:?} should be written as WPA, WEP, or nopass",
+                value
+            ),
+            Self::InvalidVisibility(value) => {
+                write!(f, "expected H:true or H:false, found H:{}", value)
+            }
+            Self::UnusedPassword => f.write_str("open networks do not have a password"),
+            Self::Validation(error) => Display::fmt(error, f),
+        }
+    }
+}
+
+impl Display for Diagnostic {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} at bytes {}..{}: {}",
+            self.severity, self.span.start, self.span.end, self.kind
+        )
+    }
+}
+
+/// A field of the payload, with the byte ranges of its key and raw value.
+struct Field<'a> {
+    key: &'a str,
+    key_span: Range<usize>,
+    raw_value: &'a str,
+    value_span: Range<usize>,
+}
+
+/// Check a `WIFI:` payload, such as the text of a third-party QR code, and report every problem found, in payload order, instead of stopping at the first one like [`WifiCredentials::parse`](crate::WifiCredentials::parse). An empty list means the payload is in the form produced by [`WifiCredentials::encode`](crate::WifiCredentials::encode).
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{lint, LintKind, Severity};
+///
+/// let diagnostics = lint("WIFI:S:example ssid;T:WPA;P:passphrase;with semicolon;H:false;;");
+/// assert_eq!(LintKind::UnescapedSemicolon, diagnostics[0].kind);
+/// assert_eq!(38..39, diagnostics[0].span);
+/// assert_eq!(Severity::Error, diagnostics[0].severity);
+/// assert!(lint("WIFI:S:example ssid;T:WPA;P:example password;H:false;;").is_empty());
+/// ```
+pub fn lint(payload: &str) -> Vec<Diagnostic> {
+    let mut diagnostics = Vec::new();
+    let mut report = |span: Range<usize>, severity, kind| {
+        diagnostics.push(Diagnostic {
+            span,
+            severity,
+            kind,
+        })
+    };
+
+    let prefix = payload.len() - trim_leading_noise(payload).len();
+    if prefix > 0 {
+        report(0..prefix, Severity::Warning, LintKind::LeadingCharacters);
+    }
+    let body = prefix + "WIFI:".len();
+    match payload.get(prefix..body) {
+        Some("WIFI:") => {}
+        Some(found) if found.eq_ignore_ascii_case("WIFI:") => report(
+            prefix..body - 1,
+            Severity::Warning,
+            LintKind::LowercasePrefix,
+        ),
+        _ => {
+            report(prefix..prefix, Severity::Error, LintKind::MissingPrefix);
+            return diagnostics;
+        }
+    }
+    let fields = split_fields(payload, body, &mut report);
+
+    let mut ssid = None;
+    let mut authentication_type = None;
+    let mut password = None;
+    let mut hidden = None;
+    for (index, field) in fields.iter().enumerate() {
+        if field.key_span.is_empty() && !field.value_span.is_empty() && index > 0 {
+            let semicolon = field.key_span.start - 1;
+            report(
+                semicolon..semicolon + 1,
+                Severity::Error,
+                LintKind::UnescapedSemicolon,
+            );
+            continue;
+        }
+        let slot = match field.key {
+            "S" | "s" => &mut ssid,
+            "T" | "t" => &mut authentication_type,
+            "P" | "p" => &mut password,
+            "H" | "h" => &mut hidden,
+            key => {
+                report(
+                    field.key_span.clone(),
+                    Severity::Warning,
+                    LintKind::UnknownField(String::from(key)),
+                );
+                continue;
+            }
+        };
+        if field.key.chars().all(|c| c.is_ascii_lowercase()) {
+            report(
+                field.key_span.clone(),
+                Severity::Warning,
+                LintKind::LowercaseKey(String::from(field.key)),
+            );
+        }
+        if slot.is_some() {
+            report(
+                field.key_span.clone(),
+                Severity::Error,
+                LintKind::DuplicateField(String::from(field.key)),
+            );
+            continue;
+        }
+        *slot = Some(field);
+        lint_escaping(field, &mut report);
+    }
+
+    let end = payload.len()..payload.len();
+    match ssid {
+        Some(field) => {
+            let ssid = unescape(field.raw_value);
+            let error = if ssid.is_empty() {
+                Some(ValidationError::EmptySsid)
+            } else if ssid.len() > 32 {
+                Some(ValidationError::SsidLength(ssid.len()))
+            } else {
+                None
+            };
+            if let Some(error) = error {
+                report(
+                    field.value_span.clone(),
+                    Severity::Error,
+                    LintKind::Validation(error),
+                );
+            }
+        }
+        None => report(end.clone(), Severity::Error, LintKind::MissingField("S")),
+    }
+
+    match authentication_type {
+        Some(field) => {
+            let name = unescape(field.raw_value);
+            match name.parse::<AuthenticationType>() {
+                Ok(authentication_type) => {
+                    if !["WPA", "WEP", "nopass"].contains(&name.as_str()) {
+                        report(
+                            field.value_span.clone(),
+                            Severity::Warning,
+                            LintKind::NonstandardAuthenticationType(name),
+                        );
+                    }
+                    lint_password(&authentication_type, password, end.clone(), &mut report);
+                }
+                Err(_) => report(
+                    field.value_span.clone(),
+                    Severity::Error,
+                    LintKind::InvalidAuthenticationType(name),
+                ),
+            }
+        }
+        None => report(end.clone(), Severity::Error, LintKind::MissingField("T")),
+    }
+
+    match hidden {
+        Some(field) if field.raw_value == "true" || field.raw_value == "false" => {}
+        Some(field) => report(
+            field.value_span.clone(),
+            Severity::Error,
+            LintKind::InvalidVisibility(unescape(field.raw_value)),
+        ),
+        None => report(end, Severity::Warning, LintKind::MissingField("H")),
+    }
+
+    diagnostics.sort_by_key(|diagnostic| diagnostic.span.start);
+    diagnostics
+}
+
+/// Split the payload from `body`, just after its prefix, into fields, reporting problems with how it is terminated.
+fn split_fields<'a>(
+    payload: &'a str,
+    body: usize,
+    report: &mut impl FnMut(Range<usize>, Severity, LintKind),
+) -> Vec<Field<'a>> {
+    let mut fields = Vec::new();
+    let mut start = body;
+    let mut escaped = false;
+    for (index, c) in payload
+        .char_indices()
+        .skip_while(|&(index, _)| index < body)
+    {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ';' if index == start => {
+                if index + 1 < payload.len() {
+                    report(
+                        index + 1..payload.len(),
+                        Severity::Error,
+                        LintKind::TrailingCharacters,
+                    );
+                }
+                return fields;
+            }
+            ';' => {
+                fields.push(field(payload, start..index));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if start < payload.len() {
+        fields.push(field(payload, start..payload.len()));
+    }
+    report(
+        payload.len()..payload.len(),
+        Severity::Error,
+        LintKind::MissingTerminator,
+    );
+    fields
+}
+
+fn field(payload: &str, span: Range<usize>) -> Field<'_> {
+    match payload[span.clone()].find(':') {
+        Some(colon) => Field {
+            key: &payload[span.start..span.start + colon],
+            key_span: span.start..span.start + colon,
+            raw_value: &payload[span.start + colon + 1..span.end],
+            value_span: span.start + colon + 1..span.end,
+        },
+        None => Field {
+            key: "",
+            key_span: span.start..span.start,
+            raw_value: &payload[span.clone()],
+            value_span: span,
+        },
+    }
+}
+
+/// Report the characters that the spec requires to be escaped but are not. Values wrapped in double quotes are skipped, since quoting is an alternative to escaping.
+fn lint_escaping(field: &Field<'_>, report: &mut impl FnMut(Range<usize>, Severity, LintKind)) {
+    let raw_value = field.raw_value;
+    if raw_value.len() >= 2 && raw_value.starts_with('"') && raw_value.ends_with('"') {
+        return;
+    }
+    let mut escaped = false;
+    for (index, c) in raw_value.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ':' | ',' | '"' => {
+                let offset = field.value_span.start + index;
+                report(
+                    offset..offset + 1,
+                    Severity::Warning,
+                    LintKind::UnescapedCharacter(c),
+                );
+            }
+            _ => {}
+        }
+    }
+}
+
+fn lint_password(
+    authentication_type: &AuthenticationType,
+    password: Option<&Field<'_>>,
+    end: Range<usize>,
+    report: &mut impl FnMut(Range<usize>, Severity, LintKind),
+) {
+    let field = match (authentication_type, password) {
+        (AuthenticationType::NoPassword, Some(field)) => {
+            return report(
+                field.key_span.start..field.value_span.end,
+                Severity::Warning,
+                LintKind::UnusedPassword,
+            );
+        }
+        (AuthenticationType::NoPassword, None) => return,
+        (_, Some(field)) => field,
+        (_, None) => return report(end, Severity::Error, LintKind::MissingField("P")),
+    };
+    let result = match authentication_type {
+        AuthenticationType::WEP(_) => {
+            AuthenticationTypeRef::WEP(&unescape(field.raw_value)).validate()
+        }
+        _ => parse_wpa_password(field.raw_value).validate(),
+    };
+    if let Err(error) = result {
+        report(
+            field.value_span.clone(),
+            Severity::Error,
+            LintKind::Validation(error),
+        );
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn kinds(payload: &str) -> Vec<(Range<usize>, LintKind)> {
+        lint(payload)
+            .into_iter()
+            .map(|diagnostic| (diagnostic.span, diagnostic.kind))
+            .collect()
+    }
+
+    #[test]
+    fn it_accepts_encoded_credentials() {
+        let payloads = [
+            "WIFI:S:test ssid;T:WEP;P:test password;H:false;;",
+            "WIFI:S:test ssid;T:nopass;H:false;;",
+            r#"WIFI:S:special_characters \"\;\,\:\\;T:WPA;P:"quoted, password";H:true;;"#,
+        ];
+        for payload in payloads {
+            assert_eq!(Vec::<Diagnostic>::new(), lint(payload), "{}", payload);
+        }
+    }
+
+    #[test]
+    fn it_reports_problems_with_byte_offsets() {
+        assert_eq!(
+            vec![
+                (8..9, LintKind::UnescapedCharacter(':')),
+                (11..12, LintKind::LowercaseKey(String::from("t"))),
+                (
+                    13..17,
+                    LintKind::NonstandardAuthenticationType(String::from("WPA2"))
+                ),
+                (
+                    20..25,
+                    LintKind::Validation(ValidationError::WpaPassphraseLength(5))
+                ),
+                (26..27, LintKind::UnknownField(String::from("E"))),
+                (32..32, LintKind::MissingTerminator),
+                (32..32, LintKind::MissingField("H")),
+            ],
+            kinds("WIFI:S:a:b;t:WPA2;P:short;E:PEAP")
+        );
+        assert_eq!(
+            vec![
+                (7..7, LintKind::Validation(ValidationError::EmptySsid)),
+                (8..9, LintKind::DuplicateField(String::from("S"))),
+                (24..34, LintKind::UnusedPassword),
+                (37..40, LintKind::InvalidVisibility(String::from("yes"))),
+                (42..47, LintKind::TrailingCharacters),
+            ],
+            kinds("WIFI:S:;S:ssid;T:nopass;P:password;H:yes;;extra")
+        );
+        assert_eq!(
+            vec![(27..27, LintKind::MissingField("P"))],
+            kinds("WIFI:S:ssid;T:WEP;H:false;;")
+        );
+        assert_eq!(
+            vec![(11..12, LintKind::UnescapedSemicolon)],
+            kinds("WIFI:S:semi;colon;T:WPA;P:password;H:false;;")
+        );
+    }
+
+    #[test]
+    fn it_warns_about_mangled_prefixes() {
+        assert_eq!(
+            vec![
+                (0..4, LintKind::LeadingCharacters),
+                (4..8, LintKind::LowercasePrefix),
+            ],
+            kinds("\u{feff} wifi:S:test ssid;T:nopass;H:false;;")
+        );
+        assert_eq!(
+            vec![
+                (0..1, LintKind::LeadingCharacters),
+                (1..1, LintKind::MissingPrefix),
+            ],
+            kinds("\nS:ssid;;")
+        );
+    }
+
+    #[test]
+    fn it_stops_without_a_prefix() {
+        let diagnostics = lint("S:ssid;;");
+        assert_eq!(1, diagnostics.len());
+        assert_eq!(
+            "error at bytes 0..0: payload does not start with WIFI:",
+            diagnostics[0].to_string()
+        );
+    }
+}
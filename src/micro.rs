@@ -0,0 +1,112 @@
+use crate::escaping;
+use crate::{
+    AuthenticationTypeRef, Error, EscapingMode, QRCodeError, QrCodeEcc, Secret, Visibility,
+    WifiCredentials,
+};
+
+use qrcode::{Color, EcLevel, QrCode, Version};
+
+/// Encode credentials as an M4 Micro QR code, a 17 by 17 module symbol with a single finder pattern, for labels that are too small for a regular QR code. Micro QR codes only need a quiet zone of 2 modules, rather than the 4 of regular QR codes.
+///
+/// An M4 symbol holds about 15 bytes, so this only works for open networks with SSIDs of up to a handful of characters. To make the most of that, the payload leaves out the optional `T:nopass;` and `H:false;` fields, e.g. `WIFI:S:iot;;`, which scanners read as a visible open network. Everything else fails with [`QRCodeError::DataTooLong`], as do requests for [`QrCodeEcc::High`], which Micro QR codes do not offer, so callers can fall back to a regular QR code. Many phone cameras do not read Micro QR codes, so they are best used with scanners known to support them.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{QrCodeEcc, WifiCredentials};
+///
+/// let matrix = wifi_qr_code::encode_as_micro_matrix(&WifiCredentials::open("iot"), QrCodeEcc::Low)
+///     .expect("Failed to encode example Micro QR code.");
+/// assert_eq!(17, matrix.len());
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// let matrix = wifi_qr_code::encode_as_micro_matrix(&wifi_credentials, QrCodeEcc::Low)
+///     .or_else(|_| wifi_qr_code::encode_as_matrix(&wifi_credentials, QrCodeEcc::Low))
+///     .expect("Failed to encode example QR code.");
+/// assert_eq!(33, matrix.len());
+/// ```
+pub fn encode_as_micro_matrix(
+    wifi_credentials: &WifiCredentials,
+    qr_code_error_checking: QrCodeEcc,
+) -> Result<Vec<Vec<bool>>, Error> {
+    wifi_credentials.validate()?;
+    let ec_level = match qr_code_error_checking {
+        QrCodeEcc::Low => EcLevel::L,
+        QrCodeEcc::Medium => EcLevel::M,
+        QrCodeEcc::Quartile => EcLevel::Q,
+        QrCodeEcc::High => return Err(Error::QrCode(QRCodeError::DataTooLong)),
+    };
+    let payload = compact_payload(wifi_credentials);
+    // Only M4 can hold the shortest payload in byte mode, and the smaller versions are never tried.
+    let qr_code = QrCode::with_version(payload.as_bytes(), Version::Micro(4), ec_level)
+        .map_err(|_| Error::QrCode(QRCodeError::DataTooLong))?;
+    let size = qr_code.width();
+    Ok((0..size)
+        .map(|y| (0..size).map(|x| qr_code[(x, y)] == Color::Dark).collect())
+        .collect())
+}
+
+/// The payload without the fields that scanners assume when they are missing.
+fn compact_payload(wifi_credentials: &WifiCredentials) -> Secret<String> {
+    let wifi_credentials = wifi_credentials.as_borrowed();
+    Secret::write_with(|mut writer| {
+        writer.write_str("WIFI:S:")?;
+        escaping::write_value(&mut writer, wifi_credentials.ssid, EscapingMode::Spec)?;
+        writer.write_char(';')?;
+        if wifi_credentials.authentication_type != AuthenticationTypeRef::NoPassword {
+            wifi_credentials
+                .authentication_type
+                .encode_to(&mut writer, EscapingMode::Spec)?;
+        }
+        if wifi_credentials.visibility == Visibility::Hidden {
+            writer.write_str("H:true;")?;
+        }
+        writer.write_char(';')
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_leaves_out_optional_fields() {
+        assert_eq!(
+            "WIFI:S:iot;;",
+            compact_payload(&WifiCredentials::open("iot"))
+        );
+        let wifi_credentials = WifiCredentials {
+            visibility: Visibility::Hidden,
+            ..WifiCredentials::open("iot")
+        };
+        assert_eq!("WIFI:S:iot;H:true;;", compact_payload(&wifi_credentials));
+        let wifi_credentials = WifiCredentials::wpa2("iot", "test password");
+        assert_eq!(
+            "WIFI:S:iot;T:WPA;P:test password;;",
+            compact_payload(&wifi_credentials)
+        );
+        let parsed = WifiCredentials::parse_lenient("WIFI:S:iot;;").unwrap();
+        assert_eq!(WifiCredentials::open("iot"), parsed);
+    }
+
+    #[test]
+    fn it_only_fits_short_open_networks() {
+        let matrix =
+            encode_as_micro_matrix(&WifiCredentials::open("test"), QrCodeEcc::Medium).unwrap();
+        assert_eq!(17, matrix.len());
+        assert!(matrix.iter().all(|row| row.len() == 17));
+        for (wifi_credentials, error_checking) in [
+            (WifiCredentials::open("test"), QrCodeEcc::High),
+            (WifiCredentials::open("test ssid"), QrCodeEcc::Medium),
+            (
+                WifiCredentials::wpa2("test", "test password"),
+                QrCodeEcc::Low,
+            ),
+        ] {
+            assert!(matches!(
+                encode_as_micro_matrix(&wifi_credentials, error_checking),
+                Err(Error::QrCode(QRCodeError::DataTooLong))
+            ));
+        }
+    }
+}
@@ -0,0 +1,78 @@
+use qrcode_generator::qrcodegen::QrCode;
+
+/// An iterator over the modules of a QR code, as returned by [`encode_as_modules`](crate::encode_as_modules). Each item is `(x, y, dark)`, row by row from the top left, for renderers such as plotters or LED matrices that draw one module at a time.
+pub struct Modules {
+    qr_code: QrCode,
+    index: usize,
+}
+
+impl Modules {
+    pub(crate) fn new(qr_code: QrCode) -> Self {
+        Modules { qr_code, index: 0 }
+    }
+
+    /// The width and height of the QR code in modules.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::QrCodeEcc;
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// let modules = wifi_qr_code::encode_as_modules(&wifi_credentials, QrCodeEcc::Medium)
+    ///     .expect("Failed to encode example modules.");
+    /// assert_eq!(modules.size() * modules.size(), modules.count());
+    /// ```
+    pub fn size(&self) -> usize {
+        self.qr_code.size() as usize
+    }
+}
+
+impl Iterator for Modules {
+    type Item = (usize, usize, bool);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let size = self.size();
+        if self.index >= size * size {
+            return None;
+        }
+        let (x, y) = (self.index % size, self.index / size);
+        self.index += 1;
+        Some((x, y, self.qr_code.get_module(x as i32, y as i32)))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        let remaining = self.size() * self.size() - self.index;
+        (remaining, Some(remaining))
+    }
+}
+
+impl ExactSizeIterator for Modules {}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AuthenticationType, QrCodeEcc, Visibility, WifiCredentials};
+
+    #[test]
+    fn it_iterates_the_same_modules_as_the_matrix() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        let matrix = wifi_credentials.qr(QrCodeEcc::Medium).matrix().unwrap();
+        let modules = crate::encode_as_modules(&wifi_credentials, QrCodeEcc::Medium).unwrap();
+        assert_eq!(matrix.len() * matrix.len(), modules.len());
+        let expected: Vec<_> = matrix
+            .iter()
+            .enumerate()
+            .flat_map(|(y, row)| row.iter().enumerate().map(move |(x, dark)| (x, y, *dark)))
+            .collect();
+        assert_eq!(expected, modules.collect::<Vec<_>>());
+    }
+}
@@ -0,0 +1,323 @@
+//! Publish the QR code for a network to an MQTT broker, along with [Home Assistant MQTT discovery](https://www.home-assistant.io/integrations/mqtt/#mqtt-discovery) messages, so a dashboard can show the current code as an image entity next to a sensor with the SSID.
+//!
+//! Every message is retained, so dashboards that connect later still get the code, and publishing new credentials, e.g. after the guest password was rotated, replaces it. The password is only published as part of the QR code image, never as text.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wifi_qr_code::mqtt::{self, HomeAssistant, MqttOptions};
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! let wifi_credentials = WifiCredentials::wpa2("example guest network", "example password");
+//! let messages = HomeAssistant::new("guest_wifi")
+//!     .messages(&wifi_credentials)
+//!     .expect("Failed to render the QR code.");
+//! mqtt::publish(MqttOptions::new("wifi-qr-code", "homeassistant.local", 1883), &messages)
+//!     .expect("Failed to publish the QR code.");
+//! ```
+
+use crate::{AuthenticationType, Error, QrCodeEcc, Visibility, WifiCredentials};
+
+use base64::Engine;
+use rumqttc::{Client, ClientError, ConnectionError, Event, Outgoing, Packet, QoS};
+use serde_json::json;
+use std::error;
+use std::fmt::{self, Display, Formatter};
+
+pub use rumqttc::MqttOptions;
+
+/// Errors when publishing to an MQTT broker.
+#[derive(Debug)]
+pub enum MqttError {
+    /// The QR code could not be rendered.
+    Render(Error),
+    /// A message could not be queued for publishing.
+    Client(ClientError),
+    /// The connection to the broker failed, or the broker refused it.
+    Connection(Box<ConnectionError>),
+}
+
+impl Display for MqttError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Render(error) => write!(f, "failed to render the QR code: {}", error),
+            Self::Client(error) => write!(f, "failed to publish: {}", error),
+            Self::Connection(error) => write!(f, "failed to connect to the broker: {}", error),
+        }
+    }
+}
+
+impl error::Error for MqttError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Render(error) => Some(error),
+            Self::Client(error) => Some(error),
+            Self::Connection(error) => Some(error.as_ref()),
+        }
+    }
+}
+
+impl From<Error> for MqttError {
+    fn from(error: Error) -> Self {
+        Self::Render(error)
+    }
+}
+
+impl From<ClientError> for MqttError {
+    fn from(error: ClientError) -> Self {
+        Self::Client(error)
+    }
+}
+
+impl From<ConnectionError> for MqttError {
+    fn from(error: ConnectionError) -> Self {
+        Self::Connection(Box::new(error))
+    }
+}
+
+/// A retained message to publish.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Message {
+    /// The topic to publish to.
+    pub topic: String,
+    /// The payload, which is UTF-8 text for every message built by this module.
+    pub payload: Vec<u8>,
+}
+
+/// How the network is announced to Home Assistant.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HomeAssistant {
+    /// The prefix Home Assistant listens to for discovery messages, `homeassistant` unless it was changed in the MQTT integration.
+    pub discovery_prefix: String,
+    /// The identifier of the device in Home Assistant, from which the entity ids are derived. It should only contain letters, digits, `_`, and `-`.
+    pub object_id: String,
+    /// The name of the device shown in Home Assistant.
+    pub name: String,
+    /// The topic under which the QR code and the network details are published.
+    pub base_topic: String,
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The width and height of the QR code image in pixels.
+    pub image_size: usize,
+}
+
+impl HomeAssistant {
+    /// Announce the network as a device with the object id, named "Guest Wi-Fi" and publishing under `wifi-qr-code/<object id>`, with a 512 pixel QR code using medium error correction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::mqtt::HomeAssistant;
+    ///
+    /// let home_assistant = HomeAssistant {
+    ///     name: String::from("Café Wi-Fi"),
+    ///     ..HomeAssistant::new("cafe_wifi")
+    /// };
+    /// assert_eq!("wifi-qr-code/cafe_wifi", home_assistant.base_topic);
+    /// ```
+    pub fn new(object_id: impl Into<String>) -> Self {
+        let object_id = object_id.into();
+        HomeAssistant {
+            discovery_prefix: String::from("homeassistant"),
+            base_topic: format!("wifi-qr-code/{}", object_id),
+            object_id,
+            name: String::from("Guest Wi-Fi"),
+            qr_code_error_checking: QrCodeEcc::Medium,
+            image_size: 512,
+        }
+    }
+
+    /// The messages to publish for the credentials: the discovery configuration of an image entity and an SSID sensor, the QR code as a base64 encoded PNG, and the network details as JSON attributes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::mqtt::HomeAssistant;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let messages = HomeAssistant::new("guest_wifi")
+    ///     .messages(&wifi_credentials)
+    ///     .expect("Failed to render the QR code.");
+    /// assert_eq!("homeassistant/image/guest_wifi/qr_code/config", messages[0].topic);
+    /// assert_eq!("wifi-qr-code/guest_wifi/attributes", messages[3].topic);
+    /// assert_eq!(
+    ///     br#"{"hidden":false,"security":"WPA","ssid":"example ssid"}"#.to_vec(),
+    ///     messages[3].payload
+    /// );
+    /// ```
+    pub fn messages(&self, wifi_credentials: &WifiCredentials) -> Result<Vec<Message>, Error> {
+        let mut png = Vec::new();
+        wifi_credentials
+            .qr(self.qr_code_error_checking)
+            .size(self.image_size)
+            .png(&mut png)?;
+        let qr_code_topic = format!("{}/qr_code", self.base_topic);
+        let attributes_topic = format!("{}/attributes", self.base_topic);
+        let device = json!({
+            "identifiers": [self.object_id],
+            "name": self.name,
+        });
+        let security = match wifi_credentials.authentication_type {
+            AuthenticationType::WEP(_) => "WEP",
+            AuthenticationType::WPA(_) | AuthenticationType::WpaPsk(_) => "WPA",
+            AuthenticationType::NoPassword => "nopass",
+        };
+        Ok(vec![
+            Message {
+                topic: format!(
+                    "{}/image/{}/qr_code/config",
+                    self.discovery_prefix, self.object_id
+                ),
+                payload: json!({
+                    "name": "QR code",
+                    "unique_id": format!("{}_qr_code", self.object_id),
+                    "image_topic": qr_code_topic,
+                    "image_encoding": "b64",
+                    "content_type": "image/png",
+                    "json_attributes_topic": attributes_topic,
+                    "device": device,
+                })
+                .to_string()
+                .into_bytes(),
+            },
+            Message {
+                topic: format!(
+                    "{}/sensor/{}/ssid/config",
+                    self.discovery_prefix, self.object_id
+                ),
+                payload: json!({
+                    "name": "SSID",
+                    "unique_id": format!("{}_ssid", self.object_id),
+                    "icon": "mdi:wifi",
+                    "state_topic": attributes_topic,
+                    "value_template": "{{ value_json.ssid }}",
+                    "json_attributes_topic": attributes_topic,
+                    "device": device,
+                })
+                .to_string()
+                .into_bytes(),
+            },
+            Message {
+                topic: qr_code_topic,
+                payload: base64::engine::general_purpose::STANDARD
+                    .encode(png)
+                    .into_bytes(),
+            },
+            Message {
+                topic: attributes_topic,
+                payload: json!({
+                    "ssid": wifi_credentials.ssid,
+                    "security": security,
+                    "hidden": wifi_credentials.visibility == Visibility::Hidden,
+                })
+                .to_string()
+                .into_bytes(),
+            },
+        ])
+    }
+}
+
+/// Connect to the broker, publish the messages as retained messages with at least once delivery, and disconnect once the broker has acknowledged all of them. The packet size limit of the options is raised if needed so that large QR code images fit.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wifi_qr_code::mqtt::{self, HomeAssistant, MqttOptions};
+/// use wifi_qr_code::WifiCredentials;
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// let messages = HomeAssistant::new("guest_wifi")
+///     .messages(&wifi_credentials)
+///     .expect("Failed to render the QR code.");
+/// let mut options = MqttOptions::new("wifi-qr-code", "homeassistant.local", 1883);
+/// options.set_credentials("mqtt user", "mqtt password");
+/// mqtt::publish(options, &messages).expect("Failed to publish the QR code.");
+/// ```
+pub fn publish(mut options: MqttOptions, messages: &[Message]) -> Result<(), MqttError> {
+    let largest = messages
+        .iter()
+        .map(|message| message.topic.len() + message.payload.len() + 16)
+        .max()
+        .unwrap_or(0);
+    if largest > options.max_packet_size() {
+        options.set_max_packet_size(largest, largest);
+    }
+    // The request queue holds every message and the disconnect, so queueing never blocks before the connection is polled.
+    let (client, mut connection) = Client::new(options, messages.len() + 1);
+    for message in messages {
+        client.publish(
+            message.topic.as_str(),
+            QoS::AtLeastOnce,
+            true,
+            message.payload.clone(),
+        )?;
+    }
+    let mut acknowledged = 0;
+    let mut disconnecting = messages.is_empty();
+    if disconnecting {
+        client.disconnect()?;
+    }
+    for event in connection.iter() {
+        match event? {
+            Event::Incoming(Packet::PubAck(_)) => {
+                acknowledged += 1;
+                if acknowledged == messages.len() && !disconnecting {
+                    client.disconnect()?;
+                    disconnecting = true;
+                }
+            }
+            Event::Outgoing(Outgoing::Disconnect) => break,
+            _ => {}
+        }
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_announces_the_qr_code_to_home_assistant() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test \"ssid\""),
+            authentication_type: AuthenticationType::WEP("test password".into()),
+            visibility: Visibility::Hidden,
+        };
+        let home_assistant = HomeAssistant {
+            discovery_prefix: String::from("ha"),
+            ..HomeAssistant::new("test_wifi")
+        };
+        let messages = home_assistant.messages(&wifi_credentials).unwrap();
+        let topics: Vec<_> = messages
+            .iter()
+            .map(|message| message.topic.as_str())
+            .collect();
+        assert_eq!(
+            vec![
+                "ha/image/test_wifi/qr_code/config",
+                "ha/sensor/test_wifi/ssid/config",
+                "wifi-qr-code/test_wifi/qr_code",
+                "wifi-qr-code/test_wifi/attributes",
+            ],
+            topics
+        );
+
+        let image: serde_json::Value = serde_json::from_slice(&messages[0].payload).unwrap();
+        assert_eq!("wifi-qr-code/test_wifi/qr_code", image["image_topic"]);
+        assert_eq!("b64", image["image_encoding"]);
+        assert_eq!("test_wifi", image["device"]["identifiers"][0]);
+        let png = base64::engine::general_purpose::STANDARD
+            .decode(&messages[2].payload)
+            .unwrap();
+        assert!(png.starts_with(b"\x89PNG"));
+        assert_eq!(
+            br#"{"hidden":true,"security":"WEP","ssid":"test \"ssid\""}"#.to_vec(),
+            messages[3].payload
+        );
+        assert!(messages
+            .iter()
+            .all(|message| !String::from_utf8_lossy(&message.payload).contains("test password")));
+    }
+}
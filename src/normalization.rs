@@ -0,0 +1,74 @@
+//! Unicode normalization of SSIDs and passwords.
+
+use crate::{AuthenticationType, Secret, WifiCredentials};
+
+use unicode_normalization::UnicodeNormalization;
+
+impl WifiCredentials {
+    /// Normalize the SSID and password to Unicode Normalization Form C (NFC).
+    ///
+    /// The same accented text can be written with precomposed characters (`é` as U+00E9) or decomposed characters (`e` followed by U+0301, a combining accent), and different operating systems and keyboards produce different forms. Access points compare SSIDs and passwords byte-for-byte, so credentials typed on one machine may not match the network configured from another.
+    ///
+    /// Normalizing to NFC (the precomposed form) matches what most keyboards produce, but it is only correct if the access point was also configured with NFC text. If the network was deliberately configured with decomposed characters, normalizing will produce a code that fails to join, which is why this is opt-in rather than applied automatically.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    ///
+    /// let mut wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("Cafe\u{301}"),
+    ///     authentication_type: AuthenticationType::NoPassword,
+    ///     visibility: Visibility::Visible,
+    /// };
+    /// wifi_credentials.normalize_nfc();
+    /// assert_eq!("Caf\u{e9}", wifi_credentials.ssid);
+    /// ```
+    pub fn normalize_nfc(&mut self) {
+        self.ssid = self.ssid.nfc().collect();
+        match &mut self.authentication_type {
+            AuthenticationType::WEP(password) | AuthenticationType::WPA(password) => {
+                *password = Secret::write_with(|writer| {
+                    password.nfc().try_for_each(|c| writer.write_char(c))
+                });
+            }
+            AuthenticationType::WpaPsk(_) | AuthenticationType::NoPassword => {}
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+    const COMPOSED: &str = "Caf\u{e9} Ol\u{e9}";
+    const DECOMPOSED: &str = "Cafe\u{301} Ole\u{301}";
+
+    #[test]
+    fn it_composes_decomposed_text() {
+        let mut wifi_credentials = WifiCredentials {
+            ssid: String::from(DECOMPOSED),
+            authentication_type: AuthenticationType::WPA(DECOMPOSED.into()),
+            visibility: Visibility::Visible,
+        };
+        wifi_credentials.normalize_nfc();
+        assert_eq!(
+            format!("WIFI:S:{};T:WPA;P:{};H:false;;", COMPOSED, COMPOSED),
+            wifi_credentials.encode()
+        );
+    }
+
+    #[test]
+    fn it_leaves_composed_text_unchanged() {
+        let mut wifi_credentials = WifiCredentials {
+            ssid: String::from(COMPOSED),
+            authentication_type: AuthenticationType::WEP(COMPOSED.into()),
+            visibility: Visibility::Visible,
+        };
+        wifi_credentials.normalize_nfc();
+        assert_eq!(
+            format!("WIFI:S:{};T:WEP;P:{};H:false;;", COMPOSED, COMPOSED),
+            wifi_credentials.encode()
+        );
+    }
+}
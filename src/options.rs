@@ -0,0 +1,203 @@
+use crate::{Error, QrCodeEcc, QrPayload};
+
+use qrcode_generator::qrcodegen::{Mask, QrCode, QrSegment, Version};
+
+/// Options for generating the QR code symbol, for the `encode_as_*_with_options` functions. The defaults match the other `encode_as_*` functions with [`QrCodeEcc::Medium`], so struct-update syntax only needs the options that differ.
+///
+/// Only regular QR codes are generated here. The largest Micro QR version, M4, stores at most 15 bytes, which only fits the shortened payload of an open network with a very short SSID, such as the 10 byte `WIFI:S:a;;`. Those are generated by `encode_as_micro_matrix` with the `micro` feature.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{EncodeOptions, QrCodeEcc};
+///
+/// let options = EncodeOptions {
+///     error_correction: QrCodeEcc::Quartile,
+///     min_version: 5,
+///     ..EncodeOptions::default()
+/// };
+/// assert_eq!(40, options.max_version);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeOptions {
+    /// The minimum error correction level. As with the other `encode_as_*` functions, the level is raised when that still fits in the same version.
+    pub error_correction: QrCodeEcc,
+    /// The smallest QR code version to generate, from 1 to 40. A version `v` symbol is `17 + 4 * v` modules wide, so setting this forces short payloads to the same physical density as longer ones, e.g. for a sheet of codes printed at the same size.
+    pub min_version: u8,
+    /// The largest QR code version to generate, from 1 to 40. Payloads that do not fit are rejected with [`QRCodeError::DataTooLong`](crate::QRCodeError::DataTooLong). Setting both bounds to the same version pins it.
+    pub max_version: u8,
+    /// Use the highest error correction level at which the payload still fits within `max_version`, at the cost of a denser code, rather than raising the level only while it fits in the smallest version. `error_correction` is still the minimum.
+    pub maximize_error_correction: bool,
+    /// How the mask pattern, which breaks up areas of the symbol that would otherwise be hard to scan, is chosen.
+    pub mask: MaskSelection,
+}
+
+/// How the mask pattern of the QR code is chosen.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Default)]
+pub enum MaskSelection {
+    /// Try all eight patterns and use the one the QR code standard scores as easiest to scan. The choice only depends on the payload and the other options, so the same credentials always produce the same symbol.
+    #[default]
+    Automatic,
+    /// Always use the given pattern, from 0 to 7. This keeps the symbol identical across library versions that might score patterns differently, or can be tuned for display media, such as e-ink panels, that render some patterns more cleanly than others.
+    Pattern(u8),
+}
+
+impl Default for EncodeOptions {
+    fn default() -> Self {
+        EncodeOptions {
+            error_correction: QrCodeEcc::Medium,
+            min_version: Version::MIN.value(),
+            max_version: Version::MAX.value(),
+            maximize_error_correction: false,
+            mask: MaskSelection::Automatic,
+        }
+    }
+}
+
+/// Generate the QR code symbol for the payload, honouring the options.
+pub(crate) fn generate(payload: &impl QrPayload, options: &EncodeOptions) -> Result<QrCode, Error> {
+    generate_text(&crate::encode_payload(payload)?, options)
+}
+
+/// Generate the QR code symbol for an already validated and encoded payload.
+pub(crate) fn generate_text(text: &str, options: &EncodeOptions) -> Result<QrCode, Error> {
+    let versions = Version::MIN.value()..=Version::MAX.value();
+    if !versions.contains(&options.min_version)
+        || !versions.contains(&options.max_version)
+        || options.min_version > options.max_version
+    {
+        return Err(Error::VersionRange {
+            min: options.min_version,
+            max: options.max_version,
+        });
+    }
+    let mask = match options.mask {
+        MaskSelection::Automatic => None,
+        MaskSelection::Pattern(pattern @ 0..=7) => Some(Mask::new(pattern)),
+        MaskSelection::Pattern(pattern) => return Err(Error::MaskPattern(pattern)),
+    };
+    let segments = QrSegment::make_segments(text);
+    let encode = |error_correction| {
+        QrCode::encode_segments_advanced(
+            &segments,
+            error_correction,
+            Version::new(options.min_version),
+            Version::new(options.max_version),
+            mask,
+            true,
+        )
+    };
+    if options.maximize_error_correction {
+        let highest = [QrCodeEcc::High, QrCodeEcc::Quartile, QrCodeEcc::Medium]
+            .iter()
+            .filter(|error_correction| **error_correction > options.error_correction)
+            .find_map(|error_correction| encode(*error_correction).ok());
+        if let Some(qr_code) = highest {
+            return Ok(qr_code);
+        }
+    }
+    encode(options.error_correction).map_err(|_| Error::QrCode(crate::QRCodeError::DataTooLong))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+    fn wifi_credentials() -> WifiCredentials {
+        WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Visible,
+        }
+    }
+
+    #[test]
+    fn it_honours_the_version_bounds() {
+        let options = EncodeOptions {
+            min_version: 5,
+            ..EncodeOptions::default()
+        };
+        let qr_code = generate(&wifi_credentials(), &options).expect("Payload should fit.");
+        assert_eq!(5, qr_code.version().value());
+
+        let options = EncodeOptions {
+            min_version: 2,
+            max_version: 2,
+            error_correction: QrCodeEcc::High,
+            ..EncodeOptions::default()
+        };
+        match generate(&wifi_credentials(), &options) {
+            Err(Error::QrCode(crate::QRCodeError::DataTooLong)) => {}
+            _ => panic!("Expected the payload not to fit in version 2."),
+        }
+    }
+
+    #[test]
+    fn it_maximizes_error_correction_within_the_version_bounds() {
+        let options = EncodeOptions {
+            error_correction: QrCodeEcc::Low,
+            max_version: 6,
+            maximize_error_correction: true,
+            ..EncodeOptions::default()
+        };
+        let qr_code = generate(&wifi_credentials(), &options).expect("Payload should fit.");
+        assert_eq!(QrCodeEcc::High, qr_code.error_correction_level());
+        assert_eq!(6, qr_code.version().value());
+
+        let options = EncodeOptions {
+            max_version: 4,
+            ..options
+        };
+        let qr_code = generate(&wifi_credentials(), &options).expect("Payload should fit.");
+        assert_eq!(QrCodeEcc::Medium, qr_code.error_correction_level());
+        assert_eq!(4, qr_code.version().value());
+    }
+
+    #[test]
+    fn it_honours_the_mask_selection() {
+        let automatic = generate(&wifi_credentials(), &EncodeOptions::default())
+            .expect("Payload should fit.")
+            .mask()
+            .value();
+        for pattern in 0..8 {
+            let options = EncodeOptions {
+                mask: MaskSelection::Pattern(pattern),
+                ..EncodeOptions::default()
+            };
+            let qr_code = generate(&wifi_credentials(), &options).expect("Payload should fit.");
+            assert_eq!(pattern, qr_code.mask().value());
+        }
+        assert_eq!(
+            automatic,
+            generate(&wifi_credentials(), &EncodeOptions::default())
+                .expect("Payload should fit.")
+                .mask()
+                .value()
+        );
+
+        let options = EncodeOptions {
+            mask: MaskSelection::Pattern(8),
+            ..EncodeOptions::default()
+        };
+        match generate(&wifi_credentials(), &options) {
+            Err(Error::MaskPattern(8)) => {}
+            _ => panic!("Expected mask pattern 8 to be rejected."),
+        }
+    }
+
+    #[test]
+    fn it_rejects_invalid_version_ranges() {
+        for (min, max) in [(0, 40), (1, 41), (10, 9)] {
+            let options = EncodeOptions {
+                min_version: min,
+                max_version: max,
+                ..EncodeOptions::default()
+            };
+            match generate(&wifi_credentials(), &options) {
+                Err(Error::VersionRange { .. }) => {}
+                _ => panic!("Expected {}..={} to be rejected.", min, max),
+            }
+        }
+    }
+}
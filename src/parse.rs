@@ -0,0 +1,642 @@
+use crate::{AuthenticationType, CredentialField, Secret, Visibility, WifiCredentials};
+
+use std::convert::TryFrom;
+use std::error::Error;
+use std::fmt::{self, Display, Formatter};
+use std::str::FromStr;
+
+/// Errors when parsing a `WIFI:` payload back into credentials.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ParseError {
+    /// The payload does not start with `WIFI:`.
+    MissingPrefix,
+    /// The payload does not end with the `;;` that terminates the last field and the payload.
+    MissingTerminator,
+    /// The payload continues after the terminating `;;`.
+    TrailingCharacters,
+    /// A field appeared where a different one was expected, or the same field appeared twice. Contains the expected and found field keys.
+    UnexpectedField {
+        /// The key of the field that should have appeared.
+        expected: &'static str,
+        /// The key of the field that did appear.
+        found: String,
+    },
+    /// A field follows the `H:` field. Contains the key of the field.
+    UnknownField(String),
+    /// A required field is missing. Contains the key of the field.
+    MissingField(&'static str),
+    /// The `T:` field is not `WPA`, `WEP`, or `nopass`.
+    InvalidAuthenticationType(String),
+    /// The `H:` field is not `true` or `false`.
+    InvalidVisibility(String),
+    /// The same field appeared twice in a payload parsed with [`WifiCredentials::parse_lenient`]. Contains the key of the field.
+    DuplicateField(String),
+}
+
+impl Display for ParseError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::MissingPrefix => f.write_str("payload does not start with WIFI:"),
+            Self::MissingTerminator => f.write_str("payload does not end with ;;"),
+            Self::TrailingCharacters => f.write_str("payload continues after the terminating ;;"),
+            Self::UnexpectedField { expected, found } => {
+                write!(f, "expected the {}: field, found {}:", expected, found)
+            }
+            Self::UnknownField(key) => write!(f, "unknown field {}:", key),
+            Self::MissingField(key) => write!(f, "missing the {}: field", key),
+            Self::InvalidAuthenticationType(value) => {
+                write!(f, "unknown authentication type {:?}", value)
+            }
+            Self::InvalidVisibility(value) => {
+                write!(f, "expected H:true or H:false, found H:{}", value)
+            }
+            Self::DuplicateField(key) => write!(f, "the {}: field appears more than once", key),
+        }
+    }
+}
+
+impl Error for ParseError {}
+
+/// A `K:value` field of a payload, with the value still escaped.
+pub(crate) struct Field<'a> {
+    pub(crate) key: &'a str,
+    pub(crate) raw_value: &'a str,
+}
+
+impl<'a> Field<'a> {
+    fn new(field: &'a str) -> Self {
+        let (key, raw_value) = field.split_once(':').unwrap_or((field, ""));
+        Field { key, raw_value }
+    }
+}
+
+impl WifiCredentials {
+    /// Parse a `WIFI:` payload, such as the text of a scanned QR code, back into credentials. The payload must be in the form produced by [`WifiCredentials::encode`]: fields in `S`, `T`, `P`, `H` order, with the `P` field omitted for open networks. The `WIFI:` prefix may be in any case and preceded by whitespace or a byte order mark, since OCR pipelines and chat apps often mangle the start of scanned text. Quoted values (as produced by [`EscapingMode::ZxingQuoted`](crate::EscapingMode::ZxingQuoted)) are unquoted.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::parse("WIFI:S:example ssid;T:WPA;P:example password;H:true;;")
+    ///     .expect("Failed to parse example payload.");
+    /// assert_eq!("example ssid", wifi_credentials.ssid);
+    /// ```
+    pub fn parse(payload: &str) -> Result<Self, ParseError> {
+        let fields = split_fields(payload, false)?;
+        let mut fields = fields.iter();
+        let mut next_field = |expected: &'static str| match fields.next() {
+            Some(field) if field.key == expected => Ok(field.raw_value),
+            Some(field) => Err(ParseError::UnexpectedField {
+                expected,
+                found: String::from(field.key),
+            }),
+            None => Err(ParseError::MissingField(expected)),
+        };
+
+        let ssid = unescape(next_field("S")?);
+        let authentication_type = match next_field("T")? {
+            "WPA" => parse_wpa_password(next_field("P")?),
+            "WEP" => AuthenticationType::WEP(Secret::new(unescape(next_field("P")?))),
+            "nopass" => AuthenticationType::NoPassword,
+            other => return Err(ParseError::InvalidAuthenticationType(unescape(other))),
+        };
+        let visibility = parse_visibility(next_field("H")?)?;
+        if let Some(field) = fields.next() {
+            return Err(ParseError::UnknownField(String::from(field.key)));
+        }
+
+        Ok(WifiCredentials {
+            ssid,
+            authentication_type,
+            visibility,
+        })
+    }
+}
+
+impl WifiCredentials {
+    /// Parse a `WIFI:` payload like [`WifiCredentials::parse`], but accept the variations produced by other generators:
+    ///
+    /// * field keys in any case, and fields in any order,
+    /// * a missing terminating `;`, or both of them,
+    /// * an omitted `H:` field, which means the network is visible, and an omitted `T:` field for open networks,
+    /// * authentication type names such as `wpa2`, as accepted by [`AuthenticationType::from_str`],
+    /// * fields this crate does not know about, which are ignored, and surrounding whitespace,
+    /// * SSIDs written as hex digits, as Android does for names that are not plain ASCII, when they decode to non-ASCII UTF-8 text. Hex that decodes to ASCII or to invalid UTF-8 is more likely a literal name, such as `cafe`, and is kept as written.
+    ///
+    /// Fields that appear twice are still rejected, since there is no way to tell which one the generator meant.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::parse_lenient("wifi:t:WPA2;p:example password;s:example ssid")
+    ///     .expect("Failed to parse example payload.");
+    /// assert_eq!(WifiCredentials::wpa2("example ssid", "example password"), wifi_credentials);
+    /// ```
+    pub fn parse_lenient(payload: &str) -> Result<Self, ParseError> {
+        let fields = split_fields(payload.trim(), true)?;
+        let mut ssid = None;
+        let mut authentication_type = None;
+        let mut password = None;
+        let mut hidden = None;
+        for field in &fields {
+            let slot = match field.key.to_ascii_uppercase().as_str() {
+                "S" => &mut ssid,
+                "T" => &mut authentication_type,
+                "P" => &mut password,
+                "H" => &mut hidden,
+                _ => continue,
+            };
+            if slot.replace(field.raw_value).is_some() {
+                return Err(ParseError::DuplicateField(String::from(field.key)));
+            }
+        }
+
+        let ssid = ssid.ok_or(ParseError::MissingField("S"))?;
+        let ssid = decode_hex_ssid(ssid).unwrap_or_else(|| unescape(ssid));
+        let authentication_type = match (authentication_type, password) {
+            (None, None) => AuthenticationType::NoPassword,
+            (None, Some(_)) => return Err(ParseError::MissingField("T")),
+            (Some(name), password) => match unescape(name).parse()? {
+                AuthenticationType::WPA(_) => {
+                    parse_wpa_password(password.ok_or(ParseError::MissingField("P"))?)
+                }
+                AuthenticationType::WEP(_) => AuthenticationType::WEP(Secret::new(unescape(
+                    password.ok_or(ParseError::MissingField("P"))?,
+                ))),
+                authentication_type => authentication_type,
+            },
+        };
+        let visibility = match hidden.map(str::to_ascii_lowercase).as_deref() {
+            None | Some("") => Visibility::Visible,
+            Some(hidden) => parse_visibility(hidden)?,
+        };
+
+        Ok(WifiCredentials {
+            ssid,
+            authentication_type,
+            visibility,
+        })
+    }
+}
+
+/// Credentials parsed by [`WifiCredentials::parse_recovering`], along with the fields that had to be unescaped twice.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct RecoveredCredentials {
+    /// The parsed credentials.
+    pub wifi_credentials: WifiCredentials,
+    /// The fields that were double-escaped. This is empty if the payload was parsed as written.
+    pub unescaped_twice: Vec<CredentialField>,
+}
+
+impl WifiCredentials {
+    /// Parse a `WIFI:` payload like [`WifiCredentials::parse_lenient`], detecting and undoing double escaping. Some generators escape values twice, or pass the payload through a serializer that doubles every backslash, so `\;` ends a field early and passwords come back with stray backslashes.
+    ///
+    /// When the payload contains doubled backslashes, and parsing it as written fails or leaves escape sequences such as `\;` in the SSID or password, one layer of escaping is removed from the whole payload before parsing it again. The fields that were affected are reported so tools can tell the user the code is malformed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{CredentialField, WifiCredentials};
+    ///
+    /// let recovered = WifiCredentials::parse_recovering(r"WIFI:S:example ssid;T:WPA;P:example\\;password;H:false;;")
+    ///     .expect("Failed to parse example payload.");
+    /// assert_eq!(
+    ///     WifiCredentials::wpa2("example ssid", "example;password"),
+    ///     recovered.wifi_credentials
+    /// );
+    /// assert_eq!(vec![CredentialField::Password], recovered.unescaped_twice);
+    /// ```
+    pub fn parse_recovering(payload: &str) -> Result<RecoveredCredentials, ParseError> {
+        let parsed = WifiCredentials::parse_lenient(payload);
+        let suspicious = match &parsed {
+            Ok(wifi_credentials) => has_stray_escape(wifi_credentials),
+            Err(_) => true,
+        };
+        if payload.contains(r"\\") && suspicious {
+            let unescaped = unescape(payload);
+            if let Ok(wifi_credentials) = WifiCredentials::parse_lenient(&unescaped) {
+                let unescaped_twice = split_fields(unescaped.trim(), true)?
+                    .iter()
+                    .filter(|field| field.raw_value.contains('\\'))
+                    .filter_map(|field| match field.key.to_ascii_uppercase().as_str() {
+                        "S" => Some(CredentialField::Ssid),
+                        "P" => Some(CredentialField::Password),
+                        _ => None,
+                    })
+                    .collect();
+                return Ok(RecoveredCredentials {
+                    wifi_credentials,
+                    unescaped_twice,
+                });
+            }
+        }
+        parsed.map(|wifi_credentials| RecoveredCredentials {
+            wifi_credentials,
+            unescaped_twice: Vec::new(),
+        })
+    }
+}
+
+/// Whether the SSID or password contain what looks like a leftover escape sequence, such as `\;` or a trailing backslash.
+fn has_stray_escape(wifi_credentials: &WifiCredentials) -> bool {
+    let password = match &wifi_credentials.authentication_type {
+        AuthenticationType::WEP(password) | AuthenticationType::WPA(password) => password,
+        AuthenticationType::WpaPsk(_) | AuthenticationType::NoPassword => "",
+    };
+    [wifi_credentials.ssid.as_str(), password]
+        .iter()
+        .any(|value| {
+            value.ends_with('\\')
+                || [r"\\", r"\;", r"\,", r"\:", r#"\""#]
+                    .iter()
+                    .any(|escape| value.contains(escape))
+        })
+}
+
+impl FromStr for WifiCredentials {
+    type Err = ParseError;
+
+    fn from_str(payload: &str) -> Result<Self, Self::Err> {
+        WifiCredentials::parse(payload)
+    }
+}
+
+/// Parse the name of an authentication type, ignoring case: `WPA`, `WPA2`, or `WPA3` (which share the `T:WPA` field), `WEP`, or `nopass`. The password is left empty, to be filled in with [`AuthenticationType::with_password`].
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{AuthenticationType, ParseError};
+///
+/// assert_eq!(Ok(AuthenticationType::NoPassword), "NoPass".parse());
+/// assert_eq!(
+///     Err(ParseError::InvalidAuthenticationType(String::from("WPA4"))),
+///     "WPA4".parse::<AuthenticationType>()
+/// );
+/// ```
+impl FromStr for AuthenticationType {
+    type Err = ParseError;
+
+    fn from_str(name: &str) -> Result<Self, Self::Err> {
+        match name.to_ascii_lowercase().as_str() {
+            "wpa" | "wpa2" | "wpa3" => Ok(AuthenticationType::WPA(Default::default())),
+            "wep" => Ok(AuthenticationType::WEP(Default::default())),
+            "nopass" => Ok(AuthenticationType::NoPassword),
+            _ => Err(ParseError::InvalidAuthenticationType(String::from(name))),
+        }
+    }
+}
+
+impl TryFrom<&str> for AuthenticationType {
+    type Error = ParseError;
+
+    fn try_from(name: &str) -> Result<Self, Self::Error> {
+        name.parse()
+    }
+}
+
+/// WPA passwords of exactly 64 unquoted hex digits are pre-shared keys, since passphrases are at most 63 characters.
+pub(crate) fn parse_wpa_password(raw_value: &str) -> AuthenticationType {
+    match AuthenticationType::wpa_psk_from_hex(raw_value) {
+        Ok(authentication_type) => authentication_type,
+        Err(_) => AuthenticationType::WPA(Secret::new(unescape(raw_value))),
+    }
+}
+
+/// Decode an unquoted SSID of hex digits, if it decodes to UTF-8 text with at least one non-ASCII character.
+fn decode_hex_ssid(raw_value: &str) -> Option<String> {
+    if raw_value.is_empty()
+        || !raw_value.len().is_multiple_of(2)
+        || !raw_value.bytes().all(|byte| byte.is_ascii_hexdigit())
+    {
+        return None;
+    }
+    let bytes = raw_value
+        .as_bytes()
+        .chunks(2)
+        .map(|digits| u8::from_str_radix(std::str::from_utf8(digits).ok()?, 16).ok())
+        .collect::<Option<Vec<u8>>>()?;
+    String::from_utf8(bytes)
+        .ok()
+        .filter(|ssid| !ssid.is_ascii())
+}
+
+pub(crate) fn parse_visibility(raw_value: &str) -> Result<Visibility, ParseError> {
+    match raw_value {
+        "true" => Ok(Visibility::Hidden),
+        "false" => Ok(Visibility::Visible),
+        other => Err(ParseError::InvalidVisibility(unescape(other))),
+    }
+}
+
+/// Remove the whitespace and byte order mark that OCR pipelines and chat apps leave in front of scanned payloads.
+pub(crate) fn trim_leading_noise(payload: &str) -> &str {
+    payload.trim_start_matches(|c: char| c.is_whitespace() || c == '\u{feff}')
+}
+
+/// Split the payload into its fields, stopping at the empty field that terminates it. Leading whitespace and byte order marks are skipped, and the prefix may be in any case. When `lenient`, the payload may also end without terminating the last field.
+pub(crate) fn split_fields(payload: &str, lenient: bool) -> Result<Vec<Field<'_>>, ParseError> {
+    let payload = trim_leading_noise(payload);
+    let body = match payload.get(..5) {
+        Some(prefix) if prefix.eq_ignore_ascii_case("WIFI:") => &payload[5..],
+        _ => return Err(ParseError::MissingPrefix),
+    };
+    let mut fields = Vec::new();
+    let mut start = 0;
+    let mut escaped = false;
+    for (index, c) in body.char_indices() {
+        match c {
+            _ if escaped => escaped = false,
+            '\\' => escaped = true,
+            ';' if index == start => {
+                return if index + 1 == body.len() {
+                    Ok(fields)
+                } else {
+                    Err(ParseError::TrailingCharacters)
+                };
+            }
+            ';' => {
+                fields.push(Field::new(&body[start..index]));
+                start = index + 1;
+            }
+            _ => {}
+        }
+    }
+    if !lenient {
+        return Err(ParseError::MissingTerminator);
+    }
+    if start < body.len() {
+        fields.push(Field::new(&body[start..]));
+    }
+    Ok(fields)
+}
+
+/// Remove backslash escapes, and the double quotes that some generators wrap values in.
+pub(crate) fn unescape(raw_value: &str) -> String {
+    let raw_value = match raw_value
+        .strip_prefix('"')
+        .and_then(|value| value.strip_suffix('"'))
+    {
+        Some(quoted) if !quoted.ends_with('\\') || quoted.ends_with("\\\\") => quoted,
+        _ => raw_value,
+    };
+    let mut value = String::with_capacity(raw_value.len());
+    let mut chars = raw_value.chars();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' => value.extend(chars.next()),
+            c => value.push(c),
+        }
+    }
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::EscapingMode;
+
+    #[test]
+    fn it_parses_what_it_encodes() {
+        let payloads = [
+            "WIFI:S:test ssid;T:WEP;P:test password;H:false;;",
+            "WIFI:S:test ssid;T:WPA;P:test password;H:true;;",
+            "WIFI:S:test ssid;T:nopass;H:false;;",
+            r#"WIFI:S:special_characters \"\;\,\:\\;T:WEP;P:special_characters \"\;\,\:\\;H:false;;"#,
+        ];
+        for payload in payloads {
+            let wifi_credentials = WifiCredentials::parse(payload).expect("Payload should parse.");
+            assert_eq!(payload, wifi_credentials.encode());
+        }
+    }
+
+    #[test]
+    fn it_tolerates_mangled_prefixes() {
+        let wifi_credentials = WifiCredentials::open("test ssid");
+        for payload in [
+            "wifi:S:test ssid;T:nopass;H:false;;",
+            "\u{feff}WIFI:S:test ssid;T:nopass;H:false;;",
+            " \r\n\u{feff}Wifi:S:test ssid;T:nopass;H:false;;",
+        ] {
+            assert_eq!(
+                Ok(&wifi_credentials),
+                WifiCredentials::parse(payload).as_ref()
+            );
+            assert_eq!(
+                Ok(&wifi_credentials),
+                WifiCredentials::parse_lenient(payload).as_ref()
+            );
+        }
+        assert_eq!(
+            Err(ParseError::MissingPrefix),
+            WifiCredentials::parse("x WIFI:S:test ssid;T:nopass;H:false;;").map(|_| ())
+        );
+    }
+
+    #[test]
+    fn it_parses_pre_shared_keys_and_quoted_values() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("c0ffee"),
+            authentication_type: AuthenticationType::WpaPsk([0xab; 32].into()),
+            visibility: Visibility::Visible,
+        };
+        let parsed = WifiCredentials::parse(
+            wifi_credentials
+                .encode_with(EscapingMode::ZxingQuoted)
+                .as_str(),
+        )
+        .expect("Payload should parse.");
+        assert_eq!("c0ffee", parsed.ssid);
+        assert_eq!(wifi_credentials.encode(), parsed.encode());
+    }
+
+    #[test]
+    fn it_parses_authentication_type_names() {
+        for name in ["WPA", "wpa2", "Wpa3"] {
+            assert_eq!(
+                Ok(AuthenticationType::WPA(Default::default())),
+                AuthenticationType::try_from(name)
+            );
+        }
+        assert_eq!(
+            Ok(AuthenticationType::WEP(Default::default())),
+            "wep".parse::<AuthenticationType>()
+        );
+        assert_eq!(
+            Err(ParseError::InvalidAuthenticationType(String::new())),
+            "".parse::<AuthenticationType>()
+        );
+    }
+
+    #[test]
+    fn it_parses_payloads_from_other_generators_leniently() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test;password");
+        let payloads = [
+            r"WIFI:S:test ssid;T:WPA;P:test\;password;H:false;;",
+            r"WIFI:T:WPA;S:test ssid;P:test\;password;;",
+            r"WIFI:S:test ssid;T:WPA;P:test\;password;",
+            r"wifi:s:test ssid;t:wpa2;p:test\;password",
+            "WIFI:S:test ssid;T:WPA;P:\"test\\;password\";H:FALSE;R:1;;\n",
+        ];
+        for payload in payloads {
+            assert_eq!(
+                Ok(&wifi_credentials),
+                WifiCredentials::parse_lenient(payload).as_ref(),
+                "{}",
+                payload
+            );
+        }
+        assert_eq!(
+            Ok(WifiCredentials::hidden(
+                "test ssid",
+                AuthenticationType::NoPassword
+            )),
+            WifiCredentials::parse_lenient("WIFI:H:true;S:test ssid")
+        );
+    }
+
+    #[test]
+    fn it_decodes_hex_ssids_leniently() {
+        let payload = "WIFI:S:436166c3a9;T:WPA;P:F42C6FC52DF0EBEF9EBB4B90B38A5F902E83FE1B135A70E23AED762E9710A12E;;";
+        let wifi_credentials = WifiCredentials::parse_lenient(payload).unwrap();
+        assert_eq!("Café", wifi_credentials.ssid);
+        assert!(matches!(
+            wifi_credentials.authentication_type,
+            AuthenticationType::WpaPsk(psk) if psk.starts_with(&[0xf4, 0x2c])
+        ));
+        assert_eq!(
+            "c3a9",
+            WifiCredentials::parse(
+                payload
+                    .replace("436166", "")
+                    .replace(";;", ";H:false;;")
+                    .as_str()
+            )
+            .unwrap()
+            .ssid
+        );
+        for literal in ["cafe", "4142", "\"c3a9\"", "c3a"] {
+            let payload = format!("WIFI:S:{};T:nopass;;", literal);
+            assert_eq!(
+                literal.trim_matches('"'),
+                WifiCredentials::parse_lenient(&payload).unwrap().ssid
+            );
+        }
+    }
+
+    #[test]
+    fn it_recovers_from_double_escaping() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from(r"semi;colon\back"),
+            authentication_type: AuthenticationType::WPA("pass:word,!".into()),
+            visibility: Visibility::Hidden,
+        };
+        assert_eq!(
+            r"WIFI:S:semi\;colon\\back;T:WPA;P:pass\:word\,!;H:true;;",
+            wifi_credentials.encode()
+        );
+        let payloads = [
+            // Escaped a second time by the generator.
+            r"WIFI:S:semi\\\;colon\\\\back;T:WPA;P:pass\\\:word\\\,!;H:true;;",
+            // Passed through a serializer that doubles backslashes.
+            r"WIFI:S:semi\\;colon\\\\back;T:WPA;P:pass\\:word\\,!;H:true;;",
+        ];
+        for payload in payloads {
+            let recovered = WifiCredentials::parse_recovering(payload).unwrap();
+            assert_eq!(wifi_credentials, recovered.wifi_credentials, "{}", payload);
+            assert_eq!(
+                vec![CredentialField::Ssid, CredentialField::Password],
+                recovered.unescaped_twice
+            );
+        }
+    }
+
+    #[test]
+    fn it_leaves_correctly_escaped_payloads_alone() {
+        let wifi_credentials = WifiCredentials::wpa2(r"back\slash", "password;");
+        let recovered = WifiCredentials::parse_recovering(wifi_credentials.encode().as_str());
+        assert_eq!(
+            Ok(RecoveredCredentials {
+                wifi_credentials,
+                unescaped_twice: Vec::new(),
+            }),
+            recovered
+        );
+        assert_eq!(
+            Err(ParseError::MissingField("S")),
+            WifiCredentials::parse_recovering(r"WIFI:T:WPA;P:pass\\;word;;").map(|_| ())
+        );
+    }
+
+    #[test]
+    fn it_rejects_ambiguous_payloads_leniently() {
+        assert_eq!(
+            Err(ParseError::DuplicateField(String::from("s"))),
+            WifiCredentials::parse_lenient("WIFI:S:ssid;s:other ssid;;")
+        );
+        assert_eq!(
+            Err(ParseError::MissingField("S")),
+            WifiCredentials::parse_lenient("WIFI:T:nopass;;")
+        );
+        assert_eq!(
+            Err(ParseError::MissingField("T")),
+            WifiCredentials::parse_lenient("WIFI:S:ssid;P:password;;")
+        );
+        assert_eq!(
+            Err(ParseError::MissingField("P")),
+            WifiCredentials::parse_lenient("WIFI:S:ssid;T:WEP;;")
+        );
+        assert_eq!(
+            Err(ParseError::MissingPrefix),
+            WifiCredentials::parse_lenient("WIFI")
+        );
+        assert_eq!(
+            Err(ParseError::TrailingCharacters),
+            WifiCredentials::parse_lenient("WIFI:S:ssid;;extra")
+        );
+    }
+
+    #[test]
+    fn it_rejects_malformed_payloads() {
+        assert_eq!(
+            Err(ParseError::MissingPrefix),
+            "S:ssid;T:nopass;H:false;;"
+                .parse::<WifiCredentials>()
+                .map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseError::MissingTerminator),
+            WifiCredentials::parse("WIFI:S:ssid;T:nopass;H:false;").map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseError::TrailingCharacters),
+            WifiCredentials::parse("WIFI:S:ssid;T:nopass;H:false;;extra").map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseError::UnexpectedField {
+                expected: "S",
+                found: String::from("T")
+            }),
+            WifiCredentials::parse("WIFI:T:nopass;S:ssid;H:false;;").map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseError::UnknownField(String::from("E"))),
+            WifiCredentials::parse("WIFI:S:ssid;T:nopass;H:false;E:PEAP;;").map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseError::MissingField("H")),
+            WifiCredentials::parse("WIFI:S:ssid;T:nopass;;").map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseError::InvalidAuthenticationType(String::from("WPA3"))),
+            WifiCredentials::parse("WIFI:S:ssid;T:WPA3;P:password;H:false;;").map(|_| ())
+        );
+        assert_eq!(
+            Err(ParseError::InvalidVisibility(String::from("yes"))),
+            WifiCredentials::parse("WIFI:S:ssid;T:nopass;H:yes;;").map(|_| ())
+        );
+    }
+}
@@ -0,0 +1,68 @@
+use crate::WifiCredentials;
+
+/// Letters and digits that cannot be confused with each other when read off a printed card, so `0`, `O`, `1`, `l`, and `I` are left out. None of them need escaping in a payload.
+const ALPHABET: &[u8] = b"23456789abcdefghijkmnpqrstuvwxyzABCDEFGHJKLMNPQRSTUVWXYZ";
+
+/// The number of characters in a generated passphrase, which gives about 116 bits of entropy.
+const LENGTH: usize = 20;
+
+/// Generate a random WPA passphrase from the operating system's secure random number generator. The passphrase only contains letters and digits that are hard to mistake for each other, for guests who type it in instead of scanning the code.
+///
+/// # Examples
+///
+/// ```
+/// let passphrase = wifi_qr_code::generate_passphrase().expect("Failed to generate a passphrase.");
+/// assert_eq!(20, passphrase.len());
+/// assert!(passphrase.chars().all(|c| c.is_ascii_alphanumeric()));
+/// ```
+pub fn generate_passphrase() -> Result<String, getrandom::Error> {
+    let mut passphrase = String::with_capacity(LENGTH);
+    let mut bytes = [0; LENGTH];
+    while passphrase.len() < LENGTH {
+        getrandom::getrandom(&mut bytes)?;
+        // Bytes past the largest multiple of the alphabet size are skipped so that every character is equally likely.
+        let limit = 256 - 256 % ALPHABET.len();
+        passphrase.extend(
+            bytes
+                .iter()
+                .filter(|&&byte| usize::from(byte) < limit)
+                .map(|&byte| char::from(ALPHABET[usize::from(byte) % ALPHABET.len()]))
+                .take(LENGTH - passphrase.len()),
+        );
+    }
+    Ok(passphrase)
+}
+
+impl WifiCredentials {
+    /// Credentials for a visible WPA network with a passphrase from [`generate_passphrase`](crate::generate_passphrase), e.g. for a guest network that is reconfigured with a fresh passphrase.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials =
+    ///     WifiCredentials::wpa2_random("example ssid").expect("Failed to generate a passphrase.");
+    /// assert_eq!(Ok(()), wifi_credentials.validate());
+    /// ```
+    pub fn wpa2_random(ssid: impl Into<String>) -> Result<Self, getrandom::Error> {
+        Ok(WifiCredentials::wpa2(ssid, generate_passphrase()?))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_generates_distinct_unambiguous_passphrases() {
+        let first = generate_passphrase().unwrap();
+        let second = generate_passphrase().unwrap();
+        assert_ne!(first, second);
+        for passphrase in [first, second] {
+            assert_eq!(LENGTH, passphrase.len());
+            assert!(passphrase.bytes().all(|byte| ALPHABET.contains(&byte)));
+            assert!(!passphrase.contains(['0', 'O', '1', 'l', 'I']));
+        }
+    }
+}
@@ -0,0 +1,95 @@
+use crate::Secret;
+
+use std::fmt::{self, Debug, Formatter};
+
+/// The full text of a wifi QR code, as returned by [`WifiCredentials::encode`](crate::WifiCredentials::encode). It contains the password in the clear, so it has its own type rather than being a bare `String`: places that handle it are easy to find, and its `Debug` implementation is redacted. With the `zeroize` feature, it is wiped from memory when dropped.
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct EncodedPayload(Secret<String>);
+
+impl EncodedPayload {
+    pub(crate) fn new(payload: Secret<String>) -> Self {
+        EncodedPayload(payload)
+    }
+
+    /// The payload text, e.g. to hand to a different QR code library.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::NoPassword,
+    ///     visibility: Visibility::Visible,
+    /// };
+    /// let payload = wifi_credentials.encode();
+    /// assert_eq!("WIFI:S:example ssid;T:nopass;H:false;;", payload.as_str());
+    /// assert_eq!("EncodedPayload(<redacted>)", format!("{:?}", payload));
+    /// ```
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Take the payload text out of the wrapper. With the `zeroize` feature, the returned `String` is no longer wiped automatically.
+    pub fn into_string(self) -> String {
+        self.0.into_inner()
+    }
+}
+
+impl Debug for EncodedPayload {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("EncodedPayload(<redacted>)")
+    }
+}
+
+impl AsRef<str> for EncodedPayload {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl AsRef<[u8]> for EncodedPayload {
+    fn as_ref(&self) -> &[u8] {
+        self.0.as_bytes()
+    }
+}
+
+impl PartialEq<str> for EncodedPayload {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for EncodedPayload {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for EncodedPayload {
+    fn eq(&self, other: &String) -> bool {
+        *self.0 == *other
+    }
+}
+
+impl PartialEq<EncodedPayload> for str {
+    fn eq(&self, other: &EncodedPayload) -> bool {
+        self == other.0
+    }
+}
+
+impl PartialEq<EncodedPayload> for &str {
+    fn eq(&self, other: &EncodedPayload) -> bool {
+        *self == other.0
+    }
+}
+
+impl PartialEq<EncodedPayload> for String {
+    fn eq(&self, other: &EncodedPayload) -> bool {
+        *self == *other.0
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl zeroize::ZeroizeOnDrop for EncodedPayload {}
@@ -0,0 +1,157 @@
+//! Read the wifi networks saved on the local machine, so codes can be generated for networks it already knows without looking up their passwords.
+//!
+//! Every operating system has its own backend implementing [`SavedNetworks`], and [`saved_networks`] returns the one for the current platform: `linux::NetworkManager` on Linux, `macos::Keychain` on macOS, and `windows::WlanProfiles` on Windows. Reading passwords usually requires the user's permission, e.g. a polkit or keychain prompt, or an administrator account.
+
+#[cfg(any(target_os = "linux", test))]
+pub mod linux;
+#[cfg(any(target_os = "macos", test))]
+pub mod macos;
+#[cfg(any(target_os = "windows", test))]
+pub mod windows;
+
+use crate::tool::Failure;
+use crate::{AuthenticationType, Secret, WifiCredentials};
+
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::io;
+use std::process::ExitStatus;
+
+/// Errors when reading saved networks.
+#[derive(Debug)]
+pub enum PlatformError {
+    /// The network tool could not be started, e.g. because it is not installed, or its output could not be read.
+    Io(io::Error),
+    /// The network tool ran, but failed.
+    Command {
+        /// The tool that failed, such as `nmcli`.
+        program: &'static str,
+        /// How the tool exited.
+        status: ExitStatus,
+        /// What the tool wrote to standard error, or to standard output if nothing was written to standard error.
+        message: String,
+    },
+    /// The network tool's output could not be understood.
+    UnexpectedOutput {
+        /// The tool whose output could not be understood.
+        program: &'static str,
+        /// A description of what was wrong with the output.
+        reason: String,
+    },
+    /// There is no wifi interface to read saved networks for.
+    NoInterface,
+    /// No network with the SSID is saved.
+    NotFound(String),
+    /// The network is saved, but the user is not allowed to read its password. Contains the SSID.
+    PasswordUnavailable(String),
+    /// The network uses a kind of security that wifi QR codes cannot describe, such as WPA-Enterprise.
+    UnsupportedSecurity {
+        /// The SSID of the network.
+        ssid: String,
+        /// The name the operating system uses for the kind of security, such as `wpa-eap`.
+        security: String,
+    },
+}
+
+impl Display for PlatformError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to run the network tool: {}", error),
+            Self::Command {
+                program,
+                status,
+                message,
+            } => write!(f, "{} failed ({}): {}", program, status, message),
+            Self::UnexpectedOutput { program, reason } => {
+                write!(f, "unexpected output from {}: {}", program, reason)
+            }
+            Self::NoInterface => f.write_str("no wifi interface was found"),
+            Self::NotFound(ssid) => write!(f, "no saved network is named {:?}", ssid),
+            Self::PasswordUnavailable(ssid) => {
+                write!(f, "not allowed to read the password for {:?}", ssid)
+            }
+            Self::UnsupportedSecurity { ssid, security } => write!(
+                f,
+                "{:?} uses {} security, which wifi QR codes cannot describe",
+                ssid, security
+            ),
+        }
+    }
+}
+
+impl error::Error for PlatformError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for PlatformError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<Failure> for PlatformError {
+    fn from(failure: Failure) -> Self {
+        match failure {
+            Failure::Io(error) => Self::Io(error),
+            Failure::Exit {
+                program,
+                status,
+                message,
+            } => Self::Command {
+                program,
+                status,
+                message,
+            },
+        }
+    }
+}
+
+/// The wifi networks saved by an operating system.
+pub trait SavedNetworks {
+    /// List the SSIDs of the saved networks, without reading their passwords.
+    fn ssids(&self) -> Result<Vec<String>, PlatformError>;
+
+    /// Read the saved credentials for the network with the SSID, including its password.
+    fn credentials(&self, ssid: &str) -> Result<WifiCredentials, PlatformError>;
+}
+
+/// WPA credentials for a saved password, which is a pre-shared key if it is 64 hex digits.
+#[cfg(any(target_os = "linux", target_os = "windows", test))]
+fn wpa(password: String) -> AuthenticationType {
+    match AuthenticationType::wpa_psk_from_hex(&password) {
+        Ok(authentication_type) => authentication_type,
+        Err(_) => AuthenticationType::WPA(Secret::new(password)),
+    }
+}
+
+/// The backend for the saved networks of the current platform.
+#[cfg(target_os = "linux")]
+pub type Native = linux::NetworkManager;
+/// The backend for the saved networks of the current platform.
+#[cfg(target_os = "macos")]
+pub type Native = macos::Keychain;
+/// The backend for the saved networks of the current platform.
+#[cfg(target_os = "windows")]
+pub type Native = windows::WlanProfiles;
+
+/// The saved networks of the current platform.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wifi_qr_code::platform::SavedNetworks;
+///
+/// let saved_networks = wifi_qr_code::platform::saved_networks();
+/// for ssid in saved_networks.ssids().expect("Failed to list saved networks.") {
+///     println!("{}", ssid);
+/// }
+/// ```
+#[cfg(any(target_os = "linux", target_os = "macos", target_os = "windows"))]
+pub fn saved_networks() -> Native {
+    Native::default()
+}
@@ -0,0 +1,175 @@
+//! Connections saved by NetworkManager, read with `nmcli`. Passwords are only shown to users that polkit allows to read them, which usually means users logged in at the machine or root.
+
+use super::{wpa, PlatformError, SavedNetworks};
+use crate::tool;
+use crate::{AuthenticationType, Secret, Visibility, WifiCredentials};
+
+/// The settings read for each connection, in the order `nmcli` prints them.
+const FIELDS: &str = "802-11-wireless.ssid,802-11-wireless.hidden,802-11-wireless-security.key-mgmt,802-11-wireless-security.psk,802-11-wireless-security.wep-key0";
+
+/// The wifi connections saved by NetworkManager.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct NetworkManager;
+
+impl SavedNetworks for NetworkManager {
+    fn ssids(&self) -> Result<Vec<String>, PlatformError> {
+        wifi_connections()?
+            .iter()
+            .map(|connection| connection_ssid(connection))
+            .collect()
+    }
+
+    fn credentials(&self, ssid: &str) -> Result<WifiCredentials, PlatformError> {
+        for connection in wifi_connections()? {
+            if connection_ssid(&connection)? == ssid {
+                let output = tool::run(
+                    "nmcli",
+                    &connection_arguments(&["--show-secrets"], FIELDS, &connection),
+                )?;
+                return parse_connection(&String::from_utf8_lossy(&output.stdout));
+            }
+        }
+        Err(PlatformError::NotFound(String::from(ssid)))
+    }
+}
+
+/// The names of the saved wifi connections, which may differ from their SSIDs.
+fn wifi_connections() -> Result<Vec<String>, PlatformError> {
+    let arguments = ["--terse", "--fields", "NAME,TYPE", "connection", "show"];
+    let output = tool::run(
+        "nmcli",
+        &arguments
+            .iter()
+            .map(|argument| String::from(*argument))
+            .collect::<Vec<_>>(),
+    )?;
+    Ok(parse_connections(&String::from_utf8_lossy(&output.stdout)))
+}
+
+fn connection_ssid(connection: &str) -> Result<String, PlatformError> {
+    let output = tool::run(
+        "nmcli",
+        &connection_arguments(&[], "802-11-wireless.ssid", connection),
+    )?;
+    let ssid = String::from_utf8_lossy(&output.stdout);
+    Ok(String::from(ssid.strip_suffix('\n').unwrap_or(&ssid)))
+}
+
+fn connection_arguments(options: &[&str], fields: &str, connection: &str) -> Vec<String> {
+    let mut arguments: Vec<String> = options.iter().map(|option| String::from(*option)).collect();
+    for argument in [
+        "--escape",
+        "no",
+        "--get-values",
+        fields,
+        "connection",
+        "show",
+        "id",
+        connection,
+    ] {
+        arguments.push(String::from(argument));
+    }
+    arguments
+}
+
+/// Parse the output of `nmcli --terse --fields NAME,TYPE connection show`, where `:` separates the fields and is escaped with a backslash inside them.
+fn parse_connections(output: &str) -> Vec<String> {
+    output
+        .lines()
+        .filter_map(|line| {
+            let mut name = String::new();
+            let mut characters = line.chars();
+            while let Some(c) = characters.next() {
+                match c {
+                    '\\' => name.extend(characters.next()),
+                    ':' => {
+                        return (characters.as_str() == "802-11-wireless").then_some(name);
+                    }
+                    c => name.push(c),
+                }
+            }
+            None
+        })
+        .collect()
+}
+
+/// Parse the values of [`FIELDS`], one per line, into credentials.
+fn parse_connection(output: &str) -> Result<WifiCredentials, PlatformError> {
+    let mut values = output.lines();
+    let mut next_value = || values.next().unwrap_or("");
+    let ssid = String::from(next_value());
+    let visibility = match next_value() {
+        "yes" => Visibility::Hidden,
+        _ => Visibility::Visible,
+    };
+    let key_management = next_value();
+    let psk = next_value();
+    let wep_key = next_value();
+
+    let required = |password: &str| match password {
+        "" => Err(PlatformError::PasswordUnavailable(ssid.clone())),
+        password => Ok(String::from(password)),
+    };
+    let authentication_type = match key_management {
+        "" => AuthenticationType::NoPassword,
+        "none" => AuthenticationType::WEP(Secret::new(required(wep_key)?)),
+        "wpa-psk" | "sae" => wpa(required(psk)?),
+        security => {
+            return Err(PlatformError::UnsupportedSecurity {
+                ssid,
+                security: String::from(security),
+            })
+        }
+    };
+    Ok(WifiCredentials {
+        ssid,
+        authentication_type,
+        visibility,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_wifi_connections() {
+        let output = "Wired connection 1:802-3-ethernet\nhome\\:5G:802-11-wireless\nlo:loopback\nCafé \\\\ Bar:802-11-wireless\n";
+        assert_eq!(
+            vec![String::from("home:5G"), String::from(r"Café \ Bar")],
+            parse_connections(output)
+        );
+    }
+
+    #[test]
+    fn it_parses_connection_settings() {
+        assert_eq!(
+            WifiCredentials {
+                ssid: String::from("test:ssid"),
+                authentication_type: AuthenticationType::WPA("test password".into()),
+                visibility: Visibility::Hidden,
+            },
+            parse_connection("test:ssid\nyes\nwpa-psk\ntest password\n\n").unwrap()
+        );
+        assert_eq!(
+            WifiCredentials {
+                ssid: String::from("test ssid"),
+                authentication_type: AuthenticationType::WEP("c0ffee1234".into()),
+                visibility: Visibility::Visible,
+            },
+            parse_connection("test ssid\nno\nnone\n\nc0ffee1234\n").unwrap()
+        );
+        assert_eq!(
+            WifiCredentials::open("test ssid"),
+            parse_connection("test ssid\nno\n\n\n\n").unwrap()
+        );
+        assert!(matches!(
+            parse_connection("test ssid\nno\nsae\n\n\n"),
+            Err(PlatformError::PasswordUnavailable(ssid)) if ssid == "test ssid"
+        ));
+        assert!(matches!(
+            parse_connection("test ssid\nno\nwpa-eap\n\n\n"),
+            Err(PlatformError::UnsupportedSecurity { security, .. }) if security == "wpa-eap"
+        ));
+    }
+}
@@ -0,0 +1,100 @@
+//! Networks in the preferred network list of the Wi-Fi interface, read with `networksetup`, with passwords read from the system keychain with `security`. macOS asks the user to allow access to each password.
+//!
+//! The keychain only stores the password, not the kind of security, so networks with a password are assumed to use WPA, and networks without one to be open. The preferred network list does not record whether a network is hidden either, so every network is returned as visible.
+
+use super::{PlatformError, SavedNetworks};
+use crate::tool::{self, wifi_interface, Failure};
+use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+/// The exit status of `security` when the keychain has no matching item.
+const ITEM_NOT_FOUND: i32 = 44;
+
+/// The preferred networks and keychain passwords of macOS.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct Keychain;
+
+impl SavedNetworks for Keychain {
+    fn ssids(&self) -> Result<Vec<String>, PlatformError> {
+        let output = tool::run("networksetup", &[String::from("-listallhardwareports")])?;
+        let interface = wifi_interface(&String::from_utf8_lossy(&output.stdout))
+            .map(String::from)
+            .ok_or(PlatformError::NoInterface)?;
+        let output = tool::run(
+            "networksetup",
+            &[String::from("-listpreferredwirelessnetworks"), interface],
+        )?;
+        parse_preferred_networks(&String::from_utf8_lossy(&output.stdout))
+    }
+
+    fn credentials(&self, ssid: &str) -> Result<WifiCredentials, PlatformError> {
+        if !self.ssids()?.iter().any(|saved| saved == ssid) {
+            return Err(PlatformError::NotFound(String::from(ssid)));
+        }
+        let arguments = [
+            "find-generic-password",
+            "-D",
+            "AirPort network password",
+            "-a",
+            ssid,
+            "-w",
+        ];
+        let authentication_type = match tool::run(
+            "security",
+            &arguments
+                .iter()
+                .map(|argument| String::from(*argument))
+                .collect::<Vec<_>>(),
+        ) {
+            Ok(output) => {
+                let password = String::from_utf8_lossy(&output.stdout);
+                AuthenticationType::WPA(password.strip_suffix('\n').unwrap_or(&password).into())
+            }
+            Err(Failure::Exit { status, .. }) if status.code() == Some(ITEM_NOT_FOUND) => {
+                AuthenticationType::NoPassword
+            }
+            // Denying the keychain prompt makes `security` fail, too.
+            Err(Failure::Exit { .. }) => {
+                return Err(PlatformError::PasswordUnavailable(String::from(ssid)))
+            }
+            Err(failure) => return Err(failure.into()),
+        };
+        Ok(WifiCredentials {
+            ssid: String::from(ssid),
+            authentication_type,
+            visibility: Visibility::Visible,
+        })
+    }
+}
+
+/// Parse the output of `networksetup -listpreferredwirelessnetworks`, a header followed by one tab-indented SSID per line.
+fn parse_preferred_networks(output: &str) -> Result<Vec<String>, PlatformError> {
+    let mut lines = output.lines();
+    match lines.next() {
+        Some(header) if header.starts_with("Preferred networks on ") => Ok(lines
+            .filter_map(|line| line.strip_prefix('\t'))
+            .map(String::from)
+            .collect()),
+        _ => Err(PlatformError::UnexpectedOutput {
+            program: "networksetup",
+            reason: String::from(output.trim()),
+        }),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_parses_preferred_networks() {
+        assert_eq!(
+            vec![String::from("home"), String::from(" spaced ssid ")],
+            parse_preferred_networks("Preferred networks on en0:\n\thome\n\t spaced ssid \n")
+                .unwrap()
+        );
+        assert!(matches!(
+            parse_preferred_networks("en1 is not a Wi-Fi interface.\n"),
+            Err(PlatformError::UnexpectedOutput { reason, .. }) if reason == "en1 is not a Wi-Fi interface."
+        ));
+    }
+}
@@ -0,0 +1,180 @@
+//! WLAN profiles, exported as XML with `netsh wlan export profile`, which is not localized unlike the rest of `netsh`'s output. Passwords are only exported in plain text for administrators.
+
+use super::{wpa, PlatformError, SavedNetworks};
+use crate::tool;
+use crate::{AuthenticationType, Secret, Visibility, WifiCredentials};
+
+use std::env;
+use std::fs;
+use std::process;
+
+/// The WLAN profiles saved by Windows.
+#[derive(Debug, Default, Clone, Copy)]
+pub struct WlanProfiles;
+
+impl SavedNetworks for WlanProfiles {
+    fn ssids(&self) -> Result<Vec<String>, PlatformError> {
+        export_profiles(false)?
+            .iter()
+            .map(|profile| profile_ssid(profile))
+            .collect()
+    }
+
+    fn credentials(&self, ssid: &str) -> Result<WifiCredentials, PlatformError> {
+        for profile in export_profiles(true)? {
+            if profile_ssid(&profile)? == ssid {
+                return parse_profile(&profile);
+            }
+        }
+        Err(PlatformError::NotFound(String::from(ssid)))
+    }
+}
+
+/// Export every WLAN profile to a temporary directory and read them back, removing the files again since they may contain passwords.
+fn export_profiles(with_keys: bool) -> Result<Vec<String>, PlatformError> {
+    let directory = env::temp_dir().join(format!("wifi-qr-code-{}", process::id()));
+    fs::create_dir_all(&directory)?;
+    let mut arguments = vec![
+        String::from("wlan"),
+        String::from("export"),
+        String::from("profile"),
+        format!("folder={}", directory.display()),
+    ];
+    if with_keys {
+        arguments.push(String::from("key=clear"));
+    }
+    let profiles = tool::run("netsh", &arguments)
+        .map_err(PlatformError::from)
+        .and_then(|_| {
+            let mut profiles = Vec::new();
+            for entry in fs::read_dir(&directory)? {
+                let path = entry?.path();
+                if path.extension().is_some_and(|extension| extension == "xml") {
+                    profiles.push(fs::read_to_string(path)?);
+                }
+            }
+            Ok(profiles)
+        });
+    let removed = fs::remove_dir_all(&directory);
+    let profiles = profiles?;
+    removed?;
+    Ok(profiles)
+}
+
+/// The text of the first `<tag>` element, which is enough for the flat structure of WLAN profiles.
+fn element<'a>(xml: &'a str, tag: &str) -> Option<&'a str> {
+    let start = xml.find(&format!("<{}>", tag))? + tag.len() + 2;
+    let end = xml[start..].find(&format!("</{}>", tag))?;
+    Some(&xml[start..start + end])
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+fn unexpected(reason: &str) -> PlatformError {
+    PlatformError::UnexpectedOutput {
+        program: "netsh",
+        reason: String::from(reason),
+    }
+}
+
+/// The SSID of a profile, decoded from the hex form so that SSIDs that are not valid XML text survive.
+fn profile_ssid(profile: &str) -> Result<String, PlatformError> {
+    let ssid = element(profile, "SSID").ok_or_else(|| unexpected("the profile has no SSID"))?;
+    if let Some(hex) = element(ssid, "hex") {
+        let bytes = (0..hex.len())
+            .step_by(2)
+            .map(|index| {
+                hex.get(index..index + 2)
+                    .and_then(|digits| u8::from_str_radix(digits, 16).ok())
+            })
+            .collect::<Option<Vec<u8>>>()
+            .ok_or_else(|| unexpected("the SSID is not valid hex"))?;
+        return String::from_utf8(bytes).map_err(|_| unexpected("the SSID is not valid UTF-8"));
+    }
+    element(ssid, "name")
+        .map(unescape_xml)
+        .ok_or_else(|| unexpected("the profile has no SSID"))
+}
+
+fn parse_profile(profile: &str) -> Result<WifiCredentials, PlatformError> {
+    let ssid = profile_ssid(profile)?;
+    let visibility = match element(profile, "nonBroadcast") {
+        Some("true") => Visibility::Hidden,
+        _ => Visibility::Visible,
+    };
+    let key = match (
+        element(profile, "keyMaterial"),
+        element(profile, "protected"),
+    ) {
+        (_, Some("true")) => Err(PlatformError::PasswordUnavailable(ssid.clone())),
+        (Some(key), _) => Ok(unescape_xml(key)),
+        (None, _) => Err(PlatformError::PasswordUnavailable(ssid.clone())),
+    };
+    let authentication = element(profile, "authentication").unwrap_or("");
+    let authentication_type = match (authentication, element(profile, "encryption")) {
+        ("open", Some("none")) => AuthenticationType::NoPassword,
+        ("open" | "shared", Some("WEP")) => AuthenticationType::WEP(Secret::new(key?)),
+        ("WPAPSK" | "WPA2PSK" | "WPA3SAE", _) => wpa(key?),
+        (security, _) => {
+            return Err(PlatformError::UnsupportedSecurity {
+                ssid,
+                security: String::from(security),
+            })
+        }
+    };
+    Ok(WifiCredentials {
+        ssid,
+        authentication_type,
+        visibility,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::export::windows_profile;
+
+    #[test]
+    fn it_parses_exported_profiles() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test <ssid> & more"),
+            authentication_type: AuthenticationType::WPA("test & password".into()),
+            visibility: Visibility::Hidden,
+        };
+        let profile = windows_profile::encode(&wifi_credentials).unwrap();
+        assert_eq!(wifi_credentials.ssid, profile_ssid(&profile).unwrap());
+        assert_eq!(wifi_credentials, parse_profile(&profile).unwrap());
+
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WpaPsk([0xab; 32].into()),
+            visibility: Visibility::Visible,
+        };
+        let profile = windows_profile::encode(&wifi_credentials).unwrap();
+        assert_eq!(wifi_credentials, parse_profile(&profile).unwrap());
+
+        let wifi_credentials = WifiCredentials::open("test ssid");
+        let profile = windows_profile::encode(&wifi_credentials).unwrap();
+        assert_eq!(wifi_credentials, parse_profile(&profile).unwrap());
+    }
+
+    #[test]
+    fn it_reports_protected_keys() {
+        let profile = windows_profile::encode(&WifiCredentials::wpa2("test ssid", "test password"))
+            .unwrap()
+            .replace(
+                "<protected>false</protected>",
+                "<protected>true</protected>",
+            );
+        assert!(matches!(
+            parse_profile(&profile),
+            Err(PlatformError::PasswordUnavailable(ssid)) if ssid == "test ssid"
+        ));
+    }
+}
@@ -0,0 +1,50 @@
+//! Output for printers, so the same credentials can be printed as a slip, a label, or a sheet without going through an image editor first.
+//!
+//! Printed output includes the SSID and password as text under the QR code, for guests whose devices cannot scan it, along with instructions in the [`Locale`] of the output.
+
+#[cfg(feature = "label")]
+mod bitmap;
+pub mod escpos;
+#[cfg(feature = "fonts")]
+pub mod font;
+#[cfg(feature = "ipp")]
+pub mod ipp;
+#[cfg(feature = "label")]
+pub mod label;
+mod locale;
+#[cfg(feature = "pdf")]
+mod pdf;
+#[cfg(feature = "pdf")]
+pub mod sheet;
+#[cfg(feature = "pdf")]
+pub mod tent;
+
+pub use locale::Locale;
+
+use crate::{Visibility, WifiCredentials};
+
+/// The caption lines printed under a QR code: the network name, and the password or a note that there is none.
+pub(crate) fn captions(wifi_credentials: &WifiCredentials, locale: Locale) -> [String; 2] {
+    let ssid = [("ssid", wifi_credentials.ssid.as_str())];
+    let network = match wifi_credentials.visibility {
+        Visibility::Hidden => locale.message("hidden-network", &ssid),
+        Visibility::Visible => locale.message("network", &ssid),
+    };
+    let password = match wifi_credentials.authentication_type.password_text() {
+        Some(password) => locale.message("password", &[("password", &password)]),
+        None => locale.message("no-password", &[]),
+    };
+    [network, password]
+}
+
+/// Format a coordinate with at most two decimal places, which is a precision of 1/7200 of an inch in PDF documents.
+#[cfg(any(feature = "fonts", feature = "pdf"))]
+pub(crate) fn number(value: f64) -> String {
+    let formatted = format!("{:.2}", value);
+    let formatted = formatted.trim_end_matches('0').trim_end_matches('.');
+    if formatted == "-0" {
+        String::from("0")
+    } else {
+        formatted.to_string()
+    }
+}
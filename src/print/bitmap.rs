@@ -0,0 +1,138 @@
+use font8x8::{UnicodeFonts, BASIC_FONTS, LATIN_FONTS};
+use qrcode_generator::qrcodegen::QrCode;
+
+/// The width and height of a glyph of the built-in font, in dots before scaling.
+pub(crate) const GLYPH_SIZE: usize = 8;
+
+/// A monochrome image that labels are laid out on before they are converted into printer commands.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Bitmap {
+    pub(crate) width: usize,
+    pub(crate) height: usize,
+    dots: Vec<bool>,
+}
+
+impl Bitmap {
+    /// A blank bitmap.
+    pub(crate) fn new(width: usize, height: usize) -> Self {
+        Bitmap {
+            width,
+            height,
+            dots: vec![false; width * height],
+        }
+    }
+
+    /// Whether the dot is black. Dots outside of the bitmap are white.
+    pub(crate) fn get(&self, x: usize, y: usize) -> bool {
+        x < self.width && y < self.height && self.dots[y * self.width + x]
+    }
+
+    /// Blacken a rectangle, clipped to the bitmap.
+    pub(crate) fn fill(&mut self, x: usize, y: usize, width: usize, height: usize) {
+        for y in y..(y + height).min(self.height) {
+            for x in x..(x + width).min(self.width) {
+                self.dots[y * self.width + x] = true;
+            }
+        }
+    }
+
+    /// Draw the QR code with its top left module at the position, without a quiet zone.
+    pub(crate) fn draw_qr_code(
+        &mut self,
+        qr_code: &QrCode,
+        x: usize,
+        y: usize,
+        dots_per_module: usize,
+    ) {
+        let size = qr_code.size();
+        for module_y in 0..size {
+            for module_x in 0..size {
+                if qr_code.get_module(module_x, module_y) {
+                    self.fill(
+                        x + module_x as usize * dots_per_module,
+                        y + module_y as usize * dots_per_module,
+                        dots_per_module,
+                        dots_per_module,
+                    );
+                }
+            }
+        }
+    }
+
+    /// Draw a line of text in the built-in 8 by 8 font, scaled up by the factor. Characters outside of ASCII and Latin-1 are drawn as `?`.
+    pub(crate) fn draw_text(&mut self, text: &str, x: usize, y: usize, scale: usize) {
+        for (index, character) in text.chars().enumerate() {
+            let glyph = BASIC_FONTS
+                .get(character)
+                .or_else(|| LATIN_FONTS.get(character))
+                .or_else(|| BASIC_FONTS.get('?'))
+                .unwrap_or_default();
+            let left = x + index * GLYPH_SIZE * scale;
+            for (row, bits) in glyph.iter().enumerate() {
+                for column in 0..GLYPH_SIZE {
+                    // The least significant bit is the leftmost dot of the row.
+                    if bits & (1 << column) != 0 {
+                        self.fill(left + column * scale, y + row * scale, scale, scale);
+                    }
+                }
+            }
+        }
+    }
+
+    /// The bitmap turned a quarter turn clockwise.
+    pub(crate) fn rotated(&self) -> Self {
+        let mut rotated = Bitmap::new(self.height, self.width);
+        for y in 0..self.height {
+            for x in 0..self.width {
+                if self.get(x, y) {
+                    rotated.dots[x * rotated.width + (self.height - 1 - y)] = true;
+                }
+            }
+        }
+        rotated
+    }
+
+    /// Pack a row into bytes with the most significant bit first and black as 1, the layout that label printers expect.
+    pub(crate) fn packed_row(&self, y: usize) -> Vec<u8> {
+        let mut row = vec![0; self.width.div_ceil(8)];
+        for x in 0..self.width {
+            if self.get(x, y) {
+                row[x / 8] |= 0x80 >> (x % 8);
+            }
+        }
+        row
+    }
+}
+
+/// Split text into lines of at most `width` characters. Passwords rarely contain spaces, so lines are split between any two characters.
+pub(crate) fn wrap(text: &str, width: usize) -> Vec<String> {
+    let characters: Vec<char> = text.chars().collect();
+    characters
+        .chunks(width.max(1))
+        .map(|line| line.iter().collect())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_rotates_clockwise() {
+        let mut bitmap = Bitmap::new(3, 2);
+        bitmap.fill(0, 0, 1, 1);
+        let rotated = bitmap.rotated();
+        assert_eq!((2, 3), (rotated.width, rotated.height));
+        assert!(rotated.get(1, 0));
+        assert_eq!(1, rotated.dots.iter().filter(|dot| **dot).count());
+    }
+
+    #[test]
+    fn it_draws_text_and_wraps_lines() {
+        let mut bitmap = Bitmap::new(16, 8);
+        bitmap.draw_text("|", 0, 0, 1);
+        // The vertical bar of the font is the fourth and fifth dots of the glyph.
+        assert_eq!(vec![0x18, 0x00], bitmap.packed_row(0));
+        assert_eq!(vec!["abc", "de"], wrap("abcde", 3));
+    }
+}
@@ -0,0 +1,164 @@
+//! Wi-Fi slips for thermal receipt printers that understand ESC/POS commands, which covers most printers used by point of sale systems. The QR code is sent as a raster image (`GS v 0`), so it also prints on models without a built-in QR code command.
+//!
+//! Receipt printers use single byte code pages rather than UTF-8, so slips select the Windows-1252 code page, and characters in the text outside of Latin-1 are printed as `?`. The QR code itself is unaffected.
+
+use super::{captions, Locale};
+use crate::{Error, QRCodeError, QrCodeEcc, WifiCredentials};
+
+/// The printable width of 58 mm paper, in dots at 203 dpi.
+pub const PAPER_58MM: usize = 384;
+/// The printable width of 80 mm paper, in dots at 203 dpi.
+pub const PAPER_80MM: usize = 576;
+
+/// The quiet zone around the QR code, in modules.
+const QUIET_ZONE: usize = 2;
+
+/// The settings of a printed slip.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Receipt {
+    /// The printable width of the paper in dots, such as [`PAPER_58MM`] or [`PAPER_80MM`].
+    pub paper_width: usize,
+    /// The largest side length of the QR code in dots, so it stays easy to scan without using up the paper roll.
+    pub max_qr_code_size: usize,
+    /// Whether to cut the paper after the slip, for printers with an auto-cutter.
+    pub cut: bool,
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The language of the text on the slip.
+    pub locale: Locale,
+}
+
+impl Default for Receipt {
+    fn default() -> Self {
+        Receipt {
+            paper_width: PAPER_58MM,
+            max_qr_code_size: 320,
+            cut: true,
+            qr_code_error_checking: QrCodeEcc::Medium,
+            locale: Locale::English,
+        }
+    }
+}
+
+impl Receipt {
+    /// Encode the slip as ESC/POS commands: a "Wi-Fi" heading, instructions to scan the QR code, the QR code, and the caption lines, centered, followed by a paper feed and a cut. The result can be written to the printer directly, e.g. to a USB printer device or to port 9100 of a network printer.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::escpos::Receipt;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let commands = Receipt::default()
+    ///     .encode(&wifi_credentials)
+    ///     .expect("Failed to encode example slip.");
+    /// // Every slip starts by resetting the printer.
+    /// assert!(commands.starts_with(b"\x1b@"));
+    /// ```
+    pub fn encode(&self, wifi_credentials: &WifiCredentials) -> Result<Vec<u8>, Error> {
+        let qr_code = crate::generate_symbol(
+            &crate::encode_payload(wifi_credentials)?,
+            self.qr_code_error_checking,
+        )?;
+        let modules = qr_code.size() as usize;
+        let dots_per_module =
+            self.paper_width.min(self.max_qr_code_size) / (modules + 2 * QUIET_ZONE);
+        if dots_per_module == 0 {
+            return Err(Error::QrCode(QRCodeError::ImageSizeTooSmall));
+        }
+        let size = (modules + 2 * QUIET_ZONE) * dots_per_module;
+        let bytes_per_row = size.div_ceil(8);
+
+        // ESC @ resets the printer, ESC t 16 selects Windows-1252, ESC a 1 centers, and ESC E toggles bold.
+        let mut commands = b"\x1b@\x1bt\x10\x1ba\x01\x1bE\x01".to_vec();
+        push_line(&mut commands, &self.locale.message("title", &[]));
+        commands.extend_from_slice(b"\x1bE\x00");
+        push_line(&mut commands, &self.locale.message("instructions", &[]));
+        // GS v 0 prints a raster image of `bytes_per_row` by `size` dots, with the most significant bit as the leftmost dot.
+        commands.extend_from_slice(b"\x1dv0\x00");
+        commands.extend_from_slice(&(bytes_per_row as u16).to_le_bytes());
+        commands.extend_from_slice(&(size as u16).to_le_bytes());
+        for y in 0..size {
+            let module_y = (y / dots_per_module) as i32 - QUIET_ZONE as i32;
+            let mut row = vec![0u8; bytes_per_row];
+            for x in 0..size {
+                let module_x = (x / dots_per_module) as i32 - QUIET_ZONE as i32;
+                if qr_code.get_module(module_x, module_y) {
+                    row[x / 8] |= 0x80 >> (x % 8);
+                }
+            }
+            commands.extend_from_slice(&row);
+        }
+        for caption in &captions(wifi_credentials, self.locale) {
+            push_line(&mut commands, caption);
+        }
+        // ESC d 4 feeds four lines so the slip clears the cutter, and GS V 1 makes a partial cut.
+        commands.extend_from_slice(b"\x1bd\x04");
+        if self.cut {
+            commands.extend_from_slice(b"\x1dV\x01");
+        }
+        Ok(commands)
+    }
+}
+
+/// Append a line of text in Windows-1252, which matches Latin-1 for every printable character of Latin-1.
+fn push_line(commands: &mut Vec<u8>, text: &str) {
+    commands.extend(text.chars().map(|character| match character {
+        ' '..='~' | '\u{a0}'..='\u{ff}' => character as u32 as u8,
+        _ => b'?',
+    }));
+    commands.push(b'\n');
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility};
+
+    #[test]
+    fn it_encodes_a_raster_image_and_captions() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("Café"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        let commands = Receipt::default().encode(&wifi_credentials).unwrap();
+        let header = b"\x1b@\x1bt\x10\x1ba\x01\x1bE\x01Wi-Fi\n\x1bE\x00Scan to connect\n\x1dv0\x00";
+        assert!(commands.starts_with(header));
+        // The 33 module code and its quiet zone are scaled by 8 to 296 dots, which takes 37 bytes per row.
+        let dimensions = &commands[header.len()..header.len() + 4];
+        assert_eq!(&[37, 0, 0x28, 0x01], dimensions);
+        let image = &commands[header.len() + 4..header.len() + 4 + 37 * 296];
+        // The quiet zone is blank, and the finder pattern starts right after it.
+        assert!(image[..37 * 16].iter().all(|byte| *byte == 0));
+        assert_eq!(&[0x00, 0x00, 0xff, 0xff], &image[37 * 16..37 * 16 + 4]);
+        let text = &commands[header.len() + 4 + 37 * 296..];
+        assert_eq!(
+            &b"Network: Caf\xe9\nPassword: test password\n\x1bd\x04\x1dV\x01"[..],
+            text
+        );
+
+        let receipt = Receipt {
+            cut: false,
+            ..Receipt::default()
+        };
+        assert!(receipt
+            .encode(&wifi_credentials)
+            .unwrap()
+            .ends_with(b"\n\x1bd\x04"));
+        let receipt = Receipt {
+            locale: Locale::Dutch,
+            ..Receipt::default()
+        };
+        let commands = receipt.encode(&wifi_credentials).unwrap();
+        assert!(
+            commands.ends_with(b"Netwerk: Caf\xe9\nWachtwoord: test password\n\x1bd\x04\x1dV\x01")
+        );
+        let receipt = Receipt {
+            paper_width: 20,
+            ..Receipt::default()
+        };
+        assert!(receipt.encode(&wifi_credentials).is_err());
+    }
+}
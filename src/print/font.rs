@@ -0,0 +1,276 @@
+//! Fonts for the text of SVG cards, so a card looks the same on every machine rather than depending on the fonts installed where it is opened or printed.
+//!
+//! A font is either embedded into the card as a base64 `@font-face` rule, which keeps the text selectable, or its glyphs are converted into paths, which print shops often require because it does not depend on font support in their tools at all.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wifi_qr_code::print::font::{Font, FontMode};
+//! use wifi_qr_code::print::Locale;
+//! use wifi_qr_code::{QrCodeEcc, WifiCredentials};
+//!
+//! use std::fs::{self, File};
+//!
+//! let data = fs::read("/usr/share/fonts/truetype/dejavu/DejaVuSans.ttf").expect("Failed to read the font.");
+//! let font = Font::new(data, FontMode::Outline).expect("Failed to parse the font.");
+//! let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+//! let svg_file = File::create("card.svg").expect("Failed to create the card file.");
+//! wifi_credentials
+//!     .write_svg_card_with_font(QrCodeEcc::Medium, Locale::English, &font, svg_file)
+//!     .expect("Failed to write the card.");
+//! ```
+
+use super::number;
+
+use base64::Engine;
+use std::error;
+use std::fmt::{self, Display, Formatter, Write};
+use ttf_parser::{Face, FaceParsingError, GlyphId, OutlineBuilder};
+
+/// The font family name an embedded font is declared under.
+pub(crate) const EMBEDDED_FAMILY: &str = "wifi-qr-code-embedded";
+
+/// Errors when loading a font.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontError {
+    /// The data is not a TrueType or OpenType font.
+    Parse(FaceParsingError),
+}
+
+impl Display for FontError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(error) => write!(f, "failed to parse the font: {}", error),
+        }
+    }
+}
+
+impl error::Error for FontError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Parse(error) => Some(error),
+        }
+    }
+}
+
+impl From<FaceParsingError> for FontError {
+    fn from(error: FaceParsingError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+/// How a font is included in the card.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FontMode {
+    /// Embed the whole font file into the card. The text stays text, so it can be selected and searched.
+    Embed,
+    /// Convert the text into paths of the glyph outlines. The card no longer contains any text or font.
+    Outline,
+}
+
+/// A TrueType or OpenType font that all text of a card is set in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Font {
+    data: Vec<u8>,
+    mode: FontMode,
+}
+
+impl Font {
+    /// Load a font from the contents of a `.ttf` or `.otf` file. For font collections, the first font is used.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::font::{Font, FontMode};
+    ///
+    /// assert!(Font::new(b"not a font".to_vec(), FontMode::Embed).is_err());
+    /// ```
+    pub fn new(data: Vec<u8>, mode: FontMode) -> Result<Self, FontError> {
+        Face::parse(&data, 0)?;
+        Ok(Font { data, mode })
+    }
+
+    /// How the font is included in the card.
+    pub fn mode(&self) -> FontMode {
+        self.mode
+    }
+
+    /// The `<style>` element declaring an embedded font, or nothing for outlined fonts.
+    pub(crate) fn style(&self) -> String {
+        match self.mode {
+            FontMode::Embed => {
+                let media_type = match self.data.get(..4) {
+                    Some(b"OTTO") => "font/otf",
+                    Some(b"ttcf") => "font/collection",
+                    _ => "font/ttf",
+                };
+                format!(
+                    "\t<style>@font-face{{font-family:\"{}\";src:url(data:{};base64,{})}}</style>\n",
+                    EMBEDDED_FAMILY,
+                    media_type,
+                    base64::engine::general_purpose::STANDARD.encode(&self.data)
+                )
+            }
+            FontMode::Outline => String::new(),
+        }
+    }
+
+    /// The path data of the text set at the size, centered on `x` with its baseline at `y`. Characters missing from the font are drawn as its placeholder glyph.
+    pub(crate) fn outline(&self, text: &str, size: f64, x: f64, y: f64) -> String {
+        let face = match Face::parse(&self.data, 0) {
+            Ok(face) => face,
+            Err(_) => return String::new(),
+        };
+        let scale = size / f64::from(face.units_per_em());
+        let glyphs: Vec<GlyphId> = text
+            .chars()
+            .map(|character| face.glyph_index(character).unwrap_or(GlyphId(0)))
+            .collect();
+        let advance = |glyph| f64::from(face.glyph_hor_advance(glyph).unwrap_or(0)) * scale;
+        let width: f64 = glyphs.iter().copied().map(advance).sum();
+        let mut path = SvgPath {
+            data: String::new(),
+            x: x - width / 2.0,
+            y,
+            scale,
+        };
+        for glyph in glyphs {
+            face.outline_glyph(glyph, &mut path);
+            path.x += advance(glyph);
+        }
+        path.data
+    }
+}
+
+/// Collects glyph outlines as SVG path data, moving the origin of each glyph to the pen position and flipping the y axis, which points up in fonts.
+struct SvgPath {
+    data: String,
+    x: f64,
+    y: f64,
+    scale: f64,
+}
+
+impl SvgPath {
+    fn point(&mut self, x: f32, y: f32) {
+        let _ = write!(
+            self.data,
+            "{} {}",
+            number(self.x + f64::from(x) * self.scale),
+            number(self.y - f64::from(y) * self.scale)
+        );
+    }
+}
+
+impl OutlineBuilder for SvgPath {
+    fn move_to(&mut self, x: f32, y: f32) {
+        self.data.push('M');
+        self.point(x, y);
+    }
+
+    fn line_to(&mut self, x: f32, y: f32) {
+        self.data.push('L');
+        self.point(x, y);
+    }
+
+    fn quad_to(&mut self, x1: f32, y1: f32, x: f32, y: f32) {
+        self.data.push('Q');
+        self.point(x1, y1);
+        self.data.push(' ');
+        self.point(x, y);
+    }
+
+    fn curve_to(&mut self, x1: f32, y1: f32, x2: f32, y2: f32, x: f32, y: f32) {
+        self.data.push('C');
+        self.point(x1, y1);
+        self.data.push(' ');
+        self.point(x2, y2);
+        self.data.push(' ');
+        self.point(x, y);
+    }
+
+    fn close(&mut self) {
+        self.data.push('Z');
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A TrueType font with a single 500 by 700 unit square glyph on a 1000 unit em square, advancing 600 units, which every character maps to.
+    pub(crate) fn square_font() -> Vec<u8> {
+        let glyph: Vec<u8> = [
+            &1i16.to_be_bytes()[..],
+            &[0, 0, 0, 0, 0x01, 0xf4, 0x02, 0xbc],
+            &3u16.to_be_bytes(),
+            &0u16.to_be_bytes(),
+            &[0x01; 4],
+            // The x and y coordinates, as deltas from the previous point.
+            &[0, 0, 0x01, 0xf4, 0, 0, 0xfe, 0x0c],
+            &[0, 0, 0, 0, 0x02, 0xbc, 0, 0],
+        ]
+        .concat();
+        let mut head = vec![
+            0, 1, 0, 0, 0, 1, 0, 0, 0, 0, 0, 0, 0x5f, 0x0f, 0x3c, 0xf5, 0, 0,
+        ];
+        head.extend_from_slice(&1000u16.to_be_bytes());
+        head.extend_from_slice(&[0; 16]);
+        head.extend_from_slice(&[0, 0, 0, 0, 0x01, 0xf4, 0x02, 0xbc]);
+        head.extend_from_slice(&[0, 0, 0, 8, 0, 2, 0, 0, 0, 0]);
+        let mut hhea = vec![0, 1, 0, 0];
+        hhea.extend_from_slice(&[0x03, 0x20, 0xff, 0x38]);
+        hhea.extend_from_slice(&[0; 26]);
+        hhea.extend_from_slice(&1u16.to_be_bytes());
+        let hmtx = [0x02, 0x58, 0, 0].to_vec();
+        let loca = [0, 0, 0, (glyph.len() / 2) as u8].to_vec();
+        let maxp = [0, 0, 0x50, 0, 0, 1].to_vec();
+        let tables = [
+            (b"glyf", glyph),
+            (b"head", head),
+            (b"hhea", hhea),
+            (b"hmtx", hmtx),
+            (b"loca", loca),
+            (b"maxp", maxp),
+        ];
+
+        let mut font = vec![0, 1, 0, 0, 0, tables.len() as u8, 0, 0, 0, 0, 0, 0];
+        let mut offset = 12 + 16 * tables.len();
+        let mut data = Vec::new();
+        for (tag, table) in &tables {
+            font.extend_from_slice(*tag);
+            font.extend_from_slice(&[0; 4]);
+            font.extend_from_slice(&(offset as u32).to_be_bytes());
+            font.extend_from_slice(&(table.len() as u32).to_be_bytes());
+            data.extend_from_slice(table);
+            while data.len() % 4 != 0 {
+                data.push(0);
+            }
+            offset = 12 + 16 * tables.len() + data.len();
+        }
+        font.extend_from_slice(&data);
+        font
+    }
+
+    #[test]
+    fn it_outlines_centered_text() {
+        let font = Font::new(square_font(), FontMode::Outline).unwrap();
+        assert_eq!("", font.style());
+        // Two glyphs advance 24 units at 20 units to the em, so the text starts 12 units left of the center.
+        assert_eq!(
+            "M188 480L198 480L198 466L188 466L188 480ZM200 480L210 480L210 466L200 466L200 480Z",
+            font.outline("ab", 20.0, 200.0, 480.0)
+        );
+    }
+
+    #[test]
+    fn it_embeds_fonts_as_data_urls() {
+        let font = Font::new(square_font(), FontMode::Embed).unwrap();
+        assert!(font.style().starts_with(
+            "\t<style>@font-face{font-family:\"wifi-qr-code-embedded\";src:url(data:font/ttf;base64,AAEAAA"
+        ));
+        assert!(matches!(
+            Font::new(vec![0; 12], FontMode::Embed),
+            Err(FontError::Parse(_))
+        ));
+    }
+}
@@ -0,0 +1,356 @@
+//! Print jobs submitted straight to network printers over the Internet Printing Protocol, without going through CUPS or a print dialog, so unattended devices such as kiosks can reprint a card whenever the network changes.
+//!
+//! Most network printers accept IPP on port 631, at a URI such as `ipp://printer.local/ipp/print`. Only unencrypted `ipp://` and `http://` URIs are supported, since printers on local networks rarely have certificates that could be verified.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wifi_qr_code::print::escpos::Receipt;
+//! use wifi_qr_code::print::ipp::{DocumentFormat, Printer};
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+//! let receipt = Receipt::default()
+//!     .encode(&wifi_credentials)
+//!     .expect("Failed to encode the receipt.");
+//! let job_id = Printer::new("ipp://receipt-printer.local/ipp/print")
+//!     .print("Wi-Fi", &receipt, DocumentFormat::Raw)
+//!     .expect("Failed to print the receipt.");
+//! println!("Printing job {}", job_id);
+//! ```
+
+use std::convert::TryInto;
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, Read};
+use std::time::Duration;
+
+/// The port IPP printers listen on when their URI does not name one.
+const DEFAULT_PORT: u16 = 631;
+
+/// The `Print-Job` operation.
+const PRINT_JOB: u16 = 0x0002;
+
+/// Delimiter and value tags of IPP attributes, from RFC 8010.
+const OPERATION_ATTRIBUTES: u8 = 0x01;
+const END_OF_ATTRIBUTES: u8 = 0x03;
+const INTEGER: u8 = 0x21;
+const TEXT_WITHOUT_LANGUAGE: u8 = 0x41;
+const NAME_WITHOUT_LANGUAGE: u8 = 0x42;
+const URI: u8 = 0x45;
+const CHARSET: u8 = 0x47;
+const NATURAL_LANGUAGE: u8 = 0x48;
+const MIME_MEDIA_TYPE: u8 = 0x49;
+
+/// Errors when submitting a print job.
+#[derive(Debug)]
+pub enum IppError {
+    /// The printer could not be reached, or rejected the HTTP request.
+    Http(Box<ureq::Error>),
+    /// The response could not be read.
+    Io(io::Error),
+    /// The URI of the printer does not use the `ipp` or `http` scheme.
+    UnsupportedUri(String),
+    /// The printer refused the job.
+    Rejected {
+        /// The IPP status code, such as `0x040a` for a document format the printer does not support.
+        status: u16,
+        /// The reason the printer gave, if any.
+        message: Option<String>,
+    },
+    /// The response was not a valid IPP response.
+    UnexpectedResponse(String),
+}
+
+impl Display for IppError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Http(error) => write!(f, "failed to reach the printer: {}", error),
+            Self::Io(error) => write!(f, "failed to read the response: {}", error),
+            Self::UnsupportedUri(uri) => write!(f, "unsupported printer URI: {}", uri),
+            Self::Rejected {
+                status,
+                message: Some(message),
+            } => write!(
+                f,
+                "the printer refused the job with status {:#06x}: {}",
+                status, message
+            ),
+            Self::Rejected {
+                status,
+                message: None,
+            } => write!(f, "the printer refused the job with status {:#06x}", status),
+            Self::UnexpectedResponse(reason) => {
+                write!(f, "unexpected response from the printer: {}", reason)
+            }
+        }
+    }
+}
+
+impl error::Error for IppError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Http(error) => Some(error.as_ref()),
+            Self::Io(error) => Some(error),
+            _ => None,
+        }
+    }
+}
+
+impl From<io::Error> for IppError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// The format of a printed document, which the printer has to support.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum DocumentFormat {
+    /// A PDF document, such as a sheet of labels or a table tent. Supported by most office and IPP Everywhere printers.
+    Pdf,
+    /// A PNG image.
+    Png,
+    /// Commands in the language of the printer, such as ESC/POS or a Brother QL raster job, which are passed through to it unchanged.
+    Raw,
+}
+
+impl DocumentFormat {
+    /// The media type of the format.
+    fn media_type(self) -> &'static str {
+        match self {
+            Self::Pdf => "application/pdf",
+            Self::Png => "image/png",
+            Self::Raw => "application/octet-stream",
+        }
+    }
+}
+
+/// A network printer that accepts IPP print jobs.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Printer {
+    /// The URI of the printer, such as `ipp://printer.local/ipp/print`.
+    pub uri: String,
+    /// The user that jobs are submitted as, which printers show in their job lists.
+    pub user_name: String,
+}
+
+impl Printer {
+    /// The printer at the URI, submitting jobs as `wifi-qr-code`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::ipp::Printer;
+    ///
+    /// let printer = Printer {
+    ///     user_name: String::from("kiosk"),
+    ///     ..Printer::new("ipp://192.168.1.20/ipp/print")
+    /// };
+    /// ```
+    pub fn new(uri: impl Into<String>) -> Self {
+        Printer {
+            uri: uri.into(),
+            user_name: String::from("wifi-qr-code"),
+        }
+    }
+
+    /// Submit the document as a job with the name, and return the ID the printer assigned to the job. The job is queued when this returns, not necessarily printed.
+    pub fn print(
+        &self,
+        job_name: &str,
+        document: &[u8],
+        format: DocumentFormat,
+    ) -> Result<u32, IppError> {
+        let url = http_url(&self.uri)?;
+        let request = self.request(job_name, document, format);
+        let agent = ureq::AgentBuilder::new()
+            .timeout(Duration::from_secs(30))
+            .build();
+        let response = agent
+            .post(&url)
+            .set("Content-Type", "application/ipp")
+            .send_bytes(&request)
+            .map_err(|error| IppError::Http(Box::new(error)))?;
+        let mut body = Vec::new();
+        response.into_reader().read_to_end(&mut body)?;
+        parse_response(&body)
+    }
+
+    /// The `Print-Job` request for the document.
+    fn request(&self, job_name: &str, document: &[u8], format: DocumentFormat) -> Vec<u8> {
+        let mut request = vec![2, 0];
+        request.extend_from_slice(&PRINT_JOB.to_be_bytes());
+        request.extend_from_slice(&1u32.to_be_bytes());
+        request.push(OPERATION_ATTRIBUTES);
+        for (tag, name, value) in &[
+            (CHARSET, "attributes-charset", "utf-8"),
+            (NATURAL_LANGUAGE, "attributes-natural-language", "en"),
+            (URI, "printer-uri", self.uri.as_str()),
+            (
+                NAME_WITHOUT_LANGUAGE,
+                "requesting-user-name",
+                self.user_name.as_str(),
+            ),
+            (NAME_WITHOUT_LANGUAGE, "job-name", job_name),
+            (MIME_MEDIA_TYPE, "document-format", format.media_type()),
+        ] {
+            request.push(*tag);
+            for field in &[name.as_bytes(), value.as_bytes()] {
+                // Lengths are limited to 16 bits, which only an absurdly long job name could exceed.
+                let length = field.len().min(usize::from(u16::MAX));
+                request.extend_from_slice(&(length as u16).to_be_bytes());
+                request.extend_from_slice(&field[..length]);
+            }
+        }
+        request.push(END_OF_ATTRIBUTES);
+        request.extend_from_slice(document);
+        request
+    }
+}
+
+/// The HTTP URL that IPP requests for the printer are posted to.
+fn http_url(uri: &str) -> Result<String, IppError> {
+    let (scheme, rest) = uri
+        .split_once("://")
+        .ok_or_else(|| IppError::UnsupportedUri(uri.to_string()))?;
+    if scheme.eq_ignore_ascii_case("http") {
+        return Ok(uri.to_string());
+    }
+    if !scheme.eq_ignore_ascii_case("ipp") {
+        return Err(IppError::UnsupportedUri(uri.to_string()));
+    }
+    let (authority, path) = match rest.find('/') {
+        Some(index) => rest.split_at(index),
+        None => (rest, "/"),
+    };
+    // IPv6 addresses are in brackets and contain colons of their own.
+    let has_port = authority
+        .rsplit_once(':')
+        .is_some_and(|(_, port)| !port.contains(']'));
+    if has_port {
+        Ok(format!("http://{}{}", authority, path))
+    } else {
+        Ok(format!("http://{}:{}{}", authority, DEFAULT_PORT, path))
+    }
+}
+
+/// The job ID in a `Print-Job` response, or the error the printer reported.
+fn parse_response(response: &[u8]) -> Result<u32, IppError> {
+    let truncated = || IppError::UnexpectedResponse(String::from("the response is truncated"));
+    let status = response
+        .get(2..4)
+        .ok_or_else(truncated)
+        .map(|status| u16::from_be_bytes([status[0], status[1]]))?;
+    let mut job_id = None;
+    let mut message = None;
+    let mut position = 8;
+    loop {
+        let tag = *response.get(position).ok_or_else(truncated)?;
+        position += 1;
+        if tag == END_OF_ATTRIBUTES {
+            break;
+        }
+        if tag < 0x10 {
+            // The start of another attribute group.
+            continue;
+        }
+        let mut field = || -> Result<&[u8], IppError> {
+            let length = response.get(position..position + 2).ok_or_else(truncated)?;
+            let length = usize::from(u16::from_be_bytes([length[0], length[1]]));
+            let field = response
+                .get(position + 2..position + 2 + length)
+                .ok_or_else(truncated)?;
+            position += 2 + length;
+            Ok(field)
+        };
+        let name = field()?;
+        let value = field()?;
+        match (tag, name) {
+            (INTEGER, b"job-id") => {
+                let value: [u8; 4] = value.try_into().map_err(|_| {
+                    IppError::UnexpectedResponse(String::from("the job ID is not an integer"))
+                })?;
+                job_id = Some(u32::from_be_bytes(value));
+            }
+            (TEXT_WITHOUT_LANGUAGE, b"status-message") => {
+                message = Some(String::from_utf8_lossy(value).into_owned());
+            }
+            _ => {}
+        }
+    }
+    // Status codes from 0x0000 to 0x00ff are successful, possibly with attributes ignored.
+    if status > 0x00ff {
+        return Err(IppError::Rejected { status, message });
+    }
+    job_id.ok_or_else(|| IppError::UnexpectedResponse(String::from("the response has no job ID")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_encodes_print_job_requests() {
+        let printer = Printer::new("ipp://printer/ipp/print");
+        let request = printer.request("Wi-Fi", b"%PDF", DocumentFormat::Pdf);
+        assert_eq!(&[2, 0, 0, 2, 0, 0, 0, 1, 1], &request[..9]);
+        // Each attribute is a value tag followed by its length prefixed name and value.
+        assert_eq!(
+            b"\x47\x00\x12attributes-charset\x00\x05utf-8",
+            &request[9..37]
+        );
+        let job_name = b"\x42\x00\x08job-name\x00\x05Wi-Fi";
+        assert!(request
+            .windows(job_name.len())
+            .any(|window| window == job_name));
+        assert!(request.ends_with(b"\x03%PDF"));
+    }
+
+    #[test]
+    fn it_maps_printer_uris_to_http() {
+        assert_eq!(
+            "http://printer:631/ipp/print",
+            http_url("ipp://printer/ipp/print").unwrap()
+        );
+        assert_eq!(
+            "http://[fe80::1]:8631/",
+            http_url("ipp://[fe80::1]:8631").unwrap()
+        );
+        assert_eq!(
+            "http://[fe80::1]:631/",
+            http_url("ipp://[fe80::1]").unwrap()
+        );
+        assert!(matches!(
+            http_url("ipps://printer/ipp/print"),
+            Err(IppError::UnsupportedUri(_))
+        ));
+    }
+
+    #[test]
+    fn it_reads_job_ids_and_errors_from_responses() {
+        let response = [
+            &[2, 0, 0, 0, 0, 0, 0, 1, 1][..],
+            b"\x47\x00\x12attributes-charset\x00\x05utf-8",
+            b"\x02\x21\x00\x06job-id\x00\x04\x00\x00\x00\x2a\x03",
+        ]
+        .concat();
+        assert_eq!(42, parse_response(&response).unwrap());
+
+        let response = [
+            &[2, 0, 0x04, 0x0a, 0, 0, 0, 1, 1][..],
+            b"\x41\x00\x0estatus-message\x00\x12format unsupported\x03",
+        ]
+        .concat();
+        match parse_response(&response) {
+            Err(IppError::Rejected { status, message }) => {
+                assert_eq!(0x040a, status);
+                assert_eq!(Some(String::from("format unsupported")), message);
+            }
+            result => panic!("Unexpected result: {:?}", result),
+        }
+        assert!(matches!(
+            parse_response(&[2, 0, 0, 0]),
+            Err(IppError::UnexpectedResponse(_))
+        ));
+    }
+}
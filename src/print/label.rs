@@ -0,0 +1,281 @@
+//! Print jobs for label printers, laid out at the raster size of the label media so the QR code and the captions come out at full resolution without being scaled by a driver. The jobs can be written to the printer directly, e.g. to `/dev/usb/lp0` or to port 9100 of a network printer.
+//!
+//! The captions use a built-in 8 by 8 dot font covering ASCII and Latin-1, and long lines are wrapped to the width of the label.
+
+use super::bitmap::{wrap, Bitmap, GLYPH_SIZE};
+use super::{captions, Locale};
+use crate::{Error, QRCodeError, QrCodeEcc, WifiCredentials};
+
+/// The quiet zone around the QR code, in modules.
+const QUIET_ZONE: usize = 2;
+/// How much the font is scaled up, which makes captions about 2 millimeters tall at 300 dpi.
+const TEXT_SCALE: usize = 3;
+/// The height of a line of caption text, in dots.
+const LINE_HEIGHT: usize = (GLYPH_SIZE + 2) * TEXT_SCALE;
+
+/// Label media with a known raster size, all printed at 300 dpi.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Media {
+    /// Brother DK-22205 62 mm continuous paper tape, for the QL-500 to QL-820 series. The label is cut to the length of its contents, with the QR code above the captions.
+    Brother62mmEndless,
+    /// DYMO 99012 large address labels, 36 by 89 mm, for LabelWriter 400 and 450 printers. The QR code is printed to the left of the captions, reading along the label.
+    Dymo99012,
+}
+
+impl Media {
+    /// The width of the printable area in dots, across the print head.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::label::Media;
+    ///
+    /// assert_eq!(696, Media::Brother62mmEndless.width());
+    /// ```
+    pub fn width(self) -> usize {
+        match self {
+            Self::Brother62mmEndless => 696,
+            Self::Dymo99012 => 425,
+        }
+    }
+
+    /// The length of the label in dots along the feed direction, or `None` for endless media.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::label::Media;
+    ///
+    /// assert_eq!(Some(1050), Media::Dymo99012.length());
+    /// assert_eq!(None, Media::Brother62mmEndless.length());
+    /// ```
+    pub fn length(self) -> Option<usize> {
+        match self {
+            Self::Brother62mmEndless => None,
+            Self::Dymo99012 => Some(1050),
+        }
+    }
+}
+
+/// The settings of a printed label.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Label {
+    /// The media loaded in the printer, which also selects the printer command set.
+    pub media: Media,
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The language of the text on the label.
+    pub locale: Locale,
+}
+
+impl Label {
+    /// An English label on the media with a QR code using medium error correction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::label::{Label, Media};
+    /// use wifi_qr_code::QrCodeEcc;
+    ///
+    /// let label = Label {
+    ///     qr_code_error_checking: QrCodeEcc::High,
+    ///     ..Label::new(Media::Dymo99012)
+    /// };
+    /// ```
+    pub fn new(media: Media) -> Self {
+        Label {
+            media,
+            qr_code_error_checking: QrCodeEcc::Medium,
+            locale: Locale::English,
+        }
+    }
+
+    /// Encode a print job for one label: Brother QL raster commands for Brother media, and LabelWriter commands for DYMO media.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wifi_qr_code::print::label::{Label, Media};
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// use std::io::Write;
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let print_job = Label::new(Media::Brother62mmEndless)
+    ///     .print_job(&wifi_credentials)
+    ///     .expect("Failed to encode example label.");
+    /// std::fs::OpenOptions::new()
+    ///     .write(true)
+    ///     .open("/dev/usb/lp0")
+    ///     .and_then(|mut printer| printer.write_all(&print_job))
+    ///     .expect("Failed to send the label to the printer.");
+    /// ```
+    pub fn print_job(&self, wifi_credentials: &WifiCredentials) -> Result<Vec<u8>, Error> {
+        let bitmap = self.layout(wifi_credentials)?;
+        Ok(match self.media {
+            Media::Brother62mmEndless => brother_ql(&bitmap, 62),
+            Media::Dymo99012 => labelwriter(&bitmap.rotated()),
+        })
+    }
+
+    /// Lay the label out as it is read: 62 mm labels are as wide as the tape, and DYMO labels are as tall as they are wide across the print head.
+    fn layout(&self, wifi_credentials: &WifiCredentials) -> Result<Bitmap, Error> {
+        let qr_code = crate::generate_symbol(
+            &crate::encode_payload(wifi_credentials)?,
+            self.qr_code_error_checking,
+        )?;
+        let modules = qr_code.size() as usize + 2 * QUIET_ZONE;
+        let dots_per_module = self.media.width() / modules;
+        if dots_per_module == 0 {
+            return Err(Error::QrCode(QRCodeError::ImageSizeTooSmall));
+        }
+        let qr_code_size = modules * dots_per_module;
+        let quiet_zone = QUIET_ZONE * dots_per_module;
+        // Captions go under the QR code on endless media and to its right on fixed size labels, lined up with the edge of the code.
+        let (width, height, text_left, text_top) = match self.media.length() {
+            None => (self.media.width(), qr_code_size, quiet_zone, qr_code_size),
+            Some(length) => (length, self.media.width(), qr_code_size, quiet_zone),
+        };
+        let characters_per_line = (width - text_left - quiet_zone) / (GLYPH_SIZE * TEXT_SCALE);
+        let [network, password] = captions(wifi_credentials, self.locale);
+        let lines: Vec<String> = [self.locale.message("instructions", &[]), network, password]
+            .iter()
+            .flat_map(|caption| wrap(caption, characters_per_line))
+            .collect();
+        let height = match self.media.length() {
+            None => text_top + lines.len() * LINE_HEIGHT + quiet_zone,
+            Some(_) => height,
+        };
+        let mut bitmap = Bitmap::new(width, height);
+        let offset = (self.media.width() - qr_code_size) / 2;
+        let (qr_code_left, qr_code_top) = match self.media.length() {
+            None => (offset + quiet_zone, quiet_zone),
+            Some(_) => (quiet_zone, offset + quiet_zone),
+        };
+        bitmap.draw_qr_code(&qr_code, qr_code_left, qr_code_top, dots_per_module);
+        for (index, line) in lines.iter().enumerate() {
+            bitmap.draw_text(line, text_left, text_top + index * LINE_HEIGHT, TEXT_SCALE);
+        }
+        Ok(bitmap)
+    }
+}
+
+/// The number of pins of the print head of QL printers with standard width tape.
+const BROTHER_PINS: usize = 720;
+/// The pins to the right of the printable area of 62 mm tape.
+const BROTHER_RIGHT_MARGIN: usize = 12;
+
+/// Brother QL raster commands for a label on continuous tape of the width in millimeters, cut after printing.
+fn brother_ql(bitmap: &Bitmap, tape_width: u8) -> Vec<u8> {
+    // Clear any partial command left in the printer, reset it, and switch to raster mode.
+    let mut commands = vec![0; 200];
+    commands.extend_from_slice(b"\x1b@\x1bia\x01");
+    // The print information command marks the media type, width, and length as valid, for continuous tape with the number of raster lines.
+    commands.extend_from_slice(&[0x1b, b'i', b'z', 0x8e, 0x0a, tape_width, 0]);
+    commands.extend_from_slice(&(bitmap.height as u32).to_le_bytes());
+    commands.extend_from_slice(&[0, 0]);
+    // Cut automatically after every label, at the end of the job, with the default 3 mm feed margin of continuous tape.
+    commands.extend_from_slice(b"\x1biM\x40\x1biA\x01\x1biK\x08\x1bid\x23\x00");
+    for y in 0..bitmap.height {
+        // The first pin is at the right hand side of the label, so rows are sent mirrored.
+        let mut row = [0u8; BROTHER_PINS / 8];
+        for x in 0..bitmap.width {
+            if bitmap.get(x, y) {
+                let pin = BROTHER_PINS - 1 - BROTHER_RIGHT_MARGIN - x;
+                row[pin / 8] |= 0x80 >> (pin % 8);
+            }
+        }
+        commands.extend_from_slice(&[b'g', 0, row.len() as u8]);
+        commands.extend_from_slice(&row);
+    }
+    // Print and feed.
+    commands.push(0x1a);
+    commands
+}
+
+/// DYMO LabelWriter commands for a label, with the bitmap rows running across the print head.
+fn labelwriter(bitmap: &Bitmap) -> Vec<u8> {
+    let bytes_per_line = bitmap.width.div_ceil(8);
+    // Reset, then set the dot tab, the bytes per line, the label length, and 300 by 300 dpi text mode.
+    let mut commands = b"\x1b@\x1bB\x00\x1bD".to_vec();
+    commands.push(bytes_per_line as u8);
+    commands.extend_from_slice(b"\x1bL");
+    commands.extend_from_slice(&(bitmap.height as u16).to_be_bytes());
+    commands.extend_from_slice(b"\x1bh");
+    for y in 0..bitmap.height {
+        commands.push(0x16);
+        commands.extend_from_slice(&bitmap.packed_row(y));
+    }
+    // Feed the label to the tear bar.
+    commands.extend_from_slice(b"\x1bE");
+    commands
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::convert::TryInto;
+
+    #[test]
+    fn it_encodes_brother_ql_raster_jobs() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        let print_job = Label::new(Media::Brother62mmEndless)
+            .print_job(&wifi_credentials)
+            .unwrap();
+        assert!(print_job[..200].iter().all(|byte| *byte == 0));
+        assert_eq!(
+            b"\x1b@\x1bia\x01\x1biz\x8e\x0a\x3e\x00",
+            &print_job[200..213]
+        );
+        let lines = u32::from_le_bytes(print_job[213..217].try_into().unwrap()) as usize;
+        let header = 200 + 19 + 17;
+        assert_eq!(header + lines * 93 + 1, print_job.len());
+        assert!(print_job[header..]
+            .chunks(93)
+            .take(lines)
+            .all(|line| line.starts_with(b"g\x00\x5a")));
+        // The 33 module code and its quiet zone are scaled by 18 to 666 dots, followed by the instructions and two caption lines.
+        assert_eq!(666 + 3 * 30 + 36, lines);
+        assert_eq!(Some(&0x1a), print_job.last());
+    }
+
+    #[test]
+    fn it_encodes_labelwriter_jobs_across_the_label() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        let print_job = Label::new(Media::Dymo99012)
+            .print_job(&wifi_credentials)
+            .unwrap();
+        assert_eq!(
+            b"\x1b@\x1bB\x00\x1bD\x36\x1bL\x04\x1a\x1bh",
+            &print_job[..14]
+        );
+        assert_eq!(14 + 1050 * 55 + 2, print_job.len());
+        // The label starts with the quiet zone of the QR code, two modules of 11 dots.
+        assert!(print_job[14..14 + 55 * 22]
+            .chunks(55)
+            .all(|line| line[0] == 0x16 && line[1..].iter().all(|byte| *byte == 0)));
+        assert!(print_job.ends_with(b"\x1bE"));
+    }
+
+    #[test]
+    fn it_wraps_captions_to_the_label_width() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "a".repeat(63));
+        let bitmap = Label::new(Media::Brother62mmEndless)
+            .layout(&wifi_credentials)
+            .unwrap();
+        let modules = crate::generate_symbol(
+            &crate::encode_payload(&wifi_credentials).unwrap(),
+            QrCodeEcc::Medium,
+        )
+        .unwrap()
+        .size() as usize
+            + 2 * QUIET_ZONE;
+        let dots_per_module = 696 / modules;
+        // The password caption is 73 characters long, which takes three lines of up to 27 characters after the instructions and the network name.
+        assert_eq!(
+            (modules + QUIET_ZONE) * dots_per_module + 5 * LINE_HEIGHT,
+            bitmap.height
+        );
+    }
+}
@@ -0,0 +1,153 @@
+use std::fmt::{self, Display, Formatter};
+
+/// The language of the text on printed cards, slips, and labels, and on landing pages.
+///
+/// The text comes from message catalogs in a subset of the [Fluent](https://projectfluent.org) syntax, one `id = text` message per line with `{ $name }` placeholders, in `src/print/locales`. Adding a language is a matter of translating `en.ftl` and adding a variant here.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[non_exhaustive]
+pub enum Locale {
+    /// English, the default.
+    English,
+    /// German.
+    German,
+    /// French.
+    French,
+    /// Spanish.
+    Spanish,
+    /// Italian.
+    Italian,
+    /// Dutch.
+    Dutch,
+}
+
+/// Every locale, in the order of the variants.
+const LOCALES: [Locale; 6] = [
+    Locale::English,
+    Locale::German,
+    Locale::French,
+    Locale::Spanish,
+    Locale::Italian,
+    Locale::Dutch,
+];
+
+impl Locale {
+    /// The locale for a language tag such as `de`, `fr-CA`, or a POSIX locale such as `nl_BE.UTF-8`, as found in the `LANG` environment variable. Only the language is matched, and `None` is returned for languages without a catalog.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::Locale;
+    ///
+    /// assert_eq!(Some(Locale::German), Locale::from_tag("de-AT"));
+    /// assert_eq!(Some(Locale::French), Locale::from_tag("fr_FR.UTF-8"));
+    /// assert_eq!(None, Locale::from_tag("tlh"));
+    /// ```
+    pub fn from_tag(tag: &str) -> Option<Self> {
+        let language = tag.split(['-', '_', '.', '@']).next().unwrap_or_default();
+        LOCALES
+            .iter()
+            .copied()
+            .find(|locale| locale.tag().eq_ignore_ascii_case(language))
+    }
+
+    /// The language tag of the locale, such as `en`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::Locale;
+    ///
+    /// assert_eq!("es", Locale::Spanish.tag());
+    /// ```
+    pub fn tag(self) -> &'static str {
+        match self {
+            Self::English => "en",
+            Self::German => "de",
+            Self::French => "fr",
+            Self::Spanish => "es",
+            Self::Italian => "it",
+            Self::Dutch => "nl",
+        }
+    }
+
+    /// The message catalog of the locale.
+    fn catalog(self) -> &'static str {
+        match self {
+            Self::English => include_str!("locales/en.ftl"),
+            Self::German => include_str!("locales/de.ftl"),
+            Self::French => include_str!("locales/fr.ftl"),
+            Self::Spanish => include_str!("locales/es.ftl"),
+            Self::Italian => include_str!("locales/it.ftl"),
+            Self::Dutch => include_str!("locales/nl.ftl"),
+        }
+    }
+
+    /// The message with the identifier, with its placeholders filled in from the arguments. Messages missing from the catalog fall back to English.
+    pub(crate) fn message(self, id: &str, arguments: &[(&str, &str)]) -> String {
+        let pattern = find_message(self.catalog(), id)
+            .or_else(|| find_message(Locale::English.catalog(), id))
+            .unwrap_or(id);
+        arguments
+            .iter()
+            .fold(pattern.to_string(), |message, (name, value)| {
+                message.replace(&format!("{{ ${} }}", name), value)
+            })
+    }
+}
+
+impl Display for Locale {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str(self.tag())
+    }
+}
+
+/// The text of a message in a catalog, skipping comments and blank lines.
+fn find_message<'a>(catalog: &'a str, id: &str) -> Option<&'a str> {
+    catalog
+        .lines()
+        .filter(|line| !line.starts_with('#'))
+        .filter_map(|line| line.split_once('='))
+        .find(|(message_id, _)| message_id.trim() == id)
+        .map(|(_, pattern)| pattern.trim())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_translates_every_message() {
+        let ids: Vec<&str> = Locale::English
+            .catalog()
+            .lines()
+            .filter(|line| !line.starts_with('#'))
+            .filter_map(|line| line.split_once('='))
+            .map(|(id, _)| id.trim())
+            .collect();
+        assert_eq!(11, ids.len());
+        for locale in &LOCALES {
+            assert_eq!(Some(*locale), Locale::from_tag(locale.tag()));
+            for id in &ids {
+                let pattern = find_message(locale.catalog(), id);
+                assert!(pattern.is_some(), "{} is missing {}", locale, id);
+                let english = find_message(Locale::English.catalog(), id).unwrap_or_default();
+                assert_eq!(
+                    english.matches("{ $").count(),
+                    pattern.unwrap_or_default().matches("{ $").count(),
+                    "{} has different placeholders in {}",
+                    locale,
+                    id
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn it_fills_in_placeholders() {
+        assert_eq!(
+            "Réseau : test ssid (masqué)",
+            Locale::French.message("hidden-network", &[("ssid", "test ssid")])
+        );
+        assert_eq!("unknown-id", Locale::German.message("unknown-id", &[]));
+    }
+}
@@ -0,0 +1,11 @@
+title = WLAN
+instructions = Zum Verbinden scannen
+network = Netzwerk: { $ssid }
+hidden-network = Netzwerk: { $ssid } (versteckt)
+password = Passwort: { $password }
+no-password = Kein Passwort
+instructions-ios = iPhone und iPad: Kamera-App öffnen, auf den Code richten und auf die erscheinende Mitteilung tippen.
+instructions-android = Android: Kamera-App oder Google Lens öffnen und auf den Code richten, oder in den WLAN-Einstellungen auf das QR-Code-Symbol tippen.
+instructions-windows = Windows: { $ssid } in der WLAN-Liste der Taskleiste auswählen.
+instructions-macos = Mac: { $ssid } im WLAN-Menü der Menüleiste auswählen.
+show-password = Passwort anzeigen
@@ -0,0 +1,12 @@
+# Text printed on cards, slips, and labels, and shown on landing pages. Every catalog defines the same messages; missing ones fall back to this catalog.
+title = Wi-Fi
+instructions = Scan to connect
+network = Network: { $ssid }
+hidden-network = Network: { $ssid } (hidden)
+password = Password: { $password }
+no-password = No password
+instructions-ios = iPhone and iPad: open the Camera app, point it at the code, and tap the notification that appears.
+instructions-android = Android: open the Camera app or Google Lens and point it at the code, or tap the QR code icon in the Wi-Fi settings.
+instructions-windows = Windows: choose { $ssid } in the Wi-Fi list of the taskbar.
+instructions-macos = Mac: choose { $ssid } in the Wi-Fi menu of the menu bar.
+show-password = Show password
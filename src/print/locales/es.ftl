@@ -0,0 +1,11 @@
+title = Wi-Fi
+instructions = Escanea para conectarte
+network = Red: { $ssid }
+hidden-network = Red: { $ssid } (oculta)
+password = Contraseña: { $password }
+no-password = Sin contraseña
+instructions-ios = iPhone y iPad: abre la app Cámara, apunta al código y toca la notificación que aparece.
+instructions-android = Android: abre la cámara o Google Lens y apunta al código, o toca el icono de código QR en los ajustes de Wi-Fi.
+instructions-windows = Windows: elige { $ssid } en la lista de Wi-Fi de la barra de tareas.
+instructions-macos = Mac: elige { $ssid } en el menú Wi-Fi de la barra de menús.
+show-password = Mostrar contraseña
@@ -0,0 +1,11 @@
+title = Wi-Fi
+instructions = Scannez pour vous connecter
+network = Réseau : { $ssid }
+hidden-network = Réseau : { $ssid } (masqué)
+password = Mot de passe : { $password }
+no-password = Pas de mot de passe
+instructions-ios = iPhone et iPad : ouvrez l’app Appareil photo, visez le code et touchez la notification qui apparaît.
+instructions-android = Android : ouvrez l’appareil photo ou Google Lens et visez le code, ou touchez l’icône de code QR dans les paramètres Wi-Fi.
+instructions-windows = Windows : choisissez { $ssid } dans la liste Wi-Fi de la barre des tâches.
+instructions-macos = Mac : choisissez { $ssid } dans le menu Wi-Fi de la barre des menus.
+show-password = Afficher le mot de passe
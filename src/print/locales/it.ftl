@@ -0,0 +1,11 @@
+title = Wi-Fi
+instructions = Scansiona per connetterti
+network = Rete: { $ssid }
+hidden-network = Rete: { $ssid } (nascosta)
+password = Password: { $password }
+no-password = Nessuna password
+instructions-ios = iPhone e iPad: apri l’app Fotocamera, inquadra il codice e tocca la notifica che compare.
+instructions-android = Android: apri la fotocamera o Google Lens e inquadra il codice, oppure tocca l’icona del codice QR nelle impostazioni Wi-Fi.
+instructions-windows = Windows: scegli { $ssid } nell’elenco Wi-Fi della barra delle applicazioni.
+instructions-macos = Mac: scegli { $ssid } nel menu Wi-Fi della barra dei menu.
+show-password = Mostra password
@@ -0,0 +1,11 @@
+title = Wifi
+instructions = Scan om verbinding te maken
+network = Netwerk: { $ssid }
+hidden-network = Netwerk: { $ssid } (verborgen)
+password = Wachtwoord: { $password }
+no-password = Geen wachtwoord
+instructions-ios = iPhone en iPad: open de Camera-app, richt op de code en tik op de melding die verschijnt.
+instructions-android = Android: open de camera of Google Lens en richt op de code, of tik op het QR-codesymbool in de wifi-instellingen.
+instructions-windows = Windows: kies { $ssid } in de wifilijst van de taakbalk.
+instructions-macos = Mac: kies { $ssid } in het wifimenu van de menubalk.
+show-password = Wachtwoord tonen
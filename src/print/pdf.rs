@@ -0,0 +1,328 @@
+use super::number;
+
+use qrcode_generator::qrcodegen::QrCode;
+use std::io::{self, Write};
+
+/// One of the standard fonts that every PDF reader provides, so none have to be embedded.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(crate) enum Font {
+    Helvetica,
+    Courier,
+}
+
+/// The advance widths of the printable ASCII characters in Helvetica, in thousandths of the font size.
+const HELVETICA_WIDTHS: [u16; 95] = [
+    278, 278, 355, 556, 556, 889, 667, 191, 333, 333, 389, 584, 278, 333, 278, 278, 556, 556, 556,
+    556, 556, 556, 556, 556, 556, 556, 278, 278, 584, 584, 584, 556, 1015, 667, 667, 722, 722, 667,
+    611, 778, 722, 278, 500, 667, 556, 833, 722, 778, 667, 778, 722, 667, 611, 722, 667, 944, 667,
+    667, 611, 278, 278, 278, 469, 556, 333, 556, 556, 500, 556, 556, 278, 556, 556, 222, 222, 500,
+    222, 833, 556, 556, 556, 556, 333, 500, 278, 556, 500, 722, 500, 500, 500, 334, 260, 334, 584,
+];
+
+impl Font {
+    /// The name the font is referred to by in page content.
+    fn resource(self) -> &'static str {
+        match self {
+            Self::Helvetica => "F1",
+            Self::Courier => "F2",
+        }
+    }
+
+    /// The width of the text when set in the font at the size. Accented letters are measured as an average lowercase letter.
+    pub(crate) fn text_width(self, text: &str, size: f64) -> f64 {
+        let width: u32 = text
+            .chars()
+            .map(|character| match self {
+                Self::Courier => 600,
+                Self::Helvetica => match character {
+                    ' '..='~' => u32::from(HELVETICA_WIDTHS[character as usize - 0x20]),
+                    _ => 556,
+                },
+            })
+            .sum();
+        f64::from(width) * size / 1000.0
+    }
+}
+
+/// A page of a PDF document. Coordinates are in points, 1/72 of an inch, measured from the top left corner of the page.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct Page {
+    pub(crate) width: f64,
+    pub(crate) height: f64,
+    /// The bleed and the distance from the edges of the page to the edges of the paper, for pages sent to commercial printers.
+    trim: Option<(f64, f64)>,
+    content: Vec<u8>,
+}
+
+impl Page {
+    /// A blank page.
+    pub(crate) fn new(width: f64, height: f64) -> Self {
+        Page {
+            width,
+            height,
+            trim: None,
+            content: Vec::new(),
+        }
+    }
+
+    /// A blank page for commercial printing, on paper that extends `slug` beyond the page on every side, with a bleed area of `bleed` in it. Everything is drawn in pure black ink of the CMYK color space rather than the gray that office printers use, which some presses would print as a mix of all four inks.
+    pub(crate) fn production(width: f64, height: f64, bleed: f64, slug: f64) -> Self {
+        Page {
+            width,
+            height,
+            trim: Some((bleed, slug)),
+            content: format!(
+                "1 0 0 1 {} {} cm 0 0 0 1 k 0 0 0 1 K\n",
+                number(slug),
+                number(slug)
+            )
+            .into_bytes(),
+        }
+    }
+
+    /// Draw the QR code as filled squares, with its top left module at the position and without a quiet zone.
+    pub(crate) fn qr_code(&mut self, qr_code: &QrCode, x: f64, y: f64, module_size: f64) {
+        for module_y in 0..qr_code.size() {
+            for module_x in 0..qr_code.size() {
+                if qr_code.get_module(module_x, module_y) {
+                    let left = x + f64::from(module_x) * module_size;
+                    let bottom = self.height - y - f64::from(module_y + 1) * module_size;
+                    self.content.extend_from_slice(
+                        format!(
+                            "{} {} {} {} re\n",
+                            number(left),
+                            number(bottom),
+                            number(module_size),
+                            number(module_size)
+                        )
+                        .as_bytes(),
+                    );
+                }
+            }
+        }
+        self.content.extend_from_slice(b"f\n");
+    }
+
+    /// Draw a line of text starting at `x`, with its baseline at `y`. Characters outside of Latin-1 are drawn as `?`.
+    pub(crate) fn text(&mut self, text: &str, font: Font, size: f64, x: f64, y: f64) {
+        self.content.extend_from_slice(
+            format!(
+                "BT /{} {} Tf {} {} Td (",
+                font.resource(),
+                number(size),
+                number(x),
+                number(self.height - y)
+            )
+            .as_bytes(),
+        );
+        for character in text.chars() {
+            match character {
+                '(' | ')' | '\\' => self.content.extend_from_slice(&[b'\\', character as u8]),
+                ' '..='~' | '\u{a0}'..='\u{ff}' => self.content.push(character as u32 as u8),
+                _ => self.content.push(b'?'),
+            }
+        }
+        self.content.extend_from_slice(b") Tj ET\n");
+    }
+
+    /// Draw a thin straight line, dashed or solid.
+    pub(crate) fn line(&mut self, from: (f64, f64), to: (f64, f64), dashed: bool) {
+        self.content.extend_from_slice(
+            format!(
+                "q 0.5 w [{}] 0 d {} {} m {} {} l S Q\n",
+                if dashed { "4 3" } else { "" },
+                number(from.0),
+                number(self.height - from.1),
+                number(to.0),
+                number(self.height - to.1)
+            )
+            .as_bytes(),
+        );
+    }
+
+    /// Draw upside down, turning everything drawn by the function half a turn around the point.
+    pub(crate) fn rotated(&mut self, x: f64, y: f64, draw: impl FnOnce(&mut Page)) {
+        self.content.extend_from_slice(
+            format!(
+                "q -1 0 0 -1 {} {} cm\n",
+                number(2.0 * x),
+                number(2.0 * (self.height - y))
+            )
+            .as_bytes(),
+        );
+        draw(self);
+        self.content.extend_from_slice(b"Q\n");
+    }
+
+    /// Draw crop marks at the corners of the rectangle, in line with its edges and pointing away from it, starting `gap` away from the corners so the marks do not show on the cut edges.
+    pub(crate) fn crop_marks(
+        &mut self,
+        x: f64,
+        y: f64,
+        width: f64,
+        height: f64,
+        gap: f64,
+        length: f64,
+    ) {
+        for &(corner_x, outward_x) in &[(x, -1.0), (x + width, 1.0)] {
+            for &(corner_y, outward_y) in &[(y, -1.0), (y + height, 1.0)] {
+                self.line(
+                    (corner_x + outward_x * gap, corner_y),
+                    (corner_x + outward_x * (gap + length), corner_y),
+                    false,
+                );
+                self.line(
+                    (corner_x, corner_y + outward_y * gap),
+                    (corner_x, corner_y + outward_y * (gap + length)),
+                    false,
+                );
+            }
+        }
+    }
+}
+
+/// Split text into lines no wider than `width` when set in the font at the size, breaking between any two characters.
+pub(crate) fn wrap_to_width(text: &str, font: Font, size: f64, width: f64) -> Vec<String> {
+    let mut lines = vec![String::new()];
+    for character in text.chars() {
+        let mut line = lines.pop().unwrap_or_default();
+        line.push(character);
+        if line.chars().count() > 1 && font.text_width(&line, size) > width {
+            line.pop();
+            lines.push(line);
+            lines.push(character.to_string());
+        } else {
+            lines.push(line);
+        }
+    }
+    lines
+}
+
+/// Write the pages as a PDF document.
+pub(crate) fn write(pages: &[Page], writer: impl Write) -> io::Result<()> {
+    // Objects 1 to 4 are the catalog, the page tree, and the fonts, followed by each page and its content stream.
+    let mut objects = vec![
+        b"<< /Type /Catalog /Pages 2 0 R >>".to_vec(),
+        format!(
+            "<< /Type /Pages /Kids [{}] /Count {} >>",
+            (0..pages.len())
+                .map(|index| format!("{} 0 R", 5 + 2 * index))
+                .collect::<Vec<_>>()
+                .join(" "),
+            pages.len()
+        )
+        .into_bytes(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Helvetica /Encoding /WinAnsiEncoding >>"
+            .to_vec(),
+        b"<< /Type /Font /Subtype /Type1 /BaseFont /Courier /Encoding /WinAnsiEncoding >>".to_vec(),
+    ];
+    for (index, page) in pages.iter().enumerate() {
+        let boxes = match page.trim {
+            Some((bleed, slug)) => format!(
+                "/MediaBox [0 0 {} {}] /BleedBox [{} {} {} {}] /TrimBox [{} {} {} {}]",
+                number(page.width + 2.0 * slug),
+                number(page.height + 2.0 * slug),
+                number(slug - bleed),
+                number(slug - bleed),
+                number(slug + page.width + bleed),
+                number(slug + page.height + bleed),
+                number(slug),
+                number(slug),
+                number(slug + page.width),
+                number(slug + page.height)
+            ),
+            None => format!(
+                "/MediaBox [0 0 {} {}]",
+                number(page.width),
+                number(page.height)
+            ),
+        };
+        objects.push(
+            format!(
+                "<< /Type /Page /Parent 2 0 R {} /Resources << /Font << /F1 3 0 R /F2 4 0 R >> >> /Contents {} 0 R >>",
+                boxes,
+                6 + 2 * index
+            )
+            .into_bytes(),
+        );
+        let mut stream = format!("<< /Length {} >>\nstream\n", page.content.len()).into_bytes();
+        stream.extend_from_slice(&page.content);
+        stream.extend_from_slice(b"endstream");
+        objects.push(stream);
+    }
+
+    let mut writer = io::BufWriter::new(writer);
+    // The comment with bytes above 127 marks the file as binary for tools that guess.
+    let mut offset = b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n".len();
+    writer.write_all(b"%PDF-1.4\n%\xe2\xe3\xcf\xd3\n")?;
+    let mut offsets = Vec::with_capacity(objects.len());
+    for (index, object) in objects.iter().enumerate() {
+        offsets.push(offset);
+        let header = format!("{} 0 obj\n", index + 1);
+        writer.write_all(header.as_bytes())?;
+        writer.write_all(object)?;
+        writer.write_all(b"\nendobj\n")?;
+        offset += header.len() + object.len() + b"\nendobj\n".len();
+    }
+    write!(
+        writer,
+        "xref\n0 {}\n0000000000 65535 f \n",
+        objects.len() + 1
+    )?;
+    for object_offset in offsets {
+        writeln!(writer, "{:010} 00000 n ", object_offset)?;
+    }
+    write!(
+        writer,
+        "trailer\n<< /Size {} /Root 1 0 R >>\nstartxref\n{}\n%%EOF\n",
+        objects.len() + 1,
+        offset
+    )?;
+    writer.flush()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_writes_cross_references_to_every_object() {
+        let mut page = Page::new(100.0, 50.0);
+        page.text("a (b) ü €", Font::Helvetica, 12.0, 10.0, 20.5);
+        let mut pdf = Vec::new();
+        write(&[page], &mut pdf).unwrap();
+        assert!(pdf.starts_with(b"%PDF-1.4\n"));
+        assert!(pdf.ends_with(b"%%EOF\n"));
+        let xref = pdf
+            .windows(5)
+            .position(|window| window == b"xref\n")
+            .unwrap();
+        let trailer = String::from_utf8(pdf[xref..].to_vec()).unwrap();
+        assert!(trailer.ends_with(&format!("startxref\n{}\n%%EOF\n", xref)));
+        for (index, line) in trailer.lines().skip(3).take(6).enumerate() {
+            let object_offset: usize = line[..10].parse().unwrap();
+            assert!(pdf[object_offset..].starts_with(format!("{} 0 obj\n", index + 1).as_bytes()));
+        }
+        let expected: &[u8] = b"BT /F1 12 Tf 10 29.5 Td (a \\(b\\) \xfc ?) Tj ET";
+        assert!(pdf.windows(expected.len()).any(|window| window == expected));
+    }
+
+    #[test]
+    fn it_measures_text() {
+        assert_eq!(6.0, Font::Courier.text_width("ab", 5.0));
+        assert_eq!(11.12, Font::Helvetica.text_width("ab", 10.0));
+    }
+
+    #[test]
+    fn it_wraps_text_to_the_width() {
+        assert_eq!(
+            vec!["Password: aaaa", "aaaaaaaaaaaaaa", "aa"],
+            wrap_to_width(
+                &format!("Password: {}", "a".repeat(20)),
+                Font::Courier,
+                10.0,
+                85.0
+            )
+        );
+    }
+}
@@ -0,0 +1,392 @@
+//! Sheets of sticker labels, written as PDF documents that print one QR code per label, with the network name and password next to it. Layouts for common Avery sheets are predefined, and other sheets can be described with a [`SheetLayout`].
+//!
+//! Print the document at actual size, with scaling to fit the page turned off, or the codes will not line up with the labels.
+//!
+//! Artwork for commercial printers can be written with a bleed area, crop marks, and pure black CMYK ink by setting [`Sheet::production`].
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wifi_qr_code::print::sheet::{Sheet, SheetLayout};
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! use std::fs::File;
+//!
+//! // Fill a whole sheet with labels for the guest network.
+//! let wifi_credentials = WifiCredentials::wpa2("example guest network", "example password");
+//! let labels = vec![wifi_credentials; SheetLayout::AVERY_L7160.labels_per_page()];
+//! let pdf_file = File::create("labels.pdf").expect("Failed to create the PDF file.");
+//! Sheet::new(SheetLayout::AVERY_L7160)
+//!     .write_pdf(&labels, pdf_file)
+//!     .expect("Failed to write the labels.");
+//! ```
+
+use super::pdf::{self, Font, Page};
+use super::Locale;
+use crate::{Error, OutputStage, QrCodeEcc, WifiCredentials};
+
+use std::io::Write;
+
+/// A millimeter in points.
+const MM: f64 = 72.0 / 25.4;
+/// An inch in points.
+const INCH: f64 = 72.0;
+
+/// The geometry of a sheet of labels, in points (1/72 of an inch).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SheetLayout {
+    /// The width of the page.
+    pub page_width: f64,
+    /// The height of the page.
+    pub page_height: f64,
+    /// The number of labels across the page.
+    pub columns: usize,
+    /// The number of labels down the page.
+    pub rows: usize,
+    /// The width of a label.
+    pub label_width: f64,
+    /// The height of a label.
+    pub label_height: f64,
+    /// The distance from the left edge of the page to the left edge of the first column.
+    pub left_margin: f64,
+    /// The distance from the top edge of the page to the top edge of the first row.
+    pub top_margin: f64,
+    /// The distance between the left edges of neighbouring columns, which is the label width plus the gutter between them.
+    pub column_pitch: f64,
+    /// The distance between the top edges of neighbouring rows.
+    pub row_pitch: f64,
+}
+
+impl SheetLayout {
+    /// Avery 5160 and 8160 address labels: 30 labels of 2⅝ by 1 inches on US Letter paper.
+    pub const AVERY_5160: Self = SheetLayout {
+        page_width: 8.5 * INCH,
+        page_height: 11.0 * INCH,
+        columns: 3,
+        rows: 10,
+        label_width: 2.625 * INCH,
+        label_height: INCH,
+        left_margin: 0.1875 * INCH,
+        top_margin: 0.5 * INCH,
+        column_pitch: 2.75 * INCH,
+        row_pitch: INCH,
+    };
+    /// Avery 5163 and 8163 shipping labels: 10 labels of 4 by 2 inches on US Letter paper.
+    pub const AVERY_5163: Self = SheetLayout {
+        page_width: 8.5 * INCH,
+        page_height: 11.0 * INCH,
+        columns: 2,
+        rows: 5,
+        label_width: 4.0 * INCH,
+        label_height: 2.0 * INCH,
+        left_margin: 0.15625 * INCH,
+        top_margin: 0.5 * INCH,
+        column_pitch: 4.1875 * INCH,
+        row_pitch: 2.0 * INCH,
+    };
+    /// Avery L7160 address labels: 21 labels of 63.5 by 38.1 mm on A4 paper.
+    pub const AVERY_L7160: Self = SheetLayout {
+        page_width: 210.0 * MM,
+        page_height: 297.0 * MM,
+        columns: 3,
+        rows: 7,
+        label_width: 63.5 * MM,
+        label_height: 38.1 * MM,
+        left_margin: 7.25 * MM,
+        top_margin: 15.15 * MM,
+        column_pitch: 66.04 * MM,
+        row_pitch: 38.1 * MM,
+    };
+    /// Avery L7163 parcel labels: 14 labels of 99.1 by 38.1 mm on A4 paper.
+    pub const AVERY_L7163: Self = SheetLayout {
+        page_width: 210.0 * MM,
+        page_height: 297.0 * MM,
+        columns: 2,
+        rows: 7,
+        label_width: 99.1 * MM,
+        label_height: 38.1 * MM,
+        left_margin: 4.65 * MM,
+        top_margin: 15.15 * MM,
+        column_pitch: 101.6 * MM,
+        row_pitch: 38.1 * MM,
+    };
+    /// Avery L7651 mini labels: 65 labels of 38.1 by 21.2 mm on A4 paper.
+    pub const AVERY_L7651: Self = SheetLayout {
+        page_width: 210.0 * MM,
+        page_height: 297.0 * MM,
+        columns: 5,
+        rows: 13,
+        label_width: 38.1 * MM,
+        label_height: 21.2 * MM,
+        left_margin: 4.75 * MM,
+        top_margin: 10.7 * MM,
+        column_pitch: 40.64 * MM,
+        row_pitch: 21.2 * MM,
+    };
+
+    /// The number of labels on one sheet.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::sheet::SheetLayout;
+    ///
+    /// assert_eq!(30, SheetLayout::AVERY_5160.labels_per_page());
+    /// ```
+    pub fn labels_per_page(&self) -> usize {
+        self.columns * self.rows
+    }
+
+    /// The position of the top left corner of a label on its page, counting labels in reading order.
+    fn label_position(&self, index: usize) -> (f64, f64) {
+        let index = index % self.labels_per_page();
+        (
+            self.left_margin + (index % self.columns) as f64 * self.column_pitch,
+            self.top_margin + (index / self.columns) as f64 * self.row_pitch,
+        )
+    }
+}
+
+/// The settings of a printed sheet of labels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Sheet {
+    /// The geometry of the sheet.
+    pub layout: SheetLayout,
+    /// The number of labels at the start of the first sheet to leave blank, so partially used sheets can be printed on.
+    pub skip: usize,
+    /// The error correction level of the QR codes.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The language of the captions.
+    pub locale: Locale,
+    /// Settings for sending the sheet to a commercial printer, or `None` for printing it on an office printer.
+    pub production: Option<Production>,
+}
+
+/// Print production settings for sheets sent to commercial printers as artwork.
+///
+/// The pages of the document are surrounded by a bleed area and crop marks at the corners of each sheet, which are described by the `TrimBox` and `BleedBox` of each page, and the QR codes and text are printed in pure black ink (`0 0 0 1 k` in the CMYK color space) so the modules are not printed as a mix of inks that could be misregistered.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Production {
+    /// How far the paper extends beyond the edges of the sheet before it is trimmed to size.
+    pub bleed: f64,
+}
+
+impl Default for Production {
+    /// A bleed of 3 mm, which most printers ask for.
+    fn default() -> Self {
+        Production { bleed: 3.0 * MM }
+    }
+}
+
+impl Sheet {
+    /// An English sheet with the layout, starting at the first label, with QR codes using medium error correction.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::sheet::{Sheet, SheetLayout};
+    ///
+    /// // The top row of the sheet has already been used.
+    /// let sheet = Sheet {
+    ///     skip: 3,
+    ///     ..Sheet::new(SheetLayout::AVERY_5160)
+    /// };
+    /// ```
+    pub fn new(layout: SheetLayout) -> Self {
+        Sheet {
+            layout,
+            skip: 0,
+            qr_code_error_checking: QrCodeEcc::Medium,
+            locale: Locale::English,
+            production: None,
+        }
+    }
+
+    /// Write a PDF document with a label for each of the credentials, in reading order, adding pages as they are needed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::sheet::{Sheet, SheetLayout};
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let labels = vec![
+    ///     WifiCredentials::wpa2("example ssid", "example password"),
+    ///     WifiCredentials::wpa2("example guest ssid", "example guest password"),
+    /// ];
+    /// let mut pdf = Vec::new();
+    /// Sheet::new(SheetLayout::AVERY_5163)
+    ///     .write_pdf(&labels, &mut pdf)
+    ///     .expect("Failed to write example labels.");
+    /// assert!(pdf.starts_with(b"%PDF"));
+    /// ```
+    pub fn write_pdf(&self, labels: &[WifiCredentials], writer: impl Write) -> Result<(), Error> {
+        let layout = &self.layout;
+        let mut pages: Vec<Page> = Vec::new();
+        for (index, wifi_credentials) in (self.skip..).zip(labels) {
+            if pages.is_empty() || index % layout.labels_per_page() == 0 {
+                pages.push(self.page());
+            }
+            let (x, y) = layout.label_position(index);
+            if let Some(page) = pages.last_mut() {
+                self.draw_label(page, wifi_credentials, x, y)?;
+            }
+        }
+        pdf::write(&pages, writer).map_err(|source| Error::Io {
+            stage: OutputStage::Pdf,
+            source,
+        })
+    }
+
+    /// A blank page, with crop marks around it in print production mode.
+    fn page(&self) -> Page {
+        let layout = &self.layout;
+        match self.production {
+            Some(production) => {
+                let mut page = Page::production(
+                    layout.page_width,
+                    layout.page_height,
+                    production.bleed,
+                    production.bleed + CROP_MARK_LENGTH,
+                );
+                page.crop_marks(
+                    0.0,
+                    0.0,
+                    layout.page_width,
+                    layout.page_height,
+                    production.bleed,
+                    CROP_MARK_LENGTH,
+                );
+                page
+            }
+            None => Page::new(layout.page_width, layout.page_height),
+        }
+    }
+
+    /// Draw one label with its top left corner at the position: the QR code filling the height of the label on the left, and the captions to its right, wrapped and shrunk until they fit.
+    fn draw_label(
+        &self,
+        page: &mut Page,
+        wifi_credentials: &WifiCredentials,
+        x: f64,
+        y: f64,
+    ) -> Result<(), Error> {
+        let layout = &self.layout;
+        let qr_code = crate::generate_symbol(
+            &crate::encode_payload(wifi_credentials)?,
+            self.qr_code_error_checking,
+        )?;
+        // The padding around the code is wide enough to serve as its quiet zone.
+        let padding = layout.label_height * 0.1;
+        let qr_code_size = layout.label_height - 2.0 * padding;
+        page.qr_code(
+            &qr_code,
+            x + padding,
+            y + padding,
+            qr_code_size / f64::from(qr_code.size()),
+        );
+
+        let [network, password] = super::captions(wifi_credentials, self.locale);
+        let text_left = x + qr_code_size + 2.0 * padding;
+        let text_width = layout.label_width - qr_code_size - 3.0 * padding;
+        let text_height = layout.label_height - 2.0 * padding;
+        let mut size = ((layout.label_height / 6.0).min(12.0) * 2.0).floor() / 2.0;
+        let lines = loop {
+            let lines: Vec<(Font, String)> =
+                pdf::wrap_to_width(&network, Font::Helvetica, size, text_width)
+                    .into_iter()
+                    .map(|line| (Font::Helvetica, line))
+                    .chain(
+                        pdf::wrap_to_width(&password, Font::Courier, size, text_width)
+                            .into_iter()
+                            .map(|line| (Font::Courier, line)),
+                    )
+                    .collect();
+            // Captions are only wrapped once shrinking them on to one line each would make them too small.
+            let fits = lines.len() as f64 * LINE_SPACING * size <= text_height;
+            if (fits && (lines.len() == 2 || size <= WRAP_TEXT_SIZE)) || size <= MIN_TEXT_SIZE {
+                break lines;
+            }
+            size -= 0.5;
+        };
+        let top = y + (layout.label_height - lines.len() as f64 * LINE_SPACING * size) / 2.0;
+        for (index, (font, line)) in lines.iter().enumerate() {
+            // Baselines leave a quarter of the font size below them, which centers capital letters in their line.
+            let baseline = top + ((index + 1) as f64 * LINE_SPACING - 0.25) * size;
+            page.text(line, *font, size, text_left, baseline);
+        }
+        Ok(())
+    }
+}
+
+/// The length of the crop marks in print production mode, which start at the edge of the bleed area.
+const CROP_MARK_LENGTH: f64 = 5.0 * MM;
+/// The height of a line of caption text, as a multiple of the font size.
+const LINE_SPACING: f64 = 1.25;
+/// The font size below which captions are wrapped rather than shrunk further, in points.
+const WRAP_TEXT_SIZE: f64 = 7.0;
+/// The smallest font size captions are shrunk to, in points, below which they are hard to read.
+const MIN_TEXT_SIZE: f64 = 5.0;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_fits_the_avery_layouts_on_their_pages() {
+        for layout in &[
+            SheetLayout::AVERY_5160,
+            SheetLayout::AVERY_5163,
+            SheetLayout::AVERY_L7160,
+            SheetLayout::AVERY_L7163,
+            SheetLayout::AVERY_L7651,
+        ] {
+            let (right, bottom) = layout.label_position(layout.labels_per_page() - 1);
+            // Avery rounds its published measurements, so allow for a fraction of a millimeter.
+            assert!(right + layout.label_width <= layout.page_width + MM / 2.0);
+            assert!(bottom + layout.label_height <= layout.page_height + MM / 2.0);
+            assert!(layout.column_pitch >= layout.label_width);
+            assert!(layout.row_pitch >= layout.label_height);
+        }
+    }
+
+    #[test]
+    fn it_adds_pages_after_skipped_labels() {
+        let labels = vec![WifiCredentials::wpa2("test ssid", "test password"); 10];
+        let sheet = Sheet {
+            skip: 8,
+            ..Sheet::new(SheetLayout::AVERY_5163)
+        };
+        let mut pdf = Vec::new();
+        sheet.write_pdf(&labels, &mut pdf).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/Count 2"));
+        assert!(text.contains("/MediaBox [0 0 612 792]"));
+        assert_eq!(20, text.matches(" Tj ET").count());
+        // The first label is the left one of the bottom row, with its captions next to a two inch square for the code and its padding.
+        assert!(text.contains("BT /F1 9 Tf 155.25 110.25 Td (Network: test ssid) Tj ET"));
+    }
+
+    #[test]
+    fn it_adds_bleed_and_crop_marks_for_production() {
+        let labels = vec![WifiCredentials::wpa2("test ssid", "test password")];
+        let sheet = Sheet {
+            production: Some(Production::default()),
+            ..Sheet::new(SheetLayout::AVERY_5163)
+        };
+        let mut pdf = Vec::new();
+        sheet.write_pdf(&labels, &mut pdf).unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        // The sheet is moved in by the bleed of 3 mm and the crop marks of 5 mm.
+        assert!(text.contains("/MediaBox [0 0 657.35 837.35] /BleedBox [14.17 14.17 643.18 823.18] /TrimBox [22.68 22.68 634.68 814.68]"));
+        assert!(text.contains("1 0 0 1 22.68 22.68 cm 0 0 0 1 k 0 0 0 1 K\n"));
+        assert_eq!(8, text.matches(" l S Q").count());
+
+        let mut pdf = Vec::new();
+        Sheet::new(SheetLayout::AVERY_5163)
+            .write_pdf(&labels, &mut pdf)
+            .unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(!text.contains("/TrimBox") && !text.contains(" k "));
+    }
+}
@@ -0,0 +1,261 @@
+//! Table tents, written as PDF documents that fold into a card standing on a table, so guests on either side of it can scan the QR code.
+//!
+//! The page holds both faces of the tent, one above the other, with the upper face upside down so that both are the right way up once folded. Cut along the crop marks at the corners, then fold along the dashed line in the middle, with the printed side out.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wifi_qr_code::print::tent::{Paper, TableTent};
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! use std::fs::File;
+//!
+//! let wifi_credentials = WifiCredentials::wpa2("example guest network", "example password");
+//! let pdf_file = File::create("table-tent.pdf").expect("Failed to create the PDF file.");
+//! TableTent::new(Paper::A4)
+//!     .write_pdf(&wifi_credentials, pdf_file)
+//!     .expect("Failed to write the table tent.");
+//! ```
+
+use super::pdf::{self, Font, Page};
+use super::Locale;
+use crate::{Error, OutputStage, QrCodeEcc, WifiCredentials};
+
+use qrcode_generator::qrcodegen::QrCode;
+use std::io::Write;
+
+/// A millimeter in points.
+const MM: f64 = 72.0 / 25.4;
+/// The distance from the edges of the page to the edges of the tent, which most printers can print right up to.
+const MARGIN: f64 = 15.0 * MM;
+/// The length of the crop marks at the corners of the tent.
+const CROP_MARK_LENGTH: f64 = 5.0 * MM;
+/// The gap between the corners of the tent and the start of their crop marks, so the marks do not show on the cut edges.
+const CROP_MARK_GAP: f64 = 2.0 * MM;
+/// The height of a line of text, as a multiple of the font size.
+const LINE_SPACING: f64 = 1.25;
+/// The smallest font size text is shrunk to, in points, before it is wrapped instead.
+const MIN_TEXT_SIZE: f64 = 10.0;
+
+/// The paper a table tent is printed on.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Paper {
+    /// A4, 210 by 297 mm.
+    A4,
+    /// US Letter, 8½ by 11 inches.
+    Letter,
+}
+
+impl Paper {
+    /// The width and height of the paper in portrait orientation, in points.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::tent::Paper;
+    ///
+    /// assert_eq!((612.0, 792.0), Paper::Letter.size());
+    /// ```
+    pub fn size(self) -> (f64, f64) {
+        match self {
+            Self::A4 => (210.0 * MM, 297.0 * MM),
+            Self::Letter => (612.0, 792.0),
+        }
+    }
+}
+
+/// The settings of a printed table tent.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TableTent {
+    /// The paper the tent is printed on.
+    pub paper: Paper,
+    /// The error correction level of the QR code.
+    pub qr_code_error_checking: QrCodeEcc,
+    /// The language of the text.
+    pub locale: Locale,
+}
+
+impl TableTent {
+    /// An English table tent on the paper, with a QR code using medium error correction.
+    pub fn new(paper: Paper) -> Self {
+        TableTent {
+            paper,
+            qr_code_error_checking: QrCodeEcc::Medium,
+            locale: Locale::English,
+        }
+    }
+
+    /// Write a PDF document with a one page table tent for the network. Each face has a title, the QR code, instructions to scan it, and the SSID and password written out.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::tent::{Paper, TableTent};
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// let mut pdf = Vec::new();
+    /// TableTent::new(Paper::Letter)
+    ///     .write_pdf(&wifi_credentials, &mut pdf)
+    ///     .expect("Failed to write example table tent.");
+    /// assert!(pdf.starts_with(b"%PDF"));
+    /// ```
+    pub fn write_pdf(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        writer: impl Write,
+    ) -> Result<(), Error> {
+        let qr_code = crate::generate_symbol(
+            &crate::encode_payload(wifi_credentials)?,
+            self.qr_code_error_checking,
+        )?;
+        let (width, height) = self.paper.size();
+        let mut page = Page::new(width, height);
+        let face_width = width - 2.0 * MARGIN;
+        let face_height = height / 2.0 - MARGIN;
+        // The front face is drawn in the lower half of the page, and turned around the fold for the back face.
+        self.draw_face(
+            &mut page,
+            wifi_credentials,
+            &qr_code,
+            face_width,
+            face_height,
+        );
+        page.rotated(width / 2.0, height / 2.0, |page| {
+            self.draw_face(page, wifi_credentials, &qr_code, face_width, face_height)
+        });
+
+        page.line((MARGIN, height / 2.0), (width - MARGIN, height / 2.0), true);
+        page.crop_marks(
+            MARGIN,
+            MARGIN,
+            face_width,
+            2.0 * face_height,
+            CROP_MARK_GAP,
+            CROP_MARK_LENGTH,
+        );
+
+        pdf::write(&[page], writer).map_err(|source| Error::Io {
+            stage: OutputStage::Pdf,
+            source,
+        })
+    }
+
+    /// Draw the front face in the lower half of the page, from the fold down: the title, the QR code, and the captions, centered. Text is shrunk and wrapped to the width of the face, and the QR code fills the height left over.
+    fn draw_face(
+        &self,
+        page: &mut Page,
+        wifi_credentials: &WifiCredentials,
+        qr_code: &QrCode,
+        width: f64,
+        height: f64,
+    ) {
+        let left = MARGIN;
+        let top = page.height / 2.0;
+        let padding = height * 0.06;
+        let text_width = width - 2.0 * padding;
+        let size = |fraction: f64| (height * fraction * 2.0).floor() / 2.0;
+        let title = fit(
+            &self.locale.message("title", &[]),
+            Font::Helvetica,
+            size(0.1),
+            text_width,
+        );
+        let [network, password] = super::captions(wifi_credentials, self.locale);
+        let captions = [
+            fit(
+                &self.locale.message("instructions", &[]),
+                Font::Helvetica,
+                size(0.045),
+                text_width,
+            ),
+            fit(&network, Font::Helvetica, size(0.05), text_width),
+            fit(&password, Font::Courier, size(0.05), text_width),
+        ];
+        let block_height = |lines: &[Lines]| -> f64 {
+            lines
+                .iter()
+                .map(|(_, size, lines)| lines.len() as f64 * LINE_SPACING * size)
+                .sum()
+        };
+
+        let center = left + width / 2.0;
+        let y = draw_lines(page, std::slice::from_ref(&title), center, top + padding);
+        // The padding around the code is wide enough to serve as its quiet zone.
+        let qr_code_size = (top + height - padding - block_height(&captions) - y - 2.0 * padding)
+            .min(text_width)
+            .max(0.0);
+        page.qr_code(
+            qr_code,
+            center - qr_code_size / 2.0,
+            y + padding,
+            qr_code_size / f64::from(qr_code.size()),
+        );
+        draw_lines(page, &captions, center, y + qr_code_size + 2.0 * padding);
+    }
+}
+
+/// Text in a font and size, broken into the lines it is drawn in.
+type Lines = (Font, f64, Vec<String>);
+
+/// Shrink the text from the size until it fits the width, and wrap it if it does not fit at the smallest size.
+fn fit(text: &str, font: Font, size: f64, width: f64) -> Lines {
+    let mut size = size;
+    while size > MIN_TEXT_SIZE && font.text_width(text, size) > width {
+        size -= 0.5;
+    }
+    (font, size, pdf::wrap_to_width(text, font, size, width))
+}
+
+/// Draw each line centered on `x`, the first one starting at `y`, and return where the last one ends.
+fn draw_lines(page: &mut Page, blocks: &[Lines], x: f64, y: f64) -> f64 {
+    let mut y = y;
+    for (font, size, lines) in blocks {
+        for line in lines {
+            y += LINE_SPACING * size;
+            // Baselines leave a quarter of the font size below them, which centers capital letters in their line.
+            page.text(
+                line,
+                *font,
+                *size,
+                x - font.text_width(line, *size) / 2.0,
+                y - 0.25 * size,
+            );
+        }
+    }
+    y
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_draws_both_faces_with_guides() {
+        let mut pdf = Vec::new();
+        TableTent::new(Paper::A4)
+            .write_pdf(
+                &WifiCredentials::wpa2("test ssid", "test password"),
+                &mut pdf,
+            )
+            .unwrap();
+        let text = String::from_utf8_lossy(&pdf);
+        assert!(text.contains("/Count 1"));
+        // The back face is turned around the center of the page.
+        assert_eq!(1, text.matches("q -1 0 0 -1 595.28 841.89 cm\n").count());
+        assert_eq!(2, text.matches("(Network: test ssid) Tj ET").count());
+        assert_eq!(2, text.matches("(Password: test password) Tj ET").count());
+        assert_eq!(1, text.matches("[4 3] 0 d").count());
+        assert_eq!(8, text.matches("[] 0 d").count());
+    }
+
+    #[test]
+    fn it_shrinks_then_wraps_text_to_the_face() {
+        let (_, size, lines) = fit("Network: test ssid", Font::Helvetica, 18.0, 200.0);
+        assert_eq!((18.0, 1), (size, lines.len()));
+        let (_, size, lines) = fit(&"a".repeat(30), Font::Courier, 18.0, 200.0);
+        assert_eq!((11.0, 1), (size, lines.len()));
+        let (_, size, lines) = fit(&"a".repeat(60), Font::Courier, 18.0, 200.0);
+        assert_eq!((MIN_TEXT_SIZE, 2), (size, lines.len()));
+    }
+}
@@ -0,0 +1,173 @@
+use crate::{AuthenticationType, Secret, ValidationError, Visibility, WifiCredentials};
+
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::io::{self, BufRead, Write};
+
+/// Errors when prompting for credentials with [`prompt_for_credentials`].
+#[derive(Debug)]
+pub enum PromptError {
+    /// The terminal could not be read from or written to, or input ended before every question was answered.
+    Io(io::Error),
+    /// The entered credentials do not meet the requirements of the wifi standards.
+    Validation(ValidationError),
+}
+
+impl Display for PromptError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to read credentials: {}", error),
+            Self::Validation(error) => write!(f, "invalid credentials: {}", error),
+        }
+    }
+}
+
+impl error::Error for PromptError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Validation(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for PromptError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ValidationError> for PromptError {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+/// Ask for an SSID, whether the network is hidden, and a password on the terminal, and return the validated credentials. The questions are written to standard error so that standard output can be piped, e.g. into an image file, and the password is read without echoing it. Leaving the password empty creates credentials for an open network, and any other password is used for WPA.
+///
+/// Only the line ending is removed from each answer, because leading and trailing spaces are significant in SSIDs and passwords.
+///
+/// # Examples
+///
+/// ```no_run
+/// use wifi_qr_code::QrCodeEcc;
+///
+/// let wifi_credentials =
+///     wifi_qr_code::prompt_for_credentials().expect("Failed to read credentials.");
+/// wifi_credentials
+///     .qr(QrCodeEcc::Medium)
+///     .png(std::io::stdout())
+///     .expect("Failed to write PNG.");
+/// ```
+pub fn prompt_for_credentials() -> Result<WifiCredentials, PromptError> {
+    let stdin = io::stdin();
+    prompt_with(stdin.lock(), io::stderr(), |prompt| {
+        rpassword::prompt_password(prompt)
+    })
+}
+
+fn prompt_with(
+    mut input: impl BufRead,
+    mut output: impl Write,
+    read_password: impl FnOnce(&str) -> io::Result<String>,
+) -> Result<WifiCredentials, PromptError> {
+    let ssid = prompt_line(&mut input, &mut output, "SSID: ")?;
+    let hidden = prompt_line(&mut input, &mut output, "Hidden network? [y/N]: ")?;
+    let visibility = match hidden.trim().to_ascii_lowercase().as_str() {
+        "y" | "yes" => Visibility::Hidden,
+        _ => Visibility::Visible,
+    };
+    let mut password = read_password("Password (leave empty for an open network): ")?;
+    trim_line_ending(&mut password);
+    let authentication_type = if password.is_empty() {
+        AuthenticationType::NoPassword
+    } else {
+        AuthenticationType::WPA(Secret::new(password))
+    };
+
+    let wifi_credentials = WifiCredentials {
+        ssid,
+        authentication_type,
+        visibility,
+    };
+    wifi_credentials.validate()?;
+    Ok(wifi_credentials)
+}
+
+fn prompt_line(
+    input: &mut impl BufRead,
+    output: &mut impl Write,
+    prompt: &str,
+) -> io::Result<String> {
+    output.write_all(prompt.as_bytes())?;
+    output.flush()?;
+    let mut line = String::new();
+    if input.read_line(&mut line)? == 0 {
+        return Err(io::Error::new(
+            io::ErrorKind::UnexpectedEof,
+            "input ended before the question was answered",
+        ));
+    }
+    trim_line_ending(&mut line);
+    Ok(line)
+}
+
+fn trim_line_ending(line: &mut String) {
+    if line.ends_with('\n') {
+        line.pop();
+        if line.ends_with('\r') {
+            line.pop();
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_reads_hidden_wpa_credentials() {
+        let mut output = Vec::new();
+        let wifi_credentials = prompt_with(&b" test ssid \r\nYes\n"[..], &mut output, |_| {
+            Ok(String::from("test password\n"))
+        })
+        .unwrap();
+        assert_eq!(
+            WifiCredentials {
+                ssid: String::from(" test ssid "),
+                authentication_type: AuthenticationType::WPA("test password".into()),
+                visibility: Visibility::Hidden,
+            },
+            wifi_credentials
+        );
+        assert_eq!(
+            "SSID: Hidden network? [y/N]: ",
+            String::from_utf8(output).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_reads_open_credentials_and_validates_them() {
+        let wifi_credentials =
+            prompt_with(&b"test ssid\n\n"[..], io::sink(), |_| Ok(String::new())).unwrap();
+        assert_eq!(WifiCredentials::open("test ssid"), wifi_credentials);
+
+        let result = prompt_with(&b"test ssid\nn\n"[..], io::sink(), |_| {
+            Ok(String::from("short"))
+        });
+        assert!(matches!(
+            result,
+            Err(PromptError::Validation(
+                ValidationError::WpaPassphraseLength(5)
+            ))
+        ));
+    }
+
+    #[test]
+    fn it_fails_when_input_ends_early() {
+        let result = prompt_with(&b"test ssid\n"[..], io::sink(), |_| Ok(String::new()));
+        assert!(
+            matches!(result, Err(PromptError::Io(error)) if error.kind() == io::ErrorKind::UnexpectedEof)
+        );
+    }
+}
@@ -0,0 +1,252 @@
+#[cfg(feature = "svg")]
+use crate::SvgAttributes;
+use crate::{render, BitMatrix, EncodeOptions, Error, Modules, QrCodeEcc, QrPayload};
+
+use qrcode_generator::qrcodegen::QrCode;
+use std::fmt;
+#[cfg(any(feature = "png", feature = "svg"))]
+use std::io::Write;
+
+/// A generated QR code that can be written out in several formats. The `encode_as_*` functions build the QR code from scratch on every call, so this is cheaper when the same credentials are needed as, say, both a PNG and an SVG.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials, WifiQrCode};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let qr_code = WifiQrCode::new(&wifi_credentials, QrCodeEcc::Medium)
+///     .expect("Failed to encode example QR code.");
+/// let mut png = Vec::new();
+/// qr_code.to_png(200, &mut png).expect("Failed to write example PNG.");
+/// let mut svg = Vec::new();
+/// qr_code.to_svg(200, &mut svg).expect("Failed to write example SVG.");
+/// print!("{}", qr_code.to_terminal());
+/// ```
+#[derive(Clone, PartialEq, Eq)]
+pub struct WifiQrCode {
+    qr_code: QrCode,
+}
+
+impl WifiQrCode {
+    /// Generate the QR code for the payload, as the `encode_as_*` functions do.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::QrCodeEcc;
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials, WifiQrCode};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// assert!(WifiQrCode::new(&wifi_credentials, QrCodeEcc::Medium).is_ok());
+    /// ```
+    pub fn new(payload: &impl QrPayload, qr_code_error_checking: QrCodeEcc) -> Result<Self, Error> {
+        Ok(WifiQrCode {
+            qr_code: crate::generate_symbol(
+                &crate::encode_payload(payload)?,
+                qr_code_error_checking,
+            )?,
+        })
+    }
+
+    /// Generate the QR code for the payload, with control over how it is generated.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, EncodeOptions, Visibility, WifiCredentials, WifiQrCode};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// let options = EncodeOptions {
+    ///     min_version: 5,
+    ///     ..EncodeOptions::default()
+    /// };
+    /// let qr_code = WifiQrCode::with_options(&wifi_credentials, &options)
+    ///     .expect("Failed to encode example QR code.");
+    /// assert_eq!(5, qr_code.version());
+    /// ```
+    pub fn with_options(payload: &impl QrPayload, options: &EncodeOptions) -> Result<Self, Error> {
+        Ok(WifiQrCode {
+            qr_code: crate::options::generate(payload, options)?,
+        })
+    }
+
+    /// The QR code version, from 1 to 40.
+    pub fn version(&self) -> u8 {
+        self.qr_code.version().value()
+    }
+
+    /// The width and height of the QR code in modules, not including the quiet zone.
+    pub fn size(&self) -> usize {
+        self.qr_code.size() as usize
+    }
+
+    /// The error correction level of the QR code, which may be higher than the requested level.
+    pub fn error_correction(&self) -> QrCodeEcc {
+        self.qr_code.error_correction_level()
+    }
+
+    /// The QR code as a matrix of boolean values, like [`encode_as_matrix`](crate::encode_as_matrix).
+    pub fn to_matrix(&self) -> Vec<Vec<bool>> {
+        render::to_matrix(&self.qr_code)
+    }
+
+    /// The QR code as a single buffer of boolean values and its size, like [`encode_as_flat_matrix`](crate::encode_as_flat_matrix).
+    pub fn to_flat_matrix(&self) -> (Vec<bool>, usize) {
+        render::to_flat_matrix(&self.qr_code)
+    }
+
+    /// The QR code packed one bit per module, like [`encode_as_bitmatrix`](crate::encode_as_bitmatrix).
+    pub fn to_bitmatrix(&self) -> BitMatrix {
+        BitMatrix::new(&self.qr_code)
+    }
+
+    /// An iterator over the modules of the QR code, like [`encode_as_modules`](crate::encode_as_modules).
+    pub fn modules(&self) -> Modules {
+        Modules::new(self.qr_code.clone())
+    }
+
+    /// The QR code as raw image data, like [`encode_as_image`](crate::encode_as_image).
+    pub fn to_image(&self, image_size: usize) -> Result<Vec<u8>, Error> {
+        render::to_image(&self.qr_code, image_size)
+    }
+
+    /// Write the QR code as a PNG image, laid out like [`encode_as_image`](crate::encode_as_image).
+    #[cfg(feature = "png")]
+    pub fn to_png(&self, image_size: usize, writer: impl Write) -> Result<(), Error> {
+        render::write_png(&self.to_image(image_size)?, image_size, writer)
+    }
+
+    /// Write the QR code as a PNG image one pixel row at a time, like [`encode_as_png_streamed`](crate::encode_as_png_streamed).
+    #[cfg(feature = "png")]
+    pub fn to_png_streamed(&self, image_size: usize, writer: impl Write) -> Result<(), Error> {
+        render::write_png_streamed(&self.qr_code, image_size, writer)
+    }
+
+    /// Write the QR code as an SVG image, like [`encode_as_svg`](crate::encode_as_svg).
+    #[cfg(feature = "svg")]
+    pub fn to_svg(&self, image_size: usize, writer: impl Write) -> Result<(), Error> {
+        render::write_svg(&self.qr_code, image_size, writer)
+    }
+
+    /// The QR code as a bare `<svg>` element with the attributes, for including directly in an HTML page instead of linking to an image, laid out like [`WifiQrCode::to_svg`].
+    #[cfg(feature = "svg")]
+    pub fn to_svg_element(
+        &self,
+        image_size: usize,
+        attributes: &SvgAttributes,
+    ) -> Result<String, Error> {
+        render::svg_element(&self.qr_code, image_size, attributes)
+    }
+
+    /// The QR code as lines of text for a terminal, two modules per character using Unicode half blocks, with a two module quiet zone. Light modules are drawn as blocks, so the code reads correctly on the usual dark terminal background.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::QrCodeEcc;
+    /// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials, WifiQrCode};
+    ///
+    /// let wifi_credentials = WifiCredentials {
+    ///     ssid: String::from("example ssid"),
+    ///     authentication_type: AuthenticationType::WPA("example password".into()),
+    ///     visibility: Visibility::Hidden,
+    /// };
+    /// let qr_code = WifiQrCode::new(&wifi_credentials, QrCodeEcc::Medium)
+    ///     .expect("Failed to encode example QR code.");
+    /// let terminal = qr_code.to_terminal();
+    /// assert_eq!((qr_code.size() + 4 + 1) / 2, terminal.lines().count());
+    /// ```
+    pub fn to_terminal(&self) -> String {
+        let matrix = render::with_quiet_zone(self.to_matrix(), 2);
+        let mut terminal = String::with_capacity(matrix.len() * (matrix.len() * 3 + 1) / 2);
+        for rows in matrix.chunks(2) {
+            for (x, top) in rows[0].iter().enumerate() {
+                let bottom = rows.get(1).is_some_and(|row| row[x]);
+                terminal.push(match (top, bottom) {
+                    (false, false) => '█',
+                    (false, true) => '▀',
+                    (true, false) => '▄',
+                    (true, true) => ' ',
+                });
+            }
+            terminal.push('\n');
+        }
+        terminal
+    }
+}
+
+impl fmt::Debug for WifiQrCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WifiQrCode")
+            .field("version", &self.version())
+            .field("size", &self.size())
+            .field("error_correction", &self.error_correction())
+            .finish()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+    fn wifi_credentials() -> WifiCredentials {
+        WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Visible,
+        }
+    }
+
+    #[test]
+    #[allow(deprecated)]
+    fn it_renders_the_same_output_as_the_encode_functions() {
+        let qr_code = WifiQrCode::new(&wifi_credentials(), QrCodeEcc::Medium).unwrap();
+        assert_eq!(
+            crate::encode_as_matrix(&wifi_credentials(), QrCodeEcc::Medium).unwrap(),
+            qr_code.to_matrix()
+        );
+        assert_eq!(
+            crate::encode_as_image(&wifi_credentials(), QrCodeEcc::Medium, 100).unwrap(),
+            qr_code.to_image(100).unwrap()
+        );
+        #[cfg(feature = "svg")]
+        {
+            let mut expected = Vec::new();
+            crate::encode_as_svg(&wifi_credentials(), QrCodeEcc::Medium, 100, &mut expected)
+                .unwrap();
+            let mut svg = Vec::new();
+            qr_code.to_svg(100, &mut svg).unwrap();
+            assert_eq!(expected, svg);
+        }
+        assert_eq!(qr_code.size() * qr_code.size(), qr_code.modules().count());
+    }
+
+    #[test]
+    fn it_draws_light_modules_as_blocks_in_terminals() {
+        let qr_code = WifiQrCode::new(&wifi_credentials(), QrCodeEcc::Medium).unwrap();
+        let terminal = qr_code.to_terminal();
+        let lines: Vec<_> = terminal.lines().collect();
+        assert_eq!(19, lines.len());
+        assert!(lines.iter().all(|line| line.chars().count() == 37));
+        // The quiet zone, then the top of the top left finder pattern.
+        assert_eq!("████", lines[0].chars().take(4).collect::<String>());
+        assert_eq!("██ ▄▄▄▄▄ ", lines[1].chars().take(9).collect::<String>());
+        // An odd number of rows leaves the last line with only the quiet zone on top.
+        assert!(lines[18].chars().all(|c| c == '█'));
+    }
+}
@@ -0,0 +1,186 @@
+use crate::{generator, CapacityError, QrCodeEcc};
+#[cfg(feature = "std")]
+use crate::{options, EncodeOptions, Error, QrPayload};
+
+/// The width and height of a QR code of the given version in modules, `17 + 4 * version`, for sizing a [`QrMatrix`].
+///
+/// # Examples
+///
+/// ```
+/// assert_eq!(21, wifi_qr_code::qr_matrix_side(1));
+/// assert_eq!(177, wifi_qr_code::qr_matrix_side(40));
+/// ```
+pub const fn qr_matrix_side(version: u8) -> usize {
+    17 + 4 * version as usize
+}
+
+/// A QR code in a fixed-size array of `SIDE` by `SIDE` modules, for firmware that knows the largest QR code version it shows at compile time and keeps the matrix in a `static` or on the stack rather than in a `Vec`. The code is in the top left corner of the array, and the modules past its size are light.
+///
+/// Use [`qr_matrix_side`] for the `SIDE` of a version. A version 5 matrix, which holds payloads of up to 84 bytes at medium error correction, takes 1369 bytes. [`QrMatrix::encode`] still generates the symbol with the temporary buffers of the underlying QR code library, while [`WifiCredentialsRef::encode_to_qr_matrix`](crate::WifiCredentialsRef::encode_to_qr_matrix) draws it straight into the array.
+///
+/// # Examples
+///
+/// ```
+/// # #[cfg(feature = "std")] {
+/// use wifi_qr_code::{QrCodeEcc, QrMatrix, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// let mut matrix = QrMatrix::<{ wifi_qr_code::qr_matrix_side(5) }>::new();
+/// matrix
+///     .encode(&wifi_credentials, QrCodeEcc::Medium)
+///     .expect("The credentials do not fit into a version 5 QR code.");
+/// assert_eq!(33, matrix.size());
+/// // The top left corner is part of a finder pattern.
+/// assert!(matrix.get(0, 0));
+/// assert!(!matrix.get(matrix.size(), 0));
+/// # }
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct QrMatrix<const SIDE: usize> {
+    size: usize,
+    modules: [[bool; SIDE]; SIDE],
+}
+
+impl<const SIDE: usize> QrMatrix<SIDE> {
+    /// The largest QR code version that fits into the matrix, or 0 if `SIDE` is less than the 21 modules of version 1.
+    pub const MAX_VERSION: u8 = if SIDE < qr_matrix_side(1) {
+        0
+    } else if SIDE >= qr_matrix_side(40) {
+        40
+    } else {
+        ((SIDE - 17) / 4) as u8
+    };
+
+    /// An empty matrix, with a size of 0.
+    pub const fn new() -> Self {
+        QrMatrix {
+            size: 0,
+            modules: [[false; SIDE]; SIDE],
+        }
+    }
+
+    /// Replace the contents of the matrix with the QR code of the credentials, like [`encode_as_bitmatrix`](crate::encode_as_bitmatrix), limited to [`QrMatrix::MAX_VERSION`]. Payloads that do not fit are rejected with [`QRCodeError::DataTooLong`](crate::QRCodeError::DataTooLong), and the matrix is left empty. Without the `std` feature, use [`WifiCredentialsRef::encode_to_qr_matrix`](crate::WifiCredentialsRef::encode_to_qr_matrix) instead.
+    #[cfg(feature = "std")]
+    pub fn encode(
+        &mut self,
+        payload: &impl QrPayload,
+        qr_code_error_checking: QrCodeEcc,
+    ) -> Result<(), Error> {
+        self.size = 0;
+        self.modules = [[false; SIDE]; SIDE];
+        let qr_code = options::generate(
+            payload,
+            &EncodeOptions {
+                error_correction: qr_code_error_checking,
+                max_version: Self::MAX_VERSION,
+                ..EncodeOptions::default()
+            },
+        )?;
+        let size = qr_code.size() as usize;
+        for (y, row) in self.modules[..size].iter_mut().enumerate() {
+            for (x, module) in row[..size].iter_mut().enumerate() {
+                *module = qr_code.get_module(x as i32, y as i32);
+            }
+        }
+        self.size = size;
+        Ok(())
+    }
+
+    /// Replace the contents of the matrix with the QR code of an encoded and validated payload, generated without allocating. If it does not fit, the matrix is left empty.
+    pub(crate) fn encode_bytes(
+        &mut self,
+        payload: &[u8],
+        qr_code_error_checking: QrCodeEcc,
+    ) -> Result<(), CapacityError> {
+        self.size = 0;
+        self.modules = [[false; SIDE]; SIDE];
+        let size = generator::generate(
+            &mut self.modules,
+            Self::MAX_VERSION,
+            payload,
+            qr_code_error_checking,
+        );
+        self.size = size.unwrap_or(0);
+        size.map(|_| ())
+    }
+
+    /// The width and height of the QR code in modules.
+    pub fn size(&self) -> usize {
+        self.size
+    }
+
+    /// Whether the module at column `x` and row `y` is dark. Modules outside of the QR code are light.
+    pub fn get(&self, x: usize, y: usize) -> bool {
+        x < self.size && y < self.size && self.modules[y][x]
+    }
+
+    /// The rows of the QR code, each [`QrMatrix::size`] modules long.
+    pub fn rows(&self) -> impl Iterator<Item = &[bool]> + '_ {
+        self.modules[..self.size]
+            .iter()
+            .map(move |row| &row[..self.size])
+    }
+}
+
+impl<const SIDE: usize> Default for QrMatrix<SIDE> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Encode credentials as a [`QrMatrix`] of `SIDE` by `SIDE` modules, without allocating the matrix. See [`QrMatrix::encode`].
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{QrCodeEcc, QrMatrix, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// let matrix: QrMatrix<{ wifi_qr_code::qr_matrix_side(5) }> =
+///     wifi_qr_code::encode_as_qr_matrix(&wifi_credentials, QrCodeEcc::Medium)
+///         .expect("Failed to encode example matrix.");
+/// assert!(matrix.rows().all(|row| row.len() == matrix.size()));
+/// ```
+#[cfg(feature = "std")]
+pub fn encode_as_qr_matrix<const SIDE: usize>(
+    payload: &impl QrPayload,
+    qr_code_error_checking: QrCodeEcc,
+) -> Result<QrMatrix<SIDE>, Error> {
+    let mut matrix = QrMatrix::new();
+    matrix.encode(payload, qr_code_error_checking)?;
+    Ok(matrix)
+}
+
+#[cfg(all(test, feature = "std"))]
+mod tests {
+    use super::*;
+    use crate::{QRCodeError, WifiCredentials};
+
+    #[test]
+    fn it_fits_the_code_into_the_array() {
+        let wifi_credentials = WifiCredentials::wpa2("test ssid", "test password");
+        let bitmatrix = crate::encode_as_bitmatrix(&wifi_credentials, QrCodeEcc::Medium).unwrap();
+        let matrix: QrMatrix<40> =
+            encode_as_qr_matrix(&wifi_credentials, QrCodeEcc::Medium).unwrap();
+        assert_eq!(5, QrMatrix::<40>::MAX_VERSION);
+        assert_eq!(33, matrix.size());
+        for y in 0..40 {
+            for x in 0..40 {
+                assert_eq!(bitmatrix.get(x, y), matrix.get(x, y), "{}, {}", x, y);
+            }
+        }
+        assert_eq!(33, matrix.rows().count());
+
+        // 33 modules is version 4, so a version 3 matrix is too small, and nothing fits into 20 modules.
+        let mut matrix = QrMatrix::<{ qr_matrix_side(3) }>::new();
+        assert!(matches!(
+            matrix.encode(&wifi_credentials, QrCodeEcc::Medium),
+            Err(Error::QrCode(QRCodeError::DataTooLong))
+        ));
+        assert_eq!(0, matrix.size());
+        assert!(matches!(
+            encode_as_qr_matrix::<20>(&wifi_credentials, QrCodeEcc::Medium),
+            Err(Error::VersionRange { min: 1, max: 0 })
+        ));
+    }
+}
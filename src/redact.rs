@@ -0,0 +1,183 @@
+//! `Debug` and `Display` implementations that hide passwords, so a stray `{:?}` or `{}` does not leak them into logs, along with the `defmt::Format` implementations for embedded logs, which hide them the same way.
+
+use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+use std::fmt::{self, Debug, Display, Formatter};
+
+/// Formats a value with its passwords either redacted or shown.
+pub(crate) struct Redactable<'a, T: ?Sized> {
+    pub(crate) value: &'a T,
+    pub(crate) redact: bool,
+}
+
+impl<'a, T: ?Sized> Redactable<'a, T> {
+    fn with<U: ?Sized>(&self, value: &'a U) -> Redactable<'a, U> {
+        Redactable {
+            value,
+            redact: self.redact,
+        }
+    }
+}
+
+impl Debug for Redactable<'_, str> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        if self.redact {
+            f.write_str("<redacted>")
+        } else {
+            Debug::fmt(self.value, f)
+        }
+    }
+}
+
+impl Debug for Redactable<'_, AuthenticationType> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self.value {
+            AuthenticationType::WEP(password) => f
+                .debug_tuple("WEP")
+                .field(&self.with(password.as_str()))
+                .finish(),
+            AuthenticationType::WPA(password) => f
+                .debug_tuple("WPA")
+                .field(&self.with(password.as_str()))
+                .finish(),
+            AuthenticationType::WpaPsk(psk) => f
+                .debug_tuple("WpaPsk")
+                .field(&self.with(crate::encode_hex(&**psk).as_str()))
+                .finish(),
+            AuthenticationType::NoPassword => f.write_str("NoPassword"),
+        }
+    }
+}
+
+impl Debug for Redactable<'_, WifiCredentials> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WifiCredentials")
+            .field("ssid", &self.value.ssid)
+            .field(
+                "authentication_type",
+                &self.with(&self.value.authentication_type),
+            )
+            .field("visibility", &self.value.visibility)
+            .finish()
+    }
+}
+
+impl Debug for AuthenticationType {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Redactable {
+            value: self,
+            redact: true,
+        }
+        .fmt(f)
+    }
+}
+
+impl Debug for WifiCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        Redactable {
+            value: self,
+            redact: true,
+        }
+        .fmt(f)
+    }
+}
+
+/// Shows the SSID and authentication type, e.g. `example ssid (WPA, password <redacted>, hidden)`, for log lines and user interfaces. The password is never shown; use [`WifiCredentials::debug_unredacted`] when it is really needed.
+impl Display for WifiCredentials {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (", self.ssid)?;
+        f.write_str(match self.authentication_type {
+            AuthenticationType::WEP(_) => "WEP, password <redacted>",
+            AuthenticationType::WPA(_) => "WPA, password <redacted>",
+            AuthenticationType::WpaPsk(_) => "WPA, pre-shared key <redacted>",
+            AuthenticationType::NoPassword => "open",
+        })?;
+        if let Visibility::Hidden = self.visibility {
+            f.write_str(", hidden")?;
+        }
+        f.write_str(")")
+    }
+}
+
+/// Redacts the password, like the `Debug` implementation.
+#[cfg(feature = "defmt")]
+impl defmt::Format for AuthenticationType {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::Format::format(&self.as_borrowed(), f)
+    }
+}
+
+/// Redacts the password, like the `Debug` implementation.
+#[cfg(feature = "defmt")]
+impl defmt::Format for WifiCredentials {
+    fn format(&self, f: defmt::Formatter<'_>) {
+        defmt::write!(
+            f,
+            "WifiCredentials {{ ssid: {=str}, authentication_type: {}, visibility: {} }}",
+            self.ssid.as_str(),
+            self.authentication_type,
+            self.visibility
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+    #[test]
+    fn it_redacts_passwords_by_default() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Hidden,
+        };
+        assert_eq!(
+            r#"WifiCredentials { ssid: "test ssid", authentication_type: WPA(<redacted>), visibility: Hidden }"#,
+            format!("{:?}", wifi_credentials)
+        );
+        assert_eq!(
+            "NoPassword",
+            format!("{:?}", AuthenticationType::NoPassword)
+        );
+    }
+
+    #[test]
+    fn it_displays_credentials_without_passwords() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Hidden,
+        };
+        assert_eq!(
+            "test ssid (WPA, password <redacted>, hidden)",
+            wifi_credentials.to_string()
+        );
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::NoPassword,
+            visibility: Visibility::Visible,
+        };
+        assert_eq!("test ssid (open)", wifi_credentials.to_string());
+    }
+
+    #[test]
+    fn it_shows_passwords_when_explicitly_unredacted() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(
+            r#"WifiCredentials { ssid: "test ssid", authentication_type: WEP("test password"), visibility: Visible }"#,
+            format!("{:?}", wifi_credentials.debug_unredacted())
+        );
+        assert_eq!(
+            r#"WEP("test password")"#,
+            format!(
+                "{:?}",
+                wifi_credentials.authentication_type.debug_unredacted()
+            )
+        );
+    }
+}
@@ -0,0 +1,443 @@
+//! Pluggable importers and exporters, so router and operating system integrations can live in their own crates and still be offered next to the built-in formats by tools such as a command line `import` or `export` subcommand.
+//!
+//! An integration implements [`CredentialSource`] to read credentials, or [`CredentialSink`] to write them, and is added to a [`Registry`] under a short name. By convention, integration crates expose a `register(registry: &mut Registry)` function that adds all of their sources and sinks, so an application can enable one with a single call.
+//!
+//! # Examples
+//!
+//! ```
+//! use wifi_qr_code::registry::{CredentialSink, Registry};
+//! use wifi_qr_code::WifiCredentials;
+//! use std::error::Error;
+//! use std::io::Write;
+//!
+//! struct Ssid;
+//!
+//! impl CredentialSink for Ssid {
+//!     fn name(&self) -> &str {
+//!         "ssid"
+//!     }
+//!
+//!     fn description(&self) -> &str {
+//!         "only the network name"
+//!     }
+//!
+//!     fn write(
+//!         &self,
+//!         wifi_credentials: &WifiCredentials,
+//!         output: &mut dyn Write,
+//!     ) -> Result<(), Box<dyn Error + Send + Sync>> {
+//!         Ok(writeln!(output, "{}", wifi_credentials.ssid)?)
+//!     }
+//! }
+//!
+//! let mut registry = Registry::with_builtins();
+//! registry.register_sink(Ssid);
+//!
+//! let config = "config wifi-iface\n\toption mode 'ap'\n\toption ssid 'example ssid'\n\toption encryption 'none'\n";
+//! let wifi_credentials = registry
+//!     .source("openwrt")
+//!     .expect("OpenWrt is built in.")
+//!     .read(&mut config.as_bytes())
+//!     .expect("Failed to read example config.");
+//! let mut output = Vec::new();
+//! registry
+//!     .sink("ssid")
+//!     .expect("The sink was registered.")
+//!     .write(&wifi_credentials[0], &mut output)
+//!     .expect("Failed to write example credentials.");
+//! assert_eq!(b"example ssid\n".to_vec(), output);
+//! ```
+
+use crate::export::{android, iwd, ndef, network_manager, windows_profile, wpa_supplicant};
+use crate::import::{self, ImportError};
+use crate::{Secret, WifiCredentials};
+
+use std::error::Error;
+use std::io::{Read, Write};
+
+/// A format or device that credentials can be imported from.
+pub trait CredentialSource {
+    /// The short name the source is registered and selected under, such as `openwrt`.
+    fn name(&self) -> &str;
+
+    /// A one line description of the source, for listings such as `--help` output.
+    fn description(&self) -> &str;
+
+    /// Read every network from the input, which is the contents of a file for configuration formats, or e.g. connection settings for sources that query a device.
+    fn read(
+        &self,
+        input: &mut dyn Read,
+    ) -> Result<Vec<WifiCredentials>, Box<dyn Error + Send + Sync>>;
+}
+
+/// A format or device that credentials can be exported to.
+pub trait CredentialSink {
+    /// The short name the sink is registered and selected under, such as `wpa_supplicant`.
+    fn name(&self) -> &str;
+
+    /// A one line description of the sink, for listings such as `--help` output.
+    fn description(&self) -> &str;
+
+    /// Write the credentials of one network to the output.
+    fn write(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        output: &mut dyn Write,
+    ) -> Result<(), Box<dyn Error + Send + Sync>>;
+}
+
+/// The sources and sinks available to an application, looked up by name. Registering a source or sink under a name that is already taken replaces the earlier one, so integrations can override built-in formats.
+#[derive(Default)]
+pub struct Registry {
+    sources: Vec<Box<dyn CredentialSource + Send + Sync>>,
+    sinks: Vec<Box<dyn CredentialSink + Send + Sync>>,
+}
+
+impl Registry {
+    /// A registry without any sources or sinks.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::registry::Registry;
+    ///
+    /// assert_eq!(0, Registry::new().sources().count());
+    /// ```
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A registry with the importers of the [`import`](crate::import) module that read configuration files, and the exporters of the [`export`](crate::export) module that produce a single document without further settings.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::registry::Registry;
+    ///
+    /// let registry = Registry::with_builtins();
+    /// assert!(registry.source("mikrotik").is_some());
+    /// assert!(registry.sink("network_manager").is_some());
+    /// ```
+    pub fn with_builtins() -> Self {
+        let mut registry = Self::new();
+        registry
+            .register_source(BuiltinSource {
+                name: "mikrotik",
+                description: "MikroTik RouterOS /export dumps",
+                parse: import::mikrotik::parse,
+            })
+            .register_source(BuiltinSource {
+                name: "openwrt",
+                description: "OpenWrt /etc/config/wireless files",
+                parse: import::openwrt::parse,
+            });
+        #[cfg(feature = "unifi")]
+        registry.register_source(BuiltinSource {
+            name: "unifi",
+            description: "UniFi Network controller wlanconf responses",
+            parse: import::unifi::parse,
+        });
+        registry
+            .register_sink(BuiltinSink {
+                name: "android",
+                description: "Android WifiNetworkSuggestion JSON",
+                encode: |wifi_credentials| Ok(android::encode(wifi_credentials).into_bytes()),
+            })
+            .register_sink(BuiltinSink {
+                name: "iwd",
+                description: "iwd network files",
+                encode: |wifi_credentials| {
+                    iwd::encode(wifi_credentials)?
+                        .map(|config_file| config_file.contents.into_bytes())
+                        .ok_or_else(|| "iwd does not support WEP".into())
+                },
+            })
+            .register_sink(BuiltinSink {
+                name: "ndef",
+                description: "NFC NDEF messages with a Wi-Fi Simple Configuration record",
+                encode: |wifi_credentials| Ok(ndef::encode(wifi_credentials)),
+            })
+            .register_sink(BuiltinSink {
+                name: "network_manager",
+                description: "NetworkManager .nmconnection keyfiles",
+                encode: |wifi_credentials| {
+                    Ok(network_manager::encode(wifi_credentials)?.into_bytes())
+                },
+            })
+            .register_sink(BuiltinSink {
+                name: "windows_profile",
+                description: "Windows WLAN profile XML",
+                encode: |wifi_credentials| {
+                    Ok(windows_profile::encode(wifi_credentials)?.into_bytes())
+                },
+            })
+            .register_sink(BuiltinSink {
+                name: "wpa_supplicant",
+                description: "wpa_supplicant.conf network blocks",
+                encode: |wifi_credentials| {
+                    Ok(wpa_supplicant::encode(wifi_credentials)?.into_bytes())
+                },
+            });
+        registry
+    }
+
+    /// Add the source, replacing any source registered under the same name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::registry::{CredentialSource, Registry};
+    /// use wifi_qr_code::WifiCredentials;
+    /// use std::error::Error;
+    /// use std::io::Read;
+    ///
+    /// struct Ssids;
+    ///
+    /// impl CredentialSource for Ssids {
+    ///     fn name(&self) -> &str {
+    ///         "ssids"
+    ///     }
+    ///
+    ///     fn description(&self) -> &str {
+    ///         "open networks, one SSID per line"
+    ///     }
+    ///
+    ///     fn read(&self, input: &mut dyn Read) -> Result<Vec<WifiCredentials>, Box<dyn Error + Send + Sync>> {
+    ///         let mut text = String::new();
+    ///         input.read_to_string(&mut text)?;
+    ///         Ok(text.lines().map(WifiCredentials::open).collect())
+    ///     }
+    /// }
+    ///
+    /// let mut registry = Registry::new();
+    /// registry.register_source(Ssids);
+    /// let wifi_credentials = registry
+    ///     .source("ssids")
+    ///     .expect("The source was registered.")
+    ///     .read(&mut "example ssid\n".as_bytes())
+    ///     .expect("Failed to read example input.");
+    /// assert_eq!("example ssid", wifi_credentials[0].ssid);
+    /// ```
+    pub fn register_source(
+        &mut self,
+        source: impl CredentialSource + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.sources
+            .retain(|existing| existing.name() != source.name());
+        self.sources.push(Box::new(source));
+        self
+    }
+
+    /// Add the sink, replacing any sink registered under the same name. See the [module documentation](self) for an example.
+    pub fn register_sink(
+        &mut self,
+        sink: impl CredentialSink + Send + Sync + 'static,
+    ) -> &mut Self {
+        self.sinks.retain(|existing| existing.name() != sink.name());
+        self.sinks.push(Box::new(sink));
+        self
+    }
+
+    /// The source registered under the name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::registry::Registry;
+    ///
+    /// let registry = Registry::with_builtins();
+    /// assert_eq!("openwrt", registry.source("openwrt").unwrap().name());
+    /// assert!(registry.source("missing").is_none());
+    /// ```
+    pub fn source(&self, name: &str) -> Option<&(dyn CredentialSource + Send + Sync)> {
+        self.sources
+            .iter()
+            .find(|source| source.name() == name)
+            .map(|source| source.as_ref())
+    }
+
+    /// The sink registered under the name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::registry::Registry;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let mut keyfile = Vec::new();
+    /// Registry::with_builtins()
+    ///     .sink("network_manager")
+    ///     .expect("NetworkManager is built in.")
+    ///     .write(&WifiCredentials::open("example ssid"), &mut keyfile)
+    ///     .expect("Failed to write example keyfile.");
+    /// assert!(String::from_utf8(keyfile).unwrap().contains("ssid=example ssid\n"));
+    /// ```
+    pub fn sink(&self, name: &str) -> Option<&(dyn CredentialSink + Send + Sync)> {
+        self.sinks
+            .iter()
+            .find(|sink| sink.name() == name)
+            .map(|sink| sink.as_ref())
+    }
+
+    /// Every registered source, in the order they were registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::registry::Registry;
+    ///
+    /// for source in Registry::with_builtins().sources() {
+    ///     println!("{:16} {}", source.name(), source.description());
+    /// }
+    /// ```
+    pub fn sources(&self) -> impl Iterator<Item = &(dyn CredentialSource + Send + Sync)> {
+        self.sources.iter().map(|source| source.as_ref())
+    }
+
+    /// Every registered sink, in the order they were registered.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::registry::Registry;
+    ///
+    /// let registry = Registry::with_builtins();
+    /// assert!(registry.sinks().any(|sink| sink.name() == "wpa_supplicant"));
+    /// ```
+    pub fn sinks(&self) -> impl Iterator<Item = &(dyn CredentialSink + Send + Sync)> {
+        self.sinks.iter().map(|sink| sink.as_ref())
+    }
+}
+
+/// An importer of the [`import`](crate::import) module, which reads UTF-8 text.
+struct BuiltinSource {
+    name: &'static str,
+    description: &'static str,
+    parse: fn(&str) -> Result<Vec<WifiCredentials>, ImportError>,
+}
+
+impl CredentialSource for BuiltinSource {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn read(
+        &self,
+        input: &mut dyn Read,
+    ) -> Result<Vec<WifiCredentials>, Box<dyn Error + Send + Sync>> {
+        let mut text = String::new();
+        input.read_to_string(&mut text)?;
+        Ok((self.parse)(&text)?)
+    }
+}
+
+type Encode = fn(&WifiCredentials) -> Result<Secret<Vec<u8>>, Box<dyn Error + Send + Sync>>;
+
+/// An exporter of the [`export`](crate::export) module.
+struct BuiltinSink {
+    name: &'static str,
+    description: &'static str,
+    encode: Encode,
+}
+
+impl CredentialSink for BuiltinSink {
+    fn name(&self) -> &str {
+        self.name
+    }
+
+    fn description(&self) -> &str {
+        self.description
+    }
+
+    fn write(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        output: &mut dyn Write,
+    ) -> Result<(), Box<dyn Error + Send + Sync>> {
+        Ok(output.write_all(&(self.encode)(wifi_credentials)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility};
+
+    struct Fixed(&'static str);
+
+    impl CredentialSource for Fixed {
+        fn name(&self) -> &str {
+            self.0
+        }
+
+        fn description(&self) -> &str {
+            "a fixed network"
+        }
+
+        fn read(
+            &self,
+            _: &mut dyn Read,
+        ) -> Result<Vec<WifiCredentials>, Box<dyn Error + Send + Sync>> {
+            Ok(vec![WifiCredentials::open("test ssid")])
+        }
+    }
+
+    #[test]
+    fn it_replaces_sources_registered_under_the_same_name() {
+        let mut registry = Registry::with_builtins();
+        let builtins = registry.sources().count();
+        registry
+            .register_source(Fixed("openwrt"))
+            .register_source(Fixed("test"));
+        assert_eq!(builtins + 1, registry.sources().count());
+        assert_eq!(
+            "a fixed network",
+            registry.source("openwrt").unwrap().description()
+        );
+        assert_eq!(
+            vec![WifiCredentials::open("test ssid")],
+            registry
+                .source("test")
+                .unwrap()
+                .read(&mut std::io::empty())
+                .unwrap()
+        );
+        assert!(registry.source("missing").is_none());
+    }
+
+    #[test]
+    fn it_reports_errors_from_builtins() {
+        let registry = Registry::with_builtins();
+        assert!(registry
+            .source("openwrt")
+            .unwrap()
+            .read(&mut "config wifi-iface\n\toption ssid 'unterminated\n".as_bytes())
+            .is_err());
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WEP("abcde".into()),
+            visibility: Visibility::Visible,
+        };
+        let mut output = Vec::new();
+        assert!(registry
+            .sink("iwd")
+            .unwrap()
+            .write(&wifi_credentials, &mut output)
+            .is_err());
+        registry
+            .sink("wpa_supplicant")
+            .unwrap()
+            .write(&wifi_credentials, &mut output)
+            .unwrap();
+        assert_eq!(
+            *wpa_supplicant::encode(&wifi_credentials)
+                .unwrap()
+                .into_bytes(),
+            output
+        );
+    }
+}
@@ -0,0 +1,509 @@
+//! Rendering of generated QR code symbols, for output that `qrcode_generator` cannot produce from a symbol generated with custom options.
+
+#[cfg(any(feature = "png", feature = "svg"))]
+use crate::OutputStage;
+use crate::{Error, QRCodeError};
+
+use qrcode_generator::qrcodegen::QrCode;
+#[cfg(any(feature = "png", feature = "svg"))]
+use std::io::{self, Write};
+
+pub(crate) fn to_matrix(qr_code: &QrCode) -> Vec<Vec<bool>> {
+    (0..qr_code.size())
+        .map(|y| {
+            (0..qr_code.size())
+                .map(|x| qr_code.get_module(x, y))
+                .collect()
+        })
+        .collect()
+}
+
+/// The modules row by row in one buffer, along with the width and height of the symbol.
+pub(crate) fn to_flat_matrix(qr_code: &QrCode) -> (Vec<bool>, usize) {
+    let size = qr_code.size();
+    let modules = (0..size)
+        .flat_map(|y| (0..size).map(move |x| qr_code.get_module(x, y)))
+        .collect();
+    (modules, size as usize)
+}
+
+/// Surround the matrix with `quiet_zone` light modules on every side.
+pub(crate) fn with_quiet_zone(matrix: Vec<Vec<bool>>, quiet_zone: usize) -> Vec<Vec<bool>> {
+    let size = matrix.len() + 2 * quiet_zone;
+    let mut padded = vec![vec![false; size]; quiet_zone];
+    padded.extend(matrix.into_iter().map(|row| {
+        let mut padded_row = vec![false; quiet_zone];
+        padded_row.extend(row);
+        padded_row.resize(size, false);
+        padded_row
+    }));
+    padded.resize(size, vec![false; size]);
+    padded
+}
+
+/// Rasterize the symbol as a square greyscale image, laid out the same way as `qrcode_generator::to_image`: a margin of at least one module, with the symbol centered.
+pub(crate) fn to_image(qr_code: &QrCode, image_size: usize) -> Result<Vec<u8>, Error> {
+    let mut image = Vec::new();
+    to_image_into(qr_code, image_size, &mut image)?;
+    Ok(image)
+}
+
+/// Rasterize the symbol like [`to_image`], into an existing buffer so its allocation can be reused.
+pub(crate) fn to_image_into(
+    qr_code: &QrCode,
+    image_size: usize,
+    image: &mut Vec<u8>,
+) -> Result<(), Error> {
+    let layout = Layout::new(qr_code, image_size)?;
+    image.clear();
+    image.resize(image_size * image_size, 255);
+    for (y, row) in image.chunks_exact_mut(image_size).enumerate() {
+        layout.fill_row(qr_code, y, row);
+    }
+    Ok(())
+}
+
+/// Where the symbol sits in an image laid out like `qrcode_generator::to_image`.
+struct Layout {
+    point_size: usize,
+    margin: usize,
+}
+
+impl Layout {
+    fn new(qr_code: &QrCode, image_size: usize) -> Result<Self, Error> {
+        if image_size >= 1 << (std::mem::size_of::<usize>() * 4) {
+            return Err(Error::QrCode(QRCodeError::ImageSizeTooLarge));
+        }
+        let modules = qr_code.size() as usize;
+        let point_size = image_size / (modules + 2);
+        if point_size == 0 {
+            return Err(Error::QrCode(QRCodeError::ImageSizeTooSmall));
+        }
+        let margin = (image_size - point_size * modules) / 2;
+        Ok(Layout { point_size, margin })
+    }
+
+    /// Draw the dark modules of pixel row `y` into a row that is already light.
+    fn fill_row(&self, qr_code: &QrCode, y: usize, row: &mut [u8]) {
+        let modules = qr_code.size() as usize;
+        let module_y = match (y.checked_sub(self.margin)).map(|y| y / self.point_size) {
+            Some(module_y) if module_y < modules => module_y as i32,
+            _ => return,
+        };
+        for module_x in 0..modules {
+            if qr_code.get_module(module_x as i32, module_y) {
+                let x = self.margin + module_x * self.point_size;
+                row[x..x + self.point_size].fill(0);
+            }
+        }
+    }
+}
+
+/// Wrap a square greyscale image from [`to_image`] for the `image` crate.
+#[cfg(feature = "image")]
+pub(crate) fn to_dynamic_image(
+    image: Vec<u8>,
+    image_size: usize,
+) -> Result<image::DynamicImage, Error> {
+    image::GrayImage::from_raw(image_size as u32, image_size as u32, image)
+        .map(image::DynamicImage::ImageLuma8)
+        .ok_or(Error::QrCode(QRCodeError::ImageSizeTooLarge))
+}
+
+/// Write the symbol as an SVG image, laid out like [`to_image`]. Each horizontal run of dark modules is merged into one rectangle of a single path, which keeps the document small, and is written through a small buffer, so memory use does not grow with the size of the code.
+#[cfg(feature = "svg")]
+pub(crate) fn write_svg(
+    qr_code: &QrCode,
+    image_size: usize,
+    writer: impl Write,
+) -> Result<(), Error> {
+    let modules = qr_code.size() as usize;
+    let point_size = image_size / (modules + 2);
+    if point_size == 0 {
+        return Err(Error::QrCode(QRCodeError::ImageSizeTooSmall));
+    }
+    let margin = (image_size - point_size * modules) / 2;
+    let mut writer = io::BufWriter::new(writer);
+    let mut write = || -> io::Result<()> {
+        write!(
+            writer,
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<svg width=\"{size}\" height=\"{size}\" shape-rendering=\"crispEdges\" version=\"1.1\" xmlns=\"http://www.w3.org/2000/svg\">\n\t<rect width=\"{size}\" height=\"{size}\" fill=\"#FFF\"/>\n\t<path d=\"",
+            size = image_size
+        )?;
+        write_svg_path(&mut writer, qr_code, margin, margin, point_size)?;
+        writer.write_all(b"\"/>\n</svg>\n")?;
+        writer.flush()
+    };
+    write().map_err(|source| Error::Io {
+        stage: OutputStage::Svg,
+        source,
+    })
+}
+
+/// Attributes of a bare `<svg>` element, for embedding the QR code directly in HTML.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{QrCodeEcc, SvgAttributes, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+/// let svg = wifi_credentials
+///     .qr(QrCodeEcc::Medium)
+///     .size(256)
+///     .svg_element(&SvgAttributes {
+///         class: Some(String::from("wifi-qr")),
+///         title: Some(String::from("Scan to join example ssid")),
+///         ..SvgAttributes::default()
+///     })
+///     .expect("Failed to render example SVG element.");
+/// assert!(svg.starts_with("<svg class=\"wifi-qr\" role=\"img\""));
+/// ```
+#[cfg(feature = "svg")]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+pub struct SvgAttributes {
+    /// The `id` attribute of the element.
+    pub id: Option<String>,
+    /// The `class` attribute of the element, for styling it from the page.
+    pub class: Option<String>,
+    /// A `<title>` for screen readers and tooltips. The element gets the `img` role when it has a title.
+    pub title: Option<String>,
+}
+
+/// The symbol as a bare `<svg>` element, without an XML declaration and with a `viewBox` so it can be scaled with CSS, laid out like [`write_svg`].
+#[cfg(feature = "svg")]
+pub(crate) fn svg_element(
+    qr_code: &QrCode,
+    image_size: usize,
+    attributes: &SvgAttributes,
+) -> Result<String, Error> {
+    let modules = qr_code.size() as usize;
+    let point_size = image_size / (modules + 2);
+    if point_size == 0 {
+        return Err(Error::QrCode(QRCodeError::ImageSizeTooSmall));
+    }
+    let margin = (image_size - point_size * modules) / 2;
+    let mut svg = String::from("<svg");
+    for (name, value) in &[("id", &attributes.id), ("class", &attributes.class)] {
+        if let Some(value) = value {
+            svg.push_str(&format!(
+                " {}=\"{}\"",
+                name,
+                crate::export::escape_xml(value)
+            ));
+        }
+    }
+    if attributes.title.is_some() {
+        svg.push_str(" role=\"img\"");
+    }
+    svg.push_str(&format!(
+        " width=\"{size}\" height=\"{size}\" viewBox=\"0 0 {size} {size}\" shape-rendering=\"crispEdges\" xmlns=\"http://www.w3.org/2000/svg\">",
+        size = image_size
+    ));
+    if let Some(title) = &attributes.title {
+        svg.push_str(&format!(
+            "<title>{}</title>",
+            crate::export::escape_xml(title)
+        ));
+    }
+    svg.push_str(&format!(
+        "<rect width=\"{size}\" height=\"{size}\" fill=\"#FFF\"/><path d=\"",
+        size = image_size
+    ));
+    let mut path = Vec::new();
+    write_svg_path(&mut path, qr_code, margin, margin, point_size).map_err(|source| Error::Io {
+        stage: OutputStage::Svg,
+        source,
+    })?;
+    svg.push_str(&String::from_utf8_lossy(&path));
+    svg.push_str("\"/></svg>");
+    Ok(svg)
+}
+
+/// Write the path data for the dark modules of the symbol, with the top left module at `(x, y)`, as one `M…z` rectangle per horizontal run of dark modules.
+#[cfg(feature = "svg")]
+pub(crate) fn write_svg_path(
+    mut writer: impl Write,
+    qr_code: &QrCode,
+    x: usize,
+    y: usize,
+    point_size: usize,
+) -> io::Result<()> {
+    let modules = qr_code.size() as usize;
+    for module_y in 0..modules {
+        let dark = |module_x: usize| qr_code.get_module(module_x as i32, module_y as i32);
+        let mut module_x = 0;
+        while module_x < modules {
+            if !dark(module_x) {
+                module_x += 1;
+                continue;
+            }
+            let run = (module_x..modules).take_while(|x| dark(*x)).count();
+            write!(
+                writer,
+                "M{x} {y}h{width}v{height}H{x}z",
+                x = x + module_x * point_size,
+                y = y + module_y * point_size,
+                width = run * point_size,
+                height = point_size
+            )?;
+            module_x += run;
+        }
+    }
+    Ok(())
+}
+
+/// Write the symbol as a PNG laid out like [`to_image`], one pixel row at a time, so only a single row is held in memory however large the image is.
+#[cfg(feature = "png")]
+pub(crate) fn write_png_streamed(
+    qr_code: &QrCode,
+    image_size: usize,
+    writer: impl Write,
+) -> Result<(), Error> {
+    let layout = Layout::new(qr_code, image_size)?;
+    let mut encoder = png::Encoder::new(writer, image_size as u32, image_size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(png::Compression::Best);
+    encoder.set_filter(png::FilterType::NoFilter);
+    encoder.set_adaptive_filter(png::AdaptiveFilterType::NonAdaptive);
+    let mut png_writer = encoder.write_header().map_err(png_error)?;
+    let mut stream = png_writer.stream_writer().map_err(png_error)?;
+    let mut row = vec![255u8; image_size];
+    for y in 0..image_size {
+        row.fill(255);
+        layout.fill_row(qr_code, y, &mut row);
+        stream.write_all(&row).map_err(|source| Error::Io {
+            stage: OutputStage::Png,
+            source,
+        })?;
+    }
+    stream.finish().map_err(png_error)?;
+    png_writer.finish().map_err(png_error)
+}
+
+#[cfg(feature = "png")]
+fn png_error(error: png::EncodingError) -> Error {
+    match error {
+        png::EncodingError::IoError(source) => Error::Io {
+            stage: OutputStage::Png,
+            source,
+        },
+        error => Error::Io {
+            stage: OutputStage::Png,
+            source: io::Error::other(error),
+        },
+    }
+}
+
+/// Write a square greyscale image as a PNG.
+#[cfg(feature = "png")]
+pub(crate) fn write_png(image: &[u8], image_size: usize, writer: impl Write) -> Result<(), Error> {
+    let mut encoder = png::Encoder::new(writer, image_size as u32, image_size as u32);
+    encoder.set_color(png::ColorType::Grayscale);
+    encoder.set_depth(png::BitDepth::Eight);
+    encoder.set_compression(png::Compression::Best);
+    encoder
+        .write_header()
+        .and_then(|mut writer| writer.write_image_data(image))
+        .map_err(png_error)
+}
+
+/// Rasterize the symbol at exactly `pixels_per_module` pixels per module, with a margin of `margin` modules, returning the image and its side length.
+pub(crate) fn to_scaled_image(
+    qr_code: &QrCode,
+    pixels_per_module: usize,
+    margin: usize,
+) -> Result<(Vec<u8>, usize), Error> {
+    if pixels_per_module == 0 {
+        return Err(Error::QrCode(QRCodeError::ImageSizeTooSmall));
+    }
+    let modules = qr_code.size() as usize;
+    let image_size = margin
+        .checked_mul(2)
+        .and_then(|margin| margin.checked_add(modules))
+        .and_then(|modules| modules.checked_mul(pixels_per_module))
+        .filter(|image_size| *image_size < 1 << (std::mem::size_of::<usize>() * 4))
+        .ok_or(Error::QrCode(QRCodeError::ImageSizeTooLarge))?;
+    let mut image = vec![255u8; image_size * image_size];
+    for (y, row) in image.chunks_exact_mut(image_size).enumerate() {
+        let module_y = match (y / pixels_per_module).checked_sub(margin) {
+            Some(module_y) if module_y < modules => module_y as i32,
+            _ => continue,
+        };
+        for module_x in 0..modules {
+            if qr_code.get_module(module_x as i32, module_y) {
+                let x = (margin + module_x) * pixels_per_module;
+                row[x..x + pixels_per_module].fill(0);
+            }
+        }
+    }
+    Ok((image, image_size))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use qrcode_generator::QrCodeEcc;
+
+    #[test]
+    fn it_renders_the_same_image_as_the_generator() {
+        let payload = "WIFI:S:test ssid;T:WPA;P:test password;H:false;;";
+        let qr_code = QrCode::encode_text(payload, QrCodeEcc::Medium).expect("Payload should fit.");
+        for image_size in [40, 100, 257] {
+            assert_eq!(
+                qrcode_generator::to_image(payload, QrCodeEcc::Medium, image_size)
+                    .expect("Image should render."),
+                to_image(&qr_code, image_size).expect("Image should render.")
+            );
+        }
+        assert_eq!(
+            qrcode_generator::to_matrix(payload, QrCodeEcc::Medium).expect("Matrix should render."),
+            to_matrix(&qr_code)
+        );
+    }
+
+    #[test]
+    fn it_scales_by_whole_pixels_per_module() {
+        let payload = "WIFI:S:test ssid;T:WPA;P:test password;H:false;;";
+        let qr_code = QrCode::encode_text(payload, QrCodeEcc::Medium).expect("Payload should fit.");
+        let (image, image_size) = to_scaled_image(&qr_code, 3, 4).expect("Image should render.");
+        assert_eq!((33 + 8) * 3, image_size);
+        assert_eq!(image_size * image_size, image.len());
+        assert_eq!(255, image[(12 * image_size) + 11]);
+        assert_eq!(0, image[(12 * image_size) + 12]);
+        assert_eq!(0, image[(14 * image_size) + 14]);
+
+        assert_eq!(
+            qrcode_generator::to_image(payload, QrCodeEcc::Medium, 35 * 3).unwrap(),
+            to_scaled_image(&qr_code, 3, 1).unwrap().0
+        );
+        assert!(to_scaled_image(&qr_code, 0, 4).is_err());
+        assert!(to_scaled_image(&qr_code, usize::MAX, 4).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "svg")]
+    fn it_writes_svg_paths_for_dark_modules() {
+        let payload = "WIFI:S:test ssid;T:WPA;P:test password;H:false;;";
+        let qr_code = QrCode::encode_text(payload, QrCodeEcc::Medium).expect("Payload should fit.");
+        let mut svg = Vec::new();
+        write_svg(&qr_code, 105, &mut svg).expect("SVG should render.");
+        let svg = String::from_utf8(svg).expect("SVG should be UTF-8.");
+        assert!(svg.starts_with("<?xml"));
+        assert!(svg.contains(r#"<svg width="105" height="105""#));
+        // The top row starts with the seven dark modules of a finder pattern, one module in from the edge.
+        assert!(svg.contains(r#"<path d="M3 3h21v3H3z"#));
+        let runs = (0..33)
+            .flat_map(|y| (0..33).map(move |x| (x, y)))
+            .filter(|(x, y)| qr_code.get_module(*x, *y) && !qr_code.get_module(x - 1, *y))
+            .count();
+        assert_eq!(runs, svg.matches('M').count());
+        assert!(svg.ends_with("\"/>\n</svg>\n"));
+        assert!(write_svg(&qr_code, 34, &mut Vec::new()).is_err());
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn it_writes_greyscale_pngs() {
+        let image = [0, 255, 255, 0];
+        let mut png = Vec::new();
+        write_png(&image, 2, &mut png).expect("PNG should encode.");
+        let mut reader = png::Decoder::new(png.as_slice())
+            .read_info()
+            .expect("PNG should decode.");
+        let mut decoded = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut decoded).expect("PNG should decode.");
+        assert_eq!((2, 2), (reader.info().width, reader.info().height));
+        assert_eq!(png::ColorType::Grayscale, reader.info().color_type);
+        assert_eq!(&image[..], &decoded[..]);
+    }
+
+    #[test]
+    #[cfg(feature = "png")]
+    fn it_streams_the_same_pixels_as_the_buffered_png() {
+        let payload = "WIFI:S:test ssid;T:WPA;P:test password;H:false;;";
+        let qr_code = QrCode::encode_text(payload, QrCodeEcc::Medium).expect("Payload should fit.");
+        let mut png = Vec::new();
+        write_png_streamed(&qr_code, 300, &mut png).expect("PNG should encode.");
+        let mut reader = png::Decoder::new(png.as_slice())
+            .read_info()
+            .expect("PNG should decode.");
+        let mut decoded = vec![0; reader.output_buffer_size()];
+        reader.next_frame(&mut decoded).expect("PNG should decode.");
+        assert_eq!(to_image(&qr_code, 300).unwrap(), decoded);
+    }
+
+    #[test]
+    #[cfg(all(feature = "png", feature = "svg"))]
+    fn it_reports_which_output_failed_to_write() {
+        let payload = "WIFI:S:test ssid;T:WPA;P:test password;H:false;;";
+        let qr_code = QrCode::encode_text(payload, QrCodeEcc::Medium).expect("Payload should fit.");
+        let full = || io::Cursor::new([0u8; 16]);
+        assert!(matches!(
+            write_png_streamed(&qr_code, 300, full()),
+            Err(Error::Io {
+                stage: OutputStage::Png,
+                source,
+            }) if source.kind() == io::ErrorKind::WriteZero
+        ));
+        match write_svg(&qr_code, 300, full()) {
+            Err(
+                error @ Error::Io {
+                    stage: OutputStage::Svg,
+                    ..
+                },
+            ) => assert!(error
+                .to_string()
+                .starts_with("failed to write the SVG image: ")),
+            result => panic!("Expected an SVG write error, got {:?}", result),
+        }
+    }
+
+    #[test]
+    fn it_flattens_the_matrix_row_by_row() {
+        let payload = "WIFI:S:test ssid;T:WPA;P:test password;H:false;;";
+        let qr_code = QrCode::encode_text(payload, QrCodeEcc::Medium).expect("Payload should fit.");
+        let (modules, size) = to_flat_matrix(&qr_code);
+        assert_eq!(33, size);
+        assert_eq!(to_matrix(&qr_code).concat(), modules);
+    }
+
+    #[test]
+    fn it_adds_a_quiet_zone() {
+        assert_eq!(
+            vec![
+                vec![false, false, false, false],
+                vec![false, true, false, false],
+                vec![false, false, true, false],
+                vec![false, false, false, false],
+            ],
+            with_quiet_zone(vec![vec![true, false], vec![false, true]], 1)
+        );
+        assert_eq!(vec![vec![true]], with_quiet_zone(vec![vec![true]], 0));
+    }
+
+    #[cfg(feature = "svg")]
+    #[test]
+    fn it_renders_bare_svg_elements_with_escaped_attributes() {
+        let payload = "WIFI:S:test ssid;T:WPA;P:test password;H:false;;";
+        let qr_code = QrCode::encode_text(payload, QrCodeEcc::Medium).expect("Payload should fit.");
+        let svg = svg_element(
+            &qr_code,
+            70,
+            &SvgAttributes {
+                id: Some(String::from("guest")),
+                class: None,
+                title: Some(String::from("Join \"test\" & more")),
+            },
+        )
+        .expect("Element should render.");
+        assert!(svg.starts_with(
+            "<svg id=\"guest\" role=\"img\" width=\"70\" height=\"70\" viewBox=\"0 0 70 70\""
+        ));
+        assert!(svg.contains("><title>Join &quot;test&quot; &amp; more</title><rect "));
+        // The symbol is 33 modules wide, so modules are 2 units with a 2 unit margin.
+        assert!(svg.contains("<path d=\"M2 2h14v2H2z"));
+        assert!(svg.ends_with("\"/></svg>"));
+    }
+}
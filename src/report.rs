@@ -0,0 +1,100 @@
+use crate::QrCodeEcc;
+
+use qrcode_generator::qrcodegen::QrCode;
+#[cfg(any(feature = "png", feature = "svg"))]
+use std::io::{self, Write};
+
+/// Details of a rendered QR code, returned by the `encode_as_*_with_report` functions so that tools can describe the code (e.g. "version 6, 41×41 modules") or make layout decisions without inspecting the output.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::QrCodeEcc;
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let (_, report) = wifi_qr_code::encode_as_image_with_report(&wifi_credentials, QrCodeEcc::Medium, 100)
+///     .expect("Failed to encode example image.");
+/// assert_eq!(17 + 4 * usize::from(report.version), report.modules_per_side);
+/// assert_eq!(100 * 100, report.output_bytes);
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct EncodeReport {
+    /// The QR code version, from 1 to 40.
+    pub version: u8,
+    /// The width and height of the QR code in modules, not including the quiet zone.
+    pub modules_per_side: usize,
+    /// The error correction level of the QR code. This can be higher than the requested level, since the level is raised when that still fits in the same version.
+    pub ecc: QrCodeEcc,
+    /// The length of the encoded payload in bytes.
+    pub payload_len: usize,
+    /// The number of bytes of image data produced.
+    pub output_bytes: usize,
+}
+
+impl EncodeReport {
+    pub(crate) fn new(qr_code: &QrCode, payload_len: usize, output_bytes: usize) -> Self {
+        EncodeReport {
+            version: qr_code.version().value(),
+            modules_per_side: qr_code.size() as usize,
+            ecc: qr_code.error_correction_level(),
+            payload_len,
+            output_bytes,
+        }
+    }
+}
+
+/// Count the bytes written through to the inner writer.
+#[cfg(any(feature = "png", feature = "svg"))]
+pub(crate) struct CountingWriter<W> {
+    pub(crate) inner: W,
+    pub(crate) count: usize,
+}
+
+#[cfg(any(feature = "png", feature = "svg"))]
+impl<W: Write> Write for CountingWriter<W> {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        let written = self.inner.write(buf)?;
+        self.count += written;
+        Ok(written)
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        self.inner.flush()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{AuthenticationType, QrCodeEcc, Visibility, WifiCredentials};
+
+    #[test]
+    fn it_reports_the_rendered_qr_code() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA("test password".into()),
+            visibility: Visibility::Visible,
+        };
+        let matrix = wifi_credentials.qr(QrCodeEcc::Low).matrix().unwrap();
+        let (image, report) =
+            crate::encode_as_image_with_report(&wifi_credentials, QrCodeEcc::Low, 100).unwrap();
+        assert_eq!(matrix.len(), report.modules_per_side);
+        assert_eq!(
+            17 + 4 * usize::from(report.version),
+            report.modules_per_side
+        );
+        assert_eq!(wifi_credentials.encode().as_str().len(), report.payload_len);
+        assert_eq!(
+            wifi_credentials
+                .qr(QrCodeEcc::Low)
+                .size(100)
+                .image()
+                .unwrap(),
+            image
+        );
+    }
+}
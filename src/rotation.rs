@@ -0,0 +1,429 @@
+//! Rotate the passphrase of a guest network: generate a new passphrase, configure the access point with it, and replace the QR code and printable card in a directory, keeping the previous ones in an `archive` subdirectory named by when they were replaced.
+//!
+//! Access points are configured through the [`AccessPoint`] trait, so any router or controller API can be plugged in. Closures taking the new credentials implement it, which is enough for most one-off integrations.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wifi_qr_code::rotation::Rotation;
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! let access_point = |wifi_credentials: &WifiCredentials| {
+//!     // Send the new passphrase to the router here.
+//!     Ok(())
+//! };
+//! let mut rotation = Rotation::new("example guest network", access_point, "guest-wifi");
+//! let rotated = rotation.rotate().expect("Failed to rotate the guest network.");
+//! println!("Print {}", rotated.card.display());
+//! ```
+
+use crate::export;
+use crate::{Error, QrCodeEcc};
+use crate::{ValidationError, WifiCredentials};
+
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// The file name of the current QR code in the rotation directory.
+pub const QR_CODE_FILE: &str = "qr.svg";
+/// The file name of the current printable card in the rotation directory.
+pub const CARD_FILE: &str = "card.svg";
+/// The subdirectory of the rotation directory that replaced files are moved to.
+pub const ARCHIVE_DIRECTORY: &str = "archive";
+
+/// Errors when rotating a guest network.
+#[derive(Debug)]
+pub enum RotationError {
+    /// No passphrase could be generated because the operating system's random number generator failed.
+    Random(getrandom::Error),
+    /// The generated credentials are not valid, e.g. because the SSID is too long.
+    Validation(ValidationError),
+    /// The access point could not be configured. The previous QR code and card are left in place.
+    AccessPoint(Box<dyn error::Error + Send + Sync>),
+    /// The QR code or card could not be rendered.
+    Render(Error),
+    /// The QR code or card could not be written, or the previous ones could not be archived. The access point has already been configured at this point.
+    Io(io::Error),
+}
+
+impl Display for RotationError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Random(error) => write!(f, "failed to generate a passphrase: {}", error),
+            Self::Validation(error) => write!(f, "invalid credentials: {}", error),
+            Self::AccessPoint(error) => {
+                write!(f, "failed to configure the access point: {}", error)
+            }
+            Self::Render(error) => write!(f, "failed to render the QR code: {}", error),
+            Self::Io(error) => write!(f, "failed to write the QR code: {}", error),
+        }
+    }
+}
+
+impl error::Error for RotationError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Random(error) => Some(error),
+            Self::Validation(error) => Some(error),
+            Self::AccessPoint(error) => Some(error.as_ref()),
+            Self::Render(error) => Some(error),
+            Self::Io(error) => Some(error),
+        }
+    }
+}
+
+impl From<getrandom::Error> for RotationError {
+    fn from(error: getrandom::Error) -> Self {
+        Self::Random(error)
+    }
+}
+
+impl From<ValidationError> for RotationError {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+impl From<Error> for RotationError {
+    fn from(error: Error) -> Self {
+        Self::Render(error)
+    }
+}
+
+impl From<io::Error> for RotationError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+/// An access point, router, or controller whose network credentials can be changed.
+pub trait AccessPoint {
+    /// Configure the network with the new credentials, so that guests can join with them once this returns.
+    fn set_credentials(
+        &mut self,
+        wifi_credentials: &WifiCredentials,
+    ) -> Result<(), Box<dyn error::Error + Send + Sync>>;
+}
+
+impl<F> AccessPoint for F
+where
+    F: FnMut(&WifiCredentials) -> Result<(), Box<dyn error::Error + Send + Sync>>,
+{
+    fn set_credentials(
+        &mut self,
+        wifi_credentials: &WifiCredentials,
+    ) -> Result<(), Box<dyn error::Error + Send + Sync>> {
+        self(wifi_credentials)
+    }
+}
+
+/// The outcome of a successful rotation.
+#[derive(Debug, Clone)]
+pub struct Rotated {
+    /// The new credentials of the network.
+    pub wifi_credentials: WifiCredentials,
+    /// The path of the new QR code.
+    pub qr_code: PathBuf,
+    /// The path of the new printable card.
+    pub card: PathBuf,
+    /// Where the replaced QR code and card were moved to, which is empty the first time a directory is used.
+    pub archived: Vec<PathBuf>,
+}
+
+/// The rotation of one guest network, whose QR code and card are kept in a directory.
+pub struct Rotation<A> {
+    ssid: String,
+    access_point: A,
+    directory: PathBuf,
+    qr_code_error_checking: QrCodeEcc,
+    image_size: usize,
+}
+
+impl<A: AccessPoint> Rotation<A> {
+    /// Rotate the network with the SSID through the access point, keeping its QR code and card in the directory, which is created if needed. QR codes use medium error correction and are 512 pixels square.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::rotation::Rotation;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let rotation = Rotation::new("example ssid", |_: &WifiCredentials| Ok(()), "guest-wifi");
+    /// ```
+    pub fn new(ssid: impl Into<String>, access_point: A, directory: impl Into<PathBuf>) -> Self {
+        Rotation {
+            ssid: ssid.into(),
+            access_point,
+            directory: directory.into(),
+            qr_code_error_checking: QrCodeEcc::Medium,
+            image_size: 512,
+        }
+    }
+
+    /// Use the error correction level for new QR codes and cards.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::rotation::Rotation;
+    /// use wifi_qr_code::{QrCodeEcc, WifiCredentials};
+    ///
+    /// let rotation = Rotation::new("example ssid", |_: &WifiCredentials| Ok(()), "guest-wifi")
+    ///     .error_checking(QrCodeEcc::High);
+    /// ```
+    pub fn error_checking(mut self, qr_code_error_checking: QrCodeEcc) -> Self {
+        self.qr_code_error_checking = qr_code_error_checking;
+        self
+    }
+
+    /// Write new QR codes at the size, in pixels.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::rotation::Rotation;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let rotation =
+    ///     Rotation::new("example ssid", |_: &WifiCredentials| Ok(()), "guest-wifi").size(1024);
+    /// ```
+    pub fn size(mut self, image_size: usize) -> Self {
+        self.image_size = image_size;
+        self
+    }
+
+    /// The access point the network is rotated through.
+    pub fn access_point(&self) -> &A {
+        &self.access_point
+    }
+
+    /// Generate a new passphrase, configure the access point with it, archive the current QR code and card, and write the new ones. Both contain the passphrase, so on Unix they are only readable by their owner, like the private files of [`ConfigFile::write_to`](crate::export::ConfigFile::write_to), and so are the archived copies.
+    ///
+    /// Everything is rendered before the access point is configured, so a failure before then leaves both the network and the directory as they were.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wifi_qr_code::rotation::Rotation;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let mut rotation = Rotation::new("example ssid", |_: &WifiCredentials| Ok(()), "guest-wifi");
+    /// let rotated = rotation.rotate().expect("Failed to rotate the guest network.");
+    /// for path in rotated.archived {
+    ///     println!("Archived {}", path.display());
+    /// }
+    /// ```
+    pub fn rotate(&mut self) -> Result<Rotated, RotationError> {
+        let wifi_credentials = WifiCredentials::wpa2_random(self.ssid.clone())?;
+        wifi_credentials.validate()?;
+        let mut qr_code = Vec::new();
+        wifi_credentials
+            .qr(self.qr_code_error_checking)
+            .size(self.image_size)
+            .svg(&mut qr_code)?;
+        let mut card = Vec::new();
+        wifi_credentials.write_svg_card(self.qr_code_error_checking, &mut card)?;
+
+        self.access_point
+            .set_credentials(&wifi_credentials)
+            .map_err(RotationError::AccessPoint)?;
+
+        fs::create_dir_all(&self.directory)?;
+        let timestamp = timestamp(SystemTime::now());
+        let mut archived = Vec::new();
+        for file in &[QR_CODE_FILE, CARD_FILE] {
+            let current = self.directory.join(file);
+            if current.exists() {
+                let archive =
+                    archive_path(&self.directory.join(ARCHIVE_DIRECTORY), &timestamp, file);
+                fs::create_dir_all(self.directory.join(ARCHIVE_DIRECTORY))?;
+                fs::rename(&current, &archive)?;
+                // Files written before the current ones were restricted keep their old permissions when they are moved.
+                #[cfg(unix)]
+                {
+                    use std::os::unix::fs::PermissionsExt;
+                    fs::set_permissions(&archive, fs::Permissions::from_mode(0o600))?;
+                }
+                archived.push(archive);
+            }
+        }
+        let qr_code_path = self.directory.join(QR_CODE_FILE);
+        export::write_file(&qr_code_path, &qr_code, true)?;
+        let card_path = self.directory.join(CARD_FILE);
+        export::write_file(&card_path, &card, true)?;
+        Ok(Rotated {
+            wifi_credentials,
+            qr_code: qr_code_path,
+            card: card_path,
+            archived,
+        })
+    }
+}
+
+/// A path in the archive that is not taken yet, numbering files that were replaced within the same second.
+fn archive_path(archive: &Path, timestamp: &str, file: &str) -> PathBuf {
+    let path = archive.join(format!("{}-{}", timestamp, file));
+    if !path.exists() {
+        return path;
+    }
+    (2..)
+        .map(|count| archive.join(format!("{}-{}-{}", timestamp, count, file)))
+        .find(|path| !path.exists())
+        .expect("There should be an unused archive path.")
+}
+
+/// The time as a compact ISO 8601 UTC timestamp, such as `20240229T134500Z`, which sorts in the order the files were archived.
+fn timestamp(time: SystemTime) -> String {
+    let seconds = time
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_secs())
+        .unwrap_or(0);
+    let (days, seconds) = (seconds / 86_400, seconds % 86_400);
+    // Converts days since 1970-01-01 to a date in the proleptic Gregorian calendar, counting years from March so that leap days come last.
+    let days = days as i64 + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days.rem_euclid(146_097);
+    let year_of_era =
+        (day_of_era - day_of_era / 1_460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = day_of_year - (153 * month_from_march + 2) / 5 + 1;
+    let month = if month_from_march < 10 {
+        month_from_march + 3
+    } else {
+        month_from_march - 9
+    };
+    let year = year_of_era + era * 400 + i64::from(month <= 2);
+    format!(
+        "{:04}{:02}{:02}T{:02}{:02}{:02}Z",
+        year,
+        month,
+        day,
+        seconds / 3_600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::AuthenticationType;
+
+    use std::time::Duration;
+
+    fn directory(name: &str) -> PathBuf {
+        let directory = std::env::temp_dir().join(format!(
+            "wifi-qr-code-rotation-{}-{}",
+            name,
+            std::process::id()
+        ));
+        let _ = fs::remove_dir_all(&directory);
+        directory
+    }
+
+    #[test]
+    fn it_formats_utc_timestamps() {
+        assert_eq!("19700101T000000Z", timestamp(UNIX_EPOCH));
+        assert_eq!(
+            "20240229T134500Z",
+            timestamp(UNIX_EPOCH + Duration::from_secs(1_709_214_300))
+        );
+        assert_eq!(
+            "21000301T000001Z",
+            timestamp(UNIX_EPOCH + Duration::from_secs(4_107_542_401))
+        );
+    }
+
+    #[test]
+    fn it_archives_the_previous_code() {
+        let directory = directory("archive");
+        let mut passphrases = Vec::new();
+        let mut rotation = Rotation::new(
+            "guest ssid",
+            |wifi_credentials: &WifiCredentials| {
+                if let AuthenticationType::WPA(passphrase) = &wifi_credentials.authentication_type {
+                    passphrases.push(passphrase.clone());
+                }
+                Ok(())
+            },
+            &directory,
+        );
+
+        let first = rotation.rotate().expect("First rotation should succeed.");
+        assert!(first.archived.is_empty());
+        let first_card = fs::read_to_string(&first.card).unwrap();
+        let second = rotation.rotate().expect("Second rotation should succeed.");
+        assert_eq!(2, second.archived.len());
+        assert_eq!(first_card, fs::read_to_string(&second.archived[1]).unwrap());
+        assert!(second.archived[1]
+            .to_string_lossy()
+            .ends_with(&format!("Z-{}", CARD_FILE)));
+        let third = rotation.rotate().expect("Third rotation should succeed.");
+        assert!(third.archived.iter().all(|path| path.exists()));
+        assert_eq!(
+            4,
+            fs::read_dir(directory.join(ARCHIVE_DIRECTORY))
+                .unwrap()
+                .count()
+        );
+        drop(rotation);
+
+        assert_eq!(3, passphrases.len());
+        assert_ne!(passphrases[0], passphrases[1]);
+        let card = fs::read_to_string(&third.card).unwrap();
+        assert!(card.contains(&format!("Password: {}", passphrases[2].as_str())));
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn it_keeps_the_previous_code_when_the_access_point_fails() {
+        let directory = directory("failure");
+        let mut rotation = Rotation::new("guest ssid", |_: &WifiCredentials| Ok(()), &directory);
+        let rotated = rotation.rotate().expect("Rotation should succeed.");
+        let card = fs::read_to_string(&rotated.card).unwrap();
+
+        let mut rotation = Rotation::new(
+            "guest ssid",
+            |_: &WifiCredentials| Err("the router is unreachable".into()),
+            &directory,
+        );
+        assert!(matches!(
+            rotation.rotate(),
+            Err(RotationError::AccessPoint(error)) if error.to_string() == "the router is unreachable"
+        ));
+        assert_eq!(card, fs::read_to_string(&rotated.card).unwrap());
+        assert!(!directory.join(ARCHIVE_DIRECTORY).exists());
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[cfg(unix)]
+    #[test]
+    fn it_only_lets_the_owner_read_the_passphrase() {
+        use std::os::unix::fs::PermissionsExt;
+
+        let mode = |path: &Path| fs::metadata(path).unwrap().permissions().mode() & 0o777;
+        let directory = directory("permissions");
+        fs::create_dir_all(&directory).unwrap();
+        // A card left behind with the permissions of the umask, before files were restricted.
+        fs::write(directory.join(CARD_FILE), "").unwrap();
+        fs::set_permissions(directory.join(CARD_FILE), fs::Permissions::from_mode(0o644)).unwrap();
+        let mut rotation = Rotation::new("guest ssid", |_: &WifiCredentials| Ok(()), &directory);
+        let first = rotation.rotate().expect("First rotation should succeed.");
+        assert_eq!(1, first.archived.len());
+        let second = rotation.rotate().expect("Second rotation should succeed.");
+        for path in first
+            .archived
+            .iter()
+            .chain(&second.archived)
+            .chain([&second.qr_code, &second.card])
+        {
+            assert_eq!(0o600, mode(path), "{}", path.display());
+        }
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
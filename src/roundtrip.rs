@@ -0,0 +1,155 @@
+use crate::{CredentialField, EscapingMode, ParseError, WifiCredentials};
+
+use std::error;
+use std::fmt::{self, Display, Formatter};
+
+/// Reasons credentials do not survive being encoded and parsed again, reported by [`WifiCredentials::roundtrips`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum RoundtripError {
+    /// The encoded payload could not be parsed at all.
+    Parse(ParseError),
+    /// The payload parsed, but these fields came back different from the original credentials.
+    Lossy(Vec<CredentialField>),
+}
+
+impl Display for RoundtripError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Parse(error) => write!(f, "the encoded payload does not parse: {}", error),
+            Self::Lossy(fields) => {
+                f.write_str("the encoded payload changes the ")?;
+                for (index, field) in fields.iter().enumerate() {
+                    if index > 0 {
+                        f.write_str(" and ")?;
+                    }
+                    Display::fmt(field, f)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl error::Error for RoundtripError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Parse(error) => Some(error),
+            Self::Lossy(_) => None,
+        }
+    }
+}
+
+impl From<ParseError> for RoundtripError {
+    fn from(error: ParseError) -> Self {
+        Self::Parse(error)
+    }
+}
+
+impl WifiCredentials {
+    /// The credentials as a scanner will read them: encoded with [`WifiCredentials::encode`] and parsed back with [`WifiCredentials::parse`]. For example, a WPA passphrase of 64 hex digits comes back as a pre-shared key.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "ab".repeat(32));
+    /// let canonical = wifi_credentials.canonicalize().expect("Failed to parse example payload.");
+    /// assert_eq!(AuthenticationType::WpaPsk([0xab; 32].into()), canonical.authentication_type);
+    /// ```
+    pub fn canonicalize(&self) -> Result<WifiCredentials, ParseError> {
+        self.canonicalize_with(EscapingMode::Spec)
+    }
+
+    /// The credentials as a scanner will read them when encoded with a specific escaping dialect, like [`WifiCredentials::canonicalize`].
+    pub fn canonicalize_with(
+        &self,
+        escaping_mode: EscapingMode,
+    ) -> Result<WifiCredentials, ParseError> {
+        WifiCredentials::parse(self.encode_with(escaping_mode).as_str())
+    }
+
+    /// Check that the credentials survive being encoded and parsed again, reporting every field that does not. Together with [`WifiCredentials::validate`], this guarantees that a generated code describes the network it was generated for.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{CredentialField, RoundtripError, WifiCredentials};
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// assert_eq!(Ok(()), wifi_credentials.roundtrips());
+    ///
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "ab".repeat(32));
+    /// assert_eq!(
+    ///     Err(RoundtripError::Lossy(vec![CredentialField::Password])),
+    ///     wifi_credentials.roundtrips()
+    /// );
+    /// ```
+    pub fn roundtrips(&self) -> Result<(), RoundtripError> {
+        self.roundtrips_with(EscapingMode::Spec)
+    }
+
+    /// Check that the credentials survive being encoded with a specific escaping dialect and parsed again, like [`WifiCredentials::roundtrips`]. Dialects that escape less, such as [`EscapingMode::Minimal`], lose values that only make sense escaped.
+    pub fn roundtrips_with(&self, escaping_mode: EscapingMode) -> Result<(), RoundtripError> {
+        let canonical = self.canonicalize_with(escaping_mode)?;
+        let mut lossy = Vec::new();
+        if canonical.ssid != self.ssid {
+            lossy.push(CredentialField::Ssid);
+        }
+        if canonical.authentication_type != self.authentication_type {
+            lossy.push(CredentialField::Password);
+        }
+        if lossy.is_empty() {
+            Ok(())
+        } else {
+            Err(RoundtripError::Lossy(lossy))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility};
+
+    #[test]
+    fn it_roundtrips_special_characters_in_every_dialect() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from(r"special_characters ;,:\"),
+            authentication_type: AuthenticationType::WEP("c0ffee1234".into()),
+            visibility: Visibility::Hidden,
+        };
+        for escaping_mode in [
+            EscapingMode::Spec,
+            EscapingMode::ZxingQuoted,
+            EscapingMode::Minimal,
+        ] {
+            assert_eq!(Ok(()), wifi_credentials.roundtrips_with(escaping_mode));
+            assert_eq!(
+                Ok(&wifi_credentials),
+                wifi_credentials.canonicalize_with(escaping_mode).as_ref()
+            );
+        }
+    }
+
+    #[test]
+    fn it_reports_lossy_fields() {
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from(r#""quoted""#),
+            authentication_type: AuthenticationType::WPA(r#""quoted password""#.into()),
+            visibility: Visibility::Visible,
+        };
+        assert_eq!(Ok(()), wifi_credentials.roundtrips());
+        let error = wifi_credentials
+            .roundtrips_with(EscapingMode::Minimal)
+            .unwrap_err();
+        assert_eq!(
+            RoundtripError::Lossy(vec![CredentialField::Ssid, CredentialField::Password]),
+            error
+        );
+        assert_eq!(
+            "the encoded payload changes the SSID and password",
+            error.to_string()
+        );
+    }
+}
@@ -0,0 +1,227 @@
+use std::fmt::{self, Debug, Formatter};
+use std::ops::{Deref, DerefMut};
+
+/// A password, pre-shared key, or text containing one, such as the password of an [`AuthenticationType`](crate::AuthenticationType) or a configuration file from the [`export`](crate::export) module. Its `Debug` implementation is redacted, and with the `zeroize` feature, it is wiped from memory when dropped.
+///
+/// The wrapper is the same with and without the feature, so enabling `zeroize` does not break code elsewhere in the dependency tree. The value is read through `Deref`, and moved out with [`Secret::into_inner`], after which it is no longer wiped automatically. Growing a `String` inside the wrapper reallocates it and leaves the old buffer behind unwiped, so reserve its capacity up front where that matters.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{AuthenticationType, Secret};
+///
+/// let authentication_type = AuthenticationType::WPA(Secret::from("example password"));
+/// if let AuthenticationType::WPA(password) = &authentication_type {
+///     assert_eq!(16, password.len());
+///     assert_eq!("<redacted>", format!("{:?}", password));
+/// }
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash, Default)]
+pub struct Secret<T: sealed::Wipe>(T);
+
+mod sealed {
+    /// The types a [`Secret`](super::Secret) can hold, which are the ones that can be wiped.
+    pub trait Wipe {
+        fn wipe(&mut self);
+    }
+
+    impl Wipe for String {
+        fn wipe(&mut self) {
+            #[cfg(feature = "zeroize")]
+            zeroize::Zeroize::zeroize(self);
+        }
+    }
+
+    impl Wipe for Vec<u8> {
+        fn wipe(&mut self) {
+            #[cfg(feature = "zeroize")]
+            zeroize::Zeroize::zeroize(self);
+        }
+    }
+
+    impl Wipe for [u8; 32] {
+        fn wipe(&mut self) {
+            #[cfg(feature = "zeroize")]
+            zeroize::Zeroize::zeroize(self);
+        }
+    }
+}
+
+impl<T: sealed::Wipe> Secret<T> {
+    /// Wrap the value.
+    pub fn new(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+impl Secret<String> {
+    /// Format `arguments` into a string that is allocated at its final length, so that growing it does not leave partial copies of a password behind.
+    pub(crate) fn format(arguments: fmt::Arguments<'_>) -> Self {
+        Self::write_with(|writer| writer.write_fmt(arguments))
+    }
+
+    /// The UTF-8 bytes of the text, reusing its buffer.
+    pub(crate) fn into_bytes(mut self) -> Secret<Vec<u8>> {
+        Secret(std::mem::take(&mut self.0).into_bytes())
+    }
+
+    /// Collect the output of `write` like [`Secret::format`], calling it twice: once to measure the text, and once to write it.
+    pub(crate) fn write_with(write: impl Fn(&mut dyn fmt::Write) -> fmt::Result) -> Self {
+        let mut text = Secret(String::with_capacity(measure(&write)));
+        write(&mut *text).expect("Writing to a String cannot fail.");
+        text
+    }
+}
+
+/// The number of bytes that `write` writes, for allocating a buffer of the right size up front.
+pub(crate) fn measure(write: impl Fn(&mut dyn fmt::Write) -> fmt::Result) -> usize {
+    let mut counter = Counter(0);
+    write(&mut counter).expect("Counting the length of a string cannot fail.");
+    counter.0
+}
+
+/// Counts the bytes written to it.
+struct Counter(usize);
+
+impl fmt::Write for Counter {
+    fn write_str(&mut self, s: &str) -> fmt::Result {
+        self.0 += s.len();
+        Ok(())
+    }
+}
+
+impl<T: sealed::Wipe + Default> Secret<T> {
+    /// Move the value out of the wrapper. With the `zeroize` feature, the returned value is no longer wiped automatically.
+    pub fn into_inner(mut self) -> T {
+        std::mem::take(&mut self.0)
+    }
+}
+
+impl<T: sealed::Wipe> Deref for Secret<T> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.0
+    }
+}
+
+impl<T: sealed::Wipe> DerefMut for Secret<T> {
+    fn deref_mut(&mut self) -> &mut T {
+        &mut self.0
+    }
+}
+
+impl<T: sealed::Wipe> From<T> for Secret<T> {
+    fn from(value: T) -> Self {
+        Secret(value)
+    }
+}
+
+impl From<&str> for Secret<String> {
+    fn from(value: &str) -> Self {
+        Secret(String::from(value))
+    }
+}
+
+impl<T: sealed::Wipe> Debug for Secret<T> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.write_str("<redacted>")
+    }
+}
+
+impl PartialEq<str> for Secret<String> {
+    fn eq(&self, other: &str) -> bool {
+        self.0 == other
+    }
+}
+
+impl PartialEq<&str> for Secret<String> {
+    fn eq(&self, other: &&str) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<String> for Secret<String> {
+    fn eq(&self, other: &String) -> bool {
+        self.0 == *other
+    }
+}
+
+impl PartialEq<Secret<String>> for str {
+    fn eq(&self, other: &Secret<String>) -> bool {
+        self == other.0
+    }
+}
+
+impl PartialEq<Secret<String>> for &str {
+    fn eq(&self, other: &Secret<String>) -> bool {
+        *self == other.0
+    }
+}
+
+impl PartialEq<Secret<String>> for String {
+    fn eq(&self, other: &Secret<String>) -> bool {
+        *self == other.0
+    }
+}
+
+impl<T: sealed::Wipe> Drop for Secret<T> {
+    fn drop(&mut self) {
+        sealed::Wipe::wipe(&mut self.0);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: sealed::Wipe> zeroize::Zeroize for Secret<T> {
+    fn zeroize(&mut self) {
+        sealed::Wipe::wipe(&mut self.0);
+    }
+}
+
+#[cfg(feature = "zeroize")]
+impl<T: sealed::Wipe> zeroize::ZeroizeOnDrop for Secret<T> {}
+
+/// Describes the wrapped value, since the wrapper is transparent to serialization.
+#[cfg(feature = "schemars")]
+impl<T: sealed::Wipe + schemars::JsonSchema> schemars::JsonSchema for Secret<T> {
+    fn inline_schema() -> bool {
+        T::inline_schema()
+    }
+
+    fn schema_name() -> std::borrow::Cow<'static, str> {
+        T::schema_name()
+    }
+
+    fn json_schema(generator: &mut schemars::SchemaGenerator) -> schemars::Schema {
+        T::json_schema(generator)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_hides_the_value_but_compares_it() {
+        let secret = Secret::from("test password");
+        assert_eq!("<redacted>", format!("{:?}", secret));
+        assert_eq!("test password", secret);
+        assert_eq!(Secret::from("test password"), secret);
+        assert_eq!(String::from("test password"), secret.into_inner());
+    }
+
+    #[test]
+    fn it_formats_without_growing_the_string() {
+        let secret = Secret::format(format_args!("{}={}", "psk", "test password"));
+        assert_eq!("psk=test password", secret);
+        assert_eq!(secret.len(), secret.capacity());
+    }
+
+    #[cfg(feature = "zeroize")]
+    #[test]
+    fn it_wipes_the_value() {
+        let mut secret = Secret::new([0xab; 32]);
+        zeroize::Zeroize::zeroize(&mut secret);
+        assert_eq!([0; 32], *secret);
+    }
+}
@@ -0,0 +1,1759 @@
+//! A small HTTP server for the QR code of a network, so a display or kiosk on the network can always show the current one.
+//!
+//! The endpoint gives away the password of the network to whoever can reach it, so the server only listens on loopback addresses and Unix sockets unless [`Server::allow_remote`] is set, can require a bearer token with [`Server::token`], and can limit how often each client may ask with [`Server::rate_limit`]. Requests are served one at a time, over plain HTTP unless [`Server::tls`] is set with the `tls` feature; without it, put a reverse proxy in front of the server to serve it beyond the local machine.
+//!
+//! | Endpoint | Response |
+//! |---|---|
+//! | `GET /health` | `{"status":"ok"}`, without the token |
+//! | `GET /openapi.json` | An [OpenAPI](https://www.openapis.org) 3.0 description of the endpoints, without the token |
+//! | `GET /metrics` | Counters of requests, generated QR codes, decode attempts, and render durations, in the [Prometheus](https://prometheus.io) text format, without the token |
+//! | `GET /wifi.png` | The QR code as a PNG image |
+//! | `GET /wifi.svg` | The QR code as an SVG image |
+//! | `POST /generate` | The QR code of the network in the JSON body, such as `{"ssid":"Café","password":"example password","format":"svg"}` |
+//! | `POST /decode` | The credentials in the QR code of a PNG or JPEG image, as JSON with whether they are valid, with the `decode` feature. The image is the body, or the `image` field of a `multipart/form-data` upload |
+//! | `POST /guest` | The QR code of a short-lived passphrase for the guest network set with [`Server::guest`], with the `guest` feature |
+//!
+//! Errors are answered with a JSON body such as `{"error":"ssid is required"}`.
+//!
+//! # Examples
+//!
+//! ```no_run
+//! use wifi_qr_code::serve::{Listen, Server};
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! use std::time::Duration;
+//!
+//! let wifi_credentials = WifiCredentials::wpa2("example guest network", "example password");
+//! Server::new(wifi_credentials)
+//!     .token("example token")
+//!     .rate_limit(10, Duration::from_secs(60))
+//!     .serve(&Listen::Tcp(([127, 0, 0, 1], 8080).into()))
+//!     .expect("Failed to start the server.");
+//! ```
+
+mod metrics;
+#[cfg(feature = "tls")]
+mod tls;
+
+#[cfg(feature = "guest")]
+use crate::rotation::{AccessPoint, RotationError};
+use crate::{AuthenticationType, Error, QrCodeEcc, ValidationError, Visibility, WifiCredentials};
+use metrics::Metrics;
+#[cfg(feature = "tls")]
+use tls::Tls;
+
+use serde_json::json;
+use std::collections::HashMap;
+use std::error;
+use std::fmt::{self, Debug, Display, Formatter};
+use std::io::{self, BufRead, BufReader, Read, Write};
+use std::net::{IpAddr, Ipv6Addr, SocketAddr, TcpListener, TcpStream};
+#[cfg(any(unix, feature = "tls"))]
+use std::path::PathBuf;
+#[cfg(feature = "guest")]
+use std::sync::{Arc, Mutex, Weak};
+#[cfg(feature = "guest")]
+use std::thread;
+use std::time::{Duration, Instant};
+
+/// How long a client may take to send its whole request, or to receive the response. Connections are answered one at a time, so the limit is for the whole request rather than each read, which keeps a client that sends a byte at a time from holding up everyone else.
+const TIMEOUT: Duration = Duration::from_secs(10);
+/// The longest request line or header line that is accepted, in bytes.
+const MAX_LINE_LENGTH: usize = 8 * 1024;
+/// The most headers a request may have.
+const MAX_HEADERS: usize = 64;
+/// The largest request body that is accepted, in bytes.
+const MAX_BODY_LENGTH: usize = 1024 * 1024;
+/// The width and height of QR code images, in pixels, unless a request asks for another size.
+const DEFAULT_IMAGE_SIZE: usize = 512;
+/// The largest QR code image a request may ask for, which keeps a PNG at most 16 MiB before compression.
+const MAX_IMAGE_SIZE: usize = 4096;
+/// How often expired guest passphrases are looked for.
+#[cfg(feature = "guest")]
+const GUEST_EXPIRY_INTERVAL: Duration = Duration::from_secs(1);
+/// The number of clients whose requests are counted. Once it is reached, clients that have not made a request in the current period are forgotten, and new clients are limited until there is room for them.
+const MAX_TRACKED_CLIENTS: usize = 4096;
+
+/// Errors when starting the server.
+#[derive(Debug)]
+pub enum ServeError {
+    /// The address or socket could not be listened on.
+    Io(io::Error),
+    /// The credentials to serve do not meet the requirements of the wifi standards, so their QR code could not be rendered.
+    Validation(ValidationError),
+    /// The address is not a loopback address, and [`Server::allow_remote`] was not set.
+    RemoteAddress(SocketAddr),
+    /// The certificate or key file of [`Server::tls`] could not be read, or has no PEM sections of the right kind.
+    #[cfg(feature = "tls")]
+    Pem {
+        /// The file that could not be loaded.
+        path: PathBuf,
+        /// Why it could not be loaded.
+        source: rustls::pki_types::pem::Error,
+    },
+    /// The certificate and key of [`Server::tls`] were rejected, e.g. because the key does not belong to the certificate.
+    #[cfg(feature = "tls")]
+    Tls(rustls::Error),
+}
+
+impl Display for ServeError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Io(error) => write!(f, "failed to listen: {}", error),
+            Self::Validation(error) => write!(f, "invalid credentials: {}", error),
+            Self::RemoteAddress(address) => write!(
+                f,
+                "refusing to serve network credentials on {}, which is not a loopback address",
+                address
+            ),
+            #[cfg(feature = "tls")]
+            Self::Pem { path, source } => {
+                write!(f, "failed to load {}: {}", path.display(), source)
+            }
+            #[cfg(feature = "tls")]
+            Self::Tls(error) => write!(f, "invalid certificate or key: {}", error),
+        }
+    }
+}
+
+impl error::Error for ServeError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Io(error) => Some(error),
+            Self::Validation(error) => Some(error),
+            Self::RemoteAddress(_) => None,
+            #[cfg(feature = "tls")]
+            Self::Pem { source, .. } => Some(source),
+            #[cfg(feature = "tls")]
+            Self::Tls(error) => Some(error),
+        }
+    }
+}
+
+impl From<io::Error> for ServeError {
+    fn from(error: io::Error) -> Self {
+        Self::Io(error)
+    }
+}
+
+impl From<ValidationError> for ServeError {
+    fn from(error: ValidationError) -> Self {
+        Self::Validation(error)
+    }
+}
+
+#[cfg(feature = "tls")]
+impl From<rustls::Error> for ServeError {
+    fn from(error: rustls::Error) -> Self {
+        Self::Tls(error)
+    }
+}
+
+/// Where the server listens for connections.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Listen {
+    /// A TCP address, such as `127.0.0.1:8080`.
+    Tcp(SocketAddr),
+    /// A Unix domain socket at the path, which must not exist yet. Who may connect is decided by the permissions of the socket file.
+    #[cfg(unix)]
+    Unix(PathBuf),
+}
+
+/// The requests counted for a client in the current period.
+#[derive(Debug, Clone, Copy)]
+struct Window {
+    start: Instant,
+    requests: u32,
+}
+
+/// The guest network whose passphrases are handed out by `POST /guest`.
+#[cfg(feature = "guest")]
+struct Guest {
+    ssid: String,
+    access_point: Box<dyn AccessPoint + Send>,
+    lifetime: Duration,
+    /// The passphrase being handed out, and when it expires.
+    current: Option<(WifiCredentials, Instant)>,
+}
+
+#[cfg(feature = "guest")]
+impl fmt::Debug for Guest {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Guest")
+            .field("ssid", &self.ssid)
+            .field("lifetime", &self.lifetime)
+            .field("current", &self.current)
+            .finish_non_exhaustive()
+    }
+}
+
+#[cfg(feature = "guest")]
+impl Guest {
+    /// Configure the access point with a new random passphrase.
+    fn rotate(&mut self) -> Result<WifiCredentials, RotationError> {
+        let wifi_credentials = WifiCredentials::wpa2_random(self.ssid.clone())?;
+        wifi_credentials.validate()?;
+        self.access_point
+            .set_credentials(&wifi_credentials)
+            .map_err(RotationError::AccessPoint)?;
+        Ok(wifi_credentials)
+    }
+
+    /// The credentials to hand out at the time, and when they expire. Everyone who asks before they expire gets the same ones, and a new passphrase is minted for the first one to ask after that.
+    fn credentials(&mut self, now: Instant) -> Result<(WifiCredentials, Instant), RotationError> {
+        match &self.current {
+            Some((wifi_credentials, expires)) if now < *expires => {
+                Ok((wifi_credentials.clone(), *expires))
+            }
+            _ => {
+                let current = (self.rotate()?, now + self.lifetime);
+                self.current = Some(current.clone());
+                Ok(current)
+            }
+        }
+    }
+
+    /// Replace the passphrase if it has expired by the time, so that it stops working even if nobody asks for a new one. The replacement is never handed out. If the access point cannot be configured, it is tried again the next time.
+    fn expire(&mut self, now: Instant) {
+        if self
+            .current
+            .as_ref()
+            .is_some_and(|(_, expires)| now >= *expires)
+            && self.rotate().is_ok()
+        {
+            self.current = None;
+        }
+    }
+}
+
+/// An HTTP server for the QR code of a network.
+#[derive(Clone)]
+pub struct Server {
+    wifi_credentials: WifiCredentials,
+    qr_code_error_checking: QrCodeEcc,
+    token: Option<String>,
+    rate_limit: Option<(u32, Duration)>,
+    allow_remote: bool,
+    clients: HashMap<IpAddr, Window>,
+    /// When the earliest window of the tracked clients ends, before which forgetting clients would not make room.
+    clients_expire: Instant,
+    metrics: Metrics,
+    #[cfg(feature = "guest")]
+    guest: Option<Arc<Mutex<Guest>>>,
+    #[cfg(feature = "tls")]
+    tls: Option<Tls>,
+}
+
+/// Shows whether a token is required, but not the token itself.
+impl Debug for Server {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        let mut debug = f.debug_struct("Server");
+        debug
+            .field("wifi_credentials", &self.wifi_credentials)
+            .field("qr_code_error_checking", &self.qr_code_error_checking)
+            .field(
+                "token",
+                &self.token.as_ref().map(|_| format_args!("<redacted>")),
+            )
+            .field("rate_limit", &self.rate_limit)
+            .field("allow_remote", &self.allow_remote)
+            .field("clients", &self.clients)
+            .field("clients_expire", &self.clients_expire)
+            .field("metrics", &self.metrics);
+        #[cfg(feature = "guest")]
+        debug.field("guest", &self.guest);
+        #[cfg(feature = "tls")]
+        debug.field("tls", &self.tls);
+        debug.finish()
+    }
+}
+
+impl Server {
+    /// Serve the QR code of the network, with medium error correction, to anyone who can connect.
+    pub fn new(wifi_credentials: WifiCredentials) -> Self {
+        Server {
+            wifi_credentials,
+            qr_code_error_checking: QrCodeEcc::Medium,
+            token: None,
+            rate_limit: None,
+            allow_remote: false,
+            clients: HashMap::new(),
+            clients_expire: Instant::now(),
+            metrics: Metrics::default(),
+            #[cfg(feature = "guest")]
+            guest: None,
+            #[cfg(feature = "tls")]
+            tls: None,
+        }
+    }
+
+    /// Use the error correction level for the QR code.
+    pub fn error_checking(mut self, qr_code_error_checking: QrCodeEcc) -> Self {
+        self.qr_code_error_checking = qr_code_error_checking;
+        self
+    }
+
+    /// Require requests to have an `Authorization: Bearer` header with the token, and answer others with `401 Unauthorized`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::serve::Server;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let token = std::env::var("WIFI_QR_TOKEN").unwrap_or_default();
+    /// let server = Server::new(WifiCredentials::wpa2("example ssid", "example password")).token(token);
+    /// ```
+    pub fn token(mut self, token: impl Into<String>) -> Self {
+        self.token = Some(token.into());
+        self
+    }
+
+    /// Answer clients that make more than `requests` requests within the period, counted by IP address, or by `/64` prefix for IPv6 addresses since a single host often has a whole prefix to itself, with `429 Too Many Requests` until the period is over. Requests are counted before the token is checked, so the limit also slows down guessing it. Clients connecting through Unix sockets are not limited.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::serve::Server;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// use std::time::Duration;
+    ///
+    /// let server = Server::new(WifiCredentials::wpa2("example ssid", "example password"))
+    ///     .rate_limit(60, Duration::from_secs(60));
+    /// ```
+    pub fn rate_limit(mut self, requests: u32, period: Duration) -> Self {
+        self.rate_limit = Some((requests, period));
+        self
+    }
+
+    /// Allow listening on addresses other than loopback addresses, which makes the password of the network available to the whole network the server is on.
+    pub fn allow_remote(mut self, allow_remote: bool) -> Self {
+        self.allow_remote = allow_remote;
+        self
+    }
+
+    /// Hand out short-lived passphrases for the guest network with the SSID at `POST /guest`, configuring the access point with a new random passphrase whenever the previous one has expired. Passphrases are also replaced once they expire when nobody asks for a new one, so they stop working after the lifetime either way.
+    ///
+    /// Requires the `guest` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::serve::Server;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// use std::time::Duration;
+    ///
+    /// let access_point = |wifi_credentials: &WifiCredentials| {
+    ///     // Send the new passphrase to the router here.
+    ///     Ok(())
+    /// };
+    /// let server = Server::new(WifiCredentials::wpa2("example ssid", "example password"))
+    ///     .token("example token")
+    ///     .guest("example guest network", access_point, Duration::from_secs(4 * 60 * 60));
+    /// ```
+    #[cfg(feature = "guest")]
+    pub fn guest(
+        mut self,
+        ssid: impl Into<String>,
+        access_point: impl AccessPoint + Send + 'static,
+        lifetime: Duration,
+    ) -> Self {
+        self.guest = Some(Arc::new(Mutex::new(Guest {
+            ssid: ssid.into(),
+            access_point: Box::new(access_point),
+            lifetime,
+            current: None,
+        })));
+        self
+    }
+
+    /// Serve TCP connections over TLS, identified by the certificate chain and private key in the PEM files. The files are loaded again when they change, so certificates renewed by an ACME client such as certbot are picked up without restarting the server. Unix sockets are still served without TLS.
+    ///
+    /// Requires the `tls` feature.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wifi_qr_code::serve::{Listen, Server};
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// Server::new(WifiCredentials::wpa2("example ssid", "example password"))
+    ///     .tls(
+    ///         "/etc/letsencrypt/live/wifi.example.com/fullchain.pem",
+    ///         "/etc/letsencrypt/live/wifi.example.com/privkey.pem",
+    ///     )
+    ///     .allow_remote(true)
+    ///     .serve(&Listen::Tcp(([0, 0, 0, 0], 8443).into()))
+    ///     .expect("Failed to start the server.");
+    /// ```
+    #[cfg(feature = "tls")]
+    pub fn tls(mut self, certificate: impl Into<PathBuf>, key: impl Into<PathBuf>) -> Self {
+        self.tls = Some(Tls::new(certificate.into(), key.into()));
+        self
+    }
+
+    /// Validate the credentials, then listen for connections and answer them until the process exits. Only errors from starting to listen are returned; clients that fail to send a valid request are disconnected.
+    pub fn serve(mut self, listen: &Listen) -> Result<(), ServeError> {
+        self.wifi_credentials.validate()?;
+        match listen {
+            Listen::Tcp(address) => {
+                if !address.ip().is_loopback() && !self.allow_remote {
+                    return Err(ServeError::RemoteAddress(*address));
+                }
+                #[cfg(feature = "tls")]
+                if let Some(tls) = &mut self.tls {
+                    tls.config()?;
+                }
+                let listener = TcpListener::bind(address)?;
+                self.expire_guest_passphrases();
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(_) => continue,
+                    };
+                    if stream.set_read_timeout(Some(TIMEOUT)).is_ok()
+                        && stream.set_write_timeout(Some(TIMEOUT)).is_ok()
+                    {
+                        let _ = self.tcp_connection(stream);
+                    }
+                }
+            }
+            #[cfg(unix)]
+            Listen::Unix(path) => {
+                let listener = std::os::unix::net::UnixListener::bind(path)?;
+                self.expire_guest_passphrases();
+                for stream in listener.incoming() {
+                    let stream = match stream {
+                        Ok(stream) => stream,
+                        Err(_) => continue,
+                    };
+                    if stream.set_read_timeout(Some(TIMEOUT)).is_ok()
+                        && stream.set_write_timeout(Some(TIMEOUT)).is_ok()
+                    {
+                        let _ = self.connection(stream, None);
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Replace expired guest passphrases in the background while the server runs, since it blocks waiting for connections.
+    fn expire_guest_passphrases(&self) {
+        #[cfg(feature = "guest")]
+        if let Some(guest) = &self.guest {
+            let guest = Arc::downgrade(guest);
+            thread::spawn(move || expire_until_stopped(&guest));
+        }
+    }
+
+    /// Answer the one request of a TCP connection, over TLS if it is configured.
+    fn tcp_connection(&mut self, stream: TcpStream) -> io::Result<()> {
+        let peer = stream.peer_addr().ok().map(|address| address.ip());
+        #[cfg(feature = "tls")]
+        if let Some(tls) = &mut self.tls {
+            let config = tls.config().map_err(io::Error::other)?;
+            let connection = rustls::ServerConnection::new(config).map_err(io::Error::other)?;
+            let mut stream = rustls::StreamOwned::new(connection, stream);
+            self.connection(&mut stream, peer)?;
+            stream.conn.send_close_notify();
+            return stream.flush();
+        }
+        self.connection(stream, peer)
+    }
+
+    /// Answer the one request of a connection, from the peer if it has an IP address.
+    fn connection(&mut self, stream: impl Read + Write, peer: Option<IpAddr>) -> io::Result<()> {
+        let mut reader = BufReader::new(Deadline {
+            inner: stream,
+            deadline: Instant::now() + TIMEOUT,
+        });
+        let response = match read_request(&mut reader) {
+            Ok(request) => self.respond(&request, peer, Instant::now()),
+            Err(error) if error.kind() == io::ErrorKind::InvalidData => {
+                Response::error(400, "Bad Request", &error.to_string())
+            }
+            Err(error) => return Err(error),
+        };
+        let mut stream = reader.into_inner().inner;
+        response.write_to(&mut stream)?;
+        stream.flush()
+    }
+
+    /// The response to the request at the time, counted in the metrics.
+    fn respond(&mut self, request: &Request, peer: Option<IpAddr>, now: Instant) -> Response {
+        let response = self.answer(request, peer, now);
+        // Unknown paths are counted together, so clients cannot add labels.
+        let path = ROUTES
+            .iter()
+            .find(|route| route.path == request.path())
+            .map_or("other", |route| route.path);
+        self.metrics.request(path, response.status);
+        response
+    }
+
+    /// The response to the request at the time.
+    fn answer(&mut self, request: &Request, peer: Option<IpAddr>, now: Instant) -> Response {
+        if let Some(retry_after) = peer.and_then(|peer| self.limit(peer, now)) {
+            let mut response = Response::error(429, "Too Many Requests", "too many requests");
+            response
+                .headers
+                .push(("Retry-After", retry_after.as_secs().max(1).to_string()));
+            return response;
+        }
+        let route = ROUTES
+            .iter()
+            .find(|route| route.path == request.path() && route.method == request.method);
+        let route = match route {
+            Some(route) => route,
+            None => return not_routed(request.path()),
+        };
+        if let (true, Some(token)) = (route.authorized, &self.token) {
+            let authorized = request
+                .header("authorization")
+                .and_then(|authorization| authorization.strip_prefix("Bearer "))
+                .is_some_and(|candidate| constant_time_eq(candidate.as_bytes(), token.as_bytes()));
+            if !authorized {
+                let mut response =
+                    Response::error(401, "Unauthorized", "a bearer token is required");
+                response
+                    .headers
+                    .push(("WWW-Authenticate", String::from("Bearer")));
+                return response;
+            }
+        }
+
+        match route.path {
+            "/health" => Response::json(200, "OK", &json!({ "status": "ok" })),
+            "/openapi.json" => Response::json(200, "OK", &self.openapi()),
+            "/metrics" => Response {
+                status: 200,
+                reason: "OK",
+                headers: vec![(
+                    "Content-Type",
+                    String::from("text/plain; version=0.0.4; charset=utf-8"),
+                )],
+                body: self.metrics.to_prometheus().into_bytes(),
+            },
+            "/wifi.png" => image(
+                &self.wifi_credentials,
+                self.qr_code_error_checking,
+                Format::Png,
+                DEFAULT_IMAGE_SIZE,
+                &mut self.metrics,
+            ),
+            "/wifi.svg" => image(
+                &self.wifi_credentials,
+                self.qr_code_error_checking,
+                Format::Svg,
+                DEFAULT_IMAGE_SIZE,
+                &mut self.metrics,
+            ),
+            "/generate" => match generate_request(&request.body) {
+                Ok((wifi_credentials, format, size)) => image(
+                    &wifi_credentials,
+                    self.qr_code_error_checking,
+                    format,
+                    size,
+                    &mut self.metrics,
+                ),
+                Err(reason) => Response::error(400, "Bad Request", &reason),
+            },
+            #[cfg(feature = "decode")]
+            "/decode" => match upload(request).map(crate::decode_from_image) {
+                Ok(Ok(wifi_credentials)) => {
+                    self.metrics.decode(true);
+                    Response::json(200, "OK", &credentials_json(&wifi_credentials))
+                }
+                Ok(Err(error)) => {
+                    self.metrics.decode(false);
+                    Response::error(422, "Unprocessable Entity", &error.to_string())
+                }
+                Err(reason) => Response::error(400, "Bad Request", reason),
+            },
+            #[cfg(feature = "guest")]
+            "/guest" => self.guest_passphrase(request, now),
+            _ => not_routed(request.path()),
+        }
+    }
+
+    /// The response to `POST /guest`, with the QR code of the current guest passphrase and how many seconds it is valid for.
+    #[cfg(feature = "guest")]
+    fn guest_passphrase(&mut self, request: &Request, now: Instant) -> Response {
+        let guest = match &self.guest {
+            Some(guest) => Arc::clone(guest),
+            None => return Response::error(404, "Not Found", "no guest network is configured"),
+        };
+        let options = if request.body.is_empty() {
+            Ok(json!({}))
+        } else {
+            serde_json::from_slice(&request.body)
+                .map_err(|error| format!("invalid JSON: {}", error))
+        };
+        let (format, size) = match options.and_then(|options| image_options(&options)) {
+            Ok(options) => options,
+            Err(reason) => return Response::error(400, "Bad Request", &reason),
+        };
+        // A panic while configuring the access point leaves the guest network as it was.
+        let mut guest = guest
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        match guest.credentials(now) {
+            Ok((wifi_credentials, expires)) => {
+                let mut response = image(
+                    &wifi_credentials,
+                    self.qr_code_error_checking,
+                    format,
+                    size,
+                    &mut self.metrics,
+                );
+                response.headers.push((
+                    "X-Expires-In",
+                    expires.duration_since(now).as_secs().to_string(),
+                ));
+                response
+            }
+            Err(error @ RotationError::AccessPoint(_)) => {
+                Response::error(502, "Bad Gateway", &error.to_string())
+            }
+            Err(error) => Response::error(500, "Internal Server Error", &error.to_string()),
+        }
+    }
+
+    /// The OpenAPI description of the endpoints, which marks the endpoints that need the token if one is required.
+    fn openapi(&self) -> serde_json::Value {
+        let error = json!({
+            "description": "The reason the request failed",
+            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Error" } } },
+        });
+        let image = |description: &str| {
+            json!({
+                "description": description,
+                "headers": { "Cache-Control": { "schema": { "type": "string", "enum": ["no-store"] } } },
+                "content": {
+                    "image/png": { "schema": { "type": "string", "format": "binary" } },
+                    "image/svg+xml": { "schema": { "type": "string" } },
+                },
+            })
+        };
+        let mut paths = serde_json::Map::new();
+        for route in ROUTES {
+            #[cfg(feature = "guest")]
+            if route.path == "/guest" && self.guest.is_none() {
+                continue;
+            }
+            let mut operation = match route.path {
+                "/health" => json!({
+                    "summary": "Check that the server is running",
+                    "responses": { "200": { "description": "The server is running" } },
+                }),
+                "/openapi.json" => json!({
+                    "summary": "This description of the API",
+                    "responses": { "200": { "description": "An OpenAPI 3.0 document" } },
+                }),
+                "/metrics" => json!({
+                    "summary": "Counters of requests, generated QR codes, decode attempts, and render durations",
+                    "responses": {
+                        "200": {
+                            "description": "The metrics in the Prometheus text format",
+                            "content": { "text/plain": { "schema": { "type": "string" } } },
+                        },
+                    },
+                }),
+                "/wifi.png" | "/wifi.svg" => json!({
+                    "summary": "The QR code of the served network",
+                    "responses": { "200": image("The QR code") },
+                }),
+                "/generate" => json!({
+                    "summary": "Generate the QR code of any network",
+                    "requestBody": {
+                        "required": true,
+                        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/GenerateRequest" } } },
+                    },
+                    "responses": {
+                        "200": image("The QR code, in the requested format"),
+                        "400": error,
+                        "422": error,
+                    },
+                }),
+                "/guest" => json!({
+                    "summary": "A short-lived passphrase for the guest network",
+                    "description": "Mints a new passphrase and configures the access point with it if the previous one has expired. Everyone who asks before then gets the same one.",
+                    "requestBody": {
+                        "required": false,
+                        "content": { "application/json": { "schema": { "$ref": "#/components/schemas/ImageOptions" } } },
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "The QR code of the passphrase, in the requested format",
+                            "headers": {
+                                "Cache-Control": { "schema": { "type": "string", "enum": ["no-store"] } },
+                                "X-Expires-In": {
+                                    "description": "The number of seconds until the passphrase stops working",
+                                    "schema": { "type": "integer" },
+                                },
+                            },
+                            "content": {
+                                "image/png": { "schema": { "type": "string", "format": "binary" } },
+                                "image/svg+xml": { "schema": { "type": "string" } },
+                            },
+                        },
+                        "400": error,
+                        "502": error,
+                    },
+                }),
+                _ => json!({
+                    "summary": "Read the credentials from an image of a QR code",
+                    "requestBody": {
+                        "required": true,
+                        "content": {
+                            "image/png": { "schema": { "type": "string", "format": "binary" } },
+                            "image/jpeg": { "schema": { "type": "string", "format": "binary" } },
+                            "multipart/form-data": {
+                                "schema": {
+                                    "type": "object",
+                                    "required": ["image"],
+                                    "properties": { "image": { "type": "string", "format": "binary" } },
+                                },
+                            },
+                        },
+                    },
+                    "responses": {
+                        "200": {
+                            "description": "The decoded credentials",
+                            "content": { "application/json": { "schema": { "$ref": "#/components/schemas/Credentials" } } },
+                        },
+                        "422": error,
+                    },
+                }),
+            };
+            operation["responses"]["429"] = error.clone();
+            if route.authorized && self.token.is_some() {
+                operation["security"] = json!([{ "bearer": [] }]);
+                operation["responses"]["401"] = error.clone();
+            }
+            paths.insert(
+                route.path.to_string(),
+                json!({ route.method.to_ascii_lowercase(): operation }),
+            );
+        }
+        json!({
+            "openapi": "3.0.3",
+            "info": {
+                "title": "wifi-qr-code",
+                "version": env!("CARGO_PKG_VERSION"),
+                "description": "QR codes for joining Wi-Fi networks",
+            },
+            "paths": paths,
+            "components": {
+                "securitySchemes": { "bearer": { "type": "http", "scheme": "bearer" } },
+                "schemas": {
+                    "Credentials": {
+                        "type": "object",
+                        "required": ["ssid", "security", "password", "hidden", "valid", "problem"],
+                        "properties": {
+                            "ssid": { "type": "string" },
+                            "security": { "type": "string", "enum": ["WPA", "WEP", "nopass"] },
+                            "password": { "type": "string", "nullable": true },
+                            "hidden": { "type": "boolean" },
+                            "valid": {
+                                "type": "boolean",
+                                "description": "Whether the credentials could have come from a working network, e.g. WPA passphrases of 8 to 63 characters",
+                            },
+                            "problem": {
+                                "type": "string",
+                                "nullable": true,
+                                "description": "Why the credentials are not valid",
+                            },
+                        },
+                    },
+                    "GenerateRequest": {
+                        "allOf": [
+                            { "$ref": "#/components/schemas/ImageOptions" },
+                            {
+                                "type": "object",
+                                "required": ["ssid"],
+                                "properties": {
+                                    "ssid": { "type": "string" },
+                                    "password": { "type": "string" },
+                                    "security": {
+                                        "type": "string",
+                                        "enum": ["WPA", "WEP", "nopass"],
+                                        "description": "Defaults to WPA with a password, and nopass without one",
+                                    },
+                                    "hidden": { "type": "boolean", "default": false },
+                                },
+                            },
+                        ],
+                    },
+                    "ImageOptions": {
+                        "type": "object",
+                        "properties": {
+                            "format": { "type": "string", "enum": ["png", "svg"], "default": "png" },
+                            "size": {
+                                "type": "integer",
+                                "minimum": 1,
+                                "maximum": MAX_IMAGE_SIZE,
+                                "default": DEFAULT_IMAGE_SIZE,
+                            },
+                        },
+                    },
+                    "Error": {
+                        "type": "object",
+                        "required": ["error"],
+                        "properties": { "error": { "type": "string" } },
+                    },
+                },
+            },
+        })
+    }
+
+    /// Count a request from the client, and return how long it has to wait if it is over the limit.
+    fn limit(&mut self, peer: IpAddr, now: Instant) -> Option<Duration> {
+        let (requests, period) = self.rate_limit?;
+        let peer = client_address(peer);
+        if self.clients.len() >= MAX_TRACKED_CLIENTS && !self.clients.contains_key(&peer) {
+            // Forgetting clients takes a pass over all of them, so it is only tried again once a window has ended.
+            if now >= self.clients_expire {
+                self.clients
+                    .retain(|_, window| now.duration_since(window.start) < period);
+                self.clients_expire = self
+                    .clients
+                    .values()
+                    .map(|window| window.start + period)
+                    .min()
+                    .unwrap_or(now);
+            }
+            if self.clients.len() >= MAX_TRACKED_CLIENTS {
+                return Some(self.clients_expire.saturating_duration_since(now));
+            }
+        }
+        let window = self.clients.entry(peer).or_insert(Window {
+            start: now,
+            requests: 0,
+        });
+        let elapsed = now.duration_since(window.start);
+        if elapsed >= period {
+            *window = Window {
+                start: now,
+                requests: 0,
+            };
+        }
+        if window.requests >= requests {
+            return Some(period - now.duration_since(window.start));
+        }
+        window.requests += 1;
+        None
+    }
+}
+
+/// Expire guest passphrases every [`GUEST_EXPIRY_INTERVAL`] until the server that owns them has been dropped.
+#[cfg(feature = "guest")]
+fn expire_until_stopped(guest: &Weak<Mutex<Guest>>) {
+    loop {
+        thread::sleep(GUEST_EXPIRY_INTERVAL);
+        let guest = match guest.upgrade() {
+            Some(guest) => guest,
+            None => return,
+        };
+        // A panic while configuring the access point leaves the guest network as it was, so it can still be expired.
+        guest
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .expire(Instant::now());
+    }
+}
+
+/// The address requests from the peer are counted under: IPv4 addresses as they are, including those mapped into IPv6, and the `/64` prefix of other IPv6 addresses.
+fn client_address(peer: IpAddr) -> IpAddr {
+    match peer {
+        IpAddr::V4(_) => peer,
+        IpAddr::V6(address) => match address.to_ipv4_mapped() {
+            Some(address) => IpAddr::V4(address),
+            None => IpAddr::V6(Ipv6Addr::from(u128::from(address) & !u128::from(u64::MAX))),
+        },
+    }
+}
+
+/// Compare secrets in a time that only depends on their lengths, so the token cannot be guessed byte by byte from response times.
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len()
+        && a.iter()
+            .zip(b)
+            .fold(0, |difference, (a, b)| difference | (a ^ b))
+            == 0
+}
+
+/// An endpoint of the server.
+struct Route {
+    method: &'static str,
+    path: &'static str,
+    /// Whether the endpoint requires the token, if the server has one.
+    authorized: bool,
+}
+
+/// Every endpoint, in the order they are described in.
+const ROUTES: &[Route] = &[
+    Route {
+        method: "GET",
+        path: "/health",
+        authorized: false,
+    },
+    Route {
+        method: "GET",
+        path: "/openapi.json",
+        authorized: false,
+    },
+    Route {
+        method: "GET",
+        path: "/metrics",
+        authorized: false,
+    },
+    Route {
+        method: "GET",
+        path: "/wifi.png",
+        authorized: true,
+    },
+    Route {
+        method: "GET",
+        path: "/wifi.svg",
+        authorized: true,
+    },
+    Route {
+        method: "POST",
+        path: "/generate",
+        authorized: true,
+    },
+    #[cfg(feature = "decode")]
+    Route {
+        method: "POST",
+        path: "/decode",
+        authorized: true,
+    },
+    #[cfg(feature = "guest")]
+    Route {
+        method: "POST",
+        path: "/guest",
+        authorized: true,
+    },
+];
+
+/// The response to a request without a route: `405 Method Not Allowed` for known paths, and `404 Not Found` otherwise.
+fn not_routed(path: &str) -> Response {
+    let methods: Vec<&str> = ROUTES
+        .iter()
+        .filter(|route| route.path == path)
+        .map(|route| route.method)
+        .collect();
+    if methods.is_empty() {
+        return Response::error(404, "Not Found", "not found");
+    }
+    let mut response = Response::error(405, "Method Not Allowed", "method not allowed");
+    response.headers.push(("Allow", methods.join(", ")));
+    response
+}
+
+/// The image formats of QR codes.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Format {
+    Png,
+    Svg,
+}
+
+/// A response with the QR code of the credentials, recording how long it took to render in the metrics.
+fn image(
+    wifi_credentials: &WifiCredentials,
+    qr_code_error_checking: QrCodeEcc,
+    format: Format,
+    size: usize,
+    metrics: &mut Metrics,
+) -> Response {
+    let start = Instant::now();
+    let builder = wifi_credentials.qr(qr_code_error_checking).size(size);
+    let mut body = Vec::new();
+    let (name, content_type, result) = match format {
+        Format::Png => ("png", "image/png", builder.png(&mut body)),
+        Format::Svg => ("svg", "image/svg+xml", builder.svg(&mut body)),
+    };
+    metrics.render(name, start.elapsed(), result.is_ok());
+    match result {
+        Ok(()) => Response::image(content_type, body),
+        // Credentials from `/generate` can be invalid, while the served credentials have been checked before the server started listening.
+        Err(Error::Validation(error)) => {
+            Response::error(422, "Unprocessable Entity", &error.to_string())
+        }
+        Err(error) => Response::error(500, "Internal Server Error", &error.to_string()),
+    }
+}
+
+/// The credentials, image format, and size of a `/generate` request, or why they are invalid.
+fn generate_request(body: &[u8]) -> Result<(WifiCredentials, Format, usize), String> {
+    let request: serde_json::Value =
+        serde_json::from_slice(body).map_err(|error| format!("invalid JSON: {}", error))?;
+    let ssid = string_field(&request, "ssid")?.ok_or_else(|| String::from("ssid is required"))?;
+    let password = string_field(&request, "password")?;
+    let authentication_type = match (string_field(&request, "security")?, password) {
+        (Some(security), _) => security
+            .parse::<AuthenticationType>()
+            .map_err(|error| error.to_string())?
+            .with_password(password.unwrap_or_default()),
+        (None, Some(password)) => AuthenticationType::WPA(password.into()),
+        (None, None) => AuthenticationType::NoPassword,
+    };
+    let visibility = match &request["hidden"] {
+        serde_json::Value::Null | serde_json::Value::Bool(false) => Visibility::Visible,
+        serde_json::Value::Bool(true) => Visibility::Hidden,
+        _ => return Err(String::from("hidden must be a boolean")),
+    };
+    let (format, size) = image_options(&request)?;
+    let wifi_credentials = WifiCredentials {
+        ssid: ssid.to_string(),
+        authentication_type,
+        visibility,
+    };
+    Ok((wifi_credentials, format, size))
+}
+
+/// The image format and size a JSON request asks for, or why they are invalid.
+fn image_options(request: &serde_json::Value) -> Result<(Format, usize), String> {
+    let format = match string_field(request, "format")? {
+        None | Some("png") => Format::Png,
+        Some("svg") => Format::Svg,
+        Some(format) => return Err(format!("unsupported format: {}", format)),
+    };
+    let size = match &request["size"] {
+        serde_json::Value::Null => DEFAULT_IMAGE_SIZE,
+        size => size
+            .as_u64()
+            .filter(|size| (1..=MAX_IMAGE_SIZE as u64).contains(size))
+            .ok_or_else(|| format!("size must be an integer from 1 to {}", MAX_IMAGE_SIZE))?
+            as usize,
+    };
+    Ok((format, size))
+}
+
+/// The string field of a JSON request, if it is present.
+fn string_field<'a>(request: &'a serde_json::Value, name: &str) -> Result<Option<&'a str>, String> {
+    match &request[name] {
+        serde_json::Value::Null => Ok(None),
+        serde_json::Value::String(value) => Ok(Some(value)),
+        _ => Err(format!("{} must be a string", name)),
+    }
+}
+
+/// The credentials as JSON, in the shape of the `Credentials` schema, with whether they are valid, so that a QR code that scans but cannot work is told apart from a working one.
+#[cfg(feature = "decode")]
+fn credentials_json(wifi_credentials: &WifiCredentials) -> serde_json::Value {
+    let security = match wifi_credentials.authentication_type {
+        AuthenticationType::WEP(_) => "WEP",
+        AuthenticationType::WPA(_) | AuthenticationType::WpaPsk(_) => "WPA",
+        AuthenticationType::NoPassword => "nopass",
+    };
+    let problem = wifi_credentials
+        .validate()
+        .err()
+        .map(|error| error.to_string());
+    json!({
+        "ssid": wifi_credentials.ssid,
+        "security": security,
+        "password": wifi_credentials.authentication_type.password_text().as_deref(),
+        "hidden": wifi_credentials.visibility == Visibility::Hidden,
+        "valid": problem.is_none(),
+        "problem": problem,
+    })
+}
+
+/// The uploaded image of a `/decode` request: the `image` field of a `multipart/form-data` body, as sent by HTML forms, or else the whole body.
+#[cfg(feature = "decode")]
+fn upload(request: &Request) -> Result<&[u8], &'static str> {
+    let content_type = request.header("content-type").unwrap_or_default();
+    let (media_type, parameters) = content_type.split_once(';').unwrap_or((content_type, ""));
+    if !media_type
+        .trim()
+        .eq_ignore_ascii_case("multipart/form-data")
+    {
+        return Ok(&request.body);
+    }
+    let boundary = parameters
+        .split(';')
+        .filter_map(|parameter| parameter.trim().split_once('='))
+        .find(|(name, _)| name.eq_ignore_ascii_case("boundary"))
+        .map(|(_, boundary)| boundary.trim_matches('"'))
+        .filter(|boundary| !boundary.is_empty())
+        .ok_or("the multipart body has no boundary")?;
+
+    let body = &request.body[..];
+    let delimiter = format!("--{}", boundary).into_bytes();
+    let mut start = find(body, &delimiter, 0).ok_or("the multipart body has no parts")?;
+    // Each part starts after a delimiter line, and ends at the line break before the next one. The last delimiter is followed by `--`.
+    while !body[start + delimiter.len()..].starts_with(b"--") {
+        let headers_start = start + delimiter.len();
+        let content_start = find(body, b"\r\n\r\n", headers_start)
+            .ok_or("a part of the multipart body is malformed")?
+            + 4;
+        let end = (content_start..body.len())
+            .find(|&index| {
+                body[index..].starts_with(b"\r\n") && body[index + 2..].starts_with(&delimiter)
+            })
+            .ok_or("the multipart body ended early")?;
+        let headers = String::from_utf8_lossy(&body[headers_start..content_start]);
+        let is_image = headers.lines().any(|line| {
+            let line = line.to_ascii_lowercase();
+            line.starts_with("content-disposition:")
+                && line
+                    .split(';')
+                    .any(|parameter| matches!(parameter.trim(), "name=\"image\"" | "name=image"))
+        });
+        if is_image {
+            return Ok(&body[content_start..end]);
+        }
+        start = end + 2;
+    }
+    Err("the multipart body has no image field")
+}
+
+/// The index of the first occurrence of the needle in the bytes at or after `from`.
+#[cfg(feature = "decode")]
+fn find(bytes: &[u8], needle: &[u8], from: usize) -> Option<usize> {
+    bytes
+        .get(from..)?
+        .windows(needle.len())
+        .position(|window| window == needle)
+        .map(|index| from + index)
+}
+
+/// An HTTP request.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Request {
+    method: String,
+    target: String,
+    /// The headers, with lowercase names.
+    headers: Vec<(String, String)>,
+    body: Vec<u8>,
+}
+
+impl Request {
+    /// The path of the target, without its query.
+    fn path(&self) -> &str {
+        self.target.split('?').next().unwrap_or_default()
+    }
+
+    /// The value of the first header with the lowercase name.
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Read an HTTP/1.x request. Requests that are malformed or over the limits are reported as [`io::ErrorKind::InvalidData`].
+/// Fails reads with [`io::ErrorKind::TimedOut`] once the deadline has passed. Each read can still wait for the read timeout of the stream, so a request is given up on at most that long after the deadline.
+struct Deadline<S> {
+    inner: S,
+    deadline: Instant,
+}
+
+impl<S: Read> Read for Deadline<S> {
+    fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+        if Instant::now() >= self.deadline {
+            return Err(io::Error::new(
+                io::ErrorKind::TimedOut,
+                "the request took too long to send",
+            ));
+        }
+        self.inner.read(buffer)
+    }
+}
+
+fn read_request(reader: &mut impl BufRead) -> io::Result<Request> {
+    let invalid = |reason: &str| io::Error::new(io::ErrorKind::InvalidData, reason.to_string());
+    let mut read_line = || -> io::Result<String> {
+        let mut line = Vec::new();
+        reader
+            .by_ref()
+            .take(MAX_LINE_LENGTH as u64 + 1)
+            .read_until(b'\n', &mut line)?;
+        if line.len() > MAX_LINE_LENGTH {
+            return Err(invalid("the request has a line that is too long"));
+        }
+        if !line.ends_with(b"\n") {
+            return Err(invalid("the request ended early"));
+        }
+        let line = String::from_utf8(line).map_err(|_| invalid("the request is not UTF-8"))?;
+        Ok(line.trim_end_matches(['\r', '\n']).to_string())
+    };
+
+    let request_line = read_line()?;
+    let mut parts = request_line.split(' ');
+    let (method, target) = match (parts.next(), parts.next(), parts.next()) {
+        (Some(method), Some(target), Some(version)) if version.starts_with("HTTP/1.") => {
+            (method.to_string(), target.to_string())
+        }
+        _ => return Err(invalid("the request line is malformed")),
+    };
+    let mut headers = Vec::new();
+    loop {
+        let line = read_line()?;
+        if line.is_empty() {
+            break;
+        }
+        if headers.len() >= MAX_HEADERS {
+            return Err(invalid("the request has too many headers"));
+        }
+        let (name, value) = line
+            .split_once(':')
+            .ok_or_else(|| invalid("a header is malformed"))?;
+        headers.push((name.trim().to_ascii_lowercase(), value.trim().to_string()));
+    }
+
+    let mut request = Request {
+        method,
+        target,
+        headers,
+        body: Vec::new(),
+    };
+    if request.header("transfer-encoding").is_some() {
+        return Err(invalid("chunked requests are not supported"));
+    }
+    let length = match request.header("content-length") {
+        Some(length) => length
+            .parse::<usize>()
+            .map_err(|_| invalid("the content length is malformed"))?,
+        None => 0,
+    };
+    if length > MAX_BODY_LENGTH {
+        return Err(invalid("the request body is too large"));
+    }
+    request.body = vec![0; length];
+    reader.read_exact(&mut request.body)?;
+    Ok(request)
+}
+
+/// An HTTP response, which closes the connection.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Response {
+    status: u16,
+    reason: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+impl Response {
+    /// A JSON response with the status.
+    fn json(status: u16, reason: &'static str, value: &serde_json::Value) -> Self {
+        Response {
+            status,
+            reason,
+            headers: vec![("Content-Type", String::from("application/json"))],
+            body: value.to_string().into_bytes(),
+        }
+    }
+
+    /// A JSON response with the status and a description of the error.
+    fn error(status: u16, reason: &'static str, error: &str) -> Self {
+        Self::json(status, reason, &json!({ "error": error }))
+    }
+
+    /// A successful response with an image of the credentials, which must not be cached since it contains the password and changes when it is rotated.
+    fn image(content_type: &str, body: Vec<u8>) -> Self {
+        Response {
+            status: 200,
+            reason: "OK",
+            headers: vec![
+                ("Content-Type", content_type.to_string()),
+                ("Cache-Control", String::from("no-store")),
+            ],
+            body,
+        }
+    }
+
+    fn write_to(&self, writer: &mut impl Write) -> io::Result<()> {
+        write!(writer, "HTTP/1.1 {} {}\r\n", self.status, self.reason)?;
+        for (name, value) in &self.headers {
+            write!(writer, "{}: {}\r\n", name, value)?;
+        }
+        write!(
+            writer,
+            "Content-Length: {}\r\nConnection: close\r\n\r\n",
+            self.body.len()
+        )?;
+        writer.write_all(&self.body)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn request(method: &str, target: &str, headers: &[(&str, &str)]) -> Request {
+        Request {
+            method: method.to_string(),
+            target: target.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+            body: Vec::new(),
+        }
+    }
+
+    #[test]
+    fn it_reads_requests() {
+        let mut input: &[u8] =
+            b"POST /wifi.png?size=1 HTTP/1.1\r\nHost: localhost\r\nContent-Length: 4\r\n\r\nbodyextra";
+        let request = read_request(&mut input).unwrap();
+        assert_eq!("POST", request.method);
+        assert_eq!("/wifi.png", request.path());
+        assert_eq!(Some("localhost"), request.header("host"));
+        assert_eq!(b"body", &request.body[..]);
+
+        for malformed in &[
+            &b"GET /\r\n\r\n"[..],
+            b"GET / HTTP/1.1\r\nHost\r\n\r\n",
+            b"GET / HTTP/1.1\r\nContent-Length: 2000000\r\n\r\n",
+            b"GET / HTTP/1.1\r\nHost: localhost\r\n",
+        ] {
+            let mut input = *malformed;
+            assert_eq!(
+                io::ErrorKind::InvalidData,
+                read_request(&mut input).unwrap_err().kind()
+            );
+        }
+    }
+
+    #[test]
+    fn it_requires_the_token() {
+        let mut server =
+            Server::new(WifiCredentials::wpa2("test ssid", "test password")).token("secret");
+        let now = Instant::now();
+        let response = server.respond(&request("GET", "/wifi.svg", &[]), None, now);
+        assert_eq!(401, response.status);
+        let response = server.respond(
+            &request("GET", "/wifi.svg", &[("authorization", "Bearer secreT")]),
+            None,
+            now,
+        );
+        assert_eq!(401, response.status);
+        let response = server.respond(
+            &request("GET", "/wifi.svg", &[("authorization", "Bearer secret")]),
+            None,
+            now,
+        );
+        assert_eq!(200, response.status);
+        assert!(response
+            .headers
+            .contains(&("Cache-Control", String::from("no-store"))));
+    }
+
+    #[test]
+    fn it_redacts_the_token() {
+        let server = Server::new(WifiCredentials::open("test ssid")).token("test token");
+        let debug = format!("{:?}", server);
+        assert!(debug.contains("token: Some(<redacted>)"), "{}", debug);
+        assert!(!debug.contains("test token"), "{}", debug);
+    }
+
+    #[test]
+    fn it_limits_requests_per_client() {
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password"))
+            .rate_limit(2, Duration::from_secs(60));
+        let start = Instant::now();
+        let client = Some(IpAddr::from([127, 0, 0, 1]));
+        let other_client = Some(IpAddr::from([127, 0, 0, 2]));
+        let wifi = request("GET", "/wifi.png", &[]);
+        assert_eq!(200, server.respond(&wifi, client, start).status);
+        assert_eq!(200, server.respond(&wifi, client, start).status);
+        let response = server.respond(&wifi, client, start + Duration::from_secs(20));
+        assert_eq!(429, response.status);
+        assert!(response
+            .headers
+            .contains(&("Retry-After", String::from("40"))));
+        assert_eq!(200, server.respond(&wifi, other_client, start).status);
+        assert_eq!(200, server.respond(&wifi, None, start).status);
+        assert_eq!(
+            200,
+            server
+                .respond(&wifi, client, start + Duration::from_secs(60))
+                .status
+        );
+    }
+
+    #[test]
+    fn it_stops_tracking_new_clients_when_full() {
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password"))
+            .rate_limit(2, Duration::from_secs(60));
+        let start = Instant::now();
+        let health = request("GET", "/health", &[]);
+        for client in 0..MAX_TRACKED_CLIENTS as u32 {
+            let client = Some(IpAddr::from(client.to_be_bytes()));
+            assert_eq!(200, server.respond(&health, client, start).status);
+        }
+        let tracked = Some(IpAddr::from([0, 0, 0, 1]));
+        let new_client = Some(IpAddr::from([127, 0, 0, 1]));
+        assert_eq!(200, server.respond(&health, tracked, start).status);
+        let response = server.respond(&health, new_client, start + Duration::from_secs(20));
+        assert_eq!(429, response.status);
+        assert!(response
+            .headers
+            .contains(&("Retry-After", String::from("40"))));
+        assert_eq!(MAX_TRACKED_CLIENTS, server.clients.len());
+        let later = start + Duration::from_secs(60);
+        assert_eq!(200, server.respond(&health, new_client, later).status);
+        assert_eq!(1, server.clients.len());
+    }
+
+    #[test]
+    fn it_counts_ipv6_clients_by_prefix() {
+        assert_eq!(
+            IpAddr::from([0x2001, 0xdb8, 0, 1, 0, 0, 0, 0]),
+            client_address(IpAddr::from([0x2001, 0xdb8, 0, 1, 0x1234, 0, 0, 1]))
+        );
+        assert_eq!(
+            IpAddr::from([192, 0, 2, 1]),
+            client_address(IpAddr::from([0, 0, 0, 0, 0, 0xffff, 0xc000, 0x0201]))
+        );
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password"))
+            .rate_limit(1, Duration::from_secs(60));
+        let now = Instant::now();
+        let wifi = request("GET", "/wifi.png", &[]);
+        let client = Some(IpAddr::from([0x2001, 0xdb8, 0, 1, 0, 0, 0, 1]));
+        let same_prefix = Some(IpAddr::from([0x2001, 0xdb8, 0, 1, 0, 0, 0, 2]));
+        let other_prefix = Some(IpAddr::from([0x2001, 0xdb8, 0, 2, 0, 0, 0, 1]));
+        assert_eq!(200, server.respond(&wifi, client, now).status);
+        assert_eq!(429, server.respond(&wifi, same_prefix, now).status);
+        assert_eq!(200, server.respond(&wifi, other_prefix, now).status);
+    }
+
+    #[test]
+    fn it_gives_up_on_requests_after_the_deadline() {
+        let input: &[u8] = b"GET /health HTTP/1.1\r\n\r\n";
+        let mut reader = BufReader::new(Deadline {
+            inner: input,
+            deadline: Instant::now() + TIMEOUT,
+        });
+        assert_eq!("/health", read_request(&mut reader).unwrap().path());
+        let mut reader = BufReader::new(Deadline {
+            inner: input,
+            deadline: Instant::now(),
+        });
+        assert_eq!(
+            io::ErrorKind::TimedOut,
+            read_request(&mut reader).unwrap_err().kind()
+        );
+    }
+
+    #[test]
+    fn it_serves_health_and_the_description_without_the_token() {
+        let mut server =
+            Server::new(WifiCredentials::wpa2("test ssid", "test password")).token("secret");
+        let now = Instant::now();
+        let response = server.respond(&request("GET", "/health", &[]), None, now);
+        assert_eq!(
+            (200, &b"{\"status\":\"ok\"}"[..]),
+            (response.status, &response.body[..])
+        );
+
+        let response = server.respond(&request("GET", "/openapi.json", &[]), None, now);
+        assert_eq!(200, response.status);
+        let openapi: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!("3.0.3", openapi["openapi"]);
+        assert!(openapi["paths"]["/health"]["get"]["security"].is_null());
+        assert_eq!(
+            json!([{ "bearer": [] }]),
+            openapi["paths"]["/generate"]["post"]["security"]
+        );
+        assert_eq!(
+            cfg!(feature = "decode"),
+            openapi["paths"]["/decode"]["post"].is_object()
+        );
+    }
+
+    #[test]
+    fn it_counts_requests_in_the_metrics() {
+        let mut server =
+            Server::new(WifiCredentials::wpa2("test ssid", "test password")).token("secret");
+        let now = Instant::now();
+        let authorization = [("authorization", "Bearer secret")];
+        server.respond(&request("GET", "/wifi.png", &authorization), None, now);
+        server.respond(&request("GET", "/wifi.svg", &[]), None, now);
+        server.respond(&request("GET", "/secret.txt", &[]), None, now);
+
+        let response = server.respond(&request("GET", "/metrics", &[]), None, now);
+        assert_eq!(200, response.status);
+        let metrics = String::from_utf8(response.body).unwrap();
+        for line in &[
+            "wifi_qr_code_http_requests_total{path=\"/wifi.png\",status=\"200\"} 1",
+            "wifi_qr_code_http_requests_total{path=\"/wifi.svg\",status=\"401\"} 1",
+            "wifi_qr_code_http_requests_total{path=\"other\",status=\"404\"} 1",
+            "wifi_qr_code_errors_total{class=\"client\"} 2",
+            "wifi_qr_code_generations_total{format=\"png\"} 1",
+            "wifi_qr_code_render_duration_seconds_count 1",
+        ] {
+            assert!(metrics.lines().any(|metric| metric == *line), "{}", line);
+        }
+    }
+
+    #[test]
+    fn it_generates_qr_codes_from_json() {
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password"));
+        let now = Instant::now();
+        let mut generate = request("POST", "/generate", &[]);
+        generate.body =
+            br#"{"ssid":"other ssid","password":"other password","format":"svg","size":64}"#
+                .to_vec();
+        let response = server.respond(&generate, None, now);
+        assert_eq!(200, response.status);
+        assert!(response
+            .headers
+            .contains(&("Content-Type", String::from("image/svg+xml"))));
+
+        for (body, status) in &[
+            (&br#"{"ssid":"other ssid""#[..], 400),
+            (br#"{"password":"other password"}"#, 400),
+            (br#"{"ssid":"other ssid","security":"open"}"#, 400),
+            (br#"{"ssid":"other ssid","size":100000}"#, 400),
+            (br#"{"ssid":"","password":"other password"}"#, 422),
+        ] {
+            generate.body = body.to_vec();
+            let response = server.respond(&generate, None, now);
+            assert_eq!(*status, response.status);
+            let error: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+            assert!(error["error"].is_string());
+        }
+        assert_eq!(
+            405,
+            server
+                .respond(&request("GET", "/generate", &[]), None, now)
+                .status
+        );
+    }
+
+    #[cfg(feature = "decode")]
+    #[test]
+    fn it_decodes_qr_codes() {
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password"));
+        let now = Instant::now();
+        let mut decode = request("POST", "/decode", &[]);
+        WifiCredentials::wpa2("other ssid", "other password")
+            .qr(QrCodeEcc::Medium)
+            .png(&mut decode.body)
+            .unwrap();
+        let response = server.respond(&decode, None, now);
+        assert_eq!(200, response.status);
+        assert_eq!(
+            json!({
+                "ssid": "other ssid",
+                "security": "WPA",
+                "password": "other password",
+                "hidden": false,
+                "valid": true,
+                "problem": null,
+            }),
+            serde_json::from_slice::<serde_json::Value>(&response.body).unwrap()
+        );
+
+        decode.body = b"not an image".to_vec();
+        assert_eq!(422, server.respond(&decode, None, now).status);
+    }
+
+    #[cfg(feature = "decode")]
+    #[test]
+    fn it_decodes_uploaded_forms() {
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password"));
+        let now = Instant::now();
+        let mut png = Vec::new();
+        WifiCredentials::wpa2("other ssid", "other password")
+            .qr(QrCodeEcc::Medium)
+            .png(&mut png)
+            .unwrap();
+        let mut decode = request(
+            "POST",
+            "/decode",
+            &[(
+                "content-type",
+                "multipart/form-data; boundary=\"test-boundary\"",
+            )],
+        );
+        decode.body = b"--test-boundary\r\nContent-Disposition: form-data; name=\"room\"\r\n\r\nConference room 2\r\n--test-boundary\r\nContent-Disposition: form-data; name=\"image\"; filename=\"sign.png\"\r\nContent-Type: image/png\r\n\r\n".to_vec();
+        decode.body.extend_from_slice(&png);
+        decode.body.extend_from_slice(b"\r\n--test-boundary--\r\n");
+        assert_eq!(Ok(&png[..]), upload(&decode));
+        let response = server.respond(&decode, None, now);
+        assert_eq!(200, response.status);
+        let credentials: serde_json::Value = serde_json::from_slice(&response.body).unwrap();
+        assert_eq!(json!("other ssid"), credentials["ssid"]);
+        let credentials = credentials_json(&WifiCredentials::wpa2("other ssid", "short"));
+        assert_eq!(json!(false), credentials["valid"]);
+        assert!(credentials["problem"].is_string());
+
+        decode.body = b"--test-boundary\r\nContent-Disposition: form-data; name=\"room\"\r\n\r\nConference room 2\r\n--test-boundary--\r\n".to_vec();
+        assert_eq!(400, server.respond(&decode, None, now).status);
+    }
+
+    #[cfg(feature = "guest")]
+    #[test]
+    fn it_hands_out_short_lived_guest_passphrases() {
+        let configured = Arc::new(Mutex::new(Vec::new()));
+        let access_point = {
+            let configured = Arc::clone(&configured);
+            move |wifi_credentials: &WifiCredentials| {
+                configured.lock().unwrap().push(wifi_credentials.clone());
+                Ok(())
+            }
+        };
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password")).guest(
+            "test guest ssid",
+            access_point,
+            Duration::from_secs(60),
+        );
+        let start = Instant::now();
+        let guest = request("POST", "/guest", &[]);
+        let expires_in = |response: &Response| {
+            response
+                .headers
+                .iter()
+                .find(|(name, _)| *name == "X-Expires-In")
+                .map(|(_, value)| value.clone())
+        };
+
+        let response = server.respond(&guest, None, start);
+        assert_eq!(200, response.status);
+        assert_eq!(Some(String::from("60")), expires_in(&response));
+        let response = server.respond(&guest, None, start + Duration::from_secs(10));
+        assert_eq!(Some(String::from("50")), expires_in(&response));
+        assert_eq!(1, configured.lock().unwrap().len());
+        assert_eq!("test guest ssid", configured.lock().unwrap()[0].ssid);
+
+        server.respond(&guest, None, start + Duration::from_secs(60));
+        assert_eq!(2, configured.lock().unwrap().len());
+        let mut guest = server.guest.as_ref().unwrap().lock().unwrap();
+        guest.expire(start + Duration::from_secs(90));
+        assert_eq!(2, configured.lock().unwrap().len());
+        guest.expire(start + Duration::from_secs(120));
+        assert_eq!(3, configured.lock().unwrap().len());
+        assert!(guest.current.is_none());
+    }
+
+    #[cfg(feature = "guest")]
+    #[test]
+    fn it_stops_expiring_guest_passphrases_with_the_server() {
+        let server = Server::new(WifiCredentials::wpa2("test ssid", "test password")).guest(
+            "test guest ssid",
+            |_: &WifiCredentials| Ok(()),
+            Duration::from_secs(60),
+        );
+        let guest = Arc::downgrade(server.guest.as_ref().unwrap());
+        let expiry = thread::spawn(move || expire_until_stopped(&guest));
+        drop(server);
+        expiry.join().unwrap();
+    }
+
+    #[cfg(feature = "guest")]
+    #[test]
+    fn it_reports_guest_access_point_failures() {
+        let now = Instant::now();
+        let guest = request("POST", "/guest", &[]);
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password"));
+        assert_eq!(404, server.respond(&guest, None, now).status);
+
+        let mut server = server.guest(
+            "test guest ssid",
+            |_: &WifiCredentials| Err("the router is offline".into()),
+            Duration::from_secs(60),
+        );
+        assert_eq!(502, server.respond(&guest, None, now).status);
+    }
+
+    #[cfg(feature = "tls")]
+    #[test]
+    fn it_answers_over_tls() {
+        use rustls::pki_types::pem::PemObject;
+        use rustls::pki_types::{CertificateDer, ServerName};
+        use std::convert::TryFrom;
+        use std::sync::Arc;
+
+        let certificate = tls::tests::test_data("localhost.crt");
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password"))
+            .tls(&certificate, tls::tests::test_data("localhost.key"));
+        let listener = TcpListener::bind(("127.0.0.1", 0)).unwrap();
+        let address = listener.local_addr().unwrap();
+        let client = std::thread::spawn(move || {
+            let mut roots = rustls::RootCertStore::empty();
+            roots
+                .add(CertificateDer::from_pem_file(certificate).unwrap())
+                .unwrap();
+            let config = rustls::ClientConfig::builder_with_provider(Arc::new(
+                rustls::crypto::ring::default_provider(),
+            ))
+            .with_safe_default_protocol_versions()
+            .unwrap()
+            .with_root_certificates(roots)
+            .with_no_client_auth();
+            let connection = rustls::ClientConnection::new(
+                Arc::new(config),
+                ServerName::try_from("localhost").unwrap(),
+            )
+            .unwrap();
+            let mut stream =
+                rustls::StreamOwned::new(connection, TcpStream::connect(address).unwrap());
+            stream
+                .write_all(b"GET /health HTTP/1.1\r\nHost: localhost\r\n\r\n")
+                .unwrap();
+            let mut response = Vec::new();
+            stream.read_to_end(&mut response).unwrap();
+            String::from_utf8(response).unwrap()
+        });
+
+        let (stream, _) = listener.accept().unwrap();
+        server.tcp_connection(stream).unwrap();
+        let response = client.join().unwrap();
+        assert!(response.starts_with("HTTP/1.1 200 OK\r\n"));
+        assert!(response.ends_with("{\"status\":\"ok\"}"));
+    }
+
+    #[test]
+    fn it_answers_connections() {
+        struct Connection {
+            input: &'static [u8],
+            output: Vec<u8>,
+        }
+        impl Read for Connection {
+            fn read(&mut self, buffer: &mut [u8]) -> io::Result<usize> {
+                self.input.read(buffer)
+            }
+        }
+        impl Write for Connection {
+            fn write(&mut self, buffer: &[u8]) -> io::Result<usize> {
+                self.output.write(buffer)
+            }
+            fn flush(&mut self) -> io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let mut server = Server::new(WifiCredentials::wpa2("test ssid", "test password"));
+        let mut connection = Connection {
+            input: b"DELETE /wifi.svg HTTP/1.1\r\n\r\n",
+            output: Vec::new(),
+        };
+        server.connection(&mut connection, None).unwrap();
+        let output = String::from_utf8(connection.output).unwrap();
+        assert!(output.starts_with("HTTP/1.1 405 Method Not Allowed\r\n"));
+        assert!(output.contains("\r\nAllow: GET\r\n"));
+
+        assert!(matches!(
+            server.serve(&Listen::Tcp(([192, 0, 2, 1], 8080).into())),
+            Err(ServeError::RemoteAddress(_))
+        ));
+        let server = Server::new(WifiCredentials::wpa2("test ssid", "short"));
+        assert!(matches!(
+            server.serve(&Listen::Tcp(([127, 0, 0, 1], 0).into())),
+            Err(ServeError::Validation(
+                ValidationError::WpaPassphraseLength(5)
+            ))
+        ));
+    }
+}
@@ -0,0 +1,195 @@
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::Duration;
+
+/// The upper bounds of the render duration buckets, in seconds.
+const RENDER_DURATION_BUCKETS: [f64; 10] =
+    [0.001, 0.0025, 0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0];
+
+/// The counters served at `/metrics`, in the Prometheus text format.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub(super) struct Metrics {
+    /// Responses by endpoint and status.
+    requests: BTreeMap<(&'static str, u16), u64>,
+    /// QR codes rendered by image format.
+    generations: BTreeMap<&'static str, u64>,
+    decode_successes: u64,
+    decode_failures: u64,
+    /// How many renders took at most each bucket's duration, not counting the faster buckets.
+    render_buckets: [u64; RENDER_DURATION_BUCKETS.len()],
+    render_count: u64,
+    render_seconds: f64,
+}
+
+impl Metrics {
+    /// Count a response to a request for the endpoint.
+    pub(super) fn request(&mut self, path: &'static str, status: u16) {
+        *self.requests.entry((path, status)).or_default() += 1;
+    }
+
+    /// Record how long rendering a QR code took, and count it if it succeeded.
+    pub(super) fn render(&mut self, format: &'static str, duration: Duration, succeeded: bool) {
+        if succeeded {
+            *self.generations.entry(format).or_default() += 1;
+        }
+        let seconds = duration.as_secs_f64();
+        if let Some(bucket) = RENDER_DURATION_BUCKETS
+            .iter()
+            .position(|&bound| seconds <= bound)
+        {
+            self.render_buckets[bucket] += 1;
+        }
+        self.render_count += 1;
+        self.render_seconds += seconds;
+    }
+
+    /// Count an attempt to decode an uploaded image.
+    #[cfg(feature = "decode")]
+    pub(super) fn decode(&mut self, succeeded: bool) {
+        if succeeded {
+            self.decode_successes += 1;
+        } else {
+            self.decode_failures += 1;
+        }
+    }
+
+    /// The metrics in the Prometheus text exposition format.
+    pub(super) fn to_prometheus(&self) -> String {
+        let mut text = String::new();
+        // Writing to a string cannot fail.
+        let _ = self.write_prometheus(&mut text);
+        text
+    }
+
+    fn write_prometheus(&self, text: &mut String) -> std::fmt::Result {
+        writeln!(
+            text,
+            "# HELP wifi_qr_code_http_requests_total Requests answered, by endpoint and status."
+        )?;
+        writeln!(text, "# TYPE wifi_qr_code_http_requests_total counter")?;
+        for ((path, status), count) in &self.requests {
+            writeln!(
+                text,
+                "wifi_qr_code_http_requests_total{{path=\"{}\",status=\"{}\"}} {}",
+                path, status, count
+            )?;
+        }
+
+        writeln!(
+            text,
+            "# HELP wifi_qr_code_errors_total Requests answered with an error, by whether the client or the server caused it."
+        )?;
+        writeln!(text, "# TYPE wifi_qr_code_errors_total counter")?;
+        for (class, statuses) in &[("client", 400..500), ("server", 500..600)] {
+            let count: u64 = self
+                .requests
+                .iter()
+                .filter(|((_, status), _)| statuses.contains(status))
+                .map(|(_, count)| count)
+                .sum();
+            writeln!(
+                text,
+                "wifi_qr_code_errors_total{{class=\"{}\"}} {}",
+                class, count
+            )?;
+        }
+
+        writeln!(
+            text,
+            "# HELP wifi_qr_code_generations_total QR codes rendered, by image format."
+        )?;
+        writeln!(text, "# TYPE wifi_qr_code_generations_total counter")?;
+        for format in &["png", "svg"] {
+            writeln!(
+                text,
+                "wifi_qr_code_generations_total{{format=\"{}\"}} {}",
+                format,
+                self.generations.get(format).copied().unwrap_or_default()
+            )?;
+        }
+
+        writeln!(
+            text,
+            "# HELP wifi_qr_code_decode_attempts_total Uploaded images decoded, by whether a QR code was found in them."
+        )?;
+        writeln!(text, "# TYPE wifi_qr_code_decode_attempts_total counter")?;
+        writeln!(
+            text,
+            "wifi_qr_code_decode_attempts_total{{result=\"success\"}} {}",
+            self.decode_successes
+        )?;
+        writeln!(
+            text,
+            "wifi_qr_code_decode_attempts_total{{result=\"failure\"}} {}",
+            self.decode_failures
+        )?;
+
+        writeln!(
+            text,
+            "# HELP wifi_qr_code_render_duration_seconds How long rendering QR codes took."
+        )?;
+        writeln!(
+            text,
+            "# TYPE wifi_qr_code_render_duration_seconds histogram"
+        )?;
+        let mut cumulative = 0;
+        for (bound, count) in RENDER_DURATION_BUCKETS.iter().zip(&self.render_buckets) {
+            cumulative += count;
+            writeln!(
+                text,
+                "wifi_qr_code_render_duration_seconds_bucket{{le=\"{}\"}} {}",
+                bound, cumulative
+            )?;
+        }
+        writeln!(
+            text,
+            "wifi_qr_code_render_duration_seconds_bucket{{le=\"+Inf\"}} {}",
+            self.render_count
+        )?;
+        writeln!(
+            text,
+            "wifi_qr_code_render_duration_seconds_sum {}",
+            self.render_seconds
+        )?;
+        writeln!(
+            text,
+            "wifi_qr_code_render_duration_seconds_count {}",
+            self.render_count
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_writes_the_prometheus_text_format() {
+        let mut metrics = Metrics::default();
+        metrics.request("/wifi.png", 200);
+        metrics.request("/wifi.png", 200);
+        metrics.request("/generate", 400);
+        metrics.request("other", 404);
+        metrics.render("png", Duration::from_millis(3), true);
+        metrics.render("svg", Duration::from_secs(2), false);
+        metrics.decode_failures = 1;
+
+        let text = metrics.to_prometheus();
+        for line in &[
+            "wifi_qr_code_http_requests_total{path=\"/wifi.png\",status=\"200\"} 2",
+            "wifi_qr_code_errors_total{class=\"client\"} 2",
+            "wifi_qr_code_errors_total{class=\"server\"} 0",
+            "wifi_qr_code_generations_total{format=\"png\"} 1",
+            "wifi_qr_code_generations_total{format=\"svg\"} 0",
+            "wifi_qr_code_decode_attempts_total{result=\"failure\"} 1",
+            "wifi_qr_code_render_duration_seconds_bucket{le=\"0.0025\"} 0",
+            "wifi_qr_code_render_duration_seconds_bucket{le=\"0.005\"} 1",
+            "wifi_qr_code_render_duration_seconds_bucket{le=\"1\"} 1",
+            "wifi_qr_code_render_duration_seconds_bucket{le=\"+Inf\"} 2",
+            "wifi_qr_code_render_duration_seconds_count 2",
+        ] {
+            assert!(text.lines().any(|text_line| text_line == *line), "{}", line);
+        }
+        assert!(text.ends_with('\n'));
+    }
+}
@@ -0,0 +1,112 @@
+use super::ServeError;
+
+use rustls::pki_types::pem::PemObject;
+use rustls::pki_types::{CertificateDer, PrivateKeyDer};
+use rustls::ServerConfig;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::SystemTime;
+
+/// The certificate and private key the server is identified by, which are loaded again whenever their files change.
+#[derive(Debug, Clone)]
+pub(super) struct Tls {
+    certificate: PathBuf,
+    key: PathBuf,
+    config: Option<Arc<ServerConfig>>,
+    /// When the files that `config` was loaded from were last changed.
+    modified: Option<SystemTime>,
+}
+
+impl Tls {
+    /// The certificate chain and key in PEM files, which are not read yet.
+    pub(super) fn new(certificate: PathBuf, key: PathBuf) -> Self {
+        Tls {
+            certificate,
+            key,
+            config: None,
+            modified: None,
+        }
+    }
+
+    /// The configuration for a new connection, with the files loaded again if they have changed. If they cannot be loaded, the previous certificate is kept, so that a renewal that is only half written does not take the server down.
+    pub(super) fn config(&mut self) -> Result<Arc<ServerConfig>, ServeError> {
+        let modified = [&self.certificate, &self.key]
+            .iter()
+            .filter_map(|path| {
+                fs::metadata(path)
+                    .and_then(|metadata| metadata.modified())
+                    .ok()
+            })
+            .max();
+        if let Some(config) = &self.config {
+            if modified == self.modified {
+                return Ok(Arc::clone(config));
+            }
+        }
+        match (self.load(), &self.config) {
+            (Ok(config), _) => {
+                let config = Arc::new(config);
+                self.config = Some(Arc::clone(&config));
+                self.modified = modified;
+                Ok(config)
+            }
+            (Err(_), Some(config)) => Ok(Arc::clone(config)),
+            (Err(error), None) => Err(error),
+        }
+    }
+
+    fn load(&self) -> Result<ServerConfig, ServeError> {
+        let pem_error = |path: &PathBuf| {
+            let path = path.clone();
+            move |source| ServeError::Pem { path, source }
+        };
+        let certificates = CertificateDer::pem_file_iter(&self.certificate)
+            .and_then(|certificates| certificates.collect::<Result<Vec<_>, _>>())
+            .map_err(pem_error(&self.certificate))?;
+        let key = PrivateKeyDer::from_pem_file(&self.key).map_err(pem_error(&self.key))?;
+        Ok(
+            ServerConfig::builder_with_provider(Arc::new(rustls::crypto::ring::default_provider()))
+                .with_safe_default_protocol_versions()?
+                .with_no_client_auth()
+                .with_single_cert(certificates, key)?,
+        )
+    }
+}
+
+#[cfg(test)]
+pub(super) mod tests {
+    use super::*;
+
+    /// The path of a file in the test data directory, which has a self-signed certificate for `localhost` and its key.
+    pub(in crate::serve) fn test_data(name: &str) -> PathBuf {
+        [env!("CARGO_MANIFEST_DIR"), "src", "serve", "testdata", name]
+            .iter()
+            .collect()
+    }
+
+    #[test]
+    fn it_keeps_the_previous_certificate_when_reloading_fails() {
+        let directory =
+            std::env::temp_dir().join(format!("wifi-qr-code-tls-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let certificate = directory.join("localhost.crt");
+        fs::copy(test_data("localhost.crt"), &certificate).unwrap();
+        let mut tls = Tls::new(certificate.clone(), test_data("localhost.key"));
+        let config = tls.config().unwrap();
+
+        fs::write(&certificate, "not a certificate").unwrap();
+        tls.modified = None;
+        assert!(Arc::ptr_eq(&config, &tls.config().unwrap()));
+        fs::remove_dir_all(&directory).unwrap();
+
+        assert!(matches!(
+            Tls::new(test_data("missing.crt"), test_data("localhost.key")).config(),
+            Err(ServeError::Pem { .. })
+        ));
+        assert!(matches!(
+            Tls::new(test_data("localhost.key"), test_data("localhost.key")).config(),
+            Err(ServeError::Tls(_))
+        ));
+    }
+}
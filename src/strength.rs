@@ -0,0 +1,236 @@
+use crate::AuthenticationType;
+
+/// Passwords that appear near the top of every leaked-password list. A passphrase built around one of these is guessed almost immediately.
+const COMMON_PASSWORDS: &[&str] = &[
+    "password",
+    "12345678",
+    "123456789",
+    "1234567890",
+    "qwerty",
+    "qwertyuiop",
+    "abc123",
+    "iloveyou",
+    "letmein",
+    "welcome",
+    "admin",
+    "football",
+    "baseball",
+    "dragon",
+    "monkey",
+    "sunshine",
+    "princess",
+    "trustno1",
+    "internet",
+    "wireless",
+    "guest",
+    "wifi",
+];
+
+/// A rough estimate of how hard a password is to guess, based on its length, the variety of characters it uses, and whether it contains repetition or common passwords. It is intended for warnings such as "this guest password is trivially guessable", not as a guarantee of security.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Strength {
+    /// The estimated entropy of the password in bits.
+    pub entropy_bits: f64,
+    /// A summary of the estimate.
+    pub rating: StrengthRating,
+}
+
+/// A summary of a [`Strength`] estimate.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum StrengthRating {
+    /// Guessable in seconds. Open networks, WEP networks (which can be cracked regardless of the key), and common passwords are rated very weak.
+    VeryWeak,
+    /// Guessable with modest effort.
+    Weak,
+    /// Resistant to casual guessing, though not to a determined offline attack.
+    Reasonable,
+    /// Resistant to offline attacks.
+    Strong,
+}
+
+impl StrengthRating {
+    fn from_entropy_bits(entropy_bits: f64) -> Self {
+        if entropy_bits < 28.0 {
+            Self::VeryWeak
+        } else if entropy_bits < 36.0 {
+            Self::Weak
+        } else if entropy_bits < 60.0 {
+            Self::Reasonable
+        } else {
+            Self::Strong
+        }
+    }
+}
+
+impl AuthenticationType {
+    /// Estimate how hard the password is to guess.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::{AuthenticationType, StrengthRating};
+    ///
+    /// let weak = AuthenticationType::WPA("password1".into());
+    /// assert_eq!(StrengthRating::VeryWeak, weak.strength().rating);
+    ///
+    /// let strong = AuthenticationType::WPA("correct-Horse-battery-staple-42".into());
+    /// assert_eq!(StrengthRating::Strong, strong.strength().rating);
+    /// ```
+    pub fn strength(&self) -> Strength {
+        match self {
+            Self::WPA(passphrase) => {
+                let entropy_bits = estimate_entropy_bits(passphrase);
+                Strength {
+                    entropy_bits,
+                    rating: StrengthRating::from_entropy_bits(entropy_bits),
+                }
+            }
+            Self::WpaPsk(psk) => {
+                let entropy_bits = estimate_psk_entropy_bits(psk);
+                Strength {
+                    entropy_bits,
+                    rating: StrengthRating::from_entropy_bits(entropy_bits),
+                }
+            }
+            Self::WEP(key) => Strength {
+                entropy_bits: estimate_entropy_bits(key),
+                rating: StrengthRating::VeryWeak,
+            },
+            Self::NoPassword => Strength {
+                entropy_bits: 0.0,
+                rating: StrengthRating::VeryWeak,
+            },
+        }
+    }
+}
+
+fn estimate_entropy_bits(password: &str) -> f64 {
+    let lowercase = password.to_lowercase();
+    // Treat a common password inside the passphrase as a single guess from a small dictionary.
+    let (remainder, dictionary_bits) = match COMMON_PASSWORDS
+        .iter()
+        .filter(|common| lowercase.contains(*common))
+        .max_by_key(|common| common.len())
+    {
+        Some(common) => (lowercase.replacen(common, "", 1), 5.0),
+        None => (lowercase, 0.0),
+    };
+    // Case is estimated from the original password, but character classes from what remains.
+    let has_uppercase = password.chars().any(|c| c.is_uppercase()) && dictionary_bits == 0.0;
+    let mut pool_size = 0u32;
+    if remainder.chars().any(|c| c.is_ascii_lowercase()) {
+        pool_size += 26;
+    }
+    if has_uppercase {
+        pool_size += 26;
+    }
+    if remainder.chars().any(|c| c.is_ascii_digit()) {
+        pool_size += 10;
+    }
+    if remainder
+        .chars()
+        .any(|c| c.is_ascii() && !c.is_ascii_alphanumeric())
+    {
+        pool_size += 33;
+    }
+    if !remainder.is_ascii() {
+        pool_size += 100;
+    }
+    if pool_size == 0 {
+        return dictionary_bits;
+    }
+    dictionary_bits + effective_length(&remainder) * f64::from(pool_size).log2()
+}
+
+/// A random pre-shared key has 256 bits of entropy, but keys typed in by hand are sometimes a repeated pattern, such as all zeros. Only the first repetition of the shortest pattern counts, and bytes that repeat or count on from the previous byte add nothing.
+fn estimate_psk_entropy_bits(psk: &[u8; 32]) -> f64 {
+    let period = (1..psk.len())
+        .find(|&period| (period..psk.len()).all(|index| psk[index] == psk[index - period]))
+        .unwrap_or(psk.len());
+    let unpredictable = (0..period)
+        .filter(|&index| index == 0 || psk[index].abs_diff(psk[index - 1]) > 1)
+        .count();
+    8.0 * unpredictable as f64
+}
+
+/// Repeated characters (`aaaa`) and runs (`abcd`, `4321`) add little to the difficulty of guessing, so they only count for half a character.
+fn effective_length(password: &str) -> f64 {
+    let mut length = 0.0;
+    let mut previous: Option<char> = None;
+    for c in password.chars() {
+        let predictable = previous.is_some_and(|previous| {
+            let difference = (c as i64) - (previous as i64);
+            (-1..=1).contains(&difference)
+        });
+        length += if predictable { 0.5 } else { 1.0 };
+        previous = Some(c);
+    }
+    length
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_rates_common_and_repetitive_passwords_very_weak() {
+        for password in [
+            "password",
+            "Password123",
+            "12345678",
+            "aaaaaaaaaa",
+            "guestwifi",
+        ] {
+            assert_eq!(
+                StrengthRating::VeryWeak,
+                AuthenticationType::WPA(password.into()).strength().rating,
+                "{} should be very weak",
+                password
+            );
+        }
+    }
+
+    #[test]
+    fn it_rates_longer_and_more_varied_passwords_higher() {
+        let weak = AuthenticationType::WPA("kitchen7".into()).strength();
+        let better = AuthenticationType::WPA("Kitchen7Table!Window".into()).strength();
+        assert!(better.entropy_bits > weak.entropy_bits);
+        assert_eq!(StrengthRating::Strong, better.rating);
+    }
+
+    #[test]
+    fn it_rates_non_wpa_authentication() {
+        assert_eq!(
+            StrengthRating::VeryWeak,
+            AuthenticationType::NoPassword.strength().rating
+        );
+        assert_eq!(
+            StrengthRating::VeryWeak,
+            AuthenticationType::WEP("Zq8#kP2!mW4@x".into())
+                .strength()
+                .rating
+        );
+    }
+
+    #[test]
+    fn it_rates_patterned_pre_shared_keys_weak() {
+        let mut counting = [0u8; 32];
+        for (index, byte) in counting.iter_mut().enumerate() {
+            *byte = index as u8;
+        }
+        let mut repeated = [0u8; 32];
+        for (index, byte) in repeated.iter_mut().enumerate() {
+            *byte = [0xde, 0xad, 0xbe, 0xef][index % 4];
+        }
+        for (psk, entropy_bits) in [([0; 32], 8.0), (counting, 8.0), (repeated, 32.0)] {
+            let strength = AuthenticationType::WpaPsk(psk.into()).strength();
+            assert_eq!(entropy_bits, strength.entropy_bits);
+            assert!(strength.rating < StrengthRating::Reasonable);
+        }
+        let psk = AuthenticationType::wpa_psk_from_hex(
+            "f42c6fc52df0ebef9ebb4b90b38a5f902e83fe1b135a70e23aed762e9710a12e",
+        )
+        .unwrap();
+        assert_eq!(StrengthRating::Strong, psk.strength().rating);
+    }
+}
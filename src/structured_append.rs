@@ -0,0 +1,302 @@
+//! Structured append splits a payload that would need an unreadably dense QR code across up to 16 linked codes. Scanners that support structured append read the codes in any order and join them back into the original payload.
+//!
+//! Structured append is an optional part of the QR code standard, so a payload that fits in a single code within the version bounds is always rendered as an ordinary QR code.
+
+use crate::{EncodeOptions, Error, QRCodeError, QrPayload};
+
+use qrcode_generator::qrcodegen::{
+    DataTooLong, Mask, QrCode, QrCodeEcc, QrSegment, QrSegmentMode, Version,
+};
+
+/// The most codes that can be linked with structured append.
+const MAX_SYMBOLS: usize = 16;
+
+/// A greyscale image with the linked codes laid out in reading order, as produced by [`render_sheet`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Sheet {
+    /// The width of the image in pixels.
+    pub width: usize,
+    /// The height of the image in pixels.
+    pub height: usize,
+    /// One byte per pixel, row by row, with `0` for dark modules and `255` for the background.
+    pub pixels: Vec<u8>,
+}
+
+/// Encode the payload as linked QR codes, using as few codes as fit within the version bounds of the options. Each code is returned as a matrix of boolean values, in order.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::{structured_append, EncodeOptions};
+/// use wifi_qr_code::{AuthenticationType, Visibility, WifiCredentials};
+///
+/// let wifi_credentials = WifiCredentials {
+///     ssid: String::from("example ssid"),
+///     authentication_type: AuthenticationType::WPA("example password".into()),
+///     visibility: Visibility::Hidden,
+/// };
+/// let options = EncodeOptions {
+///     max_version: 2,
+///     ..EncodeOptions::default()
+/// };
+/// let matrices = structured_append::encode_as_matrices(&wifi_credentials, &options)
+///     .expect("Failed to encode example matrices.");
+/// assert!(matrices.len() > 1);
+/// assert!(matrices.iter().all(|matrix| matrix.len() <= 25));
+/// ```
+pub fn encode_as_matrices(
+    payload: &impl QrPayload,
+    options: &EncodeOptions,
+) -> Result<Vec<Vec<Vec<bool>>>, Error> {
+    match crate::options::generate(payload, options) {
+        Ok(qr_code) => return Ok(vec![crate::render::to_matrix(&qr_code)]),
+        Err(Error::QrCode(QRCodeError::DataTooLong)) => {}
+        Err(error) => return Err(error),
+    }
+
+    let encoded = crate::encode_payload(payload)?;
+    let parity = encoded.bytes().fold(0, |parity, byte| parity ^ byte);
+    let mask = match options.mask {
+        crate::MaskSelection::Automatic => None,
+        crate::MaskSelection::Pattern(pattern) => Some(Mask::new(pattern)),
+    };
+    'parts: for total in 2..=MAX_SYMBOLS {
+        let mut matrices = Vec::with_capacity(total);
+        for (index, part) in split(&encoded, total).into_iter().enumerate() {
+            let (version, error_correction) = match fit(part.len(), options) {
+                Some(fit) => fit,
+                None => continue 'parts,
+            };
+            let codewords = data_codewords(
+                part.as_bytes(),
+                index,
+                total,
+                parity,
+                version,
+                capacity_bits(version, error_correction),
+            );
+            let qr_code = QrCode::encode_codewords(version, error_correction, &codewords, mask);
+            matrices.push(crate::render::to_matrix(&qr_code));
+        }
+        return Ok(matrices);
+    }
+    Err(Error::QrCode(QRCodeError::DataTooLong))
+}
+
+/// Lay the codes out in a grid with `columns` codes per row, in reading order. Every code is drawn `module_size` pixels per module in a cell sized for the largest code, with a four module quiet zone around it.
+///
+/// # Examples
+///
+/// ```
+/// use wifi_qr_code::structured_append;
+///
+/// let matrices = vec![vec![vec![true; 21]; 21]; 3];
+/// let sheet = structured_append::render_sheet(&matrices, 2, 4);
+/// assert_eq!((2 * 29 * 4, 2 * 29 * 4), (sheet.width, sheet.height));
+/// ```
+pub fn render_sheet(matrices: &[Vec<Vec<bool>>], columns: usize, module_size: usize) -> Sheet {
+    let columns = columns.max(1);
+    let rows = matrices.len().div_ceil(columns);
+    let cell = (matrices.iter().map(Vec::len).max().unwrap_or(0) + 8) * module_size;
+    let width = columns.min(matrices.len()) * cell;
+    let height = rows * cell;
+    let mut pixels = vec![255u8; width * height];
+    for (index, matrix) in matrices.iter().enumerate() {
+        let left = (index % columns) * cell + 4 * module_size;
+        let top = (index / columns) * cell + 4 * module_size;
+        for (y, row) in matrix.iter().enumerate() {
+            for (x, _) in row.iter().enumerate().filter(|(_, dark)| **dark) {
+                for pixel_y in 0..module_size {
+                    let start = (top + y * module_size + pixel_y) * width + left + x * module_size;
+                    pixels[start..start + module_size].fill(0);
+                }
+            }
+        }
+    }
+    Sheet {
+        width,
+        height,
+        pixels,
+    }
+}
+
+/// Split the payload into `total` parts of nearly equal length without splitting characters, since some scanners decode each code as text on its own.
+fn split(payload: &str, total: usize) -> Vec<&str> {
+    let mut parts = Vec::with_capacity(total);
+    let mut rest = payload;
+    for remaining in (1..=total).rev() {
+        let mut end = rest.len().div_ceil(remaining);
+        while !rest.is_char_boundary(end) {
+            end += 1;
+        }
+        let (part, tail) = rest.split_at(end);
+        parts.push(part);
+        rest = tail;
+    }
+    parts
+}
+
+/// The bits needed at the given version for the structured append header followed by a byte segment.
+fn required_bits(length: usize, version: Version) -> usize {
+    20 + 4 + count_bits(version) + 8 * length
+}
+
+fn count_bits(version: Version) -> usize {
+    if version.value() <= 9 {
+        8
+    } else {
+        16
+    }
+}
+
+/// Find the smallest version within the bounds that fits the part, along with the highest error correction level that still fits in it.
+fn fit(length: usize, options: &EncodeOptions) -> Option<(Version, QrCodeEcc)> {
+    let version = (options.min_version..=options.max_version)
+        .map(Version::new)
+        .find(|version| {
+            required_bits(length, *version) <= capacity_bits(*version, options.error_correction)
+        })?;
+    let error_correction = [QrCodeEcc::Medium, QrCodeEcc::Quartile, QrCodeEcc::High]
+        .iter()
+        .copied()
+        .rev()
+        .find(|ecc| {
+            *ecc > options.error_correction
+                && required_bits(length, version) <= capacity_bits(version, *ecc)
+        })
+        .unwrap_or(options.error_correction);
+    Some((version, error_correction))
+}
+
+/// The number of data bits a symbol can hold. The generator does not expose its capacity table, but reports the capacity when given more data than any symbol can hold.
+fn capacity_bits(version: Version, error_correction: QrCodeEcc) -> usize {
+    let oversized = QrSegment::new(QrSegmentMode::Byte, 0, vec![false; 24_000]);
+    match QrCode::encode_segments_advanced(
+        &[oversized],
+        error_correction,
+        version,
+        version,
+        None,
+        false,
+    ) {
+        Err(DataTooLong::DataOverCapacity(_, capacity)) => capacity,
+        _ => 0,
+    }
+}
+
+/// Build the data codewords of one linked code: the structured append header, the part as a byte segment, then the terminator and padding required by the standard.
+fn data_codewords(
+    part: &[u8],
+    index: usize,
+    total: usize,
+    parity: u8,
+    version: Version,
+    capacity_bits: usize,
+) -> Vec<u8> {
+    let mut bits = Vec::with_capacity(capacity_bits);
+    let mut append = |value: usize, length: usize| {
+        bits.extend((0..length).rev().map(|bit| (value >> bit) & 1 == 1));
+    };
+    append(0b0011, 4);
+    append(index, 4);
+    append(total - 1, 4);
+    append(parity.into(), 8);
+    append(0b0100, 4);
+    append(part.len(), count_bits(version));
+    for byte in part {
+        append((*byte).into(), 8);
+    }
+    let terminator = (capacity_bits - bits.len()).min(4);
+    bits.resize(bits.len() + terminator, false);
+    bits.resize(bits.len().div_ceil(8) * 8, false);
+
+    let mut codewords: Vec<u8> = bits
+        .chunks(8)
+        .map(|byte| {
+            byte.iter()
+                .fold(0, |value, bit| value << 1 | u8::from(*bit))
+        })
+        .collect();
+    for padding in [0xec, 0x11].iter().copied().cycle() {
+        if codewords.len() * 8 >= capacity_bits {
+            break;
+        }
+        codewords.push(padding);
+    }
+    codewords
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{AuthenticationType, Visibility, WifiCredentials};
+
+    fn wifi_credentials() -> WifiCredentials {
+        WifiCredentials {
+            ssid: String::from("test ssid"),
+            authentication_type: AuthenticationType::WPA(
+                "a much longer test password that needs a denser code".into(),
+            ),
+            visibility: Visibility::Visible,
+        }
+    }
+
+    #[test]
+    fn it_uses_a_single_code_when_the_payload_fits() {
+        let options = EncodeOptions::default();
+        assert_eq!(
+            vec![crate::encode_as_matrix_with_options(&wifi_credentials(), &options).unwrap()],
+            encode_as_matrices(&wifi_credentials(), &options).unwrap()
+        );
+    }
+
+    #[test]
+    fn it_splits_long_payloads_within_the_version_bounds() {
+        let options = EncodeOptions {
+            max_version: 2,
+            ..EncodeOptions::default()
+        };
+        let matrices = encode_as_matrices(&wifi_credentials(), &options).unwrap();
+        assert_eq!(4, matrices.len());
+        assert!(matrices.iter().all(|matrix| matrix.len() <= 25));
+
+        let options = EncodeOptions {
+            max_version: 1,
+            error_correction: QrCodeEcc::High,
+            ..EncodeOptions::default()
+        };
+        match encode_as_matrices(&wifi_credentials(), &options) {
+            Err(Error::QrCode(QRCodeError::DataTooLong)) => {}
+            _ => panic!("Expected the payload not to fit in 16 version 1 codes."),
+        }
+    }
+
+    #[test]
+    fn it_splits_on_character_boundaries() {
+        assert_eq!(vec!["ab", "ü", "c"], split("abüc", 3));
+        assert_eq!("abüc", split("abüc", 3).concat());
+    }
+
+    #[test]
+    fn it_writes_the_structured_append_header() {
+        let version = Version::new(1);
+        let capacity = capacity_bits(version, QrCodeEcc::Low);
+        assert_eq!(152, capacity);
+        let codewords = data_codewords(b"AB", 2, 4, 0x5a, version, capacity);
+        assert_eq!(19, codewords.len());
+        assert_eq!(
+            [0x32, 0x35, 0xa4, 0x02, 0x41, 0x42, 0x00, 0xec, 0x11],
+            codewords[..9]
+        );
+    }
+
+    #[test]
+    fn it_lays_out_sheets_in_reading_order() {
+        let matrices = vec![vec![vec![true]], vec![vec![false]], vec![vec![true]]];
+        let sheet = render_sheet(&matrices, 2, 1);
+        assert_eq!((18, 18), (sheet.width, sheet.height));
+        assert_eq!(0, sheet.pixels[4 * 18 + 4]);
+        assert_eq!(255, sheet.pixels[4 * 18 + 13]);
+        assert_eq!(0, sheet.pixels[13 * 18 + 4]);
+    }
+}
@@ -0,0 +1,330 @@
+//! Fill in user supplied SVG or HTML templates with the QR code and the network details, for branded signage that the built-in cards do not cover. Templates use [Handlebars](https://handlebarsjs.com) syntax and can refer to:
+//!
+//! * `{{qr}}` - the QR code as an inline `<svg>` element, which can be placed directly in an SVG or HTML document
+//! * `{{ssid}}` - the network name
+//! * `{{password}}` - the password, or an empty string for open networks, so `{{#if password}}` can hide it
+//! * `{{security}}` - `WPA`, `WEP`, or `nopass`, as in the QR code payload
+//! * `{{hidden}}` - whether the network is hidden
+//! * `{{title}}` and `{{instructions}}` - the "Wi-Fi" heading and "Scan to connect" text of the built-in cards, in the locale of the template
+//!
+//! Every value except `{{qr}}` is escaped, so SSIDs and passwords with markup characters cannot break the document. Placeholders that do not exist are reported as errors rather than left empty.
+//!
+//! # Examples
+//!
+//! ```
+//! use wifi_qr_code::template::Template;
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! let template = Template::new(
+//!     r#"<svg xmlns="http://www.w3.org/2000/svg" width="300" height="360">{{qr}}<text y="340">{{ssid}}</text></svg>"#,
+//! )
+//! .expect("Failed to parse the template.")
+//! .size(300);
+//! let wifi_credentials = WifiCredentials::wpa2("Café <guest>", "example password");
+//! let svg = template
+//!     .render(&wifi_credentials)
+//!     .expect("Failed to render the template.");
+//! assert!(svg.contains("<text y=\"340\">Café &lt;guest&gt;</text>"));
+//! ```
+
+use crate::print::Locale;
+use crate::{
+    render, AuthenticationType, Error, QrCodeEcc, SvgAttributes, Visibility, WifiCredentials,
+};
+
+use handlebars::{Context, Handlebars, Helper, HelperResult, Output, RenderContext};
+use serde_json::json;
+use std::error;
+use std::fmt::{self, Display, Formatter};
+use std::io::Write;
+
+/// The name the template is registered under.
+const TEMPLATE_NAME: &str = "template";
+
+/// Errors when parsing or rendering a template.
+#[derive(Debug)]
+pub enum TemplateError {
+    /// The template is not valid Handlebars syntax.
+    Syntax(Box<handlebars::TemplateError>),
+    /// The template refers to a placeholder that does not exist, or could not be written.
+    Render(Box<handlebars::RenderError>),
+    /// The QR code could not be rendered.
+    QrCode(Error),
+}
+
+impl Display for TemplateError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(error) => write!(f, "invalid template: {}", error),
+            Self::Render(error) => write!(f, "failed to render the template: {}", error),
+            Self::QrCode(error) => write!(f, "failed to render the QR code: {}", error),
+        }
+    }
+}
+
+impl error::Error for TemplateError {
+    fn source(&self) -> Option<&(dyn error::Error + 'static)> {
+        match self {
+            Self::Syntax(error) => Some(error.as_ref()),
+            Self::Render(error) => Some(error.as_ref()),
+            Self::QrCode(error) => Some(error),
+        }
+    }
+}
+
+impl From<handlebars::TemplateError> for TemplateError {
+    fn from(error: handlebars::TemplateError) -> Self {
+        Self::Syntax(Box::new(error))
+    }
+}
+
+impl From<handlebars::RenderError> for TemplateError {
+    fn from(error: handlebars::RenderError) -> Self {
+        Self::Render(Box::new(error))
+    }
+}
+
+impl From<Error> for TemplateError {
+    fn from(error: Error) -> Self {
+        Self::QrCode(error)
+    }
+}
+
+/// A parsed template and the settings its QR code is rendered with.
+#[derive(Debug, Clone)]
+pub struct Template {
+    registry: Handlebars<'static>,
+    qr_code_error_checking: QrCodeEcc,
+    size: usize,
+    locale: Locale,
+}
+
+impl Template {
+    /// Parse a template. The QR code defaults to 256 by 256 units with medium error correction, and the text to English.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::template::Template;
+    ///
+    /// assert!(Template::new("<p>{{ssid}}</p>").is_ok());
+    /// assert!(Template::new("<p>{{#if password}}</p>").is_err());
+    /// ```
+    pub fn new(source: &str) -> Result<Self, TemplateError> {
+        let mut registry = Handlebars::new();
+        registry.set_strict_mode(true);
+        registry.register_template_string(TEMPLATE_NAME, source)?;
+        registry.register_helper("qr", Box::new(qr_helper));
+        Ok(Template {
+            registry,
+            qr_code_error_checking: QrCodeEcc::Medium,
+            size: 256,
+            locale: Locale::English,
+        })
+    }
+
+    /// Set the error correction level of the QR code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::template::Template;
+    /// use wifi_qr_code::QrCodeEcc;
+    ///
+    /// let template = Template::new("{{qr}}")
+    ///     .expect("Failed to parse the template.")
+    ///     .error_checking(QrCodeEcc::High);
+    /// ```
+    pub fn error_checking(mut self, qr_code_error_checking: QrCodeEcc) -> Self {
+        self.qr_code_error_checking = qr_code_error_checking;
+        self
+    }
+
+    /// Set the width and height of the inline `<svg>` element of the QR code.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::template::Template;
+    ///
+    /// let template = Template::new("{{qr}}")
+    ///     .expect("Failed to parse the template.")
+    ///     .size(512);
+    /// ```
+    pub fn size(mut self, size: usize) -> Self {
+        self.size = size;
+        self
+    }
+
+    /// Set the language of the `{{title}}` and `{{instructions}}` text.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::print::Locale;
+    /// use wifi_qr_code::template::Template;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let template = Template::new("{{instructions}}")
+    ///     .expect("Failed to parse the template.")
+    ///     .locale(Locale::Italian);
+    /// assert_eq!(
+    ///     "Scansiona per connetterti",
+    ///     template.render(&WifiCredentials::open("example ssid")).unwrap()
+    /// );
+    /// ```
+    pub fn locale(mut self, locale: Locale) -> Self {
+        self.locale = locale;
+        self
+    }
+
+    /// Render the template for the credentials.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use wifi_qr_code::template::Template;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// let template = Template::new("{{ssid}}{{#if password}}: {{password}}{{/if}}")
+    ///     .expect("Failed to parse the template.");
+    /// assert_eq!(
+    ///     "example ssid: example password",
+    ///     template
+    ///         .render(&WifiCredentials::wpa2("example ssid", "example password"))
+    ///         .unwrap()
+    /// );
+    /// assert_eq!(
+    ///     "example ssid",
+    ///     template.render(&WifiCredentials::open("example ssid")).unwrap()
+    /// );
+    /// ```
+    pub fn render(&self, wifi_credentials: &WifiCredentials) -> Result<String, TemplateError> {
+        Ok(self
+            .registry
+            .render(TEMPLATE_NAME, &self.data(wifi_credentials)?)?)
+    }
+
+    /// Render the template for the credentials into the writer.
+    ///
+    /// # Examples
+    ///
+    /// ```no_run
+    /// use wifi_qr_code::template::Template;
+    /// use wifi_qr_code::WifiCredentials;
+    ///
+    /// use std::fs::{self, File};
+    ///
+    /// let source = fs::read_to_string("poster.svg").expect("Failed to read the template.");
+    /// let template = Template::new(&source).expect("Failed to parse the template.");
+    /// let wifi_credentials = WifiCredentials::wpa2("example ssid", "example password");
+    /// template
+    ///     .render_to(
+    ///         &wifi_credentials,
+    ///         File::create("poster-filled.svg").expect("Failed to create the output file."),
+    ///     )
+    ///     .expect("Failed to render the template.");
+    /// ```
+    pub fn render_to(
+        &self,
+        wifi_credentials: &WifiCredentials,
+        writer: impl Write,
+    ) -> Result<(), TemplateError> {
+        Ok(self
+            .registry
+            .render_to_write(TEMPLATE_NAME, &self.data(wifi_credentials)?, writer)?)
+    }
+
+    /// The values the placeholders are filled in with.
+    fn data(&self, wifi_credentials: &WifiCredentials) -> Result<serde_json::Value, Error> {
+        let security = match wifi_credentials.authentication_type {
+            AuthenticationType::WEP(_) => "WEP",
+            AuthenticationType::WPA(_) | AuthenticationType::WpaPsk(_) => "WPA",
+            AuthenticationType::NoPassword => "nopass",
+        };
+        Ok(json!({
+            "qr": self.qr_code(wifi_credentials)?,
+            "ssid": wifi_credentials.ssid,
+            "password": wifi_credentials
+                .authentication_type
+                .password_text()
+                .as_deref()
+                .map_or("", String::as_str),
+            "security": security,
+            "hidden": wifi_credentials.visibility == Visibility::Hidden,
+            "title": self.locale.message("title", &[]),
+            "instructions": self.locale.message("instructions", &[]),
+        }))
+    }
+
+    /// The QR code as an inline `<svg>` element with a one module margin, laid out like the standalone SVG images.
+    fn qr_code(&self, wifi_credentials: &WifiCredentials) -> Result<String, Error> {
+        let qr_code = crate::generate_symbol(
+            &crate::encode_payload(wifi_credentials)?,
+            self.qr_code_error_checking,
+        )?;
+        render::svg_element(&qr_code, self.size, &SvgAttributes::default())
+    }
+}
+
+/// Write the pre-rendered QR code without escaping it, since it is markup rather than text.
+fn qr_helper(
+    _: &Helper<'_>,
+    _: &Handlebars<'_>,
+    context: &Context,
+    _: &mut RenderContext<'_, '_>,
+    out: &mut dyn Output,
+) -> HelperResult {
+    if let Some(svg) = context.data()["qr"].as_str() {
+        out.write(svg)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::QRCodeError;
+
+    #[test]
+    fn it_inlines_the_qr_code_and_escapes_text() {
+        let template = Template::new(
+            "<div>{{qr}}</div><p class=\"{{security}}\">{{ssid}} {{password}} {{hidden}}</p>",
+        )
+        .unwrap()
+        .size(100);
+        let wifi_credentials = WifiCredentials {
+            ssid: String::from("<b>ssid</b>"),
+            authentication_type: AuthenticationType::WPA("\"fish\" & chips".into()),
+            visibility: Visibility::Hidden,
+        };
+        let html = template.render(&wifi_credentials).unwrap();
+        assert!(html.starts_with(
+            "<div><svg width=\"100\" height=\"100\" viewBox=\"0 0 100 100\" shape-rendering=\"crispEdges\""
+        ));
+        assert!(html.contains("\"/></svg></div>"));
+        assert!(html.ends_with(
+            "<p class=\"WPA\">&lt;b&gt;ssid&lt;/b&gt; &quot;fish&quot; &amp; chips true</p>"
+        ));
+
+        let mut output = Vec::new();
+        template.render_to(&wifi_credentials, &mut output).unwrap();
+        assert_eq!(html.into_bytes(), output);
+    }
+
+    #[test]
+    fn it_reports_unknown_placeholders() {
+        let template = Template::new("{{ssid}} {{pasword}}").unwrap();
+        assert!(matches!(
+            template.render(&WifiCredentials::open("test ssid")),
+            Err(TemplateError::Render(_))
+        ));
+        let template = Template::new("{{qr}}").unwrap().size(10);
+        assert!(matches!(
+            template.render(&WifiCredentials::open("test ssid")),
+            Err(TemplateError::QrCode(Error::QrCode(
+                QRCodeError::ImageSizeTooSmall
+            )))
+        ));
+    }
+}
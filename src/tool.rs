@@ -0,0 +1,66 @@
+use std::io;
+use std::process::{Command, ExitStatus, Output};
+
+/// Why a network tool run by [`output`] failed, converted into the error type of the module that ran it.
+pub(crate) enum Failure {
+    /// The tool could not be started.
+    Io(io::Error),
+    /// The tool exited unsuccessfully.
+    Exit {
+        program: &'static str,
+        status: ExitStatus,
+        message: String,
+    },
+}
+
+/// Run a network tool, returning its output if it exits successfully.
+pub(crate) fn run(program: &'static str, arguments: &[String]) -> Result<Output, Failure> {
+    output(program, Command::new(program).args(arguments))
+}
+
+/// Run a prepared command for a network tool, returning its output if it exits successfully. Otherwise, the failure explains what the tool wrote to standard error, or to standard output if it wrote nothing to standard error.
+pub(crate) fn output(program: &'static str, command: &mut Command) -> Result<Output, Failure> {
+    let output = command.output().map_err(Failure::Io)?;
+    if output.status.success() {
+        return Ok(output);
+    }
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    let message = if stderr.trim().is_empty() {
+        stdout.trim()
+    } else {
+        stderr.trim()
+    };
+    Err(Failure::Exit {
+        program,
+        status: output.status,
+        message: String::from(message),
+    })
+}
+
+/// Find the device name of the Wi-Fi port in the output of `networksetup -listallhardwareports`.
+#[cfg(any(target_os = "macos", test))]
+pub(crate) fn wifi_interface(hardware_ports: &str) -> Option<&str> {
+    let mut lines = hardware_ports.lines();
+    while let Some(line) = lines.next() {
+        if let Some("Wi-Fi" | "AirPort") = line.strip_prefix("Hardware Port: ") {
+            return lines.next()?.strip_prefix("Device: ");
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn it_finds_the_wifi_interface() {
+        let hardware_ports = "\nHardware Port: Ethernet\nDevice: en1\nEthernet Address: N/A\n\nHardware Port: Wi-Fi\nDevice: en0\nEthernet Address: 00:00:00:00:00:00\n";
+        assert_eq!(Some("en0"), wifi_interface(hardware_ports));
+        assert_eq!(
+            None,
+            wifi_interface("Hardware Port: Ethernet\nDevice: en1\n")
+        );
+    }
+}
@@ -0,0 +1,218 @@
+//! Bindings for JavaScript, for generators running in the browser when the crate is built for `wasm32-unknown-unknown` with [wasm-bindgen](https://docs.rs/wasm-bindgen).
+//!
+//! [`draw_to_canvas`] draws the QR code straight onto a `<canvas>` element, without encoding a PNG and passing it back as a base64 data URL, so a page can redraw the code on every keystroke.
+//!
+//! ```js
+//! import init, { CanvasStyle, drawToCanvas } from "./pkg/wifi_qr_code.js";
+//!
+//! await init();
+//! const style = new CanvasStyle();
+//! style.dark_color = "#1a237e";
+//! drawToCanvas(document.querySelector("canvas"), "example ssid", "WPA", "example password", false, style);
+//! ```
+
+use crate::{AuthenticationType, QrCodeEcc, Visibility, WifiCredentials};
+
+use qrcode_generator::qrcodegen::QrCode;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{CanvasRenderingContext2d, HtmlCanvasElement};
+
+/// How a QR code is drawn onto a canvas.
+#[wasm_bindgen(getter_with_clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CanvasStyle {
+    /// The CSS color of the dark modules.
+    pub dark_color: String,
+    /// The CSS color of the light modules and the quiet zone, or an empty string to leave them transparent.
+    pub light_color: String,
+    /// The width of the light border around the code, in modules.
+    pub quiet_zone: u32,
+    /// The error correction level: `"L"`, `"M"`, `"Q"`, or `"H"`.
+    pub error_correction: String,
+}
+
+#[wasm_bindgen]
+impl CanvasStyle {
+    /// Black modules on white, with a quiet zone of 4 modules and medium error correction.
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        CanvasStyle {
+            dark_color: String::from("#000000"),
+            light_color: String::from("#ffffff"),
+            quiet_zone: 4,
+            error_correction: String::from("M"),
+        }
+    }
+}
+
+impl Default for CanvasStyle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// Draw the QR code of the network onto the canvas, as large as fits its current width and height and centered in it. Modules are a whole number of pixels wide so that they have sharp edges, which may leave a few pixels of the canvas blank around the code.
+///
+/// The security is `"WPA"`, `"WEP"`, or `"nopass"`, as in the QR code payload. Invalid credentials and canvases without a 2D context are reported as errors.
+#[wasm_bindgen(js_name = drawToCanvas)]
+pub fn draw_to_canvas(
+    canvas: &HtmlCanvasElement,
+    ssid: &str,
+    security: &str,
+    password: &str,
+    hidden: bool,
+    style: &CanvasStyle,
+) -> Result<(), JsError> {
+    let wifi_credentials = WifiCredentials {
+        ssid: ssid.to_string(),
+        authentication_type: security
+            .parse::<AuthenticationType>()?
+            .with_password(password),
+        visibility: if hidden {
+            Visibility::Hidden
+        } else {
+            Visibility::Visible
+        },
+    };
+    let qr_code_error_checking = match style.error_correction.as_str() {
+        "L" => QrCodeEcc::Low,
+        "M" => QrCodeEcc::Medium,
+        "Q" => QrCodeEcc::Quartile,
+        "H" => QrCodeEcc::High,
+        error_correction => {
+            return Err(JsError::new(&format!(
+                "unknown error correction level: {}",
+                error_correction
+            )))
+        }
+    };
+    let qr_code = crate::generate_symbol(
+        &crate::encode_payload(&wifi_credentials)?,
+        qr_code_error_checking,
+    )?;
+
+    let context = canvas
+        .get_context("2d")
+        .ok()
+        .flatten()
+        .and_then(|context| context.dyn_into::<CanvasRenderingContext2d>().ok())
+        .ok_or_else(|| JsError::new("the canvas has no 2D context"))?;
+    let (width, height) = (f64::from(canvas.width()), f64::from(canvas.height()));
+    context.clear_rect(0.0, 0.0, width, height);
+    let layout = Layout::new(&qr_code, style.quiet_zone, width, height);
+    if !style.light_color.is_empty() {
+        context.set_fill_style_str(&style.light_color);
+        context.fill_rect(layout.x, layout.y, layout.size, layout.size);
+    }
+    context.set_fill_style_str(&style.dark_color);
+    for (module_y, runs) in dark_runs(&qr_code).iter().enumerate() {
+        for &(start, length) in runs {
+            context.fill_rect(
+                layout.origin_x() + start as f64 * layout.module_size,
+                layout.origin_y() + module_y as f64 * layout.module_size,
+                length as f64 * layout.module_size,
+                layout.module_size,
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Where a QR code and its quiet zone are drawn on a canvas, in pixels.
+#[derive(Debug, Clone, Copy, PartialEq)]
+struct Layout {
+    /// The left edge of the quiet zone.
+    x: f64,
+    /// The top edge of the quiet zone.
+    y: f64,
+    /// The width and height of the code with its quiet zone.
+    size: f64,
+    module_size: f64,
+    quiet_zone: u32,
+}
+
+impl Layout {
+    /// The largest whole number of pixels per module that fits the code and its quiet zone on the canvas, centered.
+    fn new(qr_code: &QrCode, quiet_zone: u32, width: f64, height: f64) -> Self {
+        let modules = f64::from(qr_code.size() as u32 + 2 * quiet_zone);
+        let module_size = (width.min(height) / modules).floor().max(1.0);
+        let size = modules * module_size;
+        Layout {
+            x: ((width - size) / 2.0).floor(),
+            y: ((height - size) / 2.0).floor(),
+            size,
+            module_size,
+            quiet_zone,
+        }
+    }
+
+    /// The left edge of the first column of modules.
+    fn origin_x(&self) -> f64 {
+        self.x + f64::from(self.quiet_zone) * self.module_size
+    }
+
+    /// The top edge of the first row of modules.
+    fn origin_y(&self) -> f64 {
+        self.y + f64::from(self.quiet_zone) * self.module_size
+    }
+}
+
+/// The runs of dark modules in each row, as their first column and length, so adjacent modules are filled in one call and without seams between them.
+fn dark_runs(qr_code: &QrCode) -> Vec<Vec<(i32, i32)>> {
+    (0..qr_code.size())
+        .map(|y| {
+            let mut runs: Vec<(i32, i32)> = Vec::new();
+            for x in 0..qr_code.size() {
+                if qr_code.get_module(x, y) {
+                    match runs.last_mut() {
+                        Some((start, length)) if *start + *length == x => *length += 1,
+                        _ => runs.push((x, 1)),
+                    }
+                }
+            }
+            runs
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn qr_code() -> QrCode {
+        crate::generate_symbol(
+            &crate::encode_payload(&WifiCredentials::wpa2("test ssid", "test password")).unwrap(),
+            QrCodeEcc::Medium,
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn it_fits_whole_modules_on_the_canvas() {
+        let qr_code = qr_code();
+        let modules = f64::from(qr_code.size() as u32 + 8);
+        let layout = Layout::new(&qr_code, 4, 400.0, 300.0);
+        assert_eq!((300.0 / modules).floor(), layout.module_size);
+        assert_eq!(modules * layout.module_size, layout.size);
+        assert_eq!(((400.0 - layout.size) / 2.0).floor(), layout.x);
+        assert_eq!(layout.x + 4.0 * layout.module_size, layout.origin_x());
+        assert_eq!(1.0, Layout::new(&qr_code, 4, 10.0, 10.0).module_size);
+    }
+
+    #[test]
+    fn it_merges_adjacent_dark_modules() {
+        let qr_code = qr_code();
+        let runs = dark_runs(&qr_code);
+        // The finder patterns start every code with a run of 7 dark modules at both ends.
+        assert_eq!(Some(&(0, 7)), runs[0].first());
+        assert_eq!(Some(&(qr_code.size() - 7, 7)), runs[0].last());
+        for (y, runs) in runs.iter().enumerate() {
+            let dark: i32 = runs.iter().map(|(_, length)| length).sum();
+            let expected = (0..qr_code.size())
+                .filter(|&x| qr_code.get_module(x, y as i32))
+                .count();
+            assert_eq!(expected as i32, dark);
+        }
+    }
+}
@@ -0,0 +1,179 @@
+//! Responses for web frameworks, so a handler can return the QR code of a network as an image.
+//!
+//! [`WifiQrPng`] and [`WifiQrSvg`] implement `IntoResponse` for [axum](https://docs.rs/axum) with the `axum` feature, and `Responder` for [actix-web](https://docs.rs/actix-web) with the `actix` feature. Responses have the content type of the image and forbid caching, since they contain the password of the network and change when it is rotated. A QR code that cannot be encoded is answered with a `500 Internal Server Error`.
+//!
+//! # Examples
+//!
+//! ```
+//! use wifi_qr_code::web::WifiQrPng;
+//! use wifi_qr_code::WifiCredentials;
+//!
+//! // Routed with `axum::routing::get(guest_wifi)` or `actix_web::web::get().to(guest_wifi)`.
+//! async fn guest_wifi() -> WifiQrPng {
+//!     WifiQrPng(WifiCredentials::wpa2("example guest network", "example password"))
+//! }
+//! ```
+
+use crate::{Error, QrCodeEcc, WifiCredentials};
+
+/// The `Cache-Control` header of every response.
+const CACHE_CONTROL: &str = "no-store";
+
+/// The QR code of a network as a PNG image, with medium error correction.
+///
+/// Requires the `png` feature.
+#[cfg(feature = "png")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiQrPng(pub WifiCredentials);
+
+/// The QR code of a network as an SVG image, with medium error correction.
+///
+/// Requires the `svg` feature.
+#[cfg(feature = "svg")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WifiQrSvg(pub WifiCredentials);
+
+/// An image that is rendered into the body of a response.
+trait Image {
+    /// The `Content-Type` header of the response.
+    const CONTENT_TYPE: &'static str;
+
+    /// The body of the response.
+    fn render(&self) -> Result<Vec<u8>, Error>;
+}
+
+#[cfg(feature = "png")]
+impl Image for WifiQrPng {
+    const CONTENT_TYPE: &'static str = "image/png";
+
+    fn render(&self) -> Result<Vec<u8>, Error> {
+        let mut png = Vec::new();
+        self.0.qr(QrCodeEcc::Medium).png(&mut png)?;
+        Ok(png)
+    }
+}
+
+#[cfg(feature = "svg")]
+impl Image for WifiQrSvg {
+    const CONTENT_TYPE: &'static str = "image/svg+xml";
+
+    fn render(&self) -> Result<Vec<u8>, Error> {
+        let mut svg = Vec::new();
+        self.0.qr(QrCodeEcc::Medium).svg(&mut svg)?;
+        Ok(svg)
+    }
+}
+
+#[cfg(feature = "axum")]
+mod axum {
+    use super::{Image, CACHE_CONTROL};
+
+    use axum_core::response::{IntoResponse, Response};
+    use http::{header, StatusCode};
+
+    /// The image as a response, or the error that kept it from rendering.
+    pub(super) fn respond<T: Image>(image: &T) -> Response {
+        match image.render() {
+            Ok(body) => (
+                [
+                    (header::CONTENT_TYPE, T::CONTENT_TYPE),
+                    (header::CACHE_CONTROL, CACHE_CONTROL),
+                ],
+                body,
+            )
+                .into_response(),
+            Err(error) => (StatusCode::INTERNAL_SERVER_ERROR, error.to_string()).into_response(),
+        }
+    }
+
+    #[cfg(feature = "png")]
+    impl IntoResponse for super::WifiQrPng {
+        fn into_response(self) -> Response {
+            respond(&self)
+        }
+    }
+
+    #[cfg(feature = "svg")]
+    impl IntoResponse for super::WifiQrSvg {
+        fn into_response(self) -> Response {
+            respond(&self)
+        }
+    }
+}
+
+#[cfg(feature = "actix")]
+mod actix {
+    use super::{Image, CACHE_CONTROL};
+
+    use actix_web::body::BoxBody;
+    use actix_web::http::header;
+    use actix_web::{HttpRequest, HttpResponse, Responder};
+
+    /// The image as a response, or the error that kept it from rendering.
+    pub(super) fn respond<T: Image>(image: &T) -> HttpResponse {
+        match image.render() {
+            Ok(body) => HttpResponse::Ok()
+                .content_type(T::CONTENT_TYPE)
+                .insert_header((header::CACHE_CONTROL, CACHE_CONTROL))
+                .body(body),
+            Err(error) => HttpResponse::InternalServerError().body(error.to_string()),
+        }
+    }
+
+    #[cfg(feature = "png")]
+    impl Responder for super::WifiQrPng {
+        type Body = BoxBody;
+
+        fn respond_to(self, _request: &HttpRequest) -> HttpResponse {
+            respond(&self)
+        }
+    }
+
+    #[cfg(feature = "svg")]
+    impl Responder for super::WifiQrSvg {
+        type Body = BoxBody;
+
+        fn respond_to(self, _request: &HttpRequest) -> HttpResponse {
+            respond(&self)
+        }
+    }
+}
+
+#[cfg(all(test, feature = "png", feature = "svg"))]
+mod tests {
+    use super::*;
+
+    #[cfg(feature = "axum")]
+    #[test]
+    fn it_responds_to_axum_requests() {
+        use axum_core::response::IntoResponse;
+
+        let response =
+            WifiQrPng(WifiCredentials::wpa2("test ssid", "test password")).into_response();
+        assert_eq!(200, response.status().as_u16());
+        assert_eq!("image/png", response.headers()["content-type"]);
+        assert_eq!("no-store", response.headers()["cache-control"]);
+
+        let response = WifiQrSvg(WifiCredentials::wpa2("", "test password")).into_response();
+        assert_eq!(500, response.status().as_u16());
+    }
+
+    #[cfg(feature = "actix")]
+    #[test]
+    fn it_responds_to_actix_requests() {
+        use actix_web::Responder;
+
+        let request = actix_web::test::TestRequest::default().to_http_request();
+        let response =
+            WifiQrSvg(WifiCredentials::wpa2("test ssid", "test password")).respond_to(&request);
+        assert_eq!(200, response.status().as_u16());
+        let header = |name| {
+            response
+                .headers()
+                .get(name)
+                .and_then(|value| value.to_str().ok())
+        };
+        assert_eq!(Some("image/svg+xml"), header("content-type"));
+        assert_eq!(Some("no-store"), header("cache-control"));
+    }
+}